@@ -1,10 +1,14 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+pub mod migrate;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AirstackConfig {
+    #[serde(default)]
+    pub version: Option<u32>,
     pub project: ProjectConfig,
     pub infra: Option<InfraConfig>,
     pub services: Option<HashMap<String, ServiceConfig>>,
@@ -18,12 +22,32 @@ pub struct ProjectConfig {
     pub name: String,
     pub description: Option<String>,
     pub deploy_mode: Option<String>,
+    pub container_runtime: Option<String>,
+    pub registry_mirror: Option<String>,
+}
+
+pub const CONTAINER_RUNTIMES: &[&str] = &["docker", "podman"];
+
+impl ProjectConfig {
+    pub fn container_runtime(&self) -> &str {
+        self.container_runtime.as_deref().unwrap_or("docker")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InfraConfig {
     pub servers: Vec<ServerConfig>,
     pub firewall: Option<FirewallConfig>,
+    pub volumes: Option<Vec<InfraVolumeConfig>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfraVolumeConfig {
+    pub name: String,
+    pub size_gb: u32,
+    pub region: String,
+    pub server: Option<String>,
+    pub format: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +59,53 @@ pub struct ServerConfig {
     pub server_type: String,
     pub ssh_key: String,
     pub floating_ip: Option<bool>,
+    #[serde(default)]
+    pub public_net: Option<PublicNetConfig>,
+    #[serde(default)]
+    pub ssh_user: Option<String>,
+    #[serde(default)]
+    pub ssh_port: Option<u16>,
+    #[serde(default)]
+    pub labels: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub user_data: Option<String>,
+}
+
+impl ServerConfig {
+    pub const DEFAULT_SSH_USER: &'static str = "root";
+    pub const DEFAULT_SSH_PORT: u16 = 22;
+
+    pub fn ssh_user(&self) -> &str {
+        self.ssh_user
+            .as_deref()
+            .filter(|user| !user.trim().is_empty())
+            .unwrap_or(Self::DEFAULT_SSH_USER)
+    }
+
+    pub fn ssh_port(&self) -> u16 {
+        self.ssh_port.unwrap_or(Self::DEFAULT_SSH_PORT)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicNetConfig {
+    #[serde(default = "default_true")]
+    pub ipv4: bool,
+    #[serde(default)]
+    pub ipv6: bool,
+}
+
+impl Default for PublicNetConfig {
+    fn default() -> Self {
+        Self {
+            ipv4: true,
+            ipv6: false,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +118,77 @@ pub struct ServiceConfig {
     pub target_server: Option<String>,
     pub healthcheck: Option<HealthcheckConfig>,
     pub profile: Option<String>,
+    pub secrets: Option<Vec<String>>,
+    pub stop_timeout_secs: Option<u64>,
+    pub deploy_timeout_secs: Option<u64>,
+    pub verify_digest: Option<bool>,
+    pub env_file: Option<String>,
+    pub restart_policy: Option<String>,
+    pub log_max_size: Option<String>,
+    pub log_max_file: Option<u32>,
+    pub command: Option<Vec<String>>,
+    pub entrypoint: Option<Vec<String>>,
+}
+
+// `infra-volume:<name>:<target>` mounts the host path of the `[[infra.volumes]]` entry <name>.
+pub const INFRA_VOLUME_PREFIX: &str = "infra-volume:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeSpec {
+    pub source: String,
+    pub target: String,
+    pub mode: Option<String>,
+}
+
+impl VolumeSpec {
+    pub fn parse(spec: &str) -> Result<Self> {
+        if let Some(rest) = spec.trim().strip_prefix(INFRA_VOLUME_PREFIX) {
+            let mut parsed = Self::parse(rest)
+                .with_context(|| format!("Invalid infra volume mapping '{}'", spec))?;
+            parsed.source = format!("{}{}", INFRA_VOLUME_PREFIX, parsed.source);
+            return Ok(parsed);
+        }
+        let parts = spec.split(':').map(str::trim).collect::<Vec<_>>();
+        if parts.len() < 2 || parts.len() > 3 {
+            anyhow::bail!(
+                "Invalid volume '{}': expected <source>:<target>[:<mode>]",
+                spec
+            );
+        }
+        if parts[0].is_empty() || parts[1].is_empty() {
+            anyhow::bail!(
+                "Invalid volume '{}': source and target must be non-empty",
+                spec
+            );
+        }
+        if !parts[1].starts_with('/') {
+            anyhow::bail!(
+                "Invalid volume '{}': container target '{}' must be an absolute path",
+                spec,
+                parts[1]
+            );
+        }
+        if parts.get(2).is_some_and(|m| m.is_empty()) {
+            anyhow::bail!("Invalid volume '{}': mode cannot be empty", spec);
+        }
+        let mode = parts.get(2).map(|m| m.to_string());
+        Ok(Self {
+            source: parts[0].to_string(),
+            target: parts[1].to_string(),
+            mode,
+        })
+    }
+
+    pub fn infra_volume(&self) -> Option<&str> {
+        self.source.strip_prefix(INFRA_VOLUME_PREFIX)
+    }
+
+    pub fn is_bind_mount(&self) -> bool {
+        self.infra_volume().is_none() && self.source.starts_with('/')
+            || self.source.starts_with('.')
+            || self.source.starts_with('~')
+            || self.source.contains('/')
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,10 +211,13 @@ pub struct HealthcheckConfig {
     pub interval_secs: Option<u64>,
     pub retries: Option<u32>,
     pub timeout_secs: Option<u64>,
+    pub overall_timeout_secs: Option<u64>,
     pub http: Option<HttpHealthcheckConfig>,
     pub tcp: Option<TcpHealthcheckConfig>,
     pub any: Option<Vec<HealthcheckConfig>>,
     pub all: Option<Vec<HealthcheckConfig>>,
+    #[serde(default)]
+    pub quorum: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +227,8 @@ pub struct HttpHealthcheckConfig {
     pub port: Option<u16>,
     pub expected_status: Option<u16>,
     pub timeout_secs: Option<u64>,
+    pub method: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,14 +236,75 @@ pub struct TcpHealthcheckConfig {
     pub host: Option<String>,
     pub port: u16,
     pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub from_host: Option<bool>,
+}
+
+pub fn is_valid_restart_policy(policy: &str) -> bool {
+    match policy.split_once(':') {
+        Some(("on-failure", retries)) => retries.parse::<u32>().is_ok(),
+        Some(_) => false,
+        None => matches!(policy, "no" | "on-failure" | "always" | "unless-stopped"),
+    }
+}
+
+// Docker json-file max-size: a positive integer with an optional k/m/g unit.
+pub fn is_valid_log_size(size: &str) -> bool {
+    let digits = size.trim_end_matches(['k', 'm', 'g']);
+    size.len() - digits.len() <= 1
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && digits.parse::<u64>().is_ok_and(|n| n > 0)
+}
+
+pub fn is_valid_http_method(method: &str) -> bool {
+    matches!(
+        method.to_ascii_uppercase().as_str(),
+        "GET" | "HEAD" | "POST" | "PUT" | "PATCH" | "DELETE" | "OPTIONS"
+    )
+}
+
+fn validate_healthcheck_methods(service: &str, hc: &HealthcheckConfig) -> Result<()> {
+    if let Some(method) = hc.http.as_ref().and_then(|http| http.method.as_deref()) {
+        if !is_valid_http_method(method) {
+            anyhow::bail!(
+                "Healthcheck for service '{}' has unknown http method '{}' (expected GET|HEAD|POST|PUT|PATCH|DELETE|OPTIONS)",
+                service,
+                method
+            );
+        }
+    }
+    for nested in hc.any.iter().chain(hc.all.iter()).flatten() {
+        validate_healthcheck_methods(service, nested)?;
+    }
+    Ok(())
+}
+
+pub fn image_digest(image: &str) -> Option<&str> {
+    let (_, digest) = image.rsplit_once('@')?;
+    let hex = digest.strip_prefix("sha256:")?;
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(digest)
+    } else {
+        None
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeConfig {
-    pub provider: String,
+    pub provider: Option<String>,
     pub sites: Vec<EdgeSiteConfig>,
 }
 
+impl EdgeConfig {
+    pub fn site_provider<'a>(&'a self, site: &'a EdgeSiteConfig) -> Option<&'a str> {
+        site.provider
+            .as_deref()
+            .or(self.provider.as_deref())
+            .filter(|p| !p.trim().is_empty())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeSiteConfig {
     pub host: String,
@@ -104,6 +312,7 @@ pub struct EdgeSiteConfig {
     pub upstream_port: u16,
     pub tls_email: Option<String>,
     pub redirect_http: Option<bool>,
+    pub provider: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -136,12 +345,26 @@ impl AirstackConfig {
         let content = std::fs::read_to_string(&path)
             .with_context(|| format!("Failed to read config file: {:?}", path.as_ref()))?;
 
-        let mut config: AirstackConfig = match toml::from_str(&content) {
+        let raw: toml::Value = match toml::from_str(&content) {
             Ok(v) => v,
             Err(err) => {
                 anyhow::bail!("Failed to parse TOML configuration: {}", err);
             }
         };
+        // Older configs are upgraded in memory; `airstack migrate --write` persists it.
+        let (migrated, _) = migrate::migrate(raw)?;
+        let mut config: AirstackConfig = match migrated.try_into() {
+            Ok(v) => v,
+            Err(err) => {
+                anyhow::bail!("Failed to parse TOML configuration: {}", err);
+            }
+        };
+
+        let header: IncludeHeader = toml::from_str(&content)
+            .with_context(|| "Failed to parse include directive in configuration")?;
+        if let Some(patterns) = header.include {
+            config.apply_includes(path.as_ref(), &patterns)?;
+        }
 
         if let Ok(env_name) = std::env::var("AIRSTACK_ENV") {
             if !env_name.is_empty() {
@@ -164,10 +387,90 @@ impl AirstackConfig {
             }
         }
 
+        let config_dir = path.as_ref().parent().unwrap_or_else(|| Path::new("."));
+        config.resolve_relative_paths(config_dir);
         config.validate()?;
         Ok(config)
     }
 
+    fn apply_includes(&mut self, config_path: &Path, patterns: &[String]) -> Result<()> {
+        let config_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+        let mut service_origin: HashMap<String, PathBuf> = HashMap::new();
+        let mut script_origin: HashMap<String, PathBuf> = HashMap::new();
+        for name in self.services.iter().flat_map(|s| s.keys()) {
+            service_origin.insert(name.clone(), config_path.to_path_buf());
+        }
+        for name in self.scripts.iter().flat_map(|s| s.keys()) {
+            script_origin.insert(name.clone(), config_path.to_path_buf());
+        }
+
+        for pattern in patterns {
+            let matches = expand_include_pattern(config_dir, pattern)?;
+            if matches.is_empty() {
+                anyhow::bail!("include pattern '{}' matched no files", pattern);
+            }
+            for file in matches {
+                let content = std::fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read included config: {:?}", file))?;
+                let raw: toml::Value = toml::from_str(&content)
+                    .with_context(|| format!("Failed to parse included config: {:?}", file))?;
+                let (migrated, _) = migrate::migrate(raw)
+                    .with_context(|| format!("Failed to migrate included config: {:?}", file))?;
+                let included: IncludedConfig = migrated
+                    .try_into()
+                    .with_context(|| format!("Failed to parse included config: {:?}", file))?;
+                for (name, service) in included.services.unwrap_or_default() {
+                    if let Some(origin) = service_origin.get(&name) {
+                        anyhow::bail!(
+                            "service '{}' is defined in both {:?} and {:?}",
+                            name,
+                            origin,
+                            file
+                        );
+                    }
+                    service_origin.insert(name.clone(), file.clone());
+                    self.services
+                        .get_or_insert_with(HashMap::new)
+                        .insert(name, service);
+                }
+                for (name, script) in included.scripts.unwrap_or_default() {
+                    if let Some(origin) = script_origin.get(&name) {
+                        anyhow::bail!(
+                            "script '{}' is defined in both {:?} and {:?}",
+                            name,
+                            origin,
+                            file
+                        );
+                    }
+                    script_origin.insert(name.clone(), file.clone());
+                    self.scripts
+                        .get_or_insert_with(HashMap::new)
+                        .insert(name, script);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_relative_paths(&mut self, config_dir: &Path) {
+        for server in self.infra.iter_mut().flat_map(|i| i.servers.iter_mut()) {
+            if let Some(user_data) = &server.user_data {
+                if Path::new(user_data).is_relative() {
+                    server.user_data =
+                        Some(config_dir.join(user_data).to_string_lossy().to_string());
+                }
+            }
+        }
+        for service in self.services.iter_mut().flat_map(|s| s.values_mut()) {
+            if let Some(env_file) = &service.env_file {
+                if Path::new(env_file).is_relative() {
+                    service.env_file =
+                        Some(config_dir.join(env_file).to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+
     pub fn validate(&self) -> Result<()> {
         if self.project.name.is_empty() {
             anyhow::bail!("Project name cannot be empty");
@@ -179,6 +482,21 @@ impl AirstackConfig {
             }
         }
 
+        if let Some(runtime) = &self.project.container_runtime {
+            if !CONTAINER_RUNTIMES.contains(&runtime.as_str()) {
+                anyhow::bail!(
+                    "project.container_runtime must be one of: {}",
+                    CONTAINER_RUNTIMES.join(", ")
+                );
+            }
+        }
+
+        if let Some(mirror) = &self.project.registry_mirror {
+            if mirror.trim().is_empty() || mirror.contains(char::is_whitespace) {
+                anyhow::bail!("project.registry_mirror must be a registry host such as mirror.example.com:5000");
+            }
+        }
+
         if let Some(infra) = &self.infra {
             if let Some(fw) = &infra.firewall {
                 if fw.name.trim().is_empty() {
@@ -213,6 +531,52 @@ impl AirstackConfig {
                 if server.provider.is_empty() {
                     anyhow::bail!("Server provider cannot be empty");
                 }
+                if server.public_net.is_some_and(|net| !net.ipv4 && !net.ipv6) {
+                    anyhow::bail!(
+                        "Server '{}' public_net must enable at least one of ipv4/ipv6",
+                        server.name
+                    );
+                }
+                if let Some(user_data) = &server.user_data {
+                    if !Path::new(user_data).is_file() {
+                        anyhow::bail!(
+                            "Server '{}' user_data file not found: {}",
+                            server.name,
+                            user_data
+                        );
+                    }
+                }
+            }
+            for volume in infra.volumes.iter().flatten() {
+                if volume.name.trim().is_empty() {
+                    anyhow::bail!("infra.volumes name cannot be empty");
+                }
+                if volume.size_gb == 0 {
+                    anyhow::bail!(
+                        "infra volume '{}' size_gb must be greater than 0",
+                        volume.name
+                    );
+                }
+                if volume.region.trim().is_empty() {
+                    anyhow::bail!("infra volume '{}' region cannot be empty", volume.name);
+                }
+                if let Some(format) = &volume.format {
+                    if !matches!(format.as_str(), "ext4" | "xfs") {
+                        anyhow::bail!(
+                            "infra volume '{}' format must be one of ext4|xfs",
+                            volume.name
+                        );
+                    }
+                }
+                if let Some(server) = &volume.server {
+                    if !infra.servers.iter().any(|s| &s.name == server) {
+                        anyhow::bail!(
+                            "infra volume '{}' references unknown server '{}'",
+                            volume.name,
+                            server
+                        );
+                    }
+                }
             }
         }
 
@@ -224,6 +588,93 @@ impl AirstackConfig {
                 if service.image.is_empty() {
                     anyhow::bail!("Service image cannot be empty for service: {}", name);
                 }
+                if service.image.contains('@') {
+                    if image_digest(&service.image).is_none() {
+                        anyhow::bail!(
+                            "Service '{}' image '{}' has a malformed digest (expected @sha256:<64 hex>)",
+                            name,
+                            service.image
+                        );
+                    }
+                } else if service.verify_digest.unwrap_or(false) {
+                    anyhow::bail!(
+                        "Service '{}' sets verify_digest but image '{}' is not pinned by digest",
+                        name,
+                        service.image
+                    );
+                }
+                if let Some(policy) = &service.restart_policy {
+                    if !is_valid_restart_policy(policy) {
+                        anyhow::bail!(
+                            "Service '{}' restart_policy '{}' must be one of no|on-failure[:n]|always|unless-stopped",
+                            name,
+                            policy
+                        );
+                    }
+                }
+                if let Some(size) = &service.log_max_size {
+                    if !is_valid_log_size(size) {
+                        anyhow::bail!(
+                            "Service '{}' log_max_size '{}' must be a positive size like 10m, 500k, or 1g",
+                            name,
+                            size
+                        );
+                    }
+                }
+                match (service.log_max_file, &service.log_max_size) {
+                    (Some(0), _) => anyhow::bail!(
+                        "Service '{}' log_max_file must be at least 1",
+                        name
+                    ),
+                    (Some(_), None) => anyhow::bail!(
+                        "Service '{}' log_max_file requires log_max_size (docker only rotates by size)",
+                        name
+                    ),
+                    _ => {}
+                }
+                if service.command.as_ref().is_some_and(|c| c.is_empty()) {
+                    anyhow::bail!("Service '{}' command must not be empty when set", name);
+                }
+                if service
+                    .entrypoint
+                    .as_ref()
+                    .is_some_and(|e| e.first().is_none_or(|exe| exe.trim().is_empty()))
+                {
+                    anyhow::bail!(
+                        "Service '{}' entrypoint must start with an executable when set",
+                        name
+                    );
+                }
+                if service.deploy_timeout_secs == Some(0) {
+                    anyhow::bail!(
+                        "Service '{}' deploy_timeout_secs must be greater than 0",
+                        name
+                    );
+                }
+                if let Some(env_file) = &service.env_file {
+                    if !Path::new(env_file).is_file() {
+                        anyhow::bail!("Service '{}' env_file not found: {}", name, env_file);
+                    }
+                }
+                for volume in service.volumes.iter().flatten() {
+                    let spec = VolumeSpec::parse(volume)
+                        .with_context(|| format!("Service '{}' has a malformed volume", name))?;
+                    if let Some(infra_volume) = spec.infra_volume() {
+                        let declared = self
+                            .infra
+                            .as_ref()
+                            .and_then(|infra| infra.volumes.as_ref())
+                            .is_some_and(|vols| vols.iter().any(|v| v.name == infra_volume));
+                        if !declared {
+                            anyhow::bail!(
+                                "Service '{}' volume '{}' references unknown infra volume '{}'",
+                                name,
+                                volume,
+                                infra_volume
+                            );
+                        }
+                    }
+                }
                 if let Some(hc) = &service.healthcheck {
                     let has_cmd = !hc.command.is_empty();
                     let has_http = hc.http.is_some();
@@ -236,18 +687,22 @@ impl AirstackConfig {
                             name
                         );
                     }
+                    validate_healthcheck_methods(name, hc)?;
                 }
             }
         }
 
         if let Some(edge) = &self.edge {
-            if edge.provider.is_empty() {
-                anyhow::bail!("Edge provider cannot be empty");
-            }
             for site in &edge.sites {
                 if site.host.is_empty() {
                     anyhow::bail!("Edge site host cannot be empty");
                 }
+                if edge.site_provider(site).is_none() {
+                    anyhow::bail!(
+                        "Edge site '{}' has no provider (set edge.provider or a per-site provider)",
+                        site.host
+                    );
+                }
                 if site.upstream_service.is_empty() {
                     anyhow::bail!("Edge upstream_service cannot be empty");
                 }
@@ -320,6 +775,12 @@ impl AirstackConfig {
             if project.deploy_mode.is_some() {
                 self.project.deploy_mode = project.deploy_mode;
             }
+            if project.container_runtime.is_some() {
+                self.project.container_runtime = project.container_runtime;
+            }
+            if project.registry_mirror.is_some() {
+                self.project.registry_mirror = project.registry_mirror;
+            }
         }
 
         if let Some(infra) = overlay.infra {
@@ -327,6 +788,9 @@ impl AirstackConfig {
                 if infra.firewall.is_some() {
                     base_infra.firewall = infra.firewall.clone();
                 }
+                if infra.volumes.is_some() {
+                    base_infra.volumes = infra.volumes.clone();
+                }
                 for overlay_server in infra.servers {
                     if let Some(existing) = base_infra
                         .servers
@@ -342,6 +806,7 @@ impl AirstackConfig {
                 self.infra = Some(InfraConfig {
                     servers: infra.servers,
                     firewall: infra.firewall,
+                    volumes: infra.volumes,
                 });
             }
         }
@@ -381,7 +846,38 @@ impl AirstackConfig {
     }
 
     pub fn init_example<P: AsRef<Path>>(path: P) -> Result<()> {
-        let example_config = r#"[project]
+        Self::init_template(path, DEFAULT_INIT_TEMPLATE)
+    }
+
+    pub fn init_template<P: AsRef<Path>>(path: P, template: &str) -> Result<()> {
+        let content = Self::template_for(template)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write config file: {:?}", path.as_ref()))?;
+
+        Ok(())
+    }
+
+    pub fn template_for(name: &str) -> Result<&'static str> {
+        match name {
+            "web" => Ok(WEB_TEMPLATE),
+            "web+db" => Ok(WEB_DB_TEMPLATE),
+            "fly" => Ok(FLY_TEMPLATE),
+            "minimal" => Ok(MINIMAL_TEMPLATE),
+            other => anyhow::bail!(
+                "Unknown init template '{}' (valid templates: {})",
+                other,
+                INIT_TEMPLATES.join(", ")
+            ),
+        }
+    }
+}
+
+pub const INIT_TEMPLATES: &[&str] = &["web", "web+db", "fly", "minimal"];
+pub const DEFAULT_INIT_TEMPLATE: &str = "web+db";
+
+const WEB_DB_TEMPLATE: &str = r#"version = 2
+
+[project]
 name = "my-project"
 description = "Example Airstack project"
 deploy_mode = "remote"
@@ -417,11 +913,145 @@ tls_email = "ops@example.com"
 redirect_http = true
 "#;
 
-        std::fs::write(&path, example_config)
-            .with_context(|| format!("Failed to write config file: {:?}", path.as_ref()))?;
+const WEB_TEMPLATE: &str = r#"version = 2
 
-        Ok(())
+[project]
+name = "my-project"
+description = "Web service behind Caddy"
+deploy_mode = "remote"
+
+[[infra.servers]]
+name = "web-server"
+provider = "hetzner"
+region = "nbg1"
+server_type = "cx21"
+ssh_key = "~/.ssh/id_ed25519.pub"
+
+[infra.firewall]
+name = "my-project-web"
+ingress = [
+  { protocol = "tcp", port = "22", source_ips = ["0.0.0.0/0", "::/0"] },
+  { protocol = "tcp", port = "80", source_ips = ["0.0.0.0/0", "::/0"] },
+  { protocol = "tcp", port = "443", source_ips = ["0.0.0.0/0", "::/0"] },
+]
+
+[services.web]
+image = "nginx:1.27"
+ports = [80]
+restart_policy = "unless-stopped"
+healthcheck = { http = { path = "/", port = 80, expected_status = 200 }, interval_secs = 5, retries = 10, timeout_secs = 3 }
+
+[edge]
+provider = "caddy"
+
+[[edge.sites]]
+host = "www.example.com"
+upstream_service = "web"
+upstream_port = 80
+tls_email = "ops@example.com"
+redirect_http = true
+"#;
+
+const FLY_TEMPLATE: &str = r#"version = 2
+
+[project]
+name = "my-project"
+description = "Fly.io Machines deployment"
+deploy_mode = "remote"
+
+[[infra.servers]]
+name = "my-project-app"
+provider = "fly"
+region = "iad"
+server_type = "shared-cpu-1x"
+ssh_key = "~/.ssh/id_ed25519.pub"
+
+[services.api]
+image = "nginx:1.27"
+ports = [80]
+target_server = "my-project-app"
+healthcheck = { http = { path = "/", port = 80, expected_status = 200 }, interval_secs = 5, retries = 10, timeout_secs = 3 }
+"#;
+
+const MINIMAL_TEMPLATE: &str = r#"version = 2
+
+[project]
+name = "my-project"
+deploy_mode = "local"
+
+[services.app]
+image = "nginx:1.27"
+ports = [8080]
+"#;
+
+#[derive(Debug, Deserialize)]
+struct IncludeHeader {
+    include: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IncludedConfig {
+    services: Option<HashMap<String, ServiceConfig>>,
+    scripts: Option<HashMap<String, ScriptConfig>>,
+}
+
+fn expand_include_pattern(config_dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let (mut candidates, segments) = if Path::new(pattern).is_absolute() {
+        (vec![PathBuf::from("/")], pattern.trim_start_matches('/'))
+    } else {
+        (vec![config_dir.to_path_buf()], pattern)
+    };
+    for segment in segments.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        let mut next = Vec::new();
+        for dir in &candidates {
+            if !segment.contains(['*', '?']) {
+                let path = dir.join(segment);
+                if path.exists() {
+                    next.push(path);
+                }
+                continue;
+            }
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                if wildcard_match(segment, &name.to_string_lossy()) {
+                    next.push(entry.path());
+                }
+            }
+        }
+        candidates = next;
+    }
+    let mut files = candidates
+        .into_iter()
+        .filter(|p| p.is_file())
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
     }
+    pattern[p..].iter().all(|c| *c == '*')
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -439,6 +1069,8 @@ struct OverlayProjectConfig {
     name: Option<String>,
     description: Option<String>,
     deploy_mode: Option<String>,
+    container_runtime: Option<String>,
+    registry_mirror: Option<String>,
 }
 
 #[cfg(test)]
@@ -461,6 +1093,8 @@ mod tests {
                 name: "demo".to_string(),
                 description: None,
                 deploy_mode: Some("remote".to_string()),
+                container_runtime: None,
+                registry_mirror: None,
             },
             infra: Some(InfraConfig {
                 servers: vec![ServerConfig {
@@ -470,8 +1104,14 @@ mod tests {
                     server_type: "cx21".to_string(),
                     ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
                     floating_ip: Some(false),
+                    public_net: None,
+                    ssh_user: None,
+                    ssh_port: None,
+                    labels: None,
+                    user_data: None,
                 }],
                 firewall: None,
+                volumes: None,
             }),
             services: Some(HashMap::from([(
                 "api".to_string(),
@@ -484,14 +1124,134 @@ mod tests {
                     target_server: None,
                     healthcheck: None,
                     profile: None,
+                    secrets: None,
+                    stop_timeout_secs: None,
+                    deploy_timeout_secs: None,
+                    verify_digest: None,
+                    env_file: None,
+                    restart_policy: None,
+                    log_max_size: None,
+                    log_max_file: None,
+                    command: None,
+                    entrypoint: None,
                 },
             )])),
             edge: None,
             scripts: None,
             hooks: None,
+            version: None,
         }
     }
 
+    #[test]
+    fn volume_spec_parses_bind_and_named_volumes() {
+        let bind = VolumeSpec::parse("./data:/var/lib/postgresql/data").expect("bind parses");
+        assert_eq!(bind.source, "./data");
+        assert_eq!(bind.target, "/var/lib/postgresql/data");
+        assert_eq!(bind.mode, None);
+        assert!(bind.is_bind_mount());
+
+        let ro = VolumeSpec::parse("/etc/app:/config:ro").expect("mode parses");
+        assert_eq!(ro.mode.as_deref(), Some("ro"));
+        assert!(ro.is_bind_mount());
+
+        let named = VolumeSpec::parse("pgdata:/var/lib/postgresql/data").expect("named parses");
+        assert!(!named.is_bind_mount());
+
+        let infra = VolumeSpec::parse("infra-volume:pg:/var/lib/postgresql/data:rw")
+            .expect("infra volume parses");
+        assert_eq!(infra.infra_volume(), Some("pg"));
+        assert_eq!(infra.target, "/var/lib/postgresql/data");
+        assert_eq!(infra.mode.as_deref(), Some("rw"));
+        assert!(!infra.is_bind_mount());
+        assert!(VolumeSpec::parse("infra-volume:pg").is_err());
+    }
+
+    #[test]
+    fn volume_spec_rejects_malformed_specs() {
+        for bad in [
+            "/data",
+            ":/data",
+            "/data:",
+            "/a:/b:ro:extra",
+            "/a:relative",
+            "/a:/b:",
+        ] {
+            assert!(VolumeSpec::parse(bad).is_err(), "expected '{bad}' to fail");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_malformed_service_volume() {
+        let mut cfg = base_config();
+        cfg.services
+            .as_mut()
+            .expect("services should exist")
+            .get_mut("api")
+            .expect("api service should exist")
+            .volumes = Some(vec!["/srv/data".to_string()]);
+        let err = cfg.validate().expect_err("expected validation error");
+        assert!(
+            format!("{err:#}").contains("malformed volume"),
+            "unexpected error: {err:#}"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_undeclared_infra_volume() {
+        let mut cfg = base_config();
+        cfg.services
+            .as_mut()
+            .expect("services should exist")
+            .get_mut("api")
+            .expect("api service should exist")
+            .volumes = Some(vec!["infra-volume:pg:/data".to_string()]);
+        let err = cfg.validate().expect_err("expected validation error");
+        assert!(
+            err.to_string().contains("unknown infra volume 'pg'"),
+            "unexpected error: {err:#}"
+        );
+    }
+
+    #[test]
+    fn log_size_accepts_docker_units_only() {
+        assert!(is_valid_log_size("10m"));
+        assert!(is_valid_log_size("500k"));
+        assert!(is_valid_log_size("1g"));
+        assert!(is_valid_log_size("1048576"));
+        assert!(!is_valid_log_size("10mb"));
+        assert!(!is_valid_log_size("0m"));
+        assert!(!is_valid_log_size("m"));
+        assert!(!is_valid_log_size("-5m"));
+    }
+
+    #[test]
+    fn validate_rejects_zero_deploy_timeout() {
+        let mut cfg = base_config();
+        let api = cfg
+            .services
+            .as_mut()
+            .expect("services should exist")
+            .get_mut("api")
+            .expect("api service should exist");
+        api.deploy_timeout_secs = Some(0);
+        let err = cfg.validate().expect_err("expected validation error");
+        assert!(
+            err.to_string()
+                .contains("deploy_timeout_secs must be greater than 0"),
+            "unexpected error: {err}"
+        );
+
+        cfg.services
+            .as_mut()
+            .unwrap()
+            .get_mut("api")
+            .unwrap()
+            .deploy_timeout_secs = Some(600);
+        cfg.validate()
+            .expect("positive deploy timeout should validate");
+    }
+
     #[test]
     fn validate_rejects_empty_project_name() {
         let mut cfg = base_config();
@@ -547,6 +1307,96 @@ mod tests {
         fs::remove_file(&path).expect("cleanup should succeed");
     }
 
+    #[test]
+    fn validate_rejects_public_net_without_any_address_family() {
+        let path = unique_path("public-net.toml");
+        let raw = r#"
+[project]
+name = "demo"
+
+[[infra.servers]]
+name = "web"
+provider = "hetzner"
+server_type = "cpx21"
+ssh_key = "~/.ssh/id_ed25519.pub"
+public_net = { ipv4 = false, ipv6 = true }
+"#;
+        fs::write(&path, raw).expect("config should write");
+        let loaded = AirstackConfig::load(&path).expect("v6-only should load");
+        let server = &loaded.infra.as_ref().expect("infra").servers[0];
+        assert_eq!(
+            server.public_net,
+            Some(PublicNetConfig {
+                ipv4: false,
+                ipv6: true
+            })
+        );
+
+        fs::write(&path, raw.replace("ipv6 = true", "ipv6 = false")).expect("config should write");
+        let err = AirstackConfig::load(&path).expect_err("both disabled should fail");
+        assert!(err.to_string().contains("public_net"), "{err}");
+        fs::remove_file(&path).expect("cleanup should succeed");
+    }
+
+    #[test]
+    fn server_user_data_resolves_relative_to_config() {
+        let path = unique_path("user-data.toml");
+        let script = unique_path("cloud-init.yaml");
+        let raw = format!(
+            r#"
+[project]
+name = "demo"
+
+[[infra.servers]]
+name = "web"
+provider = "hetzner"
+server_type = "cpx21"
+ssh_key = "~/.ssh/id_ed25519.pub"
+user_data = "{}"
+"#,
+            script.file_name().expect("file name").to_string_lossy()
+        );
+        fs::write(&path, raw).expect("config should write");
+        let err = AirstackConfig::load(&path).expect_err("missing user_data should fail");
+        assert!(
+            err.to_string().contains("user_data file not found"),
+            "{err}"
+        );
+
+        fs::write(&script, "#cloud-config\npackages: [docker.io]\n").expect("script writes");
+        let loaded = AirstackConfig::load(&path).expect("user_data should load");
+        assert_eq!(
+            loaded.infra.expect("infra").servers[0].user_data.as_deref(),
+            Some(script.to_string_lossy().as_ref())
+        );
+        fs::remove_file(&path).expect("cleanup should succeed");
+        fs::remove_file(&script).expect("cleanup should succeed");
+    }
+
+    #[test]
+    fn every_init_template_loads_and_validates() {
+        for name in INIT_TEMPLATES {
+            let path = unique_path(&format!("template-{}.toml", name.replace('+', "-")));
+            AirstackConfig::init_template(&path, name)
+                .unwrap_or_else(|e| panic!("template {name} should write: {e}"));
+            let loaded = AirstackConfig::load(&path)
+                .unwrap_or_else(|e| panic!("template {name} should load: {e}"));
+            assert_eq!(loaded.project.name, "my-project");
+            assert!(loaded.services.is_some_and(|s| !s.is_empty()));
+            fs::remove_file(&path).expect("cleanup should succeed");
+        }
+    }
+
+    #[test]
+    fn unknown_init_template_lists_valid_names() {
+        let err = AirstackConfig::template_for("rails").expect_err("unknown template");
+        assert!(
+            err.to_string()
+                .contains("valid templates: web, web+db, fly, minimal"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn load_allows_missing_region_and_defaults_empty() {
         let path = unique_path("missing-region.toml");
@@ -566,6 +1416,129 @@ ssh_key = "~/.ssh/id_ed25519.pub"
         fs::remove_file(&path).expect("cleanup should succeed");
     }
 
+    #[test]
+    fn load_parses_and_validates_infra_volumes() {
+        let path = unique_path("infra-volumes.toml");
+        let raw = r#"
+[project]
+name = "demo"
+
+[[infra.servers]]
+name = "db"
+provider = "hetzner"
+region = "ash"
+server_type = "cpx21"
+ssh_key = "~/.ssh/id_ed25519.pub"
+
+[[infra.volumes]]
+name = "pgdata"
+size_gb = 40
+region = "ash"
+server = "db"
+format = "ext4"
+"#;
+        fs::write(&path, raw).expect("config write should succeed");
+        let loaded = AirstackConfig::load(&path).expect("config should parse");
+        fs::remove_file(&path).expect("cleanup should succeed");
+        let volumes = loaded
+            .infra
+            .expect("infra exists")
+            .volumes
+            .expect("volumes");
+        assert_eq!(volumes.len(), 1);
+        assert_eq!(volumes[0].name, "pgdata");
+        assert_eq!(volumes[0].size_gb, 40);
+        assert_eq!(volumes[0].server.as_deref(), Some("db"));
+
+        let mut cfg = base_config();
+        let infra = cfg.infra.as_mut().expect("infra exists");
+        infra.volumes = Some(vec![InfraVolumeConfig {
+            name: "pgdata".to_string(),
+            size_gb: 0,
+            region: "nbg1".to_string(),
+            server: Some("web".to_string()),
+            format: None,
+        }]);
+        let err = cfg.validate().expect_err("zero size should fail");
+        assert!(err.to_string().contains("size_gb must be greater than 0"));
+
+        let infra = cfg.infra.as_mut().expect("infra exists");
+        infra.volumes = Some(vec![InfraVolumeConfig {
+            name: "pgdata".to_string(),
+            size_gb: 10,
+            region: " ".to_string(),
+            server: Some("web".to_string()),
+            format: None,
+        }]);
+        let err = cfg.validate().expect_err("empty region should fail");
+        assert!(err.to_string().contains("region cannot be empty"));
+    }
+
+    #[test]
+    fn load_expands_include_globs_for_services_and_scripts() {
+        let dir = unique_path("include-glob");
+        fs::create_dir_all(dir.join("services")).expect("dir create should succeed");
+        fs::write(
+            dir.join("airstack.toml"),
+            r#"
+include = ["services/*.toml"]
+
+[project]
+name = "demo"
+
+[services.web]
+image = "nginx:1.27"
+ports = [80]
+"#,
+        )
+        .expect("config write should succeed");
+        fs::write(
+            dir.join("services/api.toml"),
+            "[services.api]\nimage = \"repo/api:v1\"\nports = [8080]\n",
+        )
+        .expect("include write should succeed");
+        // Unversioned includes go through the same migration as the root config.
+        fs::write(
+            dir.join("services/worker.toml"),
+            "[services.worker]\nimage = \"repo/worker:v1\"\n",
+        )
+        .expect("include write should succeed");
+        fs::write(dir.join("services/notes.md"), "not config").expect("write should succeed");
+
+        let loaded = AirstackConfig::load(dir.join("airstack.toml")).expect("config should load");
+        fs::remove_dir_all(&dir).expect("cleanup should succeed");
+        let services = loaded.services.expect("services");
+        let mut names = services.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["api", "web", "worker"]);
+        assert!(wildcard_match("*.toml", "api.toml"));
+        assert!(wildcard_match("svc-?.toml", "svc-1.toml"));
+        assert!(!wildcard_match("*.toml", "notes.md"));
+    }
+
+    #[test]
+    fn load_rejects_duplicate_service_across_includes() {
+        let dir = unique_path("include-dup");
+        fs::create_dir_all(&dir).expect("dir create should succeed");
+        fs::write(
+            dir.join("airstack.toml"),
+            "include = [\"extra.toml\"]\n\n[project]\nname = \"demo\"\n\n[services.api]\nimage = \"repo/api:v1\"\nports = [8080]\n",
+        )
+        .expect("config write should succeed");
+        fs::write(
+            dir.join("extra.toml"),
+            "[services.api]\nimage = \"repo/api:v2\"\nports = [8080]\n",
+        )
+        .expect("include write should succeed");
+
+        let err = AirstackConfig::load(dir.join("airstack.toml")).expect_err("duplicate fails");
+        fs::remove_dir_all(&dir).expect("cleanup should succeed");
+        assert!(
+            err.to_string().contains("service 'api' is defined in both"),
+            "unexpected error: {err}"
+        );
+    }
+
     #[test]
     fn load_fails_on_duplicate_key() {
         let path = unique_path("duplicate-key.toml");
@@ -666,6 +1639,7 @@ ssh_key = "~/.ssh/id_ed25519.pub"
                     source_ips: vec!["0.0.0.0/0".to_string()],
                 }],
             }),
+            volumes: None,
         });
         let err = cfg
             .validate()
@@ -676,4 +1650,193 @@ ssh_key = "~/.ssh/id_ed25519.pub"
             "unexpected error: {err}"
         );
     }
+
+    fn edge_site(host: &str, provider: Option<&str>) -> EdgeSiteConfig {
+        EdgeSiteConfig {
+            host: host.to_string(),
+            upstream_service: "api".to_string(),
+            upstream_port: 80,
+            tls_email: None,
+            redirect_http: None,
+            provider: provider.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_edge_site_without_provider() {
+        let mut cfg = base_config();
+        cfg.edge = Some(EdgeConfig {
+            provider: None,
+            sites: vec![
+                edge_site("a.example.com", Some("caddy")),
+                edge_site("b.example.com", None),
+            ],
+        });
+        let err = cfg
+            .validate()
+            .expect_err("site without provider should fail");
+        assert!(
+            err.to_string().contains("b.example.com"),
+            "unexpected error: {err}"
+        );
+
+        cfg.edge.as_mut().expect("edge exists").provider = Some("caddy".to_string());
+        cfg.validate()
+            .expect("top-level provider should cover sites without override");
+    }
+
+    #[test]
+    fn validate_accepts_digest_pinned_image() {
+        let digest = format!("sha256:{}", "a".repeat(64));
+        let mut cfg = base_config();
+        let api = cfg
+            .services
+            .as_mut()
+            .and_then(|s| s.get_mut("api"))
+            .expect("api service exists");
+        api.image = format!("nginx@{digest}");
+        api.verify_digest = Some(true);
+        cfg.validate().expect("digest-pinned image should validate");
+        assert_eq!(image_digest("nginx:1.25@"), None);
+        assert_eq!(
+            image_digest(&format!("ghcr.io/acme/api:v1@{digest}")),
+            Some(digest.as_str())
+        );
+
+        let api = cfg
+            .services
+            .as_mut()
+            .and_then(|s| s.get_mut("api"))
+            .expect("api service exists");
+        api.image = "nginx@sha256:short".to_string();
+        let err = cfg.validate().expect_err("malformed digest should fail");
+        assert!(
+            err.to_string().contains("malformed digest"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn load_resolves_env_file_relative_to_config() {
+        let dir = unique_path("env-file");
+        fs::create_dir_all(&dir).expect("create dir");
+        fs::write(dir.join("api.env"), "A=1\n").expect("write env file");
+        let config_path = dir.join("airstack.toml");
+        fs::write(
+            &config_path,
+            r#"
+[project]
+name = "demo"
+
+[services.api]
+image = "nginx:latest"
+ports = [80]
+env_file = "api.env"
+"#,
+        )
+        .expect("write config");
+
+        let cfg = AirstackConfig::load(&config_path).expect("config loads");
+        let env_file = cfg.services.expect("services")["api"]
+            .env_file
+            .clone()
+            .expect("env_file set");
+        assert_eq!(Path::new(&env_file), dir.join("api.env"));
+
+        fs::remove_file(dir.join("api.env")).expect("remove env file");
+        let err = AirstackConfig::load(&config_path).expect_err("missing env_file fails");
+        assert!(err.to_string().contains("env_file not found"), "{err}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn validate_rejects_empty_command() {
+        let mut cfg = base_config();
+        cfg.services
+            .as_mut()
+            .expect("services")
+            .get_mut("api")
+            .expect("api")
+            .command = Some(Vec::new());
+        let err = cfg.validate().expect_err("empty command should fail");
+        assert!(err.to_string().contains("command"), "{err}");
+    }
+
+    #[test]
+    fn validate_checks_restart_policy() {
+        for valid in [
+            "no",
+            "always",
+            "unless-stopped",
+            "on-failure",
+            "on-failure:5",
+        ] {
+            let mut cfg = base_config();
+            cfg.services
+                .as_mut()
+                .expect("services")
+                .get_mut("api")
+                .expect("api")
+                .restart_policy = Some(valid.to_string());
+            cfg.validate()
+                .unwrap_or_else(|e| panic!("{valid} should be accepted: {e}"));
+        }
+
+        let mut cfg = base_config();
+        cfg.services
+            .as_mut()
+            .expect("services")
+            .get_mut("api")
+            .expect("api")
+            .restart_policy = Some("sometimes".to_string());
+        let err = cfg.validate().expect_err("invalid policy should fail");
+        assert!(err.to_string().contains("restart_policy"), "{err}");
+        assert!(!is_valid_restart_policy("on-failure:x"));
+    }
+
+    #[test]
+    fn validate_checks_http_healthcheck_method() {
+        let with_method = |method: &str| {
+            let mut cfg = base_config();
+            cfg.services
+                .as_mut()
+                .expect("services")
+                .get_mut("api")
+                .expect("api")
+                .healthcheck = Some(HealthcheckConfig {
+                command: Vec::new(),
+                interval_secs: None,
+                retries: None,
+                timeout_secs: None,
+                overall_timeout_secs: None,
+                http: Some(HttpHealthcheckConfig {
+                    url: None,
+                    path: Some("/health".to_string()),
+                    port: Some(8080),
+                    expected_status: None,
+                    timeout_secs: None,
+                    method: Some(method.to_string()),
+                    headers: Some(HashMap::from([(
+                        "Authorization".to_string(),
+                        "Bearer x".to_string(),
+                    )])),
+                }),
+                tcp: None,
+                any: None,
+                all: None,
+                quorum: None,
+            });
+            cfg
+        };
+        with_method("post")
+            .validate()
+            .expect("post is a known verb");
+        let err = with_method("FETCH")
+            .validate()
+            .expect_err("unknown verb should fail");
+        assert!(
+            err.to_string().contains("unknown http method 'FETCH'"),
+            "{err}"
+        );
+    }
 }
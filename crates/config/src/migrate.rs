@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use toml::{Table, Value};
+
+// Configs without a top-level `version` predate versioning and are treated as version 1.
+pub const CURRENT_CONFIG_VERSION: u32 = 2;
+const UNVERSIONED_CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub changes: Vec<String>,
+}
+
+impl MigrationReport {
+    pub fn is_noop(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+pub fn config_version(value: &Value) -> Result<u32> {
+    match value.get("version") {
+        None => Ok(UNVERSIONED_CONFIG_VERSION),
+        Some(Value::Integer(v)) => u32::try_from(*v)
+            .ok()
+            .filter(|v| *v >= UNVERSIONED_CONFIG_VERSION)
+            .with_context(|| format!("config version {} is invalid", v)),
+        Some(other) => anyhow::bail!(
+            "config version must be an integer, got {}",
+            other.type_str()
+        ),
+    }
+}
+
+pub fn migrate(mut value: Value) -> Result<(Value, MigrationReport)> {
+    let from_version = config_version(&value)?;
+    if from_version > CURRENT_CONFIG_VERSION {
+        anyhow::bail!(
+            "config version {} is newer than this airstack supports ({}); upgrade airstack",
+            from_version,
+            CURRENT_CONFIG_VERSION
+        );
+    }
+    let table = value
+        .as_table_mut()
+        .context("config root must be a TOML table")?;
+
+    let mut changes = Vec::new();
+    let mut version = from_version;
+    while version < CURRENT_CONFIG_VERSION {
+        match version {
+            1 => migrate_v1_to_v2(table, &mut changes),
+            _ => unreachable!("every version below current has a migration step"),
+        }
+        version += 1;
+    }
+    if from_version < CURRENT_CONFIG_VERSION {
+        table.insert(
+            "version".to_string(),
+            Value::Integer(i64::from(CURRENT_CONFIG_VERSION)),
+        );
+        changes.push(format!("set version = {}", CURRENT_CONFIG_VERSION));
+    }
+
+    Ok((
+        value,
+        MigrationReport {
+            from_version,
+            to_version: CURRENT_CONFIG_VERSION,
+            changes,
+        },
+    ))
+}
+
+// v1 configs could omit `ports` on services that publish nothing; v2 requires it.
+fn migrate_v1_to_v2(table: &mut Table, changes: &mut Vec<String>) {
+    if let Some(services) = table.get_mut("services").and_then(Value::as_table_mut) {
+        for (name, service) in services.iter_mut() {
+            let Some(service) = service.as_table_mut() else {
+                continue;
+            };
+            if !service.contains_key("ports") {
+                service.insert("ports".to_string(), Value::Array(Vec::new()));
+                changes.push(format!("services.{name}: added default ports = []"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{config_version, migrate, CURRENT_CONFIG_VERSION};
+    use crate::AirstackConfig;
+    use toml::Value;
+
+    #[test]
+    fn migrates_unversioned_v1_config_to_current() {
+        let v1: Value = toml::from_str(
+            r#"
+[project]
+name = "legacy"
+
+[services.api]
+image = "repo/api:v1"
+ports = [8080]
+
+[services.worker]
+image = "repo/worker:v1"
+"#,
+        )
+        .expect("v1 fixture should parse");
+        assert_eq!(config_version(&v1).unwrap(), 1);
+
+        let (migrated, report) = migrate(v1).expect("migration should succeed");
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.to_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(
+            report.changes,
+            vec![
+                "services.worker: added default ports = []".to_string(),
+                format!("set version = {CURRENT_CONFIG_VERSION}"),
+            ]
+        );
+
+        let config: AirstackConfig = migrated.try_into().expect("migrated config should load");
+        assert_eq!(config.version, Some(CURRENT_CONFIG_VERSION));
+        let services = config.services.expect("services");
+        assert_eq!(services["api"].ports, vec![8080]);
+        assert!(services["worker"].ports.is_empty());
+
+        let (_, again) = migrate(
+            toml::from_str(&format!(
+                "version = {CURRENT_CONFIG_VERSION}\n[project]\nname = \"x\"\n"
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(again.is_noop());
+        assert!(
+            migrate(toml::from_str("version = 99\n[project]\nname = \"x\"\n").unwrap()).is_err()
+        );
+    }
+}
@@ -26,16 +26,7 @@ impl DockerProvider {
     }
 
     fn convert_status(state: &str) -> ContainerStatus {
-        match state {
-            "created" => ContainerStatus::Creating,
-            "running" => ContainerStatus::Running,
-            "paused" => ContainerStatus::Paused,
-            "restarting" => ContainerStatus::Restarting,
-            "removing" => ContainerStatus::Removing,
-            "exited" => ContainerStatus::Exited,
-            "dead" => ContainerStatus::Dead,
-            _ => ContainerStatus::Stopped,
-        }
+        ContainerStatus::from_state(state)
     }
 
     fn convert_container(summary: ContainerSummary) -> Container {
@@ -219,10 +210,12 @@ impl ContainerProvider for DockerProvider {
         })
     }
 
-    async fn stop_service(&self, name: &str) -> Result<()> {
-        info!("Stopping service: {}", name);
+    async fn stop_service(&self, name: &str, timeout_secs: u64) -> Result<()> {
+        info!("Stopping service: {} (timeout {}s)", name, timeout_secs);
 
-        let options = StopContainerOptions { t: 10 };
+        let options = StopContainerOptions {
+            t: timeout_secs as i64,
+        };
 
         self.docker
             .stop_container(name, Some(options))
@@ -290,7 +283,18 @@ impl ContainerProvider for DockerProvider {
             .collect())
     }
 
-    async fn logs(&self, name: &str, follow: bool) -> Result<Vec<String>> {
+    async fn logs(&self, name: &str, follow: bool, tail: Option<usize>) -> Result<Vec<String>> {
+        self.logs_window(name, follow, None, None, tail).await
+    }
+
+    async fn logs_window(
+        &self,
+        name: &str,
+        follow: bool,
+        since: Option<i64>,
+        until: Option<i64>,
+        tail: Option<usize>,
+    ) -> Result<Vec<String>> {
         debug!("Getting logs for container: {}", name);
 
         let options = LogsOptions::<String> {
@@ -298,7 +302,9 @@ impl ContainerProvider for DockerProvider {
             stdout: true,
             stderr: true,
             timestamps: true,
-            ..Default::default()
+            since: since.unwrap_or(0),
+            until: until.unwrap_or(0),
+            tail: tail.map_or_else(|| "all".to_string(), |n| n.to_string()),
         };
 
         let mut stream = self.docker.logs(name, Some(options));
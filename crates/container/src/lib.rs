@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub mod docker;
+pub mod podman;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Container {
@@ -25,6 +26,22 @@ pub enum ContainerStatus {
     Exited,
 }
 
+impl ContainerStatus {
+    // Maps docker/podman `.State.Status` strings; anything unrecognised counts as stopped.
+    pub fn from_state(state: &str) -> Self {
+        match state.trim().to_ascii_lowercase().as_str() {
+            "created" | "configured" | "initialized" => ContainerStatus::Creating,
+            "running" => ContainerStatus::Running,
+            "paused" => ContainerStatus::Paused,
+            "restarting" => ContainerStatus::Restarting,
+            "removing" => ContainerStatus::Removing,
+            "exited" => ContainerStatus::Exited,
+            "dead" => ContainerStatus::Dead,
+            _ => ContainerStatus::Stopped,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortMapping {
     pub container_port: u16,
@@ -46,16 +63,27 @@ pub struct RunServiceRequest {
 pub trait ContainerProvider: Send + Sync {
     async fn build_image(&self, path: &str, tag: &str) -> Result<()>;
     async fn run_service(&self, request: RunServiceRequest) -> Result<Container>;
-    async fn stop_service(&self, name: &str) -> Result<()>;
+    async fn stop_service(&self, name: &str, timeout_secs: u64) -> Result<()>;
     async fn get_container(&self, name: &str) -> Result<Container>;
     async fn list_containers(&self) -> Result<Vec<Container>>;
-    async fn logs(&self, name: &str, follow: bool) -> Result<Vec<String>>;
+    // tail fetches only the last N lines; None returns the whole log.
+    async fn logs(&self, name: &str, follow: bool, tail: Option<usize>) -> Result<Vec<String>>;
+    // since/until are unix seconds; None leaves that side of the window open.
+    async fn logs_window(
+        &self,
+        name: &str,
+        follow: bool,
+        since: Option<i64>,
+        until: Option<i64>,
+        tail: Option<usize>,
+    ) -> Result<Vec<String>>;
     async fn exec(&self, name: &str, command: Vec<String>) -> Result<String>;
 }
 
 pub fn get_provider(provider_name: &str) -> Result<Box<dyn ContainerProvider>> {
     match provider_name {
         "docker" => Ok(Box::new(docker::DockerProvider::new()?)),
+        "podman" => Ok(Box::new(podman::PodmanProvider::new()?)),
         _ => anyhow::bail!("Unsupported container provider: {}", provider_name),
     }
 }
@@ -0,0 +1,338 @@
+use crate::{Container, ContainerProvider, ContainerStatus, PortMapping, RunServiceRequest};
+use anyhow::{Context, Result};
+use std::process::{Command as StdCommand, Output};
+use tokio::process::Command;
+use tracing::{debug, info};
+
+// Same tab-separated layout the SSH inventory uses, but with .State for a stable status word.
+const PS_FORMAT: &str = "{{.ID}}\t{{.Image}}\t{{.Names}}\t{{.State}}\t{{.Ports}}";
+
+pub struct PodmanProvider;
+
+impl PodmanProvider {
+    pub fn new() -> Result<Self> {
+        let probe = StdCommand::new("podman")
+            .arg("--version")
+            .output()
+            .context(
+                "Failed to execute podman. Install podman to use container_runtime='podman'.",
+            )?;
+        if !probe.status.success() {
+            anyhow::bail!(
+                "podman is not available. Install podman to use container_runtime='podman'."
+            );
+        }
+        Ok(Self)
+    }
+
+    async fn podman(&self, args: &[String]) -> Result<Output> {
+        debug!("podman {}", args.join(" "));
+        Command::new("podman")
+            .args(args)
+            .output()
+            .await
+            .context("Failed to execute podman")
+    }
+
+    async fn podman_ok(&self, args: &[String]) -> Result<String> {
+        let out = self.podman(args).await?;
+        if !out.status.success() {
+            anyhow::bail!(
+                "podman {} failed: {}",
+                args.first().map(String::as_str).unwrap_or_default(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).to_string())
+    }
+
+    fn convert_status(state: &str) -> ContainerStatus {
+        ContainerStatus::from_state(state)
+    }
+
+    fn parse_ports(ports: &str) -> Vec<PortMapping> {
+        // e.g. "0.0.0.0:8080->8080/tcp, 9090/udp"
+        ports
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .filter_map(|mapping| {
+                let (host, container) = match mapping.split_once("->") {
+                    Some((host, container)) => (Some(host), container),
+                    None => (None, mapping),
+                };
+                let (port, protocol) = container.split_once('/').unwrap_or((container, "tcp"));
+                Some(PortMapping {
+                    container_port: port.parse().ok()?,
+                    host_port: host
+                        .and_then(|h| h.rsplit(':').next())
+                        .and_then(|p| p.parse().ok()),
+                    protocol: protocol.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn parse_ps_lines(stdout: &str) -> Vec<Container> {
+        stdout
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|line| {
+                let parts = line.splitn(5, '\t').collect::<Vec<_>>();
+                if parts.len() < 4 {
+                    return None;
+                }
+                Some(Container {
+                    id: parts[0].trim().to_string(),
+                    image: parts[1].trim().to_string(),
+                    name: parts[2].trim().to_string(),
+                    status: Self::convert_status(parts[3]),
+                    ports: parts
+                        .get(4)
+                        .map(|p| Self::parse_ports(p))
+                        .unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    fn run_args(request: &RunServiceRequest) -> Vec<String> {
+        let mut args = vec![
+            "run".to_string(),
+            "-d".to_string(),
+            "--name".to_string(),
+            request.name.clone(),
+        ];
+        if let Some(policy) = &request.restart_policy {
+            args.push("--restart".to_string());
+            args.push(policy.clone());
+        }
+        for port in &request.ports {
+            args.push("-p".to_string());
+            args.push(format!("{port}:{port}"));
+        }
+        let mut env = request
+            .env
+            .iter()
+            .flatten()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>();
+        env.sort();
+        for pair in env {
+            args.push("-e".to_string());
+            args.push(pair);
+        }
+        for volume in request.volumes.iter().flatten() {
+            args.push("-v".to_string());
+            args.push(volume.clone());
+        }
+        args.push(request.image.clone());
+        args
+    }
+}
+
+#[async_trait::async_trait]
+impl ContainerProvider for PodmanProvider {
+    async fn build_image(&self, path: &str, tag: &str) -> Result<()> {
+        info!("Building Podman image: {} from {}", tag, path);
+        if !std::path::Path::new(path).exists() {
+            anyhow::bail!("Build path does not exist: {}", path);
+        }
+        self.podman_ok(&[
+            "build".to_string(),
+            "--tag".to_string(),
+            tag.to_string(),
+            path.to_string(),
+        ])
+        .await?;
+        info!("Successfully built image: {}", tag);
+        Ok(())
+    }
+
+    async fn run_service(&self, request: RunServiceRequest) -> Result<Container> {
+        info!(
+            "Running service: {} with image: {}",
+            request.name, request.image
+        );
+
+        // Idempotent deploy: remove an existing container with the same name before create.
+        let _ = self
+            .podman(&["rm".to_string(), "-f".to_string(), request.name.clone()])
+            .await;
+
+        let id = self
+            .podman_ok(&Self::run_args(&request))
+            .await
+            .context("Failed to start container")?
+            .trim()
+            .to_string();
+
+        info!("Successfully started service: {} ({})", request.name, id);
+
+        Ok(Container {
+            id,
+            name: request.name,
+            image: request.image,
+            status: ContainerStatus::Running,
+            ports: request
+                .ports
+                .into_iter()
+                .map(|port| PortMapping {
+                    container_port: port,
+                    host_port: Some(port),
+                    protocol: "tcp".to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn stop_service(&self, name: &str, timeout_secs: u64) -> Result<()> {
+        info!("Stopping service: {} (timeout {}s)", name, timeout_secs);
+        self.podman_ok(&[
+            "stop".to_string(),
+            "--time".to_string(),
+            timeout_secs.to_string(),
+            name.to_string(),
+        ])
+        .await
+        .with_context(|| format!("Failed to stop container: {}", name))?;
+        self.podman_ok(&["rm".to_string(), name.to_string()])
+            .await
+            .with_context(|| format!("Failed to remove container: {}", name))?;
+        info!("Successfully stopped and removed service: {}", name);
+        Ok(())
+    }
+
+    async fn get_container(&self, name: &str) -> Result<Container> {
+        debug!("Getting container: {}", name);
+        let stdout = self
+            .podman_ok(&[
+                "ps".to_string(),
+                "-a".to_string(),
+                "--filter".to_string(),
+                format!("name={name}"),
+                "--format".to_string(),
+                PS_FORMAT.to_string(),
+            ])
+            .await
+            .context("Failed to list containers")?;
+        Self::parse_ps_lines(&stdout)
+            .into_iter()
+            .find(|c| c.name == name)
+            .with_context(|| format!("Container not found: {}", name))
+    }
+
+    async fn list_containers(&self) -> Result<Vec<Container>> {
+        debug!("Listing containers");
+        let stdout = self
+            .podman_ok(&[
+                "ps".to_string(),
+                "-a".to_string(),
+                "--format".to_string(),
+                PS_FORMAT.to_string(),
+            ])
+            .await
+            .context("Failed to list containers")?;
+        Ok(Self::parse_ps_lines(&stdout))
+    }
+
+    async fn logs(&self, name: &str, follow: bool, tail: Option<usize>) -> Result<Vec<String>> {
+        self.logs_window(name, follow, None, None, tail).await
+    }
+
+    async fn logs_window(
+        &self,
+        name: &str,
+        follow: bool,
+        since: Option<i64>,
+        until: Option<i64>,
+        tail: Option<usize>,
+    ) -> Result<Vec<String>> {
+        debug!("Getting logs for container: {}", name);
+        let mut args = vec!["logs".to_string(), "--timestamps".to_string()];
+        if follow {
+            args.push("--follow".to_string());
+        }
+        if let Some(since) = since {
+            args.push("--since".to_string());
+            args.push(since.to_string());
+        }
+        if let Some(until) = until {
+            args.push("--until".to_string());
+            args.push(until.to_string());
+        }
+        if let Some(tail) = tail {
+            args.push("--tail".to_string());
+            args.push(tail.to_string());
+        }
+        args.push(name.to_string());
+
+        let out = self.podman(&args).await?;
+        if !out.status.success() {
+            anyhow::bail!(
+                "podman logs failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        // podman writes container stderr to our stderr; keep both like the docker provider.
+        let mut logs = String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(|line| format!("{line}\n"))
+            .collect::<Vec<_>>();
+        logs.extend(
+            String::from_utf8_lossy(&out.stderr)
+                .lines()
+                .map(|line| format!("{line}\n")),
+        );
+        Ok(logs)
+    }
+
+    async fn exec(&self, name: &str, command: Vec<String>) -> Result<String> {
+        info!("Executing command in container {}: {:?}", name, command);
+        let mut args = vec!["exec".to_string(), name.to_string()];
+        args.extend(command);
+        let out = self.podman(&args).await?;
+        let mut result = String::from_utf8_lossy(&out.stdout).to_string();
+        result.push_str(&String::from_utf8_lossy(&out.stderr));
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PodmanProvider;
+    use crate::ContainerStatus;
+
+    #[test]
+    fn maps_podman_states_onto_container_status() {
+        assert!(matches!(
+            PodmanProvider::convert_status("running"),
+            ContainerStatus::Running
+        ));
+        assert!(matches!(
+            PodmanProvider::convert_status("Configured"),
+            ContainerStatus::Creating
+        ));
+        assert!(matches!(
+            PodmanProvider::convert_status("exited"),
+            ContainerStatus::Exited
+        ));
+        assert!(matches!(
+            PodmanProvider::convert_status("paused"),
+            ContainerStatus::Paused
+        ));
+        assert!(matches!(
+            PodmanProvider::convert_status("stopped"),
+            ContainerStatus::Stopped
+        ));
+
+        let parsed = PodmanProvider::parse_ps_lines(
+            "abc123\tdocker.io/library/nginx:1.27\tweb\trunning\t0.0.0.0:8080->80/tcp\n",
+        );
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "web");
+        assert!(matches!(parsed[0].status, ContainerStatus::Running));
+        assert_eq!(parsed[0].ports[0].container_port, 80);
+        assert_eq!(parsed[0].ports[0].host_port, Some(8080));
+    }
+}
@@ -1,18 +1,223 @@
-use anyhow::Result;
-
-use crate::commands::up;
-
-pub async fn run(config_path: &str, allow_local_deploy: bool) -> Result<()> {
-    up::run(
-        config_path,
-        None,
-        None,
-        false,
-        allow_local_deploy,
-        false,
-        false,
-        false,
-        false,
-    )
-    .await
+use airstack_config::AirstackConfig;
+use anyhow::{Context, Result};
+
+use crate::commands::{plan, up};
+use crate::output;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApplyTarget {
+    Server(String),
+    Service(String),
+}
+
+impl ApplyTarget {
+    // Names may be prefixed with `server:` or `service:` when a server and service share a name.
+    pub fn resolve(config: &AirstackConfig, name: &str) -> Result<Self> {
+        let is_server = |n: &str| {
+            config
+                .infra
+                .as_ref()
+                .is_some_and(|infra| infra.servers.iter().any(|s| s.name == n))
+        };
+        let is_service = |n: &str| {
+            config
+                .services
+                .as_ref()
+                .is_some_and(|services| services.contains_key(n))
+        };
+
+        if let Some(server) = name.strip_prefix("server:") {
+            if !is_server(server) {
+                anyhow::bail!("--target server '{}' not found in configuration", server);
+            }
+            return Ok(Self::Server(server.to_string()));
+        }
+        if let Some(service) = name.strip_prefix("service:") {
+            if !is_service(service) {
+                anyhow::bail!("--target service '{}' not found in configuration", service);
+            }
+            return Ok(Self::Service(service.to_string()));
+        }
+        match (is_server(name), is_service(name)) {
+            (true, true) => anyhow::bail!(
+                "--target '{}' matches both a server and a service; use server:{} or service:{}",
+                name,
+                name,
+                name
+            ),
+            (true, false) => Ok(Self::Server(name.to_string())),
+            (false, true) => Ok(Self::Service(name.to_string())),
+            (false, false) => anyhow::bail!(
+                "--target '{}' matches no configured server or service",
+                name
+            ),
+        }
+    }
+}
+
+pub async fn run(
+    config_path: &str,
+    allow_local_deploy: bool,
+    plan_path: Option<&str>,
+    target: Option<&str>,
+) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let target = target
+        .map(|name| ApplyTarget::resolve(&config, name))
+        .transpose()?;
+
+    let Some(plan_path) = plan_path else {
+        return match target {
+            None => run_up(config_path, allow_local_deploy, up::UpArgs::for_all()).await,
+            Some(ApplyTarget::Server(server)) => {
+                output::line(format!("🎯 applying server {}", server));
+                run_up(
+                    config_path,
+                    allow_local_deploy,
+                    up::UpArgs {
+                        only_servers: vec![server],
+                        infra_only: true,
+                        ..up::UpArgs::for_all()
+                    },
+                )
+                .await
+            }
+            Some(ApplyTarget::Service(service)) => {
+                output::line(format!(
+                    "🎯 applying service {} (and its dependencies)",
+                    service
+                ));
+                run_up(
+                    config_path,
+                    allow_local_deploy,
+                    up::UpArgs {
+                        only: vec![service],
+                        skip_infra: true,
+                        ..up::UpArgs::for_all()
+                    },
+                )
+                .await
+            }
+        };
+    };
+
+    let saved = plan::load_plan(plan_path)?;
+    plan::ensure_plan_matches_config(&saved, &config)?;
+
+    let mut servers = saved.servers_to_create();
+    let mut services = saved.services_to_deploy();
+    match &target {
+        Some(ApplyTarget::Server(server)) => {
+            servers.retain(|s| s == server);
+            services.clear();
+        }
+        Some(ApplyTarget::Service(service)) => {
+            servers.clear();
+            services.retain(|s| s == service);
+        }
+        None => {}
+    }
+    if servers.is_empty() && services.is_empty() {
+        output::line("✅ plan has no actions to apply");
+        return Ok(());
+    }
+
+    if !servers.is_empty() {
+        output::line(format!("🧭 plan: creating servers {}", servers.join(", ")));
+        let only_servers = if target.is_some() {
+            servers.clone()
+        } else {
+            Vec::new()
+        };
+        run_up(
+            config_path,
+            allow_local_deploy,
+            up::UpArgs {
+                only_servers,
+                infra_only: true,
+                ..up::UpArgs::for_all()
+            },
+        )
+        .await?;
+    }
+    if !services.is_empty() {
+        output::line(format!(
+            "🧭 plan: deploying services {}",
+            services.join(", ")
+        ));
+        run_up(
+            config_path,
+            allow_local_deploy,
+            up::UpArgs {
+                only: services,
+                skip_infra: true,
+                ..up::UpArgs::for_all()
+            },
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn run_up(config_path: &str, allow_local_deploy: bool, args: up::UpArgs) -> Result<()> {
+    up::run(config_path, args, false, allow_local_deploy).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApplyTarget;
+    use airstack_config::AirstackConfig;
+
+    #[test]
+    fn target_resolves_to_server_or_service() {
+        let config: AirstackConfig = toml::from_str(
+            r#"
+[project]
+name = "demo"
+
+[[infra.servers]]
+name = "web-1"
+provider = "hetzner"
+server_type = "cpx21"
+ssh_key = "~/.ssh/id_ed25519.pub"
+
+[[infra.servers]]
+name = "db"
+provider = "hetzner"
+server_type = "cpx21"
+ssh_key = "~/.ssh/id_ed25519.pub"
+
+[services.api]
+image = "repo/api:v1"
+ports = [8080]
+
+[services.db]
+image = "postgres:16"
+ports = [5432]
+"#,
+        )
+        .expect("config parses");
+
+        assert_eq!(
+            ApplyTarget::resolve(&config, "web-1").unwrap(),
+            ApplyTarget::Server("web-1".to_string())
+        );
+        assert_eq!(
+            ApplyTarget::resolve(&config, "api").unwrap(),
+            ApplyTarget::Service("api".to_string())
+        );
+        let err = ApplyTarget::resolve(&config, "db").expect_err("ambiguous name");
+        assert!(err.to_string().contains("server:db"), "{err}");
+        assert_eq!(
+            ApplyTarget::resolve(&config, "service:db").unwrap(),
+            ApplyTarget::Service("db".to_string())
+        );
+        assert_eq!(
+            ApplyTarget::resolve(&config, "server:db").unwrap(),
+            ApplyTarget::Server("db".to_string())
+        );
+        let err = ApplyTarget::resolve(&config, "cache").expect_err("unknown name");
+        assert!(err.to_string().contains("matches no configured"), "{err}");
+        assert!(ApplyTarget::resolve(&config, "server:api").is_err());
+    }
 }
@@ -0,0 +1,58 @@
+use crate::output;
+use crate::state::{read_audit_log, AuditEntry, AUDIT_STARTED};
+use airstack_config::AirstackConfig;
+use anyhow::{Context, Result};
+use clap::Args;
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditArgs {
+    #[arg(long, default_value_t = 20, help = "Show only the last N entries")]
+    pub tail: usize,
+    #[arg(long, help = "Only show entries for this command (e.g. deploy)")]
+    pub command: Option<String>,
+    #[arg(long, help = "Only show operations that failed")]
+    pub failed: bool,
+}
+
+pub async fn run(config_path: &str, args: AuditArgs) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let entries = filter_entries(read_audit_log(&config.project.name)?, &args);
+
+    if output::is_json() {
+        return output::emit_json(&entries);
+    }
+
+    if entries.is_empty() {
+        output::line(format!(
+            "ℹ️ no audit entries recorded for {}",
+            config.project.name
+        ));
+        return Ok(());
+    }
+    for entry in &entries {
+        let icon = match entry.result.as_str() {
+            "ok" => "✅",
+            AUDIT_STARTED => "▶️",
+            _ => "❌",
+        };
+        output::line(format!(
+            "{} {} {} ({}ms) airstack {}",
+            icon, entry.timestamp, entry.command, entry.duration_ms, entry.args_summary
+        ));
+        if entry.result != "ok" && entry.result != AUDIT_STARTED {
+            output::line(format!("   {}", entry.result));
+        }
+    }
+    Ok(())
+}
+
+fn filter_entries(entries: Vec<AuditEntry>, args: &AuditArgs) -> Vec<AuditEntry> {
+    let mut entries = entries
+        .into_iter()
+        .filter(|e| args.command.as_deref().is_none_or(|c| e.command == c))
+        .filter(|e| !args.failed || (e.result != "ok" && e.result != AUDIT_STARTED))
+        .collect::<Vec<_>>();
+    let skip = entries.len().saturating_sub(args.tail);
+    entries.drain(..skip);
+    entries
+}
@@ -3,6 +3,7 @@ use crate::ssh_utils::execute_remote_command;
 use airstack_config::AirstackConfig;
 use anyhow::{Context, Result};
 use clap::Subcommand;
+use serde::Serialize;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Subcommand)]
@@ -16,16 +17,37 @@ pub enum BackupCommands {
     },
     #[command(about = "Show backup status")]
     Status,
-    #[command(about = "Restore from backup archive")]
+    #[command(about = "Restore from backup archive (verifies <archive>.sha256 when present)")]
     Restore {
-        #[arg(long)]
-        archive: String,
-        #[arg(long)]
+        #[arg(
+            long = "id",
+            visible_alias = "archive",
+            help = "Backup id (archive name in the backup dir) or absolute archive path"
+        )]
+        id: String,
+        #[arg(long, help = "Restore target directory (service data/volume path)")]
         destination: String,
+        #[arg(
+            long,
+            help = "Verify the archive and print the restore plan without extracting"
+        )]
+        dry_run: bool,
     },
 }
 
-pub async fn run(config_path: &str, command: BackupCommands) -> Result<()> {
+#[derive(Debug, Serialize)]
+struct RestorePlanOutput {
+    server: String,
+    archive: String,
+    destination: String,
+    size_bytes: Option<u64>,
+    modified_unix: Option<u64>,
+    checksum: String,
+    checksum_verified: bool,
+    dry_run: bool,
+}
+
+pub async fn run(config_path: &str, command: BackupCommands, dry_run: bool) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
 
     match command {
@@ -93,9 +115,11 @@ pub async fn run(config_path: &str, command: BackupCommands) -> Result<()> {
             }
         }
         BackupCommands::Restore {
-            archive,
+            id,
             destination,
+            dry_run: restore_dry_run,
         } => {
+            let dry_run = dry_run || restore_dry_run;
             let profile = load_backup_profile(&config.project.name)?
                 .context("Backups are not enabled. Run 'airstack backup enable' first.")?;
             let server = config
@@ -103,6 +127,84 @@ pub async fn run(config_path: &str, command: BackupCommands) -> Result<()> {
                 .as_ref()
                 .and_then(|i| i.servers.iter().find(|s| s.name == profile.server))
                 .context("Backup profile server not found in current config")?;
+            let archive = resolve_archive_path(&profile.remote_dir, &id);
+
+            let inspect = vec![
+                "sh".to_string(),
+                "-lc".to_string(),
+                format!(
+                    "test -f {archive} || {{ echo 'archive not found' >&2; exit 2; }}; \
+                     if test -f {archive}.sha256; then cat {archive}.sha256; fi; echo '--'; \
+                     sha256sum {archive}; echo '--'; \
+                     stat -c '%s %Y' {archive}",
+                    archive = shell_quote(&archive)
+                ),
+            ];
+            let out = execute_remote_command(server, &inspect).await?;
+            if !out.status.success() {
+                anyhow::bail!(
+                    "Backup '{}' could not be verified on {}: {}",
+                    id,
+                    server.name,
+                    String::from_utf8_lossy(&out.stderr).trim()
+                );
+            }
+            let stdout = String::from_utf8_lossy(&out.stdout);
+            let sections = stdout.split("--\n").collect::<Vec<_>>();
+            if sections.len() < 3 {
+                anyhow::bail!("Unexpected backup inspection output for '{}'", archive);
+            }
+            let (checksum, checksum_verified) = restore_checksum(sections[0], sections[1])
+                .with_context(|| format!("Refusing to restore '{}'", archive))?;
+            if !checksum_verified {
+                output::line(format!(
+                    "⚠️  no {}.sha256 sidecar; restoring without checksum verification",
+                    archive
+                ));
+            }
+            let (size_bytes, modified_unix) = parse_stat_line(sections[2]);
+
+            let plan = RestorePlanOutput {
+                server: server.name.clone(),
+                archive: archive.clone(),
+                destination: destination.clone(),
+                size_bytes,
+                modified_unix,
+                checksum,
+                checksum_verified,
+                dry_run,
+            };
+            if dry_run {
+                if output::is_json() {
+                    output::emit_json(&plan)?;
+                } else {
+                    output::line(format!("🔎 restore plan on {} (dry run)", plan.server));
+                    output::line(format!("   archive: {}", plan.archive));
+                    output::line(format!("   target: {}", plan.destination));
+                    output::line(format!(
+                        "   size: {}",
+                        plan.size_bytes
+                            .map(|s| format!("{} bytes", s))
+                            .unwrap_or_else(|| "unknown".to_string())
+                    ));
+                    output::line(format!(
+                        "   timestamp: {}",
+                        plan.modified_unix
+                            .map(|t| t.to_string())
+                            .unwrap_or_else(|| "unknown".to_string())
+                    ));
+                    output::line(format!(
+                        "   checksum: {} ({})",
+                        plan.checksum,
+                        if plan.checksum_verified {
+                            "verified"
+                        } else {
+                            "unverified"
+                        }
+                    ));
+                }
+                return Ok(());
+            }
 
             let cmd = vec![
                 "sh".to_string(),
@@ -121,10 +223,14 @@ pub async fn run(config_path: &str, command: BackupCommands) -> Result<()> {
                 );
             }
 
-            output::line(format!(
-                "✅ restore completed on {} from {} -> {}",
-                server.name, archive, destination
-            ));
+            if output::is_json() {
+                output::emit_json(&plan)?;
+            } else {
+                output::line(format!(
+                    "✅ restore completed on {} from {} -> {}",
+                    server.name, archive, destination
+                ));
+            }
         }
     }
 
@@ -185,6 +291,100 @@ fn select_server<'a>(
     infra.servers.first().context("No infra.servers configured")
 }
 
+fn resolve_archive_path(remote_dir: &str, id: &str) -> String {
+    if id.starts_with('/') {
+        return id.to_string();
+    }
+    let file = if id.ends_with(".tar.gz") {
+        id.to_string()
+    } else {
+        format!("{}.tar.gz", id)
+    };
+    format!("{}/{}", remote_dir.trim_end_matches('/'), file)
+}
+
+// Archives written outside airstack may lack a `.sha256` sidecar; those restore unverified.
+fn restore_checksum(sidecar: &str, actual: &str) -> Result<(String, bool)> {
+    if sidecar.trim().is_empty() {
+        let digest = actual
+            .split_whitespace()
+            .next()
+            .context("sha256sum produced no output")?;
+        return Ok((digest.to_ascii_lowercase(), false));
+    }
+    verify_checksum(sidecar, actual).map(|digest| (digest, true))
+}
+
+fn verify_checksum(expected: &str, actual: &str) -> Result<String> {
+    let first_token = |text: &str| {
+        text.split_whitespace()
+            .next()
+            .map(|t| t.to_ascii_lowercase())
+    };
+    let expected = first_token(expected).context("checksum file is empty")?;
+    let actual = first_token(actual).context("sha256sum produced no output")?;
+    if expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("checksum file does not contain a sha256 digest");
+    }
+    if expected != actual {
+        anyhow::bail!(
+            "checksum mismatch: expected {}, archive hashes to {}",
+            expected,
+            actual
+        );
+    }
+    Ok(actual)
+}
+
+fn parse_stat_line(text: &str) -> (Option<u64>, Option<u64>) {
+    let mut parts = text.split_whitespace();
+    let size = parts.next().and_then(|v| v.parse().ok());
+    let modified = parts.next().and_then(|v| v.parse().ok());
+    (size, modified)
+}
+
 fn shell_quote(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\"'\"'"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_archive_path, restore_checksum, verify_checksum};
+
+    const DIGEST: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[test]
+    fn checksum_verification_accepts_match_and_rejects_mismatch() {
+        let expected = format!("{}  db-20260101.tar.gz\n", DIGEST.to_uppercase());
+        let actual = format!("{}  /var/backups/airstack/db-20260101.tar.gz\n", DIGEST);
+        assert_eq!(
+            verify_checksum(&expected, &actual).expect("digests match"),
+            DIGEST
+        );
+
+        let tampered = format!("{}  db.tar.gz", DIGEST.replace('9', "0"));
+        let err = verify_checksum(&expected, &tampered).expect_err("mismatch should fail");
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert!(verify_checksum("not-a-digest", &actual).is_err());
+        assert!(verify_checksum("", &actual).is_err());
+
+        assert_eq!(
+            restore_checksum("", &actual).expect("missing sidecar is allowed"),
+            (DIGEST.to_string(), false)
+        );
+        assert_eq!(
+            restore_checksum(&expected, &actual).expect("sidecar verifies"),
+            (DIGEST.to_string(), true)
+        );
+        assert!(restore_checksum(&expected, &tampered).is_err());
+    }
+
+    #[test]
+    fn archive_path_resolves_ids_against_backup_dir() {
+        assert_eq!(
+            resolve_archive_path("/var/backups/airstack/", "db-1"),
+            "/var/backups/airstack/db-1.tar.gz"
+        );
+        assert_eq!(resolve_archive_path("/b", "/tmp/x.tar.gz"), "/tmp/x.tar.gz");
+    }
+}
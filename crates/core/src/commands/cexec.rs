@@ -4,10 +4,13 @@ use serde::Serialize;
 use tokio::process::Command;
 use tracing::info;
 
+use crate::concurrency::{self, BoundedJoinSet};
+use crate::deploy_runtime::{configure_container_runtime, container_runtime, replica_containers};
 use crate::output;
 use crate::ssh_utils::{
     execute_remote_command, join_shell_command, resolve_fly_target, start_remote_session,
 };
+use crate::state::{LocalState, ServiceState};
 
 #[derive(Debug, Serialize)]
 struct ContainerExecOutput {
@@ -23,6 +26,7 @@ pub struct ContainerExec {
     pub command: Vec<String>,
     pub cmd: Option<String>,
     pub script: Option<String>,
+    pub tty: bool,
 }
 
 pub async fn run(
@@ -32,6 +36,7 @@ pub async fn run(
     exec: ContainerExec,
 ) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
     let infra = config
         .infra
         .context("No infrastructure defined in configuration")?;
@@ -47,58 +52,43 @@ pub async fn run(
         container, server, server_cfg.provider
     );
 
-    let command_modes = usize::from(!exec.command.is_empty())
-        + usize::from(exec.cmd.is_some())
-        + usize::from(exec.script.is_some());
-    if command_modes > 1 {
-        anyhow::bail!("Use only one execution mode: --cmd, --script, or -- <argv...>");
+    if exec.tty {
+        if output::is_json() {
+            anyhow::bail!("--tty cannot be used with --json");
+        }
+        if exec.script.is_some() {
+            anyhow::bail!("--tty cannot be combined with --script");
+        }
     }
+    let command = requested_command(&exec)?;
 
     if server_cfg.provider == "fly" {
-        return run_fly_container_exec(server, container, server_cfg, exec).await;
+        return run_fly_container_exec(server, container, server_cfg, exec.tty, command).await;
     }
 
-    if command_modes == 0 {
-        if output::is_json() {
-            anyhow::bail!(
-                "Interactive container exec cannot be used with --json. Provide a command."
-            );
-        }
-        let shell_cmd = vec![
-            "docker".to_string(),
-            "exec".to_string(),
-            "-it".to_string(),
-            container.to_string(),
-            "sh".to_string(),
-        ];
-        let code = start_remote_session(server_cfg, &shell_cmd).await?;
-        if code != 0 {
-            anyhow::bail!("Interactive container shell failed with {}", code);
+    let requested_command = match command {
+        Some(command) if !exec.tty => command,
+        command => {
+            if output::is_json() {
+                anyhow::bail!(
+                    "Interactive container exec cannot be used with --json. Provide a command."
+                );
+            }
+            let shell_cmd = container_exec_tty_args(container, &command.unwrap_or_default());
+            let code = start_remote_session(server_cfg, &shell_cmd, true).await?;
+            if code != 0 {
+                anyhow::bail!("Interactive container session failed with {}", code);
+            }
+            return Ok(());
         }
-        return Ok(());
-    }
+    };
 
     let mut remote_cmd = vec![
-        "docker".to_string(),
+        container_runtime(),
         "exec".to_string(),
         container.to_string(),
     ];
-    let requested_command = if let Some(cmd) = exec.cmd {
-        remote_cmd.push("sh".to_string());
-        remote_cmd.push("-lc".to_string());
-        remote_cmd.push(cmd.clone());
-        vec!["sh".to_string(), "-lc".to_string(), cmd]
-    } else if let Some(script_path) = exec.script {
-        let script = std::fs::read_to_string(&script_path)
-            .with_context(|| format!("Failed to read script '{}'", script_path))?;
-        remote_cmd.push("sh".to_string());
-        remote_cmd.push("-lc".to_string());
-        remote_cmd.push(script.clone());
-        vec!["sh".to_string(), "-lc".to_string(), script]
-    } else {
-        remote_cmd.extend(exec.command.iter().cloned());
-        exec.command.clone()
-    };
+    remote_cmd.extend(requested_command.iter().cloned());
     if !output::is_json() {
         output::line(format!("🔧 Executing: {}", join_shell_command(&remote_cmd)));
     }
@@ -138,33 +128,219 @@ pub async fn run(
     Ok(())
 }
 
+pub async fn run_service(
+    config_path: &str,
+    server: Option<&str>,
+    service: &str,
+    exec: ContainerExec,
+) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    let service_cfg = config
+        .services
+        .as_ref()
+        .and_then(|s| s.get(service))
+        .with_context(|| format!("Service '{}' not found in configuration", service))?;
+    let servers = &config
+        .infra
+        .as_ref()
+        .context("No infrastructure defined in configuration")?
+        .servers;
+    let server_name = server.or(service_cfg.target_server.as_deref());
+    let server_cfg = match server_name {
+        Some(name) => servers
+            .iter()
+            .find(|s| s.name == name)
+            .with_context(|| format!("Server '{}' not found in configuration", name))?,
+        None => servers
+            .first()
+            .context("No servers defined in configuration")?,
+    }
+    .clone();
+
+    if exec.tty {
+        anyhow::bail!("--tty cannot be used when executing across a service's containers");
+    }
+    if server_cfg.provider == "fly" {
+        anyhow::bail!(
+            "--service exec is not supported for Fly servers; target a container directly"
+        );
+    }
+    let command = requested_command(&exec)?
+        .context("Provide a command to run in each container (--cmd, --script, or -- <argv...>)")?;
+
+    let state = LocalState::load(&config.project.name)?;
+    let plans = service_exec_commands(service, state.services.get(service), &command);
+    info!(
+        "Executing command in {} container(s) of service '{}' on {}",
+        plans.len(),
+        service,
+        server_cfg.name
+    );
+    if !output::is_json() {
+        output::line(format!(
+            "🔧 Executing across {} container(s) of {}: {}",
+            plans.len(),
+            service,
+            join_shell_command(&command)
+        ));
+    }
+
+    let mut set = BoundedJoinSet::new(concurrency::semaphore());
+    let mut containers = Vec::with_capacity(plans.len());
+    for (slot, (container, remote_cmd)) in plans.into_iter().enumerate() {
+        containers.push(container);
+        let server_cfg = server_cfg.clone();
+        set.spawn(async move {
+            let result = execute_remote_command(&server_cfg, &remote_cmd).await;
+            (slot, result)
+        });
+    }
+    let mut outcomes = containers.iter().map(|_| None).collect::<Vec<_>>();
+    let mut join_errors = Vec::new();
+    while let Some(joined) = set.join_next().await {
+        match joined {
+            Ok((slot, result)) => outcomes[slot] = Some(result),
+            Err(e) => join_errors.push(e.to_string()),
+        }
+    }
+    let records = exec_records(
+        &server_cfg.name,
+        &command,
+        containers,
+        outcomes,
+        &join_errors,
+    );
+
+    if output::is_json() {
+        output::emit_json(&records)?;
+    } else {
+        for record in &records {
+            for line in record.stdout.lines() {
+                println!("[{}] {}", record.container, line);
+            }
+            for line in record.stderr.lines() {
+                output::error_line(format!("[{}] {}", record.container, line));
+            }
+        }
+    }
+
+    let failed = records
+        .iter()
+        .filter(|r| r.exit_code != 0)
+        .map(|r| format!("{} (exit {})", r.container, r.exit_code))
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "Container command failed in {}/{} container(s): {}",
+            failed.len(),
+            records.len(),
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+// A task that panicked or was cancelled cannot say which container it served, so every slot
+// left without an outcome is reported as failed with the join errors.
+fn exec_records(
+    server: &str,
+    command: &[String],
+    containers: Vec<String>,
+    outcomes: Vec<Option<Result<std::process::Output>>>,
+    join_errors: &[String],
+) -> Vec<ContainerExecOutput> {
+    containers
+        .into_iter()
+        .zip(outcomes)
+        .map(|(container, outcome)| {
+            let outcome = outcome.unwrap_or_else(|| {
+                Err(anyhow::anyhow!(
+                    "exec task failed: {}",
+                    join_errors.join("; ")
+                ))
+            });
+            match outcome {
+                Ok(out) => ContainerExecOutput {
+                    server: server.to_string(),
+                    container,
+                    command: command.to_vec(),
+                    exit_code: out.status.code().unwrap_or(1),
+                    stdout: String::from_utf8_lossy(&out.stdout).to_string(),
+                    stderr: String::from_utf8_lossy(&out.stderr).to_string(),
+                },
+                Err(e) => ContainerExecOutput {
+                    server: server.to_string(),
+                    container,
+                    command: command.to_vec(),
+                    exit_code: 1,
+                    stdout: String::new(),
+                    stderr: e.to_string(),
+                },
+            }
+        })
+        .collect()
+}
+
+// The argv to run inside the container; None means no command was given (interactive shell).
+fn requested_command(exec: &ContainerExec) -> Result<Option<Vec<String>>> {
+    let command_modes = usize::from(!exec.command.is_empty())
+        + usize::from(exec.cmd.is_some())
+        + usize::from(exec.script.is_some());
+    if command_modes > 1 {
+        anyhow::bail!("Use only one execution mode: --cmd, --script, or -- <argv...>");
+    }
+    if let Some(cmd) = &exec.cmd {
+        Ok(Some(vec!["sh".to_string(), "-lc".to_string(), cmd.clone()]))
+    } else if let Some(script_path) = &exec.script {
+        let script = std::fs::read_to_string(script_path)
+            .with_context(|| format!("Failed to read script '{}'", script_path))?;
+        Ok(Some(vec!["sh".to_string(), "-lc".to_string(), script]))
+    } else if exec.command.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(exec.command.clone()))
+    }
+}
+
+// Replica names come from recorded state; a service never scaled falls back to its own name.
+fn service_exec_commands(
+    service: &str,
+    state: Option<&ServiceState>,
+    command: &[String],
+) -> Vec<(String, Vec<String>)> {
+    replica_containers(service, state)
+        .into_iter()
+        .map(|container| {
+            let mut args = vec![container_runtime(), "exec".to_string(), container.clone()];
+            args.extend(command.iter().cloned());
+            (container, args)
+        })
+        .collect()
+}
+
 async fn run_fly_container_exec(
     server: &str,
     container: &str,
     server_cfg: &airstack_config::ServerConfig,
-    exec: ContainerExec,
+    tty: bool,
+    command: Option<Vec<String>>,
 ) -> Result<()> {
     let (app, machine) = resolve_fly_target(server_cfg).await?;
-    let command_modes = usize::from(!exec.command.is_empty())
-        + usize::from(exec.cmd.is_some())
-        + usize::from(exec.script.is_some());
-    if command_modes == 0 {
+    let Some(requested_command) = command else {
         if output::is_json() {
             anyhow::bail!(
                 "Interactive container exec cannot be used with --json. Provide a command."
             );
         }
-        let mut fly = Command::new("flyctl");
-        fly.arg("ssh")
-            .arg("console")
-            .arg("--app")
-            .arg(&app)
-            .arg("--container")
-            .arg(container);
-        if let Some(machine) = machine {
-            fly.arg("--machine").arg(machine);
-        }
-        let status = fly
+        let status = Command::new("flyctl")
+            .args(fly_console_args(
+                &app,
+                machine.as_deref(),
+                container,
+                None,
+                false,
+            ))
             .status()
             .await
             .context("Failed to start Fly container shell")?;
@@ -175,39 +351,45 @@ async fn run_fly_container_exec(
             );
         }
         return Ok(());
-    }
-
-    let requested_command = if let Some(cmd) = &exec.cmd {
-        vec!["sh".to_string(), "-lc".to_string(), cmd.clone()]
-    } else if let Some(script_path) = &exec.script {
-        let script = std::fs::read_to_string(script_path)
-            .with_context(|| format!("Failed to read script '{}'", script_path))?;
-        vec!["sh".to_string(), "-lc".to_string(), script]
-    } else {
-        exec.command.clone()
     };
     let fly_command = join_shell_command(&requested_command);
 
-    let mut fly = Command::new("flyctl");
+    if tty {
+        let status = Command::new("flyctl")
+            .args(fly_console_args(
+                &app,
+                machine.as_deref(),
+                container,
+                Some(&fly_command),
+                true,
+            ))
+            .status()
+            .await
+            .context("Failed to start Fly container session")?;
+        if !status.success() {
+            anyhow::bail!(
+                "Interactive Fly container session failed with {:?}",
+                status.code()
+            );
+        }
+        return Ok(());
+    }
 
-    fly.arg("ssh")
-        .arg("console")
-        .arg("--app")
-        .arg(&app)
-        .arg("--container")
-        .arg(container)
-        .arg("--command")
-        .arg(&fly_command);
+    let fly_args = fly_console_args(
+        &app,
+        machine.as_deref(),
+        container,
+        Some(&fly_command),
+        false,
+    );
     if !output::is_json() {
         output::line(format!(
-            "🔧 Executing: flyctl ssh console --app {} --container {} --command {}",
-            app, container, fly_command
+            "🔧 Executing: flyctl {}",
+            join_shell_command(&fly_args)
         ));
     }
-    if let Some(machine) = machine {
-        fly.arg("--machine").arg(machine);
-    }
-    let result = fly
+    let result = Command::new("flyctl")
+        .args(&fly_args)
         .output()
         .await
         .context("Failed to execute Fly container command")?;
@@ -244,3 +426,169 @@ async fn run_fly_container_exec(
 
     Ok(())
 }
+
+fn fly_console_args(
+    app: &str,
+    machine: Option<&str>,
+    container: &str,
+    command: Option<&str>,
+    pty: bool,
+) -> Vec<String> {
+    let mut args = vec!["ssh".to_string(), "console".to_string()];
+    if pty {
+        args.push("--pty".to_string());
+    }
+    args.extend([
+        "--app".to_string(),
+        app.to_string(),
+        "--container".to_string(),
+        container.to_string(),
+    ]);
+    if let Some(machine) = machine {
+        args.extend(["--machine".to_string(), machine.to_string()]);
+    }
+    if let Some(command) = command {
+        args.extend(["--command".to_string(), command.to_string()]);
+    }
+    args
+}
+
+fn container_exec_tty_args(container: &str, command: &[String]) -> Vec<String> {
+    let mut args = vec![
+        container_runtime(),
+        "exec".to_string(),
+        "-it".to_string(),
+        container.to_string(),
+    ];
+    if command.is_empty() {
+        args.push("sh".to_string());
+    } else {
+        args.extend(command.iter().cloned());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        container_exec_tty_args, exec_records, fly_console_args, requested_command,
+        service_exec_commands, ContainerExec,
+    };
+    use crate::state::{HealthState, ServiceState};
+
+    #[test]
+    fn tty_exec_args_wrap_command_with_runtime_exec_it() {
+        assert_eq!(
+            container_exec_tty_args("api", &["bash".to_string(), "-l".to_string()]),
+            vec!["docker", "exec", "-it", "api", "bash", "-l"]
+        );
+        assert_eq!(
+            container_exec_tty_args("api", &[]),
+            vec!["docker", "exec", "-it", "api", "sh"]
+        );
+    }
+
+    #[test]
+    fn service_exec_targets_every_recorded_replica() {
+        let state = ServiceState {
+            image: "api:1".to_string(),
+            replicas: 2,
+            containers: vec!["api".to_string(), "api-2".to_string()],
+            health: HealthState::Healthy,
+            last_status: None,
+            last_checked_unix: 0,
+            last_error: None,
+            last_deploy_command: None,
+            last_deploy_unix: None,
+            image_origin: None,
+            image_size_bytes: None,
+        };
+        let command = vec!["ps".to_string(), "aux".to_string()];
+        assert_eq!(
+            service_exec_commands("api", Some(&state), &command),
+            vec![
+                (
+                    "api".to_string(),
+                    vec!["docker", "exec", "api", "ps", "aux"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect::<Vec<_>>()
+                ),
+                (
+                    "api-2".to_string(),
+                    vec!["docker", "exec", "api-2", "ps", "aux"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect::<Vec<_>>()
+                ),
+            ]
+        );
+        let fallback = service_exec_commands("worker", None, &command);
+        assert_eq!(fallback.len(), 1);
+        assert_eq!(fallback[0].0, "worker");
+    }
+
+    #[test]
+    fn exec_records_fail_slots_whose_task_did_not_join() {
+        let records = exec_records(
+            "web-1",
+            &["true".to_string()],
+            vec!["api".to_string(), "api-2".to_string()],
+            vec![Some(Err(anyhow::anyhow!("ssh refused"))), None],
+            &["task 7 panicked".to_string()],
+        );
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].container, "api");
+        assert_eq!(records[0].stderr, "ssh refused");
+        assert_eq!(records[1].container, "api-2");
+        assert_eq!(records[1].exit_code, 1);
+        assert!(records[1].stderr.contains("task 7 panicked"));
+    }
+
+    #[test]
+    fn requested_command_accepts_exactly_one_mode() {
+        let exec = |command: &[&str], cmd: Option<&str>| ContainerExec {
+            command: command.iter().map(|s| s.to_string()).collect(),
+            cmd: cmd.map(str::to_string),
+            script: None,
+            tty: false,
+        };
+        assert_eq!(requested_command(&exec(&[], None)).expect("none"), None);
+        assert_eq!(
+            requested_command(&exec(&[], Some("ls"))).expect("cmd"),
+            Some(vec!["sh".to_string(), "-lc".to_string(), "ls".to_string()])
+        );
+        assert!(requested_command(&exec(&["ls"], Some("ls"))).is_err());
+    }
+
+    #[test]
+    fn fly_console_args_target_app_machine_and_container() {
+        assert_eq!(
+            fly_console_args("demo", Some("e784"), "api", Some("sh -lc 'ls /app'"), false),
+            vec![
+                "ssh",
+                "console",
+                "--app",
+                "demo",
+                "--container",
+                "api",
+                "--machine",
+                "e784",
+                "--command",
+                "sh -lc 'ls /app'"
+            ]
+        );
+        assert_eq!(
+            fly_console_args("demo", None, "api", None, true),
+            vec![
+                "ssh",
+                "console",
+                "--pty",
+                "--app",
+                "demo",
+                "--container",
+                "api"
+            ]
+        );
+    }
+}
@@ -37,9 +37,7 @@ pub async fn run(config_path: &str) -> Result<()> {
             2 => planning_menu(&theme, config_path).await?,
             3 => edge_menu(&theme, config_path).await?,
             4 => remote_menu(&theme, config_path, &server_names, &service_names).await?,
-            5 => run_and_continue(
-                commands::status::run(config_path, false, false, false, "auto").await,
-            ),
+            5 => run_and_continue(commands::status::run(config_path, Default::default()).await),
             6 => break,
             _ => {}
         }
@@ -56,11 +54,16 @@ async fn infrastructure_menu(theme: &ColorfulTheme, config_path: &str) -> Result
             &["Status", "Status (Detailed)", "Up", "Destroy", "Back"],
         )?;
         match choice {
-            0 => run_and_continue(
-                commands::status::run(config_path, false, false, false, "auto").await,
-            ),
+            0 => run_and_continue(commands::status::run(config_path, Default::default()).await),
             1 => run_and_continue(
-                commands::status::run(config_path, true, false, false, "auto").await,
+                commands::status::run(
+                    config_path,
+                    commands::status::StatusArgs {
+                        detailed: true,
+                        ..Default::default()
+                    },
+                )
+                .await,
             ),
             2 => {
                 let provider = read_optional(theme, "Provider (blank = config default)")?;
@@ -68,12 +71,11 @@ async fn infrastructure_menu(theme: &ColorfulTheme, config_path: &str) -> Result
                 run_and_continue(
                     commands::up::run(
                         config_path,
-                        target,
-                        provider,
-                        false,
-                        false,
-                        false,
-                        false,
+                        commands::up::UpArgs {
+                            target,
+                            provider,
+                            ..commands::up::UpArgs::for_all()
+                        },
                         false,
                         false,
                     )
@@ -88,7 +90,10 @@ async fn infrastructure_menu(theme: &ColorfulTheme, config_path: &str) -> Result
                     .context("Failed to read confirmation")?;
                 if confirmed {
                     let target = read_optional(theme, "Target env (blank = default)")?;
-                    run_and_continue(commands::destroy::run(config_path, target, true).await);
+                    run_and_continue(
+                        commands::destroy::run(config_path, target, true, Vec::new(), false, false)
+                            .await,
+                    );
                 }
             }
             4 => break,
@@ -121,14 +126,9 @@ async fn services_menu(
                         run_and_continue(
                             commands::deploy::run(
                                 config_path,
-                                &selected,
-                                None,
+                                commands::deploy::DeployArgs::for_service(&selected),
                                 false,
                                 false,
-                                true,
-                                None,
-                                "rolling".to_string(),
-                                45,
                             )
                             .await,
                         );
@@ -158,7 +158,15 @@ async fn services_menu(
                         .context("Failed to read follow option")?;
                     let tail = read_optional_usize(theme, "Tail lines (blank = full)")?;
                     run_and_continue(
-                        commands::logs::run(config_path, &service, follow, tail, "auto").await,
+                        commands::logs::run(
+                            config_path,
+                            &service,
+                            follow,
+                            tail,
+                            "auto",
+                            commands::logs::LogWindow::default(),
+                        )
+                        .await,
                     );
                 }
             }
@@ -187,6 +195,12 @@ async fn services_menu(
                                 update_config,
                                 remote_build: None,
                                 from: commands::release::ReleaseFrom::Build,
+                                context_dir: None,
+                                dockerfile: None,
+                                build_arg: Vec::new(),
+                                no_cache: false,
+                                pull: false,
+                                platform: Vec::new(),
                             },
                         )
                         .await,
@@ -216,9 +230,14 @@ async fn planning_menu(theme: &ColorfulTheme, config_path: &str) -> Result<()> {
             ],
         )?;
         match choice {
-            0 => run_and_continue(commands::plan::run(config_path, false, false, false).await),
-            1 => run_and_continue(commands::apply::run(config_path, false).await),
-            2 => run_and_continue(commands::doctor::run(config_path).await),
+            0 => {
+                run_and_continue(commands::plan::run(config_path, false, false, false, None).await)
+            }
+            1 => run_and_continue(commands::apply::run(config_path, false, None, None).await),
+            2 => run_and_continue(
+                commands::doctor::run(config_path, commands::doctor::DoctorArgs::default(), false)
+                    .await,
+            ),
             3 => run_and_continue(
                 commands::golive::run(
                     config_path,
@@ -231,7 +250,12 @@ async fn planning_menu(theme: &ColorfulTheme, config_path: &str) -> Result<()> {
             ),
             4 => run_and_continue(commands::runbook::run(config_path).await),
             5 => run_and_continue(
-                commands::secrets::run(config_path, commands::secrets::SecretsCommands::List).await,
+                commands::secrets::run(
+                    config_path,
+                    commands::secrets::SecretsCommands::List,
+                    false,
+                )
+                .await,
             ),
             6 => break,
             _ => {}
@@ -326,6 +350,7 @@ async fn remote_menu(
                                 command: split_command(cmd),
                                 cmd: None,
                                 script: None,
+                                tty: false,
                             },
                         )
                         .await,
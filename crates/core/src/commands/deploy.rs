@@ -2,18 +2,113 @@ use crate::commands::edge;
 use crate::commands::release;
 use crate::dependencies::deployment_order;
 use crate::deploy_runtime::{
-    collect_container_diagnostics, deploy_service_with_strategy, evaluate_service_health,
-    existing_service_image, resolve_target, rollback_service, DeployStrategy,
+    apply_health_timeout, collect_container_diagnostics, configure_container_runtime,
+    configure_force_pull, deploy_service_with_strategy, evaluate_replica_health,
+    existing_service_image, existing_service_spec, image_size_bytes, replica_containers,
+    resolve_target, rollback_service, CanaryWindow, DeployStrategy, ExistingServiceSpec,
 };
 use crate::output;
+use crate::registry_mirror;
+use crate::service_profiles;
 use crate::state::{HealthState, LocalState, ServiceState};
-use airstack_config::AirstackConfig;
+use airstack_config::{AirstackConfig, ServiceConfig};
 use anyhow::{Context, Result};
+use clap::Args;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::process::Command;
 use tracing::info;
 
+#[derive(Debug, Clone, Args)]
+pub struct DeployArgs {
+    #[arg(help = "Service name")]
+    pub service: String,
+    #[arg(long, help = "Target server")]
+    pub target: Option<String>,
+    #[arg(long, help = "Build latest local code into image before deploy")]
+    pub latest_code: bool,
+    #[arg(
+        long,
+        default_value_t = true,
+        help = "Push image when using --latest-code"
+    )]
+    pub push: bool,
+    #[arg(long, help = "Tag override for --latest-code")]
+    pub tag: Option<String>,
+    #[arg(
+        long,
+        conflicts_with_all = ["latest_code", "tag"],
+        help = "Deploy this image instead of the configured one (config file is not modified)"
+    )]
+    pub image: Option<String>,
+    #[arg(
+        long,
+        help = "Deploy strategy: rolling|recreate (stop old first; brief downtime)|bluegreen|canary",
+        default_value = "rolling"
+    )]
+    pub strategy: String,
+    #[arg(
+        long,
+        help = "Canary observation window in seconds (strategy=canary)",
+        default_value_t = 45
+    )]
+    pub canary_seconds: u64,
+    #[arg(
+        long,
+        help = "Overall healthcheck deadline in seconds (overrides healthcheck.overall_timeout_secs)"
+    )]
+    pub health_timeout: Option<u64>,
+    #[arg(
+        long,
+        overrides_with = "no_wait_healthy",
+        help = "Block on the healthcheck gate after deploy (default)"
+    )]
+    pub wait_healthy: bool,
+    #[arg(
+        long,
+        overrides_with = "wait_healthy",
+        help = "Do not block on the healthcheck gate; health is recorded as unknown"
+    )]
+    pub no_wait_healthy: bool,
+    #[arg(long, help = "Build without the layer cache when using --latest-code")]
+    pub no_cache: bool,
+    #[arg(
+        long,
+        help = "Re-pull base images when building and always pull the image before deploy"
+    )]
+    pub pull: bool,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "PLATFORMS",
+        requires = "latest_code",
+        help = "Build --latest-code for these platforms via docker buildx (several require --push)"
+    )]
+    pub platform: Vec<String>,
+}
+
+impl DeployArgs {
+    // Programmatic redeploys (drift, reconcile, the interactive cli) use the CLI defaults.
+    pub fn for_service(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+            target: None,
+            latest_code: false,
+            push: true,
+            tag: None,
+            image: None,
+            strategy: "rolling".to_string(),
+            canary_seconds: 45,
+            health_timeout: None,
+            wait_healthy: false,
+            no_wait_healthy: false,
+            no_cache: false,
+            pull: false,
+            platform: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct DeployRecord {
     service: String,
@@ -27,6 +122,32 @@ struct DeployRecord {
     detected_by: String,
 }
 
+#[derive(Debug, Serialize)]
+struct DeployPlan {
+    service: String,
+    from_image: Option<String>,
+    to_image: String,
+    ports_changed: bool,
+    env_changed: bool,
+    changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct DeployPlanOutput {
+    requested: String,
+    dry_run: bool,
+    order: Vec<String>,
+    plans: Vec<DeployPlan>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeployEvent<'a> {
+    phase: &'a str,
+    service: &'a str,
+    status: &'a str,
+    ts: u64,
+}
+
 #[derive(Debug, Serialize)]
 struct DeployOutput {
     requested: String,
@@ -36,16 +157,37 @@ struct DeployOutput {
 
 pub async fn run(
     config_path: &str,
-    service_name: &str,
-    _target: Option<String>,
+    args: DeployArgs,
+    dry_run: bool,
     allow_local_deploy: bool,
-    latest_code: bool,
-    push: bool,
-    tag: Option<String>,
-    strategy: String,
-    canary_seconds: u64,
 ) -> Result<()> {
-    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let DeployArgs {
+        service,
+        latest_code,
+        push,
+        tag,
+        image,
+        strategy,
+        canary_seconds,
+        health_timeout,
+        no_wait_healthy,
+        no_cache,
+        pull,
+        platform,
+        ..
+    } = args;
+    let service_name = service.as_str();
+    let wait_healthy = !no_wait_healthy;
+    let build_cache = release::BuildCache { no_cache, pull };
+    let platforms = platform.as_slice();
+    let mut config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    configure_force_pull(build_cache.pull);
+    if service_name == "all" {
+        service_profiles::apply_active(&mut config);
+    }
+    registry_mirror::adopt_config(&config);
+    let _lock = LocalState::acquire_lock(&config.project.name)?;
     let mut state = LocalState::load(&config.project.name)?;
 
     info!("Deploying service: {}", service_name);
@@ -62,7 +204,15 @@ pub async fn run(
     };
 
     let mut image_overrides: HashMap<String, String> = HashMap::new();
-    if latest_code {
+    if let Some(image) = &image {
+        if service_name == "all" {
+            anyhow::bail!("--image requires an explicit single service, not 'all'");
+        }
+        if !services.contains_key(service_name) {
+            anyhow::bail!("Service '{}' not found in configuration", service_name);
+        }
+        image_overrides.insert(service_name.to_string(), image.clone());
+    } else if latest_code {
         if service_name == "all" {
             anyhow::bail!("--latest-code requires an explicit single service, not 'all'");
         }
@@ -75,7 +225,18 @@ pub async fn run(
         let remote_mode = is_remote_deploy_mode(&config);
         let local_docker_ok = local_docker_available();
 
-        if !local_docker_ok && remote_mode {
+        if dry_run {
+            output::line(format!(
+                "🧪 dry-run: would build {}{} from latest code{}",
+                built_image,
+                if platforms.is_empty() {
+                    String::new()
+                } else {
+                    format!(" for {}", platforms.join(","))
+                },
+                if push { " and push it" } else { "" }
+            ));
+        } else if !local_docker_ok && remote_mode {
             if !push {
                 anyhow::bail!(
                     "Local Docker daemon unavailable and deploy mode is remote. --latest-code in remote mode requires --push so remote hosts can pull the built image."
@@ -104,13 +265,25 @@ pub async fn run(
                     update_config: false,
                     remote_build: Some(remote_server),
                     from: release::ReleaseFrom::Build,
+                    context_dir: None,
+                    dockerfile: None,
+                    build_arg: Vec::new(),
+                    no_cache: build_cache.no_cache,
+                    pull: build_cache.pull,
+                    platform: platforms.to_vec(),
                 },
             )
             .await?;
         } else {
             release::preflight_local_docker_available()?;
-            run_cmd("docker", &["build", "-t", &built_image, "."])?;
-            if push {
+            let mut build = release::build_args(&built_image, None, None, &[], build_cache);
+            if !platforms.is_empty() {
+                build = release::buildx_args(build, platforms, push)?;
+                release::preflight_buildx_available()?;
+            }
+            let build_refs = build.iter().map(String::as_str).collect::<Vec<_>>();
+            run_cmd("docker", &build_refs)?;
+            if push && platforms.is_empty() {
                 run_cmd("docker", &["push", &built_image])?;
             }
         }
@@ -127,6 +300,17 @@ pub async fn run(
         image_overrides.insert(service_name.to_string(), override_image);
     }
 
+    if dry_run {
+        return plan_deploy(
+            &config,
+            service_name,
+            order,
+            &image_overrides,
+            allow_local_deploy,
+        )
+        .await;
+    }
+
     output::line(format!("🚀 Deploying request: {}", service_name));
 
     let mut deployed = Vec::new();
@@ -138,7 +322,11 @@ pub async fn run(
             .with_context(|| format!("Service '{}' not found in configuration", deploy_name))?;
         let mut service_override = service.clone();
         if let Some(image) = image_overrides.get(deploy_name) {
-            service_override.image = image.clone();
+            service_override = service_with_image(service, image);
+            service = &service_override;
+        }
+        if health_timeout.is_some() {
+            apply_health_timeout(&mut service_override, health_timeout);
             service = &service_override;
         }
 
@@ -147,36 +335,73 @@ pub async fn run(
             deploy_name, service.image, service.ports
         ));
 
+        emit_deploy_event("preflight", deploy_name, "start");
         let runtime_target = resolve_target(&config, service, allow_local_deploy)?;
         let previous_image = existing_service_image(&runtime_target, deploy_name).await?;
+        emit_deploy_event("preflight", deploy_name, "ok");
 
-        let mut container = deploy_service_with_strategy(
+        // Pull and run both happen inside the runtime deploy, so pull only marks its start.
+        emit_deploy_event("pull", deploy_name, "start");
+        let mut container = match deploy_service_with_strategy(
             &runtime_target,
+            &config.project.name,
             deploy_name,
             service,
             service.healthcheck.as_ref(),
             strategy,
-            canary_seconds,
+            CanaryWindow::seconds(canary_seconds),
         )
         .await
-        .with_context(|| format!("Failed to deploy service {}", deploy_name))?;
-
-        if service.healthcheck.is_some() {
-            if let Err(err) =
-                evaluate_service_health(&runtime_target, deploy_name, service, false, 1, false)
-                    .await
-                    .and_then(|eval| {
-                        if eval.ok {
-                            Ok(())
-                        } else {
-                            anyhow::bail!("{}", eval.detail)
-                        }
-                    })
-            {
+        {
+            Ok(container) => {
+                emit_deploy_event("run", deploy_name, "ok");
+                container
+            }
+            Err(err) => {
+                emit_deploy_event("run", deploy_name, "failed");
+                return Err(err)
+                    .with_context(|| format!("Failed to deploy service {}", deploy_name));
+            }
+        };
+
+        let gate = health_gate(service.healthcheck.is_some(), wait_healthy);
+        if gate == HealthGate::Verify {
+            emit_deploy_event("health", deploy_name, "start");
+            let containers = replica_containers(deploy_name, state.services.get(deploy_name));
+            if let Err(err) = evaluate_replica_health(
+                &runtime_target,
+                deploy_name,
+                &containers,
+                service,
+                1,
+                false,
+            )
+            .await
+            .and_then(|eval| {
+                if eval.ok {
+                    Ok(())
+                } else {
+                    anyhow::bail!("{}", eval.detail)
+                }
+            }) {
                 container.healthy = Some(false);
+                emit_deploy_event("health", deploy_name, "failed");
                 let diag = collect_container_diagnostics(&runtime_target, deploy_name).await;
                 if let Some(prev) = &previous_image {
-                    let _ = rollback_service(&runtime_target, deploy_name, prev, service).await;
+                    emit_deploy_event("rollback", deploy_name, "start");
+                    let rolled_back = rollback_service(
+                        &runtime_target,
+                        &config.project.name,
+                        deploy_name,
+                        prev,
+                        service,
+                    )
+                    .await;
+                    emit_deploy_event(
+                        "rollback",
+                        deploy_name,
+                        if rolled_back.is_ok() { "ok" } else { "failed" },
+                    );
                     output::line(format!(
                         "↩️ rollback target for {} -> image {}",
                         deploy_name, prev
@@ -190,10 +415,20 @@ pub async fn run(
                 });
             }
             container.healthy = Some(true);
+            emit_deploy_event("health", deploy_name, "ok");
         } else {
             container.healthy = None;
+            emit_deploy_event("health", deploy_name, "skipped");
+        }
+        if gate == HealthGate::Skip {
+            output::line(format!(
+                "⚠️ health not verified for {} (--no-wait-healthy)",
+                deploy_name
+            ));
         }
 
+        emit_deploy_event("promote", deploy_name, "ok");
+        let image_size_bytes = image_size_bytes(&runtime_target, &service.image).await;
         output::line(format!(
             "✅ Successfully deployed service: {} ({})",
             deploy_name, container.id
@@ -217,27 +452,40 @@ pub async fn run(
                 image: service.image.clone(),
                 replicas: 1,
                 containers: vec![deploy_name.to_string()],
-                health: map_container_health_text(&container.status),
+                health: if gate == HealthGate::Skip {
+                    HealthState::Unknown
+                } else {
+                    map_container_health_text(&container.status)
+                },
                 last_status: Some(container.status.clone()),
                 last_checked_unix: unix_now(),
                 last_error: None,
-                last_deploy_command: Some(format!("airstack deploy {}", deploy_name)),
+                last_deploy_command: Some(deploy_command(
+                    deploy_name,
+                    image.as_deref().filter(|_| deploy_name == service_name),
+                )),
                 last_deploy_unix: Some(unix_now()),
-                image_origin: Some(if latest_code && push {
+                image_origin: Some(if image.is_some() && deploy_name == service_name {
+                    "cli-override".to_string()
+                } else if latest_code && push {
                     "registry-pushed".to_string()
                 } else if latest_code {
                     "local-build-only".to_string()
                 } else {
                     "config-declared".to_string()
                 }),
+                image_size_bytes,
             },
         );
 
-        if deploy_name == "caddy" && config.edge.is_some() {
-            edge::apply_from_config(&config)
-                .await
-                .with_context(|| "Failed to sync edge config during caddy deploy")?;
-            output::line("✅ edge config reconciled during caddy deploy");
+        if edge::is_edge_service(deploy_name) && config.edge.is_some() {
+            edge::apply_from_config(&config).await.with_context(|| {
+                format!("Failed to sync edge config during {} deploy", deploy_name)
+            })?;
+            output::line(format!(
+                "✅ edge config reconciled during {} deploy",
+                deploy_name
+            ));
         }
     }
 
@@ -249,7 +497,8 @@ pub async fn run(
             order,
             deployed,
         };
-        output::emit_json(&payload)?;
+        // Compact so the whole stream, events and result alike, stays valid NDJSON.
+        output::emit_json_line(&payload)?;
     } else if deployed.is_empty() {
         output::line("No services were deployed.");
     } else {
@@ -259,6 +508,114 @@ pub async fn run(
     Ok(())
 }
 
+async fn plan_deploy(
+    config: &AirstackConfig,
+    requested: &str,
+    order: Vec<String>,
+    image_overrides: &HashMap<String, String>,
+    allow_local_deploy: bool,
+) -> Result<()> {
+    let services = config
+        .services
+        .as_ref()
+        .context("No services defined in configuration")?;
+
+    let mut plans = Vec::new();
+    for deploy_name in &order {
+        let mut service = services
+            .get(deploy_name.as_str())
+            .with_context(|| format!("Service '{}' not found in configuration", deploy_name))?
+            .clone();
+        if let Some(image) = image_overrides.get(deploy_name) {
+            service = service_with_image(&service, image);
+        }
+        let runtime_target = resolve_target(config, &service, allow_local_deploy)?;
+        let from_image = existing_service_image(&runtime_target, deploy_name).await?;
+        let existing = existing_service_spec(&runtime_target, deploy_name).await?;
+        plans.push(build_deploy_plan(
+            deploy_name,
+            &service,
+            from_image,
+            existing.as_ref(),
+        ));
+    }
+
+    if output::is_json() {
+        output::emit_json(&DeployPlanOutput {
+            requested: requested.to_string(),
+            dry_run: true,
+            order,
+            plans,
+        })?;
+        return Ok(());
+    }
+
+    output::line(format!("🧪 Deploy plan (dry-run): {}", requested));
+    for plan in &plans {
+        let mark = if plan.changed { "~" } else { "=" };
+        output::line(format!(
+            "   {} {}: {} -> {}",
+            mark,
+            plan.service,
+            plan.from_image.as_deref().unwrap_or("(not running)"),
+            plan.to_image
+        ));
+        if plan.ports_changed {
+            output::line("       ports changed");
+        }
+        if plan.env_changed {
+            output::line("       env changed");
+        }
+    }
+    output::line("No changes were made.");
+    Ok(())
+}
+
+fn service_with_image(service: &ServiceConfig, image: &str) -> ServiceConfig {
+    let mut overridden = service.clone();
+    overridden.image = image.to_string();
+    overridden
+}
+
+fn deploy_command(name: &str, image_override: Option<&str>) -> String {
+    match image_override {
+        Some(image) => format!("airstack deploy {} --image {}", name, image),
+        None => format!("airstack deploy {}", name),
+    }
+}
+
+fn build_deploy_plan(
+    name: &str,
+    service: &ServiceConfig,
+    from_image: Option<String>,
+    existing: Option<&ExistingServiceSpec>,
+) -> DeployPlan {
+    let (ports_changed, env_changed) = match existing {
+        Some(spec) => {
+            let mut desired_ports = service.ports.clone();
+            desired_ports.sort_unstable();
+            desired_ports.dedup();
+            let env_changed = service.env.as_ref().is_some_and(|env| {
+                env.iter()
+                    .any(|(k, v)| !spec.env.contains(&format!("{}={}", k, v)))
+            });
+            (desired_ports != spec.ports, env_changed)
+        }
+        None => (false, false),
+    };
+    let image_changed = !from_image
+        .as_deref()
+        .is_some_and(|from| registry_mirror::same_image(&service.image, from));
+    DeployPlan {
+        service: name.to_string(),
+        from_image,
+        to_image: service.image.clone(),
+        ports_changed,
+        env_changed,
+        changed: image_changed || ports_changed || env_changed,
+    }
+}
+
 fn is_remote_deploy_mode(config: &AirstackConfig) -> bool {
     if let Some(mode) = config.project.deploy_mode.as_deref() {
         return mode == "remote";
@@ -296,6 +653,21 @@ fn git_sha() -> Result<String> {
     Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HealthGate {
+    Verify,
+    Skip,
+    NotConfigured,
+}
+
+fn health_gate(has_healthcheck: bool, wait_healthy: bool) -> HealthGate {
+    match (has_healthcheck, wait_healthy) {
+        (false, _) => HealthGate::NotConfigured,
+        (true, true) => HealthGate::Verify,
+        (true, false) => HealthGate::Skip,
+    }
+}
+
 fn map_container_health_text(status: &str) -> HealthState {
     let s = status.to_ascii_lowercase();
     if s.contains("up") || s.contains("running") {
@@ -307,9 +679,161 @@ fn map_container_health_text(status: &str) -> HealthState {
     }
 }
 
+fn deploy_event<'a>(phase: &'a str, service: &'a str, status: &'a str) -> DeployEvent<'a> {
+    DeployEvent {
+        phase,
+        service,
+        status,
+        ts: unix_now(),
+    }
+}
+
+// One compact line per event so `--json` consumers can stream progress before the final payload.
+fn emit_deploy_event(phase: &str, service: &str, status: &str) {
+    if output::is_json() {
+        let _ = output::emit_json_line(&deploy_event(phase, service, status));
+    }
+}
+
 fn unix_now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_secs())
         .unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_deploy_plan, deploy_command, deploy_event, health_gate, service_with_image,
+        HealthGate,
+    };
+    use crate::deploy_runtime::ExistingServiceSpec;
+    use airstack_config::{AirstackConfig, ServiceConfig};
+    use std::collections::HashMap;
+
+    fn svc(image: &str) -> ServiceConfig {
+        ServiceConfig {
+            image: image.to_string(),
+            ports: vec![8080],
+            env: Some(HashMap::from([(
+                "RUST_LOG".to_string(),
+                "info".to_string(),
+            )])),
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: None,
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        }
+    }
+
+    #[test]
+    fn image_override_clones_service_without_touching_config_file() {
+        let path = std::env::temp_dir().join(format!(
+            "airstack-deploy-image-override-{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "[project]\nname = \"demo\"\n\n[services.api]\nimage = \"repo/api:v1\"\nports = [8080]\n",
+        )
+        .expect("config write should succeed");
+        let config_path = path.to_string_lossy().to_string();
+        let config = AirstackConfig::load(&config_path).expect("config should load");
+        let original = &config.services.as_ref().expect("services")["api"];
+
+        let overridden = service_with_image(original, "repo/api:hotfix");
+        assert_eq!(overridden.image, "repo/api:hotfix");
+        assert_eq!(overridden.ports, original.ports);
+        assert_eq!(original.image, "repo/api:v1");
+
+        let reloaded = AirstackConfig::load(&config_path).expect("config should reload");
+        assert_eq!(
+            reloaded.services.expect("services")["api"].image,
+            "repo/api:v1"
+        );
+        assert_eq!(
+            deploy_command("api", Some("repo/api:hotfix")),
+            "airstack deploy api --image repo/api:hotfix"
+        );
+        assert_eq!(deploy_command("api", None), "airstack deploy api");
+        std::fs::remove_file(&path).expect("temp config cleanup should succeed");
+    }
+
+    #[test]
+    fn deploy_event_serializes_phase_service_status_and_ts() {
+        let value = serde_json::to_value(deploy_event("health", "api", "ok")).unwrap();
+        assert_eq!(value["phase"], "health");
+        assert_eq!(value["service"], "api");
+        assert_eq!(value["status"], "ok");
+        assert!(value["ts"].as_u64().unwrap() > 0);
+        assert_eq!(value.as_object().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn wait_healthy_flag_maps_to_health_gate() {
+        assert_eq!(health_gate(true, true), HealthGate::Verify);
+        assert_eq!(health_gate(true, false), HealthGate::Skip);
+        assert_eq!(health_gate(false, true), HealthGate::NotConfigured);
+        assert_eq!(health_gate(false, false), HealthGate::NotConfigured);
+    }
+
+    #[test]
+    fn deploy_plan_detects_image_change_against_existing() {
+        let existing = ExistingServiceSpec {
+            ports: vec![8080],
+            env: vec!["PATH=/usr/bin".to_string(), "RUST_LOG=info".to_string()],
+        };
+        let plan = build_deploy_plan(
+            "api",
+            &svc("repo/api:v2"),
+            Some("repo/api:v1".to_string()),
+            Some(&existing),
+        );
+        assert_eq!(plan.from_image.as_deref(), Some("repo/api:v1"));
+        assert_eq!(plan.to_image, "repo/api:v2");
+        assert!(!plan.ports_changed);
+        assert!(!plan.env_changed);
+        assert!(plan.changed);
+    }
+
+    #[test]
+    fn deploy_plan_unchanged_when_image_ports_env_match() {
+        let existing = ExistingServiceSpec {
+            ports: vec![8080],
+            env: vec!["RUST_LOG=info".to_string()],
+        };
+        let plan = build_deploy_plan(
+            "api",
+            &svc("repo/api:v1"),
+            Some("repo/api:v1".to_string()),
+            Some(&existing),
+        );
+        assert!(!plan.changed);
+
+        let moved = ExistingServiceSpec {
+            ports: vec![9090],
+            env: vec!["RUST_LOG=debug".to_string()],
+        };
+        let plan = build_deploy_plan(
+            "api",
+            &svc("repo/api:v1"),
+            Some("repo/api:v1".to_string()),
+            Some(&moved),
+        );
+        assert!(plan.ports_changed);
+        assert!(plan.env_changed);
+        assert!(plan.changed);
+    }
+}
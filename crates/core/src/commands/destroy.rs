@@ -1,10 +1,16 @@
+use crate::commands::scale::parse_replica_index;
+use crate::dependencies::teardown_order;
+use crate::deploy_runtime::{
+    configure_container_runtime, list_container_names, remove_service_container,
+    replica_containers, resolve_target, stop_service_gracefully, stop_timeout_secs, RuntimeTarget,
+};
 use crate::output;
-use crate::state::LocalState;
-use airstack_config::AirstackConfig;
-use airstack_metal::get_provider as get_metal_provider;
+use crate::state::{LocalState, ServiceState};
+use airstack_config::{AirstackConfig, ServerConfig};
+use airstack_metal::{get_provider as get_metal_provider, MetalProvider};
 use anyhow::{Context, Result};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::io::{self, Write};
 use tracing::{info, warn};
 
@@ -14,12 +20,80 @@ struct DestroyOutput {
     destroyed: Vec<String>,
     not_found: Vec<String>,
     failed: Vec<String>,
+    volumes_kept: Vec<String>,
+    volumes_deleted: Vec<String>,
 }
 
-pub async fn run(config_path: &str, _target: Option<String>, force: bool) -> Result<()> {
+#[derive(Debug, Serialize)]
+struct DestroyServiceOutput {
+    service: String,
+    dry_run: bool,
+    removed: Vec<String>,
+    failed: Vec<String>,
+    volumes_deleted: bool,
+}
+
+pub async fn run(
+    config_path: &str,
+    _target: Option<String>,
+    force: bool,
+    services: Vec<String>,
+    dry_run: bool,
+    delete_volumes: bool,
+) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    let _lock = LocalState::acquire_lock(&config.project.name)?;
     let mut state = LocalState::load(&config.project.name)?;
 
+    if !services.is_empty() {
+        let configured = config
+            .services
+            .as_ref()
+            .context("No services defined in configuration")?;
+        let order = teardown_order(configured, &services)?;
+        if order.len() > 1 {
+            output::line(format!("🧭 Teardown order: {}", order.join(" -> ")));
+        }
+        let mut outputs = Vec::new();
+        for service_name in &order {
+            let Some(result) = destroy_service(
+                &config,
+                &mut state,
+                service_name,
+                force,
+                dry_run,
+                delete_volumes,
+            )
+            .await?
+            else {
+                break;
+            };
+            // Stop before removing a dependency whose dependent could not be removed.
+            let failed = !result.failed.is_empty();
+            outputs.push(result);
+            if failed {
+                break;
+            }
+        }
+        if output::is_json() {
+            if outputs.len() == 1 {
+                output::emit_json(&outputs[0])?;
+            } else {
+                output::emit_json(&serde_json::json!({ "services": outputs }))?;
+            }
+        }
+        if let Some(failed) = outputs.iter().find(|o| !o.failed.is_empty()) {
+            anyhow::bail!(
+                "Failed to remove {} container(s) for service '{}': {}",
+                failed.failed.len(),
+                failed.service,
+                failed.failed.join(", ")
+            );
+        }
+        return Ok(());
+    }
+
     info!(
         "Planning destruction of infrastructure for project: {}",
         config.project.name
@@ -27,6 +101,8 @@ pub async fn run(config_path: &str, _target: Option<String>, force: bool) -> Res
     let mut destroyed = Vec::new();
     let mut not_found = Vec::new();
     let mut failed = Vec::new();
+    let mut volumes_kept = Vec::new();
+    let mut volumes_deleted = Vec::new();
 
     if let Some(infra) = &config.infra {
         output::line("⚠️  The following servers will be DESTROYED:");
@@ -36,6 +112,17 @@ pub async fn run(config_path: &str, _target: Option<String>, force: bool) -> Res
                 server.name, server.server_type, server.region
             ));
         }
+        for volume in infra.volumes.iter().flatten() {
+            output::line(format!(
+                "   {} volume {}",
+                if delete_volumes {
+                    "🗑️  DELETE"
+                } else {
+                    "💾 keep (detach)"
+                },
+                volume.name
+            ));
+        }
         output::line("");
 
         if !force {
@@ -51,6 +138,8 @@ pub async fn run(config_path: &str, _target: Option<String>, force: bool) -> Res
             }
         }
 
+        drain_services(&config, &state).await;
+
         for server in &infra.servers {
             info!("🗑️  Destroying server: {}", server.name);
 
@@ -63,11 +152,35 @@ pub async fn run(config_path: &str, _target: Option<String>, force: bool) -> Res
             match metal_provider.list_servers().await {
                 Ok(servers) => {
                     if let Some(found_server) = servers.iter().find(|s| s.name == server.name) {
+                        let volume_ids = detach_server_volumes(
+                            metal_provider.as_ref(),
+                            &config,
+                            server,
+                            &mut volumes_kept,
+                        )
+                        .await;
                         match metal_provider.destroy_server(&found_server.id).await {
                             Ok(_) => {
                                 output::line(format!("✅ Destroyed server: {}", server.name));
                                 destroyed.push(server.name.clone());
                                 state.servers.remove(&server.name);
+                                if delete_volumes {
+                                    for (name, id) in volume_ids {
+                                        match metal_provider.delete_volume(&id).await {
+                                            Ok(()) => {
+                                                output::line(format!(
+                                                    "🗑️  Deleted volume: {}",
+                                                    name
+                                                ));
+                                                volumes_kept.retain(|v| v != &name);
+                                                volumes_deleted.push(name);
+                                            }
+                                            Err(e) => {
+                                                warn!("❌ Failed to delete volume {}: {}", name, e)
+                                            }
+                                        }
+                                    }
+                                }
                             }
                             Err(e) => {
                                 warn!("❌ Failed to destroy server {}: {}", server.name, e);
@@ -99,8 +212,16 @@ pub async fn run(config_path: &str, _target: Option<String>, force: bool) -> Res
             destroyed,
             not_found,
             failed,
+            volumes_kept,
+            volumes_deleted,
         })?;
     } else {
+        if !volumes_kept.is_empty() {
+            output::line(format!(
+                "💾 Kept volumes (detached): {}; pass --delete-volumes to remove them",
+                volumes_kept.join(", ")
+            ));
+        }
         output::line("🧹 Infrastructure destruction completed!");
     }
 
@@ -108,3 +229,196 @@ pub async fn run(config_path: &str, _target: Option<String>, force: bool) -> Res
 
     Ok(())
 }
+
+async fn destroy_service(
+    config: &AirstackConfig,
+    state: &mut LocalState,
+    service_name: &str,
+    force: bool,
+    dry_run: bool,
+    delete_volumes: bool,
+) -> Result<Option<DestroyServiceOutput>> {
+    let service = config
+        .services
+        .as_ref()
+        .and_then(|s| s.get(service_name))
+        .with_context(|| format!("Service '{}' not found in configuration", service_name))?;
+    let target = resolve_target(config, service, false)?;
+    let existing = list_container_names(&target).await?;
+    let containers =
+        service_containers_to_remove(service_name, state.services.get(service_name), &existing);
+
+    output::line(format!(
+        "⚠️  The following containers for service '{}' will be REMOVED:",
+        service_name
+    ));
+    for name in &containers {
+        output::line(format!("   • {}", name));
+    }
+    output::line("");
+
+    if dry_run {
+        output::line("Dry run: no containers were removed.");
+        return Ok(Some(DestroyServiceOutput {
+            service: service_name.to_string(),
+            dry_run,
+            removed: containers,
+            failed: Vec::new(),
+            volumes_deleted: delete_volumes,
+        }));
+    }
+
+    if !force {
+        print!("Are you sure you want to remove this service? (y/N): ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if !input.trim().to_lowercase().starts_with('y') {
+            output::line("Aborted.");
+            return Ok(None);
+        }
+    }
+
+    let mut removed = Vec::new();
+    let mut failed = Vec::new();
+    for name in containers {
+        match remove_service_container(&target, &name, stop_timeout_secs(service), delete_volumes)
+            .await
+        {
+            Ok(()) => {
+                output::line(format!("✅ Removed container: {}", name));
+                removed.push(name);
+            }
+            Err(e) => {
+                warn!("❌ Failed to remove container {}: {}", name, e);
+                failed.push(name);
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        state.services.remove(service_name);
+        state.save()?;
+    }
+
+    if failed.is_empty() {
+        output::line(format!("🧹 Service '{}' removed.", service_name));
+    }
+
+    Ok(Some(DestroyServiceOutput {
+        service: service_name.to_string(),
+        dry_run,
+        removed,
+        failed,
+        volumes_deleted: delete_volumes,
+    }))
+}
+
+fn service_containers_to_remove(
+    service_name: &str,
+    state: Option<&ServiceState>,
+    existing: &[String],
+) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    if let Some(state) = state {
+        names.extend(state.containers.iter().cloned());
+    }
+    for name in existing {
+        if name == service_name || parse_replica_index(service_name, name).is_some() {
+            names.insert(name.clone());
+        }
+    }
+    names.into_iter().collect()
+}
+
+// Detach first so volume data never depends on how the provider treats attached disks on delete.
+async fn detach_server_volumes(
+    provider: &dyn MetalProvider,
+    config: &AirstackConfig,
+    server: &ServerConfig,
+    kept: &mut Vec<String>,
+) -> Vec<(String, String)> {
+    let mut detached = Vec::new();
+    let volumes = config
+        .infra
+        .iter()
+        .flat_map(|infra| infra.volumes.iter().flatten())
+        .filter(|v| v.server.as_deref() == Some(server.name.as_str()));
+    for volume in volumes {
+        match provider.find_volume(&volume.name).await {
+            Ok(Some(found)) => {
+                if found.server_id.is_some() {
+                    if let Err(e) = provider.detach_volume(&found.id).await {
+                        warn!("⚠️  Failed to detach volume {}: {}", volume.name, e);
+                    }
+                }
+                kept.push(volume.name.clone());
+                detached.push((volume.name.clone(), found.id));
+            }
+            Ok(None) => {}
+            Err(e) => warn!("⚠️  Failed to look up volume {}: {}", volume.name, e),
+        }
+    }
+    detached
+}
+
+async fn drain_services(config: &AirstackConfig, state: &LocalState) {
+    let (Some(_), Some(services)) = (&config.infra, &config.services) else {
+        return;
+    };
+    let names = teardown_order(services, &[]).unwrap_or_else(|_| {
+        let mut names = services.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    });
+    for name in &names {
+        let service = &services[name];
+        // Only services on the servers being destroyed need draining; local and fly
+        // targets are skipped.
+        let Ok(RuntimeTarget::Remote(server)) = resolve_target(config, service, false) else {
+            continue;
+        };
+        let server_name = server.name.clone();
+        let target = RuntimeTarget::Remote(server);
+        for container in replica_containers(name, state.services.get(name)) {
+            match stop_service_gracefully(&target, &container, stop_timeout_secs(service)).await {
+                Ok(()) => info!("Drained container '{}' on {}", container, server_name),
+                Err(e) => warn!("⚠️  Failed to drain container '{}': {}", container, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::service_containers_to_remove;
+    use crate::state::{HealthState, ServiceState};
+
+    #[test]
+    fn enumerates_state_containers_and_running_replicas() {
+        let state = ServiceState {
+            image: "repo/api:v1".to_string(),
+            replicas: 3,
+            containers: vec!["api".to_string(), "api-2".to_string(), "api-3".to_string()],
+            health: HealthState::Healthy,
+            last_status: None,
+            last_checked_unix: 0,
+            last_error: None,
+            last_deploy_command: None,
+            last_deploy_unix: None,
+            image_origin: None,
+            image_size_bytes: None,
+        };
+        let existing = vec![
+            "api".to_string(),
+            "api-4".to_string(),
+            "api-worker".to_string(),
+            "db".to_string(),
+        ];
+
+        let names = service_containers_to_remove("api", Some(&state), &existing);
+        assert_eq!(names, vec!["api", "api-2", "api-3", "api-4"]);
+    }
+}
@@ -1,15 +1,190 @@
-use crate::deploy_runtime::{preflight_image_access, resolve_target};
+use crate::commands::edge::{self, CertExpiry};
+use crate::commands::up::ensure_runtime_bootstrap;
+use crate::deploy_runtime::{
+    configure_container_runtime, create_bind_mount_dirs, missing_bind_mount_dirs,
+    preflight_image_access, resolve_target, RuntimeTarget,
+};
 use crate::infra_preflight::{check_ssh_key_path, format_validation_error, resolve_server_request};
 use crate::output;
-use airstack_config::AirstackConfig;
+use crate::ssh_utils::{execute_remote_command, resolve_server_public_ip};
+use crate::state::LocalState;
+use airstack_config::{AirstackConfig, EdgeSiteConfig, ServerConfig};
 use airstack_metal::{get_provider as get_metal_provider, CapacityResolveOptions};
 use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::net::ToSocketAddrs;
 
-pub async fn run(config_path: &str) -> Result<()> {
+#[derive(Debug, Clone, Default, Args)]
+pub struct DoctorArgs {
+    #[arg(
+        long,
+        value_enum,
+        help = "Apply safe remediations for a check category (repeatable): runtime|volume-dirs|ssh-keys|all"
+    )]
+    pub fix: Vec<FixCategory>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FixCategory {
+    Runtime,
+    VolumeDirs,
+    SshKeys,
+    All,
+}
+
+#[derive(Debug, Clone)]
+enum Remediation {
+    BootstrapRuntime(ServerConfig),
+    CreateHostDirs {
+        service: String,
+        target: RuntimeTarget,
+        dirs: Vec<String>,
+    },
+    UploadSshKey(ServerConfig),
+}
+
+#[derive(Debug, Serialize)]
+struct FixRecord {
+    category: FixCategory,
+    target: String,
+    ok: bool,
+    detail: String,
+}
+
+impl Remediation {
+    fn category(&self) -> FixCategory {
+        match self {
+            Remediation::BootstrapRuntime(_) => FixCategory::Runtime,
+            Remediation::CreateHostDirs { .. } => FixCategory::VolumeDirs,
+            Remediation::UploadSshKey(_) => FixCategory::SshKeys,
+        }
+    }
+
+    fn target(&self) -> String {
+        match self {
+            Remediation::BootstrapRuntime(server) | Remediation::UploadSshKey(server) => {
+                server.name.clone()
+            }
+            Remediation::CreateHostDirs { service, .. } => service.clone(),
+        }
+    }
+
+    async fn apply(&self) -> Result<String> {
+        match self {
+            Remediation::BootstrapRuntime(server) => {
+                ensure_runtime_bootstrap(server).await?;
+                Ok("docker installed".to_string())
+            }
+            Remediation::CreateHostDirs { target, dirs, .. } => {
+                create_bind_mount_dirs(target, dirs).await?;
+                Ok(format!("created {}", dirs.join(", ")))
+            }
+            Remediation::UploadSshKey(server) => {
+                let provider = get_metal_provider(&server.provider, HashMap::new())?;
+                let id = provider
+                    .upload_ssh_key(&ssh_key_name(server), &server.ssh_key)
+                    .await?;
+                Ok(format!("uploaded ssh key ({})", id))
+            }
+        }
+    }
+}
+
+fn fix_selected(selected: &[FixCategory], remediation: &Remediation) -> bool {
+    let category = remediation.category();
+    selected
+        .iter()
+        .any(|c| *c == FixCategory::All || *c == category)
+}
+
+fn ssh_key_name(server: &ServerConfig) -> String {
+    format!("{}-key", server.name)
+}
+
+async fn docker_available(server: &ServerConfig) -> Result<bool> {
+    let out = execute_remote_command(
+        server,
+        &[
+            "sh".to_string(),
+            "-lc".to_string(),
+            "command -v docker >/dev/null 2>&1".to_string(),
+        ],
+    )
+    .await?;
+    match out.status.code() {
+        Some(0) => Ok(true),
+        Some(255) => anyhow::bail!("ssh unreachable"),
+        _ => Ok(false),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EdgeDnsCheck {
+    Match,
+    Mismatch { resolved: Vec<String> },
+    NxDomain,
+    Unverified,
+}
+
+fn classify_edge_dns(resolved: &[String], expected_ip: Option<&str>) -> EdgeDnsCheck {
+    if resolved.is_empty() {
+        return EdgeDnsCheck::NxDomain;
+    }
+    match expected_ip {
+        Some(ip) if resolved.iter().any(|r| r == ip) => EdgeDnsCheck::Match,
+        Some(_) => EdgeDnsCheck::Mismatch {
+            resolved: resolved.to_vec(),
+        },
+        None => EdgeDnsCheck::Unverified,
+    }
+}
+
+fn resolve_host_ips(host: &str) -> Vec<String> {
+    let mut ips = (host, 443)
+        .to_socket_addrs()
+        .map(|addrs| addrs.map(|a| a.ip().to_string()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    ips.sort();
+    ips.dedup();
+    ips
+}
+
+fn edge_site_server<'a>(
+    config: &'a AirstackConfig,
+    site: &EdgeSiteConfig,
+) -> Option<&'a ServerConfig> {
+    let servers = &config.infra.as_ref()?.servers;
+    let target = config
+        .services
+        .as_ref()
+        .and_then(|s| s.get(&site.upstream_service))
+        .and_then(|svc| svc.target_server.as_deref());
+    match target {
+        Some(name) => servers.iter().find(|s| s.name == name),
+        None => servers.first(),
+    }
+}
+
+async fn expected_edge_ip(state: &LocalState, server: &ServerConfig) -> Option<String> {
+    if let Some(ip) = state
+        .servers
+        .get(&server.name)
+        .and_then(|s| s.public_ip.clone())
+    {
+        return Some(ip);
+    }
+    resolve_server_public_ip(server).await.ok()
+}
+
+pub async fn run(config_path: &str, args: DoctorArgs, dry_run: bool) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
     let mut issues = Vec::new();
     let mut warnings = Vec::new();
+    let mut fixable: Vec<(String, Remediation)> = Vec::new();
 
     if config.infra.is_some() {
         if config.project.deploy_mode.as_deref().unwrap_or("remote") == "local" {
@@ -19,15 +194,54 @@ pub async fn run(config_path: &str) -> Result<()> {
 
     if let Some(infra) = &config.infra {
         for server in &infra.servers {
-            if let Err(e) = check_ssh_key_path(server) {
-                issues.push(e.to_string());
+            let key_ok = match check_ssh_key_path(server) {
+                Ok(()) => true,
+                Err(e) => {
+                    issues.push(e.to_string());
+                    false
+                }
+            };
+            let provider = match get_metal_provider(&server.provider, HashMap::new()) {
+                Ok(provider) => provider,
+                Err(e) => {
+                    issues.push(format!(
+                        "infra '{}': provider '{}' init failed (credential/token check): {}",
+                        server.name, server.provider, e
+                    ));
+                    continue;
+                }
+            };
+            if key_ok {
+                match provider
+                    .ssh_key_registered(&ssh_key_name(server), &server.ssh_key)
+                    .await
+                {
+                    Ok(Some(false)) => fixable.push((
+                        format!(
+                            "infra '{}': ssh key '{}' is not uploaded to provider '{}'",
+                            server.name, server.ssh_key, server.provider
+                        ),
+                        Remediation::UploadSshKey(server.clone()),
+                    )),
+                    Ok(_) => {}
+                    Err(e) => warnings.push(format!(
+                        "infra '{}': ssh key lookup failed: {}",
+                        server.name, e
+                    )),
+                }
             }
-            if let Err(e) = get_metal_provider(&server.provider, HashMap::new()) {
-                issues.push(format!(
-                    "infra '{}': provider '{}' init failed (credential/token check): {}",
-                    server.name, server.provider, e
-                ));
-                continue;
+            if provider.capabilities().supports_direct_ssh {
+                match docker_available(server).await {
+                    Ok(true) => {}
+                    Ok(false) => fixable.push((
+                        format!("infra '{}': docker is not installed", server.name),
+                        Remediation::BootstrapRuntime(server.clone()),
+                    )),
+                    Err(e) => warnings.push(format!(
+                        "infra '{}': runtime check skipped: {}",
+                        server.name, e
+                    )),
+                }
             }
             match resolve_server_request(
                 server,
@@ -74,12 +288,37 @@ pub async fn run(config_path: &str) -> Result<()> {
             }
             match resolve_target(&config, svc, false) {
                 Ok(target) => {
-                    if let Err(e) = preflight_image_access(&target, &svc.image).await {
+                    if let Err(e) = preflight_image_access(
+                        &target,
+                        &svc.image,
+                        svc.verify_digest.unwrap_or(false),
+                    )
+                    .await
+                    {
                         issues.push(format!(
                             "service '{}': image preflight failed for '{}': {}",
                             name, svc.image, e
                         ));
                     }
+                    match missing_bind_mount_dirs(&target, svc).await {
+                        Ok(dirs) if !dirs.is_empty() => fixable.push((
+                            format!(
+                                "service '{}': volume host dirs missing: {}",
+                                name,
+                                dirs.join(", ")
+                            ),
+                            Remediation::CreateHostDirs {
+                                service: name.clone(),
+                                target: target.clone(),
+                                dirs,
+                            },
+                        )),
+                        Ok(_) => {}
+                        Err(e) => warnings.push(format!(
+                            "service '{}': volume host dir check skipped: {}",
+                            name, e
+                        )),
+                    }
                 }
                 Err(e) => issues.push(format!(
                     "service '{}': target resolution failed: {}",
@@ -90,29 +329,115 @@ pub async fn run(config_path: &str) -> Result<()> {
     }
 
     if let Some(edge) = &config.edge {
-        if edge.provider == "caddy" {
-            for site in &edge.sites {
-                if site.tls_email.is_none() {
-                    issues.push(format!(
-                        "edge site '{}' has no tls_email set (cert ops visibility reduced)",
+        for site in &edge.sites {
+            if edge.site_provider(site) == Some("caddy") && site.tls_email.is_none() {
+                issues.push(format!(
+                    "edge site '{}' has no tls_email set (cert ops visibility reduced)",
+                    site.host
+                ));
+            }
+        }
+        let state = LocalState::load(&config.project.name).unwrap_or_default();
+        let mut ip_cache: HashMap<String, Option<String>> = HashMap::new();
+        for site in &edge.sites {
+            let expected = match edge_site_server(&config, site) {
+                Some(server) => {
+                    if !ip_cache.contains_key(&server.name) {
+                        let ip = expected_edge_ip(&state, server).await;
+                        ip_cache.insert(server.name.clone(), ip);
+                    }
+                    ip_cache.get(&server.name).cloned().flatten()
+                }
+                None => None,
+            };
+            let dns = classify_edge_dns(&resolve_host_ips(&site.host), expected.as_deref());
+            if dns != EdgeDnsCheck::NxDomain {
+                match edge::cert_expiry(&site.host).await {
+                    Ok(CertExpiry::Valid { .. }) => {}
+                    Ok(CertExpiry::ExpiringSoon { days_left }) => warnings.push(format!(
+                        "edge site '{}' TLS certificate expires in {} day(s)",
+                        site.host, days_left
+                    )),
+                    Ok(CertExpiry::Expired) => issues.push(format!(
+                        "edge site '{}' TLS certificate has expired",
                         site.host
-                    ));
+                    )),
+                    Err(e) => warnings.push(format!(
+                        "edge site '{}': TLS certificate expiry not verified ({})",
+                        site.host, e
+                    )),
                 }
             }
+            match dns {
+                EdgeDnsCheck::Match => {}
+                EdgeDnsCheck::Mismatch { resolved } => warnings.push(format!(
+                    "edge site '{}' resolves to {} but the edge server IP is {}",
+                    site.host,
+                    resolved.join(", "),
+                    expected.as_deref().unwrap_or("?")
+                )),
+                EdgeDnsCheck::NxDomain => warnings.push(format!(
+                    "edge site '{}' does not resolve (NXDOMAIN or no A/AAAA records)",
+                    site.host
+                )),
+                EdgeDnsCheck::Unverified => warnings.push(format!(
+                    "edge site '{}': server IP unknown; DNS target not verified",
+                    site.host
+                )),
+            }
         }
     }
 
+    let mut fixes = Vec::new();
+    for (issue, remediation) in fixable {
+        if !fix_selected(&args.fix, &remediation) {
+            issues.push(issue);
+            continue;
+        }
+        if dry_run {
+            output::line(format!("🔧 would fix: {}", issue));
+            issues.push(issue);
+            continue;
+        }
+        let record = match remediation.apply().await {
+            Ok(detail) => {
+                output::line(format!("🔧 fixed: {} ({})", issue, detail));
+                FixRecord {
+                    category: remediation.category(),
+                    target: remediation.target(),
+                    ok: true,
+                    detail,
+                }
+            }
+            Err(e) => {
+                output::line(format!("❌ fix failed: {}: {}", issue, e));
+                issues.push(issue);
+                FixRecord {
+                    category: remediation.category(),
+                    target: remediation.target(),
+                    ok: false,
+                    detail: e.to_string(),
+                }
+            }
+        };
+        fixes.push(record);
+    }
+
     if output::is_json() {
         output::emit_json(&serde_json::json!({
             "ok": issues.is_empty(),
             "issues": issues,
             "warnings": warnings,
+            "fixes": fixes,
         }))?;
         return Ok(());
     }
 
     if issues.is_empty() {
         output::line("✅ doctor: no blocking issues found");
+        for w in warnings.iter().filter(|w| w.starts_with("edge site")) {
+            output::line(format!("⚠️ {}", w));
+        }
         return Ok(());
     }
 
@@ -128,3 +453,79 @@ pub async fn run(config_path: &str) -> Result<()> {
     }
     anyhow::bail!("doctor checks failed")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_edge_dns, fix_selected, EdgeDnsCheck, FixCategory, Remediation};
+    use crate::deploy_runtime::RuntimeTarget;
+    use airstack_config::ServerConfig;
+
+    fn server() -> ServerConfig {
+        ServerConfig {
+            name: "web-1".to_string(),
+            provider: "hetzner".to_string(),
+            region: "hel1".to_string(),
+            server_type: "cpx21".to_string(),
+            ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
+            floating_ip: None,
+            public_net: None,
+            ssh_user: None,
+            ssh_port: None,
+            labels: None,
+            user_data: None,
+        }
+    }
+
+    #[test]
+    fn fixes_dispatch_only_to_selected_categories() {
+        let remediations = [
+            Remediation::BootstrapRuntime(server()),
+            Remediation::CreateHostDirs {
+                service: "api".to_string(),
+                target: RuntimeTarget::Local,
+                dirs: vec!["/srv/api".to_string()],
+            },
+            Remediation::UploadSshKey(server()),
+        ];
+        let categories: Vec<_> = remediations.iter().map(Remediation::category).collect();
+        assert_eq!(
+            categories,
+            vec![
+                FixCategory::Runtime,
+                FixCategory::VolumeDirs,
+                FixCategory::SshKeys
+            ]
+        );
+
+        for remediation in &remediations {
+            assert!(!fix_selected(&[], remediation));
+            assert!(fix_selected(&[FixCategory::All], remediation));
+            assert!(fix_selected(&[remediation.category()], remediation));
+        }
+        assert!(!fix_selected(&[FixCategory::SshKeys], &remediations[0]));
+        assert!(fix_selected(
+            &[FixCategory::Runtime, FixCategory::VolumeDirs],
+            &remediations[1]
+        ));
+    }
+
+    #[test]
+    fn edge_dns_classification() {
+        let resolved = vec!["203.0.113.10".to_string()];
+        assert_eq!(
+            classify_edge_dns(&resolved, Some("203.0.113.10")),
+            EdgeDnsCheck::Match
+        );
+        assert_eq!(
+            classify_edge_dns(&resolved, Some("198.51.100.7")),
+            EdgeDnsCheck::Mismatch {
+                resolved: resolved.clone()
+            }
+        );
+        assert_eq!(
+            classify_edge_dns(&[], Some("198.51.100.7")),
+            EdgeDnsCheck::NxDomain
+        );
+        assert_eq!(classify_edge_dns(&resolved, None), EdgeDnsCheck::Unverified);
+    }
+}
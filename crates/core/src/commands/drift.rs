@@ -1,10 +1,23 @@
+use crate::commands::deploy;
 use crate::output;
+use crate::registry_mirror;
 use crate::ssh_utils::execute_remote_command;
+use crate::state::LocalState;
 use airstack_config::{AirstackConfig, ServerConfig, ServiceConfig};
 use anyhow::{Context, Result};
+use clap::Args;
 use serde::Serialize;
 use tokio::process::Command;
 
+#[derive(Debug, Clone, Args)]
+pub struct DriftArgs {
+    #[arg(
+        long,
+        help = "Redeploy services whose running image differs from config and prune stale cache entries"
+    )]
+    pub fix: bool,
+}
+
 #[derive(Debug, Serialize)]
 struct ImageDriftRecord {
     service: String,
@@ -18,10 +31,24 @@ struct ImageDriftRecord {
 struct DriftOutput {
     project: String,
     image_drift: Vec<ImageDriftRecord>,
+    fix: Option<DriftFixOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct DriftFixOutput {
+    dry_run: bool,
+    redeploy: Vec<String>,
+    prune_cache: Vec<String>,
 }
 
-pub async fn run(config_path: &str) -> Result<()> {
+pub async fn run(
+    config_path: &str,
+    args: DriftArgs,
+    dry_run: bool,
+    allow_local_deploy: bool,
+) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    registry_mirror::adopt_config(&config);
     let services = config
         .services
         .as_ref()
@@ -39,13 +66,30 @@ pub async fn run(config_path: &str) -> Result<()> {
             desired_image: svc.image.clone(),
             running_image: running.clone(),
             target_server: target.map(|s| s.name.clone()),
-            matches: running.as_deref() == Some(svc.image.as_str()),
+            matches: running
+                .as_deref()
+                .is_some_and(|running| registry_mirror::same_image(&svc.image, running)),
         });
     }
 
+    let fix = if args.fix {
+        let redeploy = drifted_services(&records);
+        let prune_cache = LocalState::load(&config.project.name)?
+            .detect_drift(&config)
+            .extra_services_in_cache;
+        Some(DriftFixOutput {
+            dry_run,
+            redeploy,
+            prune_cache,
+        })
+    } else {
+        None
+    };
+
     let out = DriftOutput {
-        project: config.project.name,
+        project: config.project.name.clone(),
         image_drift: records,
+        fix,
     };
 
     if output::is_json() {
@@ -69,6 +113,67 @@ pub async fn run(config_path: &str) -> Result<()> {
         }
     }
 
+    if let Some(fix) = &out.fix {
+        apply_fix(config_path, &config, fix, allow_local_deploy).await?;
+    }
+
+    Ok(())
+}
+
+fn drifted_services(records: &[ImageDriftRecord]) -> Vec<String> {
+    let mut drifted = records
+        .iter()
+        .filter(|r| r.running_image.is_some() && !r.matches)
+        .map(|r| r.service.clone())
+        .collect::<Vec<_>>();
+    drifted.sort();
+    drifted
+}
+
+async fn apply_fix(
+    config_path: &str,
+    config: &AirstackConfig,
+    fix: &DriftFixOutput,
+    allow_local_deploy: bool,
+) -> Result<()> {
+    if fix.dry_run {
+        for service in &fix.redeploy {
+            output::line(format!("🧪 dry-run: would redeploy {}", service));
+        }
+        for service in &fix.prune_cache {
+            output::line(format!(
+                "🧪 dry-run: would prune cached state for {}",
+                service
+            ));
+        }
+        return Ok(());
+    }
+
+    for service in &fix.redeploy {
+        output::line(format!("🔧 redeploying drifted service {}", service));
+        deploy::run(
+            config_path,
+            deploy::DeployArgs::for_service(service),
+            false,
+            allow_local_deploy,
+        )
+        .await
+        .with_context(|| format!("Failed to redeploy drifted service '{}'", service))?;
+    }
+
+    if !fix.prune_cache.is_empty() {
+        let _lock = LocalState::acquire_lock(&config.project.name)?;
+        let mut state = LocalState::load(&config.project.name)?;
+        for service in &fix.prune_cache {
+            state.services.remove(service);
+            output::line(format!("🧹 pruned cached state for {}", service));
+        }
+        state.save()?;
+    }
+
+    if fix.redeploy.is_empty() && fix.prune_cache.is_empty() {
+        output::line("✅ no drift to fix");
+    }
     Ok(())
 }
 
@@ -128,3 +233,30 @@ async fn inspect_running_image(server: &ServerConfig, service: &str) -> Result<O
         Ok(Some(img))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{drifted_services, ImageDriftRecord};
+
+    fn record(service: &str, desired: &str, running: Option<&str>) -> ImageDriftRecord {
+        ImageDriftRecord {
+            service: service.to_string(),
+            desired_image: desired.to_string(),
+            running_image: running.map(str::to_string),
+            target_server: Some("web-1".to_string()),
+            matches: running == Some(desired),
+        }
+    }
+
+    #[test]
+    fn drifted_services_are_those_running_a_different_image() {
+        let records = vec![
+            record("worker", "repo/worker:v2", Some("repo/worker:v1")),
+            record("api", "repo/api:v3", Some("repo/api:v3")),
+            record("cache", "redis:7", None),
+            record("db", "postgres:16", Some("postgres:15")),
+        ];
+        assert_eq!(drifted_services(&records), vec!["db", "worker"]);
+        assert!(drifted_services(&[]).is_empty());
+    }
+}
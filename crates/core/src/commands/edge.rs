@@ -1,12 +1,25 @@
 use crate::output;
 use crate::ssh_utils::{execute_remote_command, lookup_provider_server};
-use airstack_config::{AirstackConfig, EdgeSiteConfig};
+use airstack_config::{AirstackConfig, EdgeConfig, EdgeSiteConfig, ServerConfig};
 use anyhow::{Context, Result};
 use clap::Subcommand;
 use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::net::ToSocketAddrs;
 use tokio::process::Command;
 
+const NGINX_CONF_PATH: &str = "/etc/nginx/conf.d/airstack.conf";
+const CERT_PROBE_TIMEOUT_SECS: u64 = 15;
+pub const CERT_EXPIRY_WARN_DAYS: u64 = 14;
+
+// Sends `percent`% of a service's edge traffic to its canary candidate container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanaryRoute {
+    pub service: String,
+    pub candidate: String,
+    pub percent: u8,
+}
+
 #[derive(Debug, Clone, Subcommand)]
 pub enum EdgeCommands {
     #[command(about = "Preview reverse-proxy config and actions")]
@@ -19,17 +32,27 @@ pub enum EdgeCommands {
     Status,
     #[command(about = "Diagnose TLS/ACME edge issues with remediation hints")]
     Diagnose,
+    #[command(about = "Reload the running edge proxy without re-rendering config")]
+    Reload,
 }
 
 #[derive(Debug, Serialize)]
-struct EdgeStatus {
+struct EdgeReloadRecord {
     provider: String,
+    container: String,
+    target: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EdgeStatus {
+    provider: Option<String>,
     sites: Vec<EdgeSiteStatus>,
 }
 
 #[derive(Debug, Serialize)]
 struct EdgeSiteStatus {
     host: String,
+    provider: Option<String>,
     dns_resolved: bool,
     upstream_service: String,
     upstream_port: u16,
@@ -45,19 +68,63 @@ pub async fn run(config_path: &str, command: EdgeCommands) -> Result<()> {
         EdgeCommands::Status => status(edge),
         EdgeCommands::Diagnose => diagnose(&config).await,
         EdgeCommands::Apply => apply_from_config(&config).await,
+        EdgeCommands::Reload => reload(&config).await,
     }
 }
 
-fn plan(edge: &airstack_config::EdgeConfig) -> Result<()> {
-    let rendered = render_caddyfile(&edge.sites);
+fn plan(edge: &EdgeConfig) -> Result<()> {
     output::line("🧩 Edge Plan");
-    output::line(format!("Provider: {}", edge.provider));
-    output::line("Generated Caddyfile:");
-    output::line(rendered);
+    for (provider, sites) in group_sites_by_provider(edge)? {
+        let rendered = render_provider_config(&provider, &sites)?;
+        output::line(format!("Provider: {}", provider));
+        output::line("Generated config:");
+        output::line(rendered);
+    }
     Ok(())
 }
 
-fn validate(edge: &airstack_config::EdgeConfig) -> Result<()> {
+pub fn upstream_services(edge: &EdgeConfig) -> Vec<String> {
+    edge.sites
+        .iter()
+        .map(|site| site.upstream_service.clone())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+pub fn is_edge_service(name: &str) -> bool {
+    matches!(name, "caddy" | "nginx")
+}
+
+fn group_sites_by_provider(edge: &EdgeConfig) -> Result<BTreeMap<String, Vec<EdgeSiteConfig>>> {
+    let mut groups: BTreeMap<String, Vec<EdgeSiteConfig>> = BTreeMap::new();
+    for site in &edge.sites {
+        let provider = edge.site_provider(site).with_context(|| {
+            format!(
+                "Edge site '{}' has no provider (set edge.provider or a per-site provider)",
+                site.host
+            )
+        })?;
+        groups
+            .entry(provider.to_string())
+            .or_default()
+            .push(site.clone());
+    }
+    Ok(groups)
+}
+
+fn render_provider_config(provider: &str, sites: &[EdgeSiteConfig]) -> Result<String> {
+    match provider {
+        "caddy" => Ok(render_caddyfile(sites, None)),
+        "nginx" => {
+            let tls_hosts = sites.iter().map(|site| site.host.clone()).collect();
+            Ok(render_nginx_conf(sites, None, &tls_hosts))
+        }
+        other => anyhow::bail!("Unsupported edge provider '{}'", other),
+    }
+}
+
+fn validate(edge: &EdgeConfig) -> Result<()> {
     let mut failures = Vec::new();
     for site in &edge.sites {
         let ok = (site.host.as_str(), 443)
@@ -81,12 +148,13 @@ fn validate(edge: &airstack_config::EdgeConfig) -> Result<()> {
     anyhow::bail!("edge validation failed")
 }
 
-fn status(edge: &airstack_config::EdgeConfig) -> Result<()> {
+fn status(edge: &EdgeConfig) -> Result<()> {
     let sites = edge
         .sites
         .iter()
         .map(|s| EdgeSiteStatus {
             host: s.host.clone(),
+            provider: edge.site_provider(s).map(str::to_string),
             dns_resolved: (s.host.as_str(), 443)
                 .to_socket_addrs()
                 .map(|mut a| a.next().is_some())
@@ -105,11 +173,18 @@ fn status(edge: &airstack_config::EdgeConfig) -> Result<()> {
         output::emit_json(&payload)?;
     } else {
         output::line("🌐 Edge Status");
-        output::line(format!("Provider: {}", payload.provider));
+        output::line(format!(
+            "Provider: {}",
+            payload.provider.as_deref().unwrap_or("per-site")
+        ));
         for s in payload.sites {
             output::line(format!(
-                "- {} -> {}:{} (dns={})",
-                s.host, s.upstream_service, s.upstream_port, s.dns_resolved
+                "- {} -> {}:{} (provider={}, dns={})",
+                s.host,
+                s.upstream_service,
+                s.upstream_port,
+                s.provider.as_deref().unwrap_or("?"),
+                s.dns_resolved
             ));
         }
     }
@@ -129,6 +204,7 @@ struct EdgeDiagnosis {
 
 #[derive(Debug, Serialize)]
 struct EdgeApplySummary {
+    provider: String,
     changed: bool,
     restart_required: bool,
     target: String,
@@ -247,9 +323,56 @@ async fn diagnose(config: &AirstackConfig) -> Result<()> {
 }
 
 pub async fn apply_from_config(config: &AirstackConfig) -> Result<()> {
+    let summaries = apply_sites(config, None).await?;
+
+    if output::is_json() {
+        if summaries.len() == 1 {
+            output::emit_json(&summaries[0])?;
+        } else {
+            output::emit_json(&serde_json::json!({ "applies": summaries }))?;
+        }
+        return Ok(());
+    }
+
+    for summary in &summaries {
+        output::line(format!(
+            "✅ edge apply ({}): changed={} restart={} target={}",
+            summary.provider, summary.changed, summary.restart_required, summary.target
+        ));
+        if !summary.diff_preview.is_empty() {
+            output::line("ℹ️ edge apply: diff preview");
+            for line in &summary.diff_preview {
+                output::line(format!("   {}", line));
+            }
+        }
+        if !summary.changed {
+            output::line("ℹ️ edge apply: no changes needed");
+        } else {
+            output::line("ℹ️ edge apply: config updated");
+        }
+    }
+    Ok(())
+}
+
+// Applies edge config with `route` splicing in a weighted canary upstream, or the
+// plain config when `route` is None. Output is left to the caller.
+pub async fn apply_canary_route(
+    config: &AirstackConfig,
+    route: Option<&CanaryRoute>,
+) -> Result<()> {
+    apply_sites(config, route).await.map(|_| ())
+}
+
+async fn apply_sites(
+    config: &AirstackConfig,
+    canary: Option<&CanaryRoute>,
+) -> Result<Vec<EdgeApplySummary>> {
     let edge = config.edge.as_ref().context("No [edge] config defined")?;
-    if edge.provider != "caddy" {
-        anyhow::bail!("Only edge.provider='caddy' is currently supported");
+    let groups = group_sites_by_provider(edge)?;
+    for provider in groups.keys() {
+        if provider != "caddy" && provider != "nginx" {
+            anyhow::bail!("Unsupported edge provider '{}'", provider);
+        }
     }
 
     let infra = config
@@ -261,7 +384,24 @@ pub async fn apply_from_config(config: &AirstackConfig) -> Result<()> {
         .first()
         .context("Edge apply requires at least one server")?;
 
-    let caddyfile = render_caddyfile(&edge.sites);
+    let mut summaries = Vec::new();
+    for (provider, sites) in &groups {
+        let summary = match provider.as_str() {
+            "caddy" => apply_caddy(server, sites, canary).await?,
+            "nginx" => apply_nginx(server, sites, canary).await?,
+            other => anyhow::bail!("Unsupported edge provider '{}'", other),
+        };
+        summaries.push(summary);
+    }
+    Ok(summaries)
+}
+
+async fn apply_caddy(
+    server: &ServerConfig,
+    sites: &[EdgeSiteConfig],
+    canary: Option<&CanaryRoute>,
+) -> Result<EdgeApplySummary> {
+    let caddyfile = render_caddyfile(sites, canary);
     let upload_script = format!(
         r#"set -e
 tmp="$(mktemp /tmp/airstack-caddy.XXXXXX)"
@@ -363,26 +503,272 @@ exit 1
     }
 
     let stdout = String::from_utf8_lossy(&out.stdout);
-    let summary = parse_apply_summary(&stdout);
-    if output::is_json() {
-        output::emit_json(&summary)?;
-        return Ok(());
+    Ok(parse_apply_summary("caddy", &stdout))
+}
+
+async fn apply_nginx(
+    server: &ServerConfig,
+    sites: &[EdgeSiteConfig],
+    canary: Option<&CanaryRoute>,
+) -> Result<EdgeApplySummary> {
+    let tls_hosts = nginx_tls_hosts(server, sites).await?;
+    let conf = render_nginx_conf(sites, canary, &tls_hosts);
+    let upload_script = format!(
+        r#"set -e
+tmp="$(mktemp /tmp/airstack-nginx.XXXXXX)"
+wrapper="$(mktemp /tmp/airstack-nginx-main.XXXXXX)"
+cat > "$tmp" <<'NGINX'
+{conf}
+NGINX
+
+container_id=""
+if command -v docker >/dev/null 2>&1; then
+  container_id="$(docker ps -qf 'name=^/nginx$' | head -n1 || true)"
+fi
+
+if [ -n "$container_id" ]; then
+  current="$(mktemp /tmp/airstack-nginx-current.XXXXXX)"
+  docker exec nginx cat {path} > "$current" 2>/dev/null || true
+  if cmp -s "$tmp" "$current"; then
+    echo "changed=0 restart=0 target=container:{path}"
+    rm -f "$tmp" "$current" "$wrapper"
+    exit 0
+  fi
+  diff -u "$current" "$tmp" 2>/dev/null | head -n 200 || true
+  printf 'events {{}}\nhttp {{\n  include /etc/nginx/mime.types;\n  include /tmp/airstack-candidate.conf;\n}}\n' > "$wrapper"
+  docker cp "$tmp" nginx:/tmp/airstack-candidate.conf
+  docker cp "$wrapper" nginx:/tmp/airstack-candidate-main.conf
+  if ! docker exec nginx nginx -t -c /tmp/airstack-candidate-main.conf; then
+    echo "candidate nginx config failed validation; {path} left unchanged" >&2
+    rm -f "$tmp" "$current" "$wrapper"
+    exit 1
+  fi
+  docker cp "$tmp" nginx:{path}
+  if ! docker exec nginx nginx -t || ! docker exec nginx nginx -s reload; then
+    if [ -s "$current" ]; then
+      docker cp "$current" nginx:{path}
+    else
+      docker exec nginx rm -f {path}
+    fi
+    docker exec nginx nginx -s reload || true
+    echo "nginx rejected new config; restored previous {path}" >&2
+    rm -f "$tmp" "$current" "$wrapper"
+    exit 1
+  fi
+  echo "changed=1 restart=1 target=container:{path}"
+  rm -f "$tmp" "$current" "$wrapper"
+  exit 0
+fi
+
+if command -v nginx >/dev/null 2>&1; then
+  if [ -f {path} ] && cmp -s "$tmp" {path}; then
+    echo "changed=0 restart=0 target={path}"
+    rm -f "$tmp" "$wrapper"
+    exit 0
+  fi
+  mkdir -p "$(dirname {path})"
+  printf 'events {{}}\nhttp {{\n  include /etc/nginx/mime.types;\n  include %s;\n}}\n' "$tmp" > "$wrapper"
+  if ! nginx -t -c "$wrapper"; then
+    echo "candidate nginx config failed validation; {path} left unchanged" >&2
+    rm -f "$tmp" "$wrapper"
+    exit 1
+  fi
+  backup=""
+  if [ -f {path} ]; then
+    diff -u {path} "$tmp" 2>/dev/null | head -n 200 || true
+    backup="$(mktemp /tmp/airstack-nginx-backup.XXXXXX)"
+    cp {path} "$backup"
+  fi
+  cp "$tmp" {path}
+  reload_nginx() {{
+    if command -v systemctl >/dev/null 2>&1; then
+      systemctl reload nginx
+    else
+      nginx -s reload
+    fi
+  }}
+  if ! nginx -t || ! reload_nginx; then
+    if [ -n "$backup" ]; then
+      cp "$backup" {path}
+    else
+      rm -f {path}
+    fi
+    reload_nginx || true
+    echo "nginx rejected new config; restored previous {path}" >&2
+    rm -f "$tmp" "$wrapper" "$backup"
+    exit 1
+  fi
+  echo "changed=1 restart=1 target={path}"
+  rm -f "$tmp" "$wrapper" "$backup"
+  exit 0
+fi
+
+echo "failed to apply nginx config (no nginx container or host nginx found)" >&2
+rm -f "$tmp" "$wrapper"
+exit 1
+"#,
+        conf = conf,
+        path = NGINX_CONF_PATH
+    );
+
+    let out = execute_remote_command(
+        server,
+        &["sh".to_string(), "-lc".to_string(), upload_script],
+    )
+    .await?;
+
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        anyhow::bail!("Edge apply (nginx) failed: {}", stderr.trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    Ok(parse_apply_summary("nginx", &stdout))
+}
+
+// Hosts whose letsencrypt cert and key already exist where nginx will read them.
+async fn nginx_tls_hosts(
+    server: &ServerConfig,
+    sites: &[EdgeSiteConfig],
+) -> Result<BTreeSet<String>> {
+    let out = execute_remote_command(
+        server,
+        &[
+            "sh".to_string(),
+            "-lc".to_string(),
+            nginx_cert_probe_script(sites),
+        ],
+    )
+    .await?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        anyhow::bail!("Edge apply (nginx) cert check failed: {}", stderr.trim());
+    }
+    Ok(parse_tls_hosts(
+        &String::from_utf8_lossy(&out.stdout),
+        sites,
+    ))
+}
+
+fn nginx_cert_probe_script(sites: &[EdgeSiteConfig]) -> String {
+    let hosts = sites
+        .iter()
+        .map(|site| shell_quote(&site.host))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        r#"runner=""
+if command -v docker >/dev/null 2>&1 && [ -n "$(docker ps -qf 'name=^/nginx$' | head -n1)" ]; then
+  runner="docker exec nginx"
+fi
+for host in {hosts}; do
+  if $runner test -f "/etc/letsencrypt/live/$host/fullchain.pem" && $runner test -f "/etc/letsencrypt/live/$host/privkey.pem"; then
+    echo "$host"
+  fi
+done"#
+    )
+}
+
+fn parse_tls_hosts(stdout: &str, sites: &[EdgeSiteConfig]) -> BTreeSet<String> {
+    let found = stdout.lines().map(str::trim).collect::<BTreeSet<_>>();
+    sites
+        .iter()
+        .filter(|site| found.contains(site.host.as_str()))
+        .map(|site| site.host.clone())
+        .collect()
+}
+
+fn shell_quote(value: &str) -> String {
+    if value.is_empty() {
+        return "''".to_string();
+    }
+    if value
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || "-_./:".contains(ch))
+    {
+        return value.to_string();
     }
+    format!("'{}'", value.replace('\'', "'\"'\"'"))
+}
+
+fn reload_command(provider: &str) -> Result<(&'static str, Vec<&'static str>)> {
+    match provider {
+        "caddy" => Ok((
+            "caddy",
+            vec![
+                "caddy",
+                "reload",
+                "--config",
+                "/etc/caddy/Caddyfile",
+                "--adapter",
+                "caddyfile",
+            ],
+        )),
+        "nginx" => Ok(("nginx", vec!["nginx", "-s", "reload"])),
+        other => anyhow::bail!("Unsupported edge provider '{}' for reload", other),
+    }
+}
+
+fn reload_script(provider: &str) -> Result<(String, String)> {
+    let (container, command) = reload_command(provider)?;
+    let script = format!(
+        r#"running="$(docker inspect -f '{{{{.State.Running}}}}' {container} 2>/dev/null || true)"
+if [ "$running" != "true" ]; then
+  echo "edge container '{container}' is not running" >&2
+  exit 3
+fi
+docker exec {container} {command}"#,
+        container = container,
+        command = command.join(" ")
+    );
+    Ok((container.to_string(), script))
+}
+
+async fn reload(config: &AirstackConfig) -> Result<()> {
+    let edge = config.edge.as_ref().context("No [edge] config defined")?;
+    let groups = group_sites_by_provider(edge)?;
+    let server = config
+        .infra
+        .as_ref()
+        .and_then(|infra| infra.servers.first());
 
-    output::line(format!(
-        "✅ edge apply: changed={} restart={} target={}",
-        summary.changed, summary.restart_required, summary.target
-    ));
-    if !summary.diff_preview.is_empty() {
-        output::line("ℹ️ edge apply: diff preview");
-        for line in &summary.diff_preview {
-            output::line(format!("   {}", line));
+    let mut records = Vec::new();
+    for provider in groups.keys() {
+        let (container, script) = reload_script(provider)?;
+        let out = match server {
+            Some(server) => {
+                execute_remote_command(server, &["sh".to_string(), "-lc".to_string(), script])
+                    .await?
+            }
+            None => Command::new("sh")
+                .arg("-lc")
+                .arg(&script)
+                .output()
+                .await
+                .context("Failed to execute local edge reload")?,
+        };
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            anyhow::bail!("Edge reload ({}) failed: {}", provider, stderr.trim());
         }
+        records.push(EdgeReloadRecord {
+            provider: provider.clone(),
+            container,
+            target: server
+                .map(|s| s.name.clone())
+                .unwrap_or_else(|| "local".to_string()),
+        });
     }
-    if !summary.changed {
-        output::line("ℹ️ edge apply: no changes needed");
-    } else {
-        output::line("ℹ️ edge apply: config updated");
+
+    if output::is_json() {
+        output::emit_json(&serde_json::json!({ "reloads": records }))?;
+        return Ok(());
+    }
+    for record in &records {
+        output::line(format!(
+            "🔁 edge reload ({}): container={} target={}",
+            record.provider, record.container, record.target
+        ));
     }
     Ok(())
 }
@@ -394,7 +780,31 @@ async fn resolve_edge_server_ip(config: &AirstackConfig) -> Option<String> {
     provider_server.public_ip
 }
 
-fn render_caddyfile(sites: &[EdgeSiteConfig]) -> String {
+// Upstream addresses with relative weights (summing to 100) for a site.
+fn site_upstreams(site: &EdgeSiteConfig, canary: Option<&CanaryRoute>) -> Vec<(String, u32)> {
+    let stable = format!("{}:{}", site.upstream_service, site.upstream_port);
+    match canary.filter(|c| c.service == site.upstream_service) {
+        Some(c) => {
+            let percent = u32::from(c.percent.clamp(1, 99));
+            vec![
+                (stable, 100 - percent),
+                (format!("{}:{}", c.candidate, site.upstream_port), percent),
+            ]
+        }
+        None => vec![(stable, 100)],
+    }
+}
+
+fn nginx_upstream_name(site: &EdgeSiteConfig) -> String {
+    let host = site
+        .host
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    format!("airstack_canary_{}", host)
+}
+
+fn render_caddyfile(sites: &[EdgeSiteConfig], canary: Option<&CanaryRoute>) -> String {
     let mut lines = Vec::new();
     for site in sites {
         lines.push(format!("{} {{", site.host));
@@ -405,16 +815,210 @@ fn render_caddyfile(sites: &[EdgeSiteConfig]) -> String {
         if let Some(email) = &site.tls_email {
             lines.push(format!("  tls {}", email));
         }
+        let upstreams = site_upstreams(site, canary);
+        if upstreams.len() > 1 {
+            let addrs = upstreams
+                .iter()
+                .map(|(a, _)| a.as_str())
+                .collect::<Vec<_>>();
+            let weights = upstreams
+                .iter()
+                .map(|(_, w)| w.to_string())
+                .collect::<Vec<_>>();
+            lines.push(format!("  reverse_proxy {} {{", addrs.join(" ")));
+            lines.push(format!(
+                "    lb_policy weighted_round_robin {}",
+                weights.join(" ")
+            ));
+            lines.push("  }".to_string());
+        } else {
+            lines.push(format!(
+                "  reverse_proxy {}:{}",
+                site.upstream_service, site.upstream_port
+            ));
+        }
+        lines.push("}".to_string());
+        lines.push(String::new());
+    }
+    lines.join("\n")
+}
+
+// Sites without a cert in `tls_hosts` get an HTTP-only server so `nginx -t` passes
+// before the first certbot run; the 443 block appears once the cert exists.
+fn render_nginx_conf(
+    sites: &[EdgeSiteConfig],
+    canary: Option<&CanaryRoute>,
+    tls_hosts: &BTreeSet<String>,
+) -> String {
+    let mut lines = Vec::new();
+    for site in sites {
+        let upstreams = site_upstreams(site, canary);
+        let upstream = if upstreams.len() > 1 {
+            let name = nginx_upstream_name(site);
+            lines.push(format!("upstream {} {{", name));
+            for (addr, weight) in &upstreams {
+                lines.push(format!("  server {} weight={};", addr, weight));
+            }
+            lines.push("}".to_string());
+            lines.push(String::new());
+            format!("http://{}", name)
+        } else {
+            format!("http://{}:{}", site.upstream_service, site.upstream_port)
+        };
+        let proxy_block = |lines: &mut Vec<String>| {
+            lines.push("  location / {".to_string());
+            lines.push(format!("    proxy_pass {};", upstream));
+            lines.push("    proxy_set_header Host $host;".to_string());
+            lines.push("    proxy_set_header X-Real-IP $remote_addr;".to_string());
+            lines.push(
+                "    proxy_set_header X-Forwarded-For $proxy_add_x_forwarded_for;".to_string(),
+            );
+            lines.push("    proxy_set_header X-Forwarded-Proto $scheme;".to_string());
+            lines.push("  }".to_string());
+        };
+
+        lines.push("server {".to_string());
+        lines.push("  listen 80;".to_string());
+        lines.push(format!("  server_name {};", site.host));
+        if let Some(email) = &site.tls_email {
+            lines.push(format!(
+                "  # certbot --webroot -w /var/www/certbot -m {}",
+                email
+            ));
+            lines.push("  location /.well-known/acme-challenge/ {".to_string());
+            lines.push("    root /var/www/certbot;".to_string());
+            lines.push("  }".to_string());
+        }
+        let has_cert = tls_hosts.contains(&site.host);
+        if has_cert && site.redirect_http.unwrap_or(true) {
+            lines.push("  location / {".to_string());
+            lines.push("    return 301 https://$host$request_uri;".to_string());
+            lines.push("  }".to_string());
+        } else {
+            proxy_block(&mut lines);
+        }
+        lines.push("}".to_string());
+        lines.push(String::new());
+
+        if !has_cert {
+            continue;
+        }
+        lines.push("server {".to_string());
+        lines.push("  listen 443 ssl;".to_string());
+        lines.push(format!("  server_name {};", site.host));
         lines.push(format!(
-            "  reverse_proxy {}:{}",
-            site.upstream_service, site.upstream_port
+            "  ssl_certificate /etc/letsencrypt/live/{}/fullchain.pem;",
+            site.host
         ));
+        lines.push(format!(
+            "  ssl_certificate_key /etc/letsencrypt/live/{}/privkey.pem;",
+            site.host
+        ));
+        proxy_block(&mut lines);
         lines.push("}".to_string());
         lines.push(String::new());
     }
     lines.join("\n")
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertExpiry {
+    Valid { days_left: u64 },
+    ExpiringSoon { days_left: u64 },
+    Expired,
+}
+
+pub fn classify_cert_expiry(not_after_unix: u64, now_unix: u64) -> CertExpiry {
+    if not_after_unix <= now_unix {
+        return CertExpiry::Expired;
+    }
+    let days_left = (not_after_unix - now_unix) / 86_400;
+    if days_left < CERT_EXPIRY_WARN_DAYS {
+        CertExpiry::ExpiringSoon { days_left }
+    } else {
+        CertExpiry::Valid { days_left }
+    }
+}
+
+pub async fn cert_expiry(host: &str) -> Result<CertExpiry> {
+    let not_after = fetch_cert_not_after(host).await?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok(classify_cert_expiry(not_after, now))
+}
+
+fn cert_expiry_script(host: &str) -> String {
+    format!(
+        "echo | openssl s_client -connect {} -servername {} 2>/dev/null | openssl x509 -noout -enddate",
+        shell_quote(&format!("{host}:443")),
+        shell_quote(host)
+    )
+}
+
+// Reads the served leaf certificate's notAfter as unix seconds.
+pub async fn fetch_cert_not_after(host: &str) -> Result<u64> {
+    // s_client can hang on a host that accepts TCP but never finishes the handshake.
+    let out = tokio::time::timeout(
+        std::time::Duration::from_secs(CERT_PROBE_TIMEOUT_SECS),
+        Command::new("sh")
+            .arg("-lc")
+            .arg(cert_expiry_script(host))
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "timed out after {}s reading TLS certificate for '{}'",
+            CERT_PROBE_TIMEOUT_SECS, host
+        )
+    })?
+    .context("Failed to run openssl for certificate expiry")?;
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    if !out.status.success() {
+        anyhow::bail!("could not read TLS certificate for '{}'", host);
+    }
+    parse_not_after(&stdout).with_context(|| {
+        format!(
+            "unrecognized certificate expiry for '{}': {}",
+            host,
+            stdout.trim()
+        )
+    })
+}
+
+// Parses openssl's `notAfter=Jan  2 03:04:05 2027 GMT` into unix seconds.
+pub fn parse_not_after(raw: &str) -> Option<u64> {
+    let value = raw.trim().strip_prefix("notAfter=").unwrap_or(raw.trim());
+    let parts = value.split_whitespace().collect::<Vec<_>>();
+    let [month, day, time, year, ..] = parts.as_slice() else {
+        return None;
+    };
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|m| m == month)? as i64
+        + 1;
+    let day = day.parse::<i64>().ok()?;
+    let year = year.parse::<i64>().ok()?;
+    let mut hms = time.split(':').map(|v| v.parse::<i64>().ok());
+    let (h, m, s) = (hms.next()??, hms.next()??, hms.next()??);
+
+    // Days since 1970-01-01 for a proleptic Gregorian date.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    u64::try_from(days * 86_400 + h * 3_600 + m * 60 + s).ok()
+}
+
 async fn query_dns_ttl(host: &str) -> Option<u32> {
     let out = Command::new("sh")
         .arg("-lc")
@@ -472,7 +1076,7 @@ fn derive_apex(host: &str) -> String {
     }
 }
 
-fn parse_apply_summary(stdout: &str) -> EdgeApplySummary {
+fn parse_apply_summary(provider: &str, stdout: &str) -> EdgeApplySummary {
     let mut changed = false;
     let mut restart_required = false;
     let mut target = "unknown".to_string();
@@ -513,9 +1117,205 @@ fn parse_apply_summary(stdout: &str) -> EdgeApplySummary {
     }
 
     EdgeApplySummary {
+        provider: provider.to_string(),
         changed,
         restart_required,
         target,
         diff_preview,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cert_expiry_script, classify_cert_expiry, group_sites_by_provider, nginx_cert_probe_script,
+        parse_not_after, parse_tls_hosts, reload_command, reload_script, render_caddyfile,
+        render_nginx_conf, site_upstreams, upstream_services, CanaryRoute, CertExpiry,
+    };
+    use airstack_config::{EdgeConfig, EdgeSiteConfig};
+    use std::collections::BTreeSet;
+
+    fn site(host: &str, provider: Option<&str>) -> EdgeSiteConfig {
+        EdgeSiteConfig {
+            host: host.to_string(),
+            upstream_service: "api".to_string(),
+            upstream_port: 8080,
+            tls_email: None,
+            redirect_http: None,
+            provider: provider.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn cert_expiry_classification_by_days_left() {
+        let now = parse_not_after("notAfter=Oct 16 00:00:00 2026 GMT").unwrap();
+        assert_eq!(now, 1_792_108_800);
+        assert_eq!(
+            parse_not_after("notAfter=Jan  2 03:04:05 2027 GMT"),
+            Some(1_798_859_045)
+        );
+        assert_eq!(parse_not_after("garbage"), None);
+
+        let day = 86_400;
+        assert_eq!(
+            classify_cert_expiry(now + 60 * day, now),
+            CertExpiry::Valid { days_left: 60 }
+        );
+        assert_eq!(
+            classify_cert_expiry(now + 14 * day, now),
+            CertExpiry::Valid { days_left: 14 }
+        );
+        assert_eq!(
+            classify_cert_expiry(now + 13 * day + 5, now),
+            CertExpiry::ExpiringSoon { days_left: 13 }
+        );
+        assert_eq!(classify_cert_expiry(now, now), CertExpiry::Expired);
+        assert_eq!(classify_cert_expiry(now - day, now), CertExpiry::Expired);
+    }
+
+    #[test]
+    fn cert_expiry_script_quotes_the_host() {
+        assert!(cert_expiry_script("api.example.com").starts_with(
+            "echo | openssl s_client -connect api.example.com:443 -servername api.example.com "
+        ));
+        let script = cert_expiry_script("x.com; rm -rf /");
+        assert!(script.contains("-connect 'x.com; rm -rf /:443' -servername 'x.com; rm -rf /' "));
+    }
+
+    #[test]
+    fn groups_sites_by_effective_provider() {
+        let edge = EdgeConfig {
+            provider: Some("caddy".to_string()),
+            sites: vec![
+                site("a.example.com", None),
+                site("b.example.com", Some("nginx")),
+                site("c.example.com", Some("caddy")),
+            ],
+        };
+        let groups = group_sites_by_provider(&edge).expect("grouping succeeds");
+        let hosts = |p: &str| {
+            groups[p]
+                .iter()
+                .map(|s| s.host.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(groups.len(), 2);
+        assert_eq!(hosts("caddy"), vec!["a.example.com", "c.example.com"]);
+        assert_eq!(hosts("nginx"), vec!["b.example.com"]);
+    }
+
+    #[test]
+    fn grouping_fails_when_site_has_no_provider() {
+        let edge = EdgeConfig {
+            provider: None,
+            sites: vec![site("a.example.com", None)],
+        };
+        let err = group_sites_by_provider(&edge).expect_err("missing provider should fail");
+        assert!(err.to_string().contains("a.example.com"));
+    }
+
+    #[test]
+    fn upstream_services_are_deduplicated_across_sites() {
+        let mut api_www = site("www.example.com", None);
+        api_www.upstream_service = "api".to_string();
+        let mut admin = site("admin.example.com", Some("nginx"));
+        admin.upstream_service = "admin".to_string();
+        let mut api_root = site("example.com", None);
+        api_root.upstream_service = "api".to_string();
+        let edge = EdgeConfig {
+            provider: Some("caddy".to_string()),
+            sites: vec![api_www, admin, api_root],
+        };
+        assert_eq!(upstream_services(&edge), vec!["admin", "api"]);
+        assert!(upstream_services(&EdgeConfig {
+            provider: None,
+            sites: Vec::new()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn reload_command_matches_provider() {
+        let (container, caddy) = reload_command("caddy").expect("caddy supported");
+        assert_eq!(container, "caddy");
+        assert_eq!(&caddy[..2], &["caddy", "reload"]);
+        let (container, nginx) = reload_command("nginx").expect("nginx supported");
+        assert_eq!(container, "nginx");
+        assert_eq!(nginx, vec!["nginx", "-s", "reload"]);
+        assert!(reload_command("traefik").is_err());
+
+        let (_, script) = reload_script("nginx").expect("nginx script");
+        assert!(script.contains("docker inspect -f '{{.State.Running}}' nginx"));
+        assert!(
+            script.ends_with("docker exec nginx nginx -s reload"),
+            "{script}"
+        );
+    }
+
+    #[test]
+    fn nginx_config_proxies_site_to_upstream() {
+        let mut sample = site("api.example.com", Some("nginx"));
+        sample.tls_email = Some("ops@example.com".to_string());
+        let tls_hosts = BTreeSet::from(["api.example.com".to_string()]);
+        let conf = render_nginx_conf(&[sample], None, &tls_hosts);
+        assert!(conf.contains("server_name api.example.com;"), "{conf}");
+        assert!(conf.contains("proxy_pass http://api:8080;"), "{conf}");
+        assert!(conf.contains("return 301 https://$host$request_uri;"));
+        assert!(conf.contains("location /.well-known/acme-challenge/"));
+        assert!(conf.contains("listen 443 ssl;"));
+    }
+
+    #[test]
+    fn nginx_config_serves_http_only_until_cert_exists() {
+        let mut fresh = site("new.example.com", Some("nginx"));
+        fresh.tls_email = Some("ops@example.com".to_string());
+        let conf = render_nginx_conf(&[fresh.clone()], None, &BTreeSet::new());
+        assert!(!conf.contains("listen 443"), "{conf}");
+        assert!(!conf.contains("return 301"), "{conf}");
+        assert!(conf.contains("proxy_pass http://api:8080;"), "{conf}");
+        assert!(conf.contains("location /.well-known/acme-challenge/"));
+
+        let script = nginx_cert_probe_script(std::slice::from_ref(&fresh));
+        assert!(
+            script.contains("for host in new.example.com; do"),
+            "{script}"
+        );
+        let tls_hosts = parse_tls_hosts("new.example.com\nunrelated.example.com\n", &[fresh]);
+        assert_eq!(tls_hosts, BTreeSet::from(["new.example.com".to_string()]));
+    }
+
+    #[test]
+    fn canary_route_weights_candidate_upstream() {
+        let sample = site("api.example.com", Some("caddy"));
+        let route = CanaryRoute {
+            service: "api".to_string(),
+            candidate: "api__candidate".to_string(),
+            percent: 10,
+        };
+        assert_eq!(
+            site_upstreams(&sample, Some(&route)),
+            vec![
+                ("api:8080".to_string(), 90),
+                ("api__candidate:8080".to_string(), 10)
+            ]
+        );
+        assert_eq!(
+            site_upstreams(&sample, None),
+            vec![("api:8080".to_string(), 100)]
+        );
+
+        let caddy = render_caddyfile(std::slice::from_ref(&sample), Some(&route));
+        assert!(
+            caddy.contains("reverse_proxy api:8080 api__candidate:8080 {"),
+            "{caddy}"
+        );
+        assert!(caddy.contains("lb_policy weighted_round_robin 90 10"));
+        let tls_hosts = BTreeSet::from([sample.host.clone()]);
+        let nginx = render_nginx_conf(&[sample], Some(&route), &tls_hosts);
+        assert!(
+            nginx.contains("server api__candidate:8080 weight=10;"),
+            "{nginx}"
+        );
+        assert!(nginx.contains("proxy_pass http://airstack_canary_api_example_com;"));
+    }
+}
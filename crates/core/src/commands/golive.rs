@@ -1,8 +1,11 @@
-use crate::commands::edge;
+use crate::commands::edge::{self, CertExpiry};
 use crate::deploy_runtime::{
-    evaluate_service_health, preflight_image_access, preflight_runtime_abi, resolve_target,
+    configure_container_runtime, evaluate_replica_health, preflight_image_access,
+    preflight_runtime_abi, replica_containers, resolve_target,
 };
 use crate::output;
+use crate::registry_mirror;
+use crate::state::LocalState;
 use airstack_config::AirstackConfig;
 use airstack_metal::get_provider as get_metal_provider;
 use anyhow::{Context, Result};
@@ -10,21 +13,66 @@ use clap::Args;
 use serde::Serialize;
 use std::collections::{BTreeMap, HashMap};
 
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Blocker,
+    Warning,
+    Info,
+}
+
 #[derive(Debug, Serialize)]
 struct ReadinessCheck {
-    name: String,
-    ok: bool,
+    id: String,
+    title: String,
+    severity: Severity,
+    passed: bool,
     detail: String,
     raw: Option<Vec<serde_json::Value>>,
 }
 
+impl ReadinessCheck {
+    fn new(
+        id: impl Into<String>,
+        title: &str,
+        severity: Severity,
+        passed: bool,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            title: title.to_string(),
+            severity,
+            passed,
+            detail: detail.into(),
+            raw: None,
+        }
+    }
+
+    fn blocks_go_live(&self) -> bool {
+        self.severity == Severity::Blocker && !self.passed
+    }
+}
+
 #[derive(Debug, Serialize)]
-struct GoLiveOutput {
+struct GoLiveReport {
     project: String,
-    ok: bool,
+    overall_passed: bool,
     checks: Vec<ReadinessCheck>,
 }
 
+impl GoLiveReport {
+    // Only blockers gate go-live; failed warnings and info checks are reported but do not fail CI.
+    fn new(project: String, checks: Vec<ReadinessCheck>) -> Self {
+        let overall_passed = !checks.iter().any(ReadinessCheck::blocks_go_live);
+        Self {
+            project,
+            overall_passed,
+            checks,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Args)]
 pub struct GoLiveArgs {
     #[arg(
@@ -42,27 +90,30 @@ pub struct GoLiveArgs {
 
 pub async fn run(config_path: &str, args: GoLiveArgs) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    registry_mirror::adopt_config(&config);
     let mut checks = Vec::new();
 
     infra_up_check(&config, &mut checks).await;
     image_pull_checks(&config, &mut checks).await;
     edge_checks(config_path, &config, &mut checks).await;
+    edge_cert_checks(&config, &mut checks).await;
     app_health_checks(&config, &args, &mut checks).await;
 
-    let ok = checks.iter().all(|c| c.ok);
-    let payload = GoLiveOutput {
-        project: config.project.name.clone(),
-        ok,
-        checks,
-    };
+    let report = GoLiveReport::new(config.project.name.clone(), checks);
 
     if output::is_json() {
-        output::emit_json(&payload)?;
+        output::emit_json(&report)?;
     } else {
         output::line("🚀 Go-Live Readiness");
-        for c in &payload.checks {
-            let mark = if c.ok { "✅" } else { "❌" };
-            output::line(format!("{} {}: {}", mark, c.name, c.detail));
+        for c in &report.checks {
+            let mark = match (c.passed, c.severity) {
+                (true, _) => "✅",
+                (false, Severity::Blocker) => "❌",
+                (false, Severity::Warning) => "⚠️",
+                (false, Severity::Info) => "ℹ️",
+            };
+            output::line(format!("{} {} [{}]: {}", mark, c.title, c.id, c.detail));
             if args.explain {
                 if let Some(raw) = &c.raw {
                     for line in raw {
@@ -73,20 +124,27 @@ pub async fn run(config_path: &str, args: GoLiveArgs) -> Result<()> {
         }
     }
 
-    if !payload.ok {
-        anyhow::bail!("Go-live readiness failed");
+    if !report.overall_passed {
+        let blockers = report
+            .checks
+            .iter()
+            .filter(|c| c.blocks_go_live())
+            .map(|c| c.id.as_str())
+            .collect::<Vec<_>>();
+        anyhow::bail!("Go-live readiness failed: blockers {}", blockers.join(", "));
     }
     Ok(())
 }
 
 async fn infra_up_check(config: &AirstackConfig, checks: &mut Vec<ReadinessCheck>) {
     let Some(infra) = &config.infra else {
-        checks.push(ReadinessCheck {
-            name: "infra-up".to_string(),
-            ok: false,
-            detail: "no infra.servers configured".to_string(),
-            raw: None,
-        });
+        checks.push(ReadinessCheck::new(
+            "infra-up",
+            "Infrastructure up",
+            Severity::Blocker,
+            false,
+            "no infra.servers configured",
+        ));
         return;
     };
 
@@ -101,22 +159,24 @@ async fn infra_up_check(config: &AirstackConfig, checks: &mut Vec<ReadinessCheck
                     by_provider.insert(server.provider.clone(), servers);
                 }
                 Err(e) => {
-                    checks.push(ReadinessCheck {
-                        name: format!("infra-up:{}", server.provider),
-                        ok: false,
-                        detail: format!("provider list failed: {}", e),
-                        raw: None,
-                    });
+                    checks.push(ReadinessCheck::new(
+                        format!("infra-up:{}", server.provider),
+                        "Infrastructure up",
+                        Severity::Blocker,
+                        false,
+                        format!("provider list failed: {}", e),
+                    ));
                     return;
                 }
             },
             Err(e) => {
-                checks.push(ReadinessCheck {
-                    name: format!("infra-up:{}", server.provider),
-                    ok: false,
-                    detail: format!("provider init failed: {}", e),
-                    raw: None,
-                });
+                checks.push(ReadinessCheck::new(
+                    format!("infra-up:{}", server.provider),
+                    "Infrastructure up",
+                    Severity::Blocker,
+                    false,
+                    format!("provider init failed: {}", e),
+                ));
                 return;
             }
         }
@@ -136,26 +196,28 @@ async fn infra_up_check(config: &AirstackConfig, checks: &mut Vec<ReadinessCheck
         }
     }
 
-    checks.push(ReadinessCheck {
-        name: "infra-up".to_string(),
-        ok: failures.is_empty(),
-        detail: if failures.is_empty() {
+    checks.push(ReadinessCheck::new(
+        "infra-up",
+        "Infrastructure up",
+        Severity::Blocker,
+        failures.is_empty(),
+        if failures.is_empty() {
             "all configured servers are running".to_string()
         } else {
             format!("non-ready servers: {}", failures.join(", "))
         },
-        raw: None,
-    });
+    ));
 }
 
 async fn image_pull_checks(config: &AirstackConfig, checks: &mut Vec<ReadinessCheck>) {
     let Some(services) = &config.services else {
-        checks.push(ReadinessCheck {
-            name: "image-pull".to_string(),
-            ok: false,
-            detail: "no services configured".to_string(),
-            raw: None,
-        });
+        checks.push(ReadinessCheck::new(
+            "image-pull",
+            "Images pullable",
+            Severity::Blocker,
+            false,
+            "no services configured",
+        ));
         return;
     };
 
@@ -163,7 +225,10 @@ async fn image_pull_checks(config: &AirstackConfig, checks: &mut Vec<ReadinessCh
     for (name, svc) in services {
         match resolve_target(config, svc, false) {
             Ok(target) => {
-                if let Err(e) = preflight_image_access(&target, &svc.image).await {
+                if let Err(e) =
+                    preflight_image_access(&target, &svc.image, svc.verify_digest.unwrap_or(false))
+                        .await
+                {
                     failures.push(format!("{}: {}", name, e));
                 } else if let Err(e) = preflight_runtime_abi(&target, name, svc).await {
                     failures.push(format!("{}: {}", name, e));
@@ -175,42 +240,90 @@ async fn image_pull_checks(config: &AirstackConfig, checks: &mut Vec<ReadinessCh
         }
     }
 
-    checks.push(ReadinessCheck {
-        name: "image-pull".to_string(),
-        ok: failures.is_empty(),
-        detail: if failures.is_empty() {
+    checks.push(ReadinessCheck::new(
+        "image-pull",
+        "Images pullable",
+        Severity::Blocker,
+        failures.is_empty(),
+        if failures.is_empty() {
             "all service images are pullable/available".to_string()
         } else {
             failures.join(" | ")
         },
-        raw: None,
-    });
+    ));
 }
 
 async fn edge_checks(config_path: &str, config: &AirstackConfig, checks: &mut Vec<ReadinessCheck>) {
     if config.edge.is_none() {
-        checks.push(ReadinessCheck {
-            name: "edge-dns-tls".to_string(),
-            ok: true,
-            detail: "edge config not present (skipped)".to_string(),
-            raw: None,
-        });
+        checks.push(ReadinessCheck::new(
+            "edge-dns-tls",
+            "Edge DNS/TLS",
+            Severity::Info,
+            true,
+            "edge config not present (skipped)",
+        ));
         return;
     }
     match edge::run(config_path, edge::EdgeCommands::Diagnose).await {
-        Ok(_) => checks.push(ReadinessCheck {
-            name: "edge-dns-tls".to_string(),
-            ok: true,
-            detail: "edge DNS/TLS checks passed".to_string(),
-            raw: None,
-        }),
-        Err(e) => checks.push(ReadinessCheck {
-            name: "edge-dns-tls".to_string(),
-            ok: false,
-            detail: format!("{}", e),
-            raw: None,
-        }),
+        Ok(_) => checks.push(ReadinessCheck::new(
+            "edge-dns-tls",
+            "Edge DNS/TLS",
+            Severity::Warning,
+            true,
+            "edge DNS/TLS checks passed",
+        )),
+        Err(e) => checks.push(ReadinessCheck::new(
+            "edge-dns-tls",
+            "Edge DNS/TLS",
+            Severity::Warning,
+            false,
+            format!("{}", e),
+        )),
+    }
+}
+
+// Expired certs block go-live; certs inside the warning window only warn.
+async fn edge_cert_checks(config: &AirstackConfig, checks: &mut Vec<ReadinessCheck>) {
+    let Some(edge_cfg) = &config.edge else {
+        return;
+    };
+    let mut expired = Vec::new();
+    let mut expiring = Vec::new();
+    for site in &edge_cfg.sites {
+        match edge::cert_expiry(&site.host).await {
+            Ok(CertExpiry::Valid { .. }) => {}
+            Ok(CertExpiry::ExpiringSoon { days_left }) => {
+                expiring.push(format!("{}: expires in {} day(s)", site.host, days_left))
+            }
+            Ok(CertExpiry::Expired) => expired.push(format!("{}: expired", site.host)),
+            Err(e) => expiring.push(format!("{}: {}", site.host, e)),
+        }
     }
+    checks.push(ReadinessCheck::new(
+        "edge-tls-expiry",
+        "Edge TLS certificates not expired",
+        Severity::Blocker,
+        expired.is_empty(),
+        if expired.is_empty() {
+            "no expired edge certificates".to_string()
+        } else {
+            expired.join(" | ")
+        },
+    ));
+    checks.push(ReadinessCheck::new(
+        "edge-tls-renewal",
+        "Edge TLS certificates renew in time",
+        Severity::Warning,
+        expiring.is_empty(),
+        if expiring.is_empty() {
+            format!(
+                "all edge certificates valid for at least {} days",
+                edge::CERT_EXPIRY_WARN_DAYS
+            )
+        } else {
+            expiring.join(" | ")
+        },
+    ));
 }
 
 async fn app_health_checks(
@@ -219,15 +332,17 @@ async fn app_health_checks(
     checks: &mut Vec<ReadinessCheck>,
 ) {
     let Some(services) = &config.services else {
-        checks.push(ReadinessCheck {
-            name: "app-health".to_string(),
-            ok: false,
-            detail: "no services configured".to_string(),
-            raw: None,
-        });
+        checks.push(ReadinessCheck::new(
+            "app-health",
+            "App health",
+            Severity::Blocker,
+            false,
+            "no services configured",
+        ));
         return;
     };
 
+    let state = LocalState::load(&config.project.name).ok();
     let mut failures = Vec::new();
     let mut passed = Vec::new();
     let mut missing_hc = BTreeMap::new();
@@ -238,11 +353,11 @@ async fn app_health_checks(
             continue;
         };
         match resolve_target(config, svc, false) {
-            Ok(target) => match evaluate_service_health(
+            Ok(target) => match evaluate_replica_health(
                 &target,
                 name,
+                &replica_containers(name, state.as_ref().and_then(|s| s.services.get(name))),
                 svc,
-                args.explain,
                 args.stability,
                 args.stability > 1,
             )
@@ -254,18 +369,16 @@ async fn app_health_checks(
                     } else {
                         failures.push(format!("{}: {}", name, eval.detail));
                     }
-                    if args.explain {
-                        for rec in eval.records {
-                            raw.push(serde_json::json!({
-                                "service": name,
-                                "profile": rec.profile,
-                                "command": rec.command,
-                                "ok": rec.ok,
-                                "exit_code": rec.exit_code,
-                                "stdout": rec.stdout,
-                                "stderr": rec.stderr
-                            }));
-                        }
+                    for rec in eval.records {
+                        raw.push(serde_json::json!({
+                            "service": name,
+                            "profile": rec.profile,
+                            "command": rec.command,
+                            "ok": rec.ok,
+                            "exit_code": rec.exit_code,
+                            "stdout": rec.stdout,
+                            "stderr": rec.stderr
+                        }));
                     }
                 }
                 Err(e) => failures.push(format!("{}: {}", name, e)),
@@ -274,9 +387,31 @@ async fn app_health_checks(
         }
     }
 
+    checks.push(healthcheck_coverage_check(&missing_hc));
     checks.push(build_app_health_check(passed, missing_hc, failures, raw));
 }
 
+fn healthcheck_coverage_check(missing_hc: &BTreeMap<String, String>) -> ReadinessCheck {
+    ReadinessCheck::new(
+        "healthcheck-coverage",
+        "Healthcheck coverage",
+        Severity::Warning,
+        missing_hc.is_empty(),
+        if missing_hc.is_empty() {
+            "every service defines a healthcheck".to_string()
+        } else {
+            format!(
+                "services without a healthcheck: {}",
+                missing_hc
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        },
+    )
+}
+
 fn build_app_health_check(
     passed: Vec<String>,
     missing_hc: BTreeMap<String, String>,
@@ -308,21 +443,26 @@ fn build_app_health_check(
     if !failures.is_empty() {
         detail_parts.push(format!("failed: {}", failures.join(" | ")));
     }
-    ReadinessCheck {
-        name: "app-health".to_string(),
+    let mut check = ReadinessCheck::new(
+        "app-health",
+        "App health",
+        Severity::Blocker,
         ok,
-        detail: if detail_parts.is_empty() {
+        if detail_parts.is_empty() {
             "no service healthchecks configured (skipped)".to_string()
         } else {
             detail_parts.join(" ; ")
         },
-        raw: if raw.is_empty() { None } else { Some(raw) },
-    }
+    );
+    check.raw = if raw.is_empty() { None } else { Some(raw) };
+    check
 }
 
 #[cfg(test)]
 mod tests {
-    use super::build_app_health_check;
+    use super::{
+        build_app_health_check, healthcheck_coverage_check, GoLiveReport, ReadinessCheck, Severity,
+    };
     use std::collections::BTreeMap;
 
     #[test]
@@ -330,7 +470,7 @@ mod tests {
         let mut missing = BTreeMap::new();
         missing.insert("database".to_string(), "missing healthcheck".to_string());
         let check = build_app_health_check(vec!["api".to_string()], missing, vec![], Vec::new());
-        assert!(check.ok);
+        assert!(check.passed);
         assert!(check.detail.contains("passed: api"));
         assert!(check.detail.contains("skipped (no healthcheck): database"));
     }
@@ -343,7 +483,46 @@ mod tests {
             vec!["api: status code 500".to_string()],
             Vec::new(),
         );
-        assert!(!check.ok);
+        assert!(!check.passed);
         assert!(check.detail.contains("failed: api: status code 500"));
     }
+
+    #[test]
+    fn failing_warning_does_not_fail_overall_report() {
+        let mut missing = BTreeMap::new();
+        missing.insert("worker".to_string(), "missing healthcheck".to_string());
+        let coverage = healthcheck_coverage_check(&missing);
+        assert_eq!(coverage.severity, Severity::Warning);
+        assert!(!coverage.passed);
+
+        let report = GoLiveReport::new(
+            "demo".to_string(),
+            vec![
+                ReadinessCheck::new(
+                    "infra-up",
+                    "Infrastructure up",
+                    Severity::Blocker,
+                    true,
+                    "ok",
+                ),
+                coverage,
+            ],
+        );
+        assert!(report.overall_passed);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][1]["severity"], "warning");
+        assert_eq!(json["checks"][1]["passed"], false);
+
+        let blocked = GoLiveReport::new(
+            "demo".to_string(),
+            vec![ReadinessCheck::new(
+                "image-pull",
+                "Images pullable",
+                Severity::Blocker,
+                false,
+                "api: manifest unknown",
+            )],
+        );
+        assert!(!blocked.overall_passed);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::output;
-use airstack_config::AirstackConfig;
+use airstack_config::{AirstackConfig, DEFAULT_INIT_TEMPLATE};
 use anyhow::{Context, Result};
 use serde::Serialize;
 use std::path::Path;
@@ -9,6 +9,7 @@ use tracing::info;
 struct InitOutput {
     project: String,
     config_path: String,
+    template: String,
     created: bool,
 }
 
@@ -16,8 +17,13 @@ pub async fn run(
     name: Option<String>,
     provider: Option<String>,
     preset: Option<String>,
+    template: Option<String>,
     config_path: &str,
 ) -> Result<()> {
+    let template = template.unwrap_or_else(|| DEFAULT_INIT_TEMPLATE.to_string());
+    // Fail on an unknown template before touching the filesystem.
+    AirstackConfig::template_for(&template)?;
+
     let project_name = name.unwrap_or_else(|| {
         std::env::current_dir()
             .ok()
@@ -36,7 +42,8 @@ pub async fn run(
 
     info!("Initializing new Airstack project: {}", project_name);
 
-    AirstackConfig::init_example(config_file).context("Failed to create example configuration")?;
+    AirstackConfig::init_template(config_file, &template)
+        .context("Failed to create example configuration")?;
 
     // Replace the project name in the generated config
     let content = std::fs::read_to_string(config_file)?;
@@ -75,11 +82,15 @@ healthcheck = { http = { path = "/ping", port = 8123, expected_status = 200 }, i
         output::emit_json(&InitOutput {
             project: project_name,
             config_path: config_path.to_string(),
+            template,
             created: true,
         })?;
     } else {
         output::line(format!("✅ Initialized Airstack project: {}", project_name));
-        output::line(format!("📝 Configuration created: {}", config_path));
+        output::line(format!(
+            "📝 Configuration created: {} (template: {})",
+            config_path, template
+        ));
         if let Some(provider) = provider {
             output::line(format!("🔌 Provider preset: {}", provider));
         }
@@ -1,10 +1,21 @@
 use crate::output;
-use crate::ssh_utils::{execute_remote_command, start_remote_session};
-use airstack_config::{AirstackConfig, ServerConfig, ServiceConfig};
-use airstack_container::get_provider as get_container_provider;
+use crate::ssh_utils::{execute_remote_command, remote_shell_process, start_remote_session};
+use crate::theme;
+use airstack_config::{AirstackConfig, InfraConfig, ServerConfig, ServiceConfig};
+use airstack_container::{get_provider as get_container_provider, ContainerProvider};
 use anyhow::{Context, Result};
 use serde::Serialize;
-use tracing::info;
+use std::collections::{HashMap, VecDeque};
+use std::io::IsTerminal;
+use std::process::{Command as StdCommand, Stdio};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+const MULTI_LINE_CHANNEL_CAPACITY: usize = 1024;
+const MAX_JSON_LINES_PER_SERVICE: usize = 2000;
 
 #[derive(Debug, Serialize)]
 struct LogsOutput {
@@ -17,6 +28,34 @@ struct LogsOutput {
     lines: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct MultiLogLine<'a> {
+    service: &'a str,
+    line: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct MultiLogsOutput {
+    follow: bool,
+    services: Vec<MultiLogsService>,
+}
+
+#[derive(Debug, Serialize)]
+struct MultiLogsService {
+    service: String,
+    source_mode: String,
+    server: Option<String>,
+    truncated: bool,
+    lines: Vec<String>,
+}
+
+struct LogTail {
+    service: String,
+    source_mode: &'static str,
+    server: Option<String>,
+    command: StdCommand,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SourceMode {
     Auto,
@@ -46,6 +85,166 @@ impl SourceMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogWindow {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
+impl LogWindow {
+    pub fn parse(since: Option<&str>, until: Option<&str>) -> Result<Self> {
+        Self::parse_at(since, until, unix_now())
+    }
+
+    pub fn parse_at(since: Option<&str>, until: Option<&str>, now_unix: i64) -> Result<Self> {
+        let window = Self {
+            since: since
+                .map(|v| parse_log_time(v, now_unix).context("Invalid --since"))
+                .transpose()?,
+            until: until
+                .map(|v| parse_log_time(v, now_unix).context("Invalid --until"))
+                .transpose()?,
+        };
+        if let (Some(since), Some(until)) = (window.since, window.until) {
+            if since > until {
+                anyhow::bail!("--since must not be later than --until");
+            }
+        }
+        Ok(window)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    fn docker_args(&self, tail: Option<usize>) -> String {
+        let mut args = Vec::new();
+        if let Some(since) = self.since {
+            args.push(format!("--since {since}"));
+        }
+        if let Some(until) = self.until {
+            args.push(format!("--until {until}"));
+        }
+        // A bounded window replaces the default tail; an explicit --tail still applies.
+        match tail {
+            Some(n) => args.push(format!("--tail {n}")),
+            None if self.is_open() => args.push("--tail 200".to_string()),
+            None => {}
+        }
+        args.join(" ")
+    }
+}
+
+// Accepts unix seconds, relative durations like `30m` or `1h30m` (counted back from now),
+// and RFC3339 timestamps like `2024-05-01T12:00:00Z`. Returns unix seconds.
+pub fn parse_log_time(value: &str, now_unix: i64) -> Result<i64> {
+    let value = value.trim();
+    if value.is_empty() {
+        anyhow::bail!("time value is empty");
+    }
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        return value.parse().context("unix timestamp out of range");
+    }
+    if let Some(secs) = parse_duration_secs(value) {
+        return Ok(now_unix - secs);
+    }
+    parse_rfc3339(value).with_context(|| {
+        format!(
+            "'{}' is not a duration (e.g. 30m, 1h30m), RFC3339 timestamp, or unix time",
+            value
+        )
+    })
+}
+
+fn parse_duration_secs(value: &str) -> Option<i64> {
+    let mut total = 0i64;
+    let mut digits = String::new();
+    for ch in value.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return None,
+        };
+        let n: i64 = digits.parse().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+        digits.clear();
+    }
+    digits.is_empty().then_some(total)
+}
+
+fn parse_rfc3339(value: &str) -> Result<i64> {
+    let (date, rest) = value
+        .split_once(['T', 't'])
+        .context("missing 'T' separator")?;
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = next_number(&mut date_parts)?;
+    let month: i64 = next_number(&mut date_parts)?;
+    let day: i64 = next_number(&mut date_parts)?;
+
+    let (time, offset_secs) = if let Some(time) = rest.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let pos = rest.rfind(['+', '-']).context("missing timezone offset")?;
+        let (time, offset) = rest.split_at(pos);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (oh, om) = offset[1..]
+            .split_once(':')
+            .context("invalid timezone offset")?;
+        let oh: i64 = oh.parse().context("invalid timezone offset")?;
+        let om: i64 = om.parse().context("invalid timezone offset")?;
+        (time, sign * (oh * 3600 + om * 60))
+    };
+    let time = time.split('.').next().unwrap_or(time);
+    let mut time_parts = time.splitn(3, ':');
+    let hour: i64 = next_number(&mut time_parts)?;
+    let minute: i64 = next_number(&mut time_parts)?;
+    let second: i64 = next_number(&mut time_parts)?;
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        anyhow::bail!("timestamp field out of range");
+    }
+    Ok(
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+            - offset_secs,
+    )
+}
+
+fn next_number<'a>(parts: &mut impl Iterator<Item = &'a str>) -> Result<i64> {
+    parts
+        .next()
+        .filter(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+        .context("malformed timestamp")?
+        .parse()
+        .context("malformed timestamp")
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
 #[derive(Debug, Clone)]
 struct RemoteContainerRecord {
     server: String,
@@ -55,12 +254,26 @@ struct RemoteContainerRecord {
     status: String,
 }
 
+// The tail is pushed down to the provider so huge logs are never fetched whole.
+async fn control_plane_logs(
+    provider: &dyn ContainerProvider,
+    service: &str,
+    follow: bool,
+    tail: Option<usize>,
+    window: LogWindow,
+) -> Result<Vec<String>> {
+    provider
+        .logs_window(service, follow, window.since, window.until, tail)
+        .await
+}
+
 pub async fn run(
     config_path: &str,
     service: &str,
     follow: bool,
     tail: Option<usize>,
     source: &str,
+    window: LogWindow,
 ) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
     let source_mode = SourceMode::parse(source)?;
@@ -80,7 +293,7 @@ pub async fn run(
         .context("Service disappeared from configuration")?;
 
     if source_mode == SourceMode::Auto || source_mode == SourceMode::ControlPlane {
-        if let Ok(container_provider) = get_container_provider("docker") {
+        if let Ok(container_provider) = get_container_provider(config.project.container_runtime()) {
             if let Ok(container) = container_provider.get_container(service).await {
                 output::line(format!(
                     "📋 Logs for service: {} ({})",
@@ -90,24 +303,10 @@ pub async fn run(
                 output::line("   Source: control-plane");
                 output::line("");
 
-                match container_provider.logs(service, follow).await {
-                    Ok(logs) => {
-                        let display_logs = if let Some(tail_count) = tail {
-                            if logs.len() > tail_count {
-                                logs.into_iter()
-                                    .rev()
-                                    .take(tail_count)
-                                    .collect::<Vec<_>>()
-                                    .into_iter()
-                                    .rev()
-                                    .collect()
-                            } else {
-                                logs
-                            }
-                        } else {
-                            logs
-                        };
-
+                match control_plane_logs(container_provider.as_ref(), service, follow, tail, window)
+                    .await
+                {
+                    Ok(display_logs) => {
                         if output::is_json() {
                             output::emit_json(&LogsOutput {
                                 service: service.to_string(),
@@ -153,13 +352,7 @@ pub async fn run(
     let infra = config
         .infra
         .context("No infra servers defined; cannot inspect remote logs over SSH")?;
-
-    let mut remote_containers = Vec::new();
-    for server_cfg in &infra.servers {
-        if let Ok(mut items) = inspect_remote_containers_for_server(server_cfg).await {
-            remote_containers.append(&mut items);
-        }
-    }
+    let remote_containers = collect_remote_inventory(&infra).await;
 
     let remote = find_remote_for_service(service, service_cfg, &remote_containers).context(
         "Service was not found on local runtime or remote SSH inventory. It may not be deployed.",
@@ -173,7 +366,7 @@ pub async fn run(
     }
 
     if follow {
-        let script = remote_log_script(&remote.name, true, tail);
+        let script = remote_log_script(&remote.name, true, tail, window);
         let status = start_remote_session(
             infra
                 .servers
@@ -181,6 +374,7 @@ pub async fn run(
                 .find(|s| s.name == remote.server)
                 .context("Matched remote server configuration is missing")?,
             &["sh".to_string(), "-lc".to_string(), script],
+            false,
         )
         .await?;
         if status != 0 {
@@ -197,6 +391,7 @@ pub async fn run(
             .context("Matched remote server configuration is missing")?,
         &remote.name,
         tail,
+        window,
     )
     .await?;
 
@@ -221,6 +416,254 @@ pub async fn run(
     Ok(())
 }
 
+pub async fn run_multi(
+    config_path: &str,
+    services: &[String],
+    follow: bool,
+    tail: Option<usize>,
+    source: &str,
+    window: LogWindow,
+) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let source_mode = SourceMode::parse(source)?;
+    let service_cfgs = config
+        .services
+        .as_ref()
+        .context("No services defined in configuration")?;
+    for service in services {
+        if !service_cfgs.contains_key(service) {
+            anyhow::bail!("Service '{}' not found in configuration", service);
+        }
+    }
+
+    // Merge stderr so runtime errors show up under the service prefix too.
+    let script = |container: &str| {
+        format!(
+            "{} 2>&1",
+            remote_log_script(container, follow, tail, window)
+        )
+    };
+    let mut tails = Vec::new();
+    let mut unresolved = Vec::new();
+    let local = match source_mode {
+        SourceMode::Ssh => None,
+        _ => get_container_provider(config.project.container_runtime()).ok(),
+    };
+    for service in services {
+        match &local {
+            Some(provider) if provider.get_container(service).await.is_ok() => {
+                let mut command = StdCommand::new("sh");
+                command.args(["-lc", &script(service)]);
+                tails.push(LogTail {
+                    service: service.clone(),
+                    source_mode: SourceMode::ControlPlane.as_str(),
+                    server: None,
+                    command,
+                });
+            }
+            _ => unresolved.push(service),
+        }
+    }
+
+    if !unresolved.is_empty() {
+        if source_mode == SourceMode::ControlPlane {
+            anyhow::bail!(
+                "Services not found on the local runtime control-plane: {}. Use '--source ssh' to fetch remote logs.",
+                unresolved.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+            );
+        }
+        let infra = config
+            .infra
+            .as_ref()
+            .context("No infra servers defined; cannot inspect remote logs over SSH")?;
+        let remote_containers = collect_remote_inventory(infra).await;
+        for service in unresolved {
+            let remote = find_remote_for_service(service, &service_cfgs[service], &remote_containers)
+                .with_context(|| {
+                    format!(
+                        "Service '{}' was not found on local runtime or remote SSH inventory. It may not be deployed.",
+                        service
+                    )
+                })?;
+            let server_cfg = infra
+                .servers
+                .iter()
+                .find(|s| s.name == remote.server)
+                .context("Matched remote server configuration is missing")?;
+            tails.push(LogTail {
+                service: service.clone(),
+                source_mode: SourceMode::Ssh.as_str(),
+                server: Some(remote.server.clone()),
+                command: remote_shell_process(server_cfg, &script(&remote.name)).await?,
+            });
+        }
+    }
+    tails.sort_by_key(|t| services.iter().position(|s| *s == t.service));
+
+    stream_tails(tails, follow).await
+}
+
+async fn stream_tails(tails: Vec<LogTail>, follow: bool) -> Result<()> {
+    let json = output::is_json();
+    let color = !json && std::io::stdout().is_terminal();
+    let width = tails.iter().map(|t| t.service.len()).max().unwrap_or(0);
+    for (index, tail) in tails.iter().enumerate() {
+        let source = match &tail.server {
+            Some(server) => format!("{} ({})", tail.source_mode, server),
+            None => tail.source_mode.to_string(),
+        };
+        output::line(prefix_line(
+            &tail.service,
+            index,
+            width,
+            &format!("📋 source: {}", source),
+            color,
+        ));
+    }
+    if follow {
+        output::line("👀 Following logs... Press Ctrl+C to exit");
+    }
+
+    let (tx, mut rx) = mpsc::channel::<(usize, String)>(MULTI_LINE_CHANNEL_CAPACITY);
+    let mut readers = JoinSet::new();
+    let mut children = Vec::new();
+    let mut meta = Vec::new();
+    for (index, tail) in tails.into_iter().enumerate() {
+        let mut command = Command::from(tail.command);
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to start log tail for '{}'", tail.service))?;
+        let stdout = child
+            .stdout
+            .take()
+            .context("Failed to capture log tail output")?;
+        let tx = tx.clone();
+        readers.spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if tx.send((index, line)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        children.push(child);
+        meta.push((tail.service, tail.source_mode, tail.server));
+    }
+    drop(tx);
+
+    let mut buffers = vec![VecDeque::new(); meta.len()];
+    let mut truncated = vec![false; meta.len()];
+    loop {
+        let next = tokio::select! {
+            next = rx.recv() => next,
+            _ = tokio::signal::ctrl_c() => None,
+        };
+        let Some((index, line)) = next else {
+            break;
+        };
+        if json && follow {
+            println!(
+                "{}",
+                serde_json::to_string(&MultiLogLine {
+                    service: &meta[index].0,
+                    line: &line,
+                })?
+            );
+        } else if json {
+            let buffer: &mut VecDeque<String> = &mut buffers[index];
+            if buffer.len() == MAX_JSON_LINES_PER_SERVICE {
+                buffer.pop_front();
+                truncated[index] = true;
+            }
+            buffer.push_back(line);
+        } else {
+            println!(
+                "{}",
+                prefix_line(&meta[index].0, index, width, &line, color)
+            );
+        }
+    }
+    readers.abort_all();
+    for ((service, _, _), mut child) in meta.iter().zip(children) {
+        if follow {
+            let _ = child.start_kill();
+            continue;
+        }
+        match child.wait().await {
+            Ok(status) if !status.success() => {
+                warn!("log tail for {} exited with {}", service, status)
+            }
+            Ok(_) => {}
+            Err(e) => warn!("log tail for {} failed: {}", service, e),
+        }
+    }
+
+    if json && !follow {
+        output::emit_json(&MultiLogsOutput {
+            follow,
+            services: meta
+                .into_iter()
+                .zip(buffers)
+                .zip(truncated)
+                .map(
+                    |(((service, source_mode, server), lines), truncated)| MultiLogsService {
+                        service,
+                        source_mode: source_mode.to_string(),
+                        server,
+                        truncated,
+                        lines: lines.into(),
+                    },
+                )
+                .collect(),
+        })?;
+    }
+    Ok(())
+}
+
+fn prefix_line(service: &str, index: usize, width: usize, line: &str, color: bool) -> String {
+    let label = format!("{service:<width$} |");
+    if color {
+        let rgb = theme::SERVICE_PALETTE[index % theme::SERVICE_PALETTE.len()];
+        format!("{} {}", theme::ansi_fg(label, rgb), line)
+    } else {
+        format!("{} {}", label, line)
+    }
+}
+
+async fn collect_remote_inventory(infra: &InfraConfig) -> Vec<RemoteContainerRecord> {
+    let mut inventory_set = JoinSet::new();
+    for server_cfg in &infra.servers {
+        let cfg = server_cfg.clone();
+        inventory_set.spawn(async move {
+            let result = inspect_remote_containers_for_server(&cfg).await;
+            (cfg.name, result)
+        });
+    }
+
+    let mut inventory_results = HashMap::new();
+    while let Some(joined) = inventory_set.join_next().await {
+        match joined {
+            Ok((server_name, result)) => {
+                inventory_results.insert(server_name, result);
+            }
+            Err(e) => {
+                warn!("Remote container inventory task failed to join: {}", e);
+            }
+        }
+    }
+
+    let server_order = infra
+        .servers
+        .iter()
+        .map(|s| s.name.clone())
+        .collect::<Vec<_>>();
+    merge_remote_inventory(&server_order, inventory_results)
+}
+
 async fn inspect_remote_containers_for_server(
     server_cfg: &ServerConfig,
 ) -> Result<Vec<RemoteContainerRecord>> {
@@ -253,6 +696,26 @@ async fn inspect_remote_containers_for_server(
     anyhow::bail!("remote container inventory failed: {}", last_err);
 }
 
+fn merge_remote_inventory(
+    server_order: &[String],
+    mut results: HashMap<String, Result<Vec<RemoteContainerRecord>>>,
+) -> Vec<RemoteContainerRecord> {
+    let mut merged = Vec::new();
+    for server_name in server_order {
+        match results.remove(server_name) {
+            Some(Ok(mut items)) => merged.append(&mut items),
+            Some(Err(e)) => {
+                warn!(
+                    "Remote container inventory failed for {}: {}",
+                    server_name, e
+                );
+            }
+            None => {}
+        }
+    }
+    merged
+}
+
 fn parse_remote_container_lines(
     server_cfg: &ServerConfig,
     stdout: &[u8],
@@ -310,10 +773,9 @@ async fn fetch_remote_logs_once(
     server_cfg: &ServerConfig,
     container_name: &str,
     tail: Option<usize>,
+    window: LogWindow,
 ) -> Result<Vec<String>> {
-    let tail_arg = tail
-        .map(|n| format!("--tail {}", n))
-        .unwrap_or_else(|| "--tail 200".to_string());
+    let tail_arg = window.docker_args(tail);
     let quoted_name = shell_quote(container_name);
     let scripts = [
         format!("docker logs {tail_arg} {quoted_name} 2>&1"),
@@ -344,11 +806,14 @@ async fn fetch_remote_logs_once(
     anyhow::bail!("remote logs command failed: {}", last_err);
 }
 
-fn remote_log_script(container_name: &str, follow: bool, tail: Option<usize>) -> String {
+fn remote_log_script(
+    container_name: &str,
+    follow: bool,
+    tail: Option<usize>,
+    window: LogWindow,
+) -> String {
     let follow_arg = if follow { "-f " } else { "" };
-    let tail_arg = tail
-        .map(|n| format!("--tail {}", n))
-        .unwrap_or_else(|| "--tail 200".to_string());
+    let tail_arg = window.docker_args(tail);
     let name = shell_quote(container_name);
     format!(
         "if command -v docker >/dev/null 2>&1; then docker logs {follow_arg}{tail_arg} {name}; \
@@ -374,9 +839,56 @@ fn shell_quote(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{find_remote_for_service, RemoteContainerRecord};
+    use super::{
+        control_plane_logs, find_remote_for_service, merge_remote_inventory, parse_log_time,
+        prefix_line, LogWindow, RemoteContainerRecord,
+    };
     use airstack_config::ServiceConfig;
+    use airstack_container::{Container, ContainerProvider, RunServiceRequest};
+    use anyhow::Result;
     use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingProvider {
+        calls: Mutex<Vec<(Option<i64>, Option<usize>)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ContainerProvider for RecordingProvider {
+        async fn build_image(&self, _path: &str, _tag: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn run_service(&self, _request: RunServiceRequest) -> Result<Container> {
+            unimplemented!()
+        }
+        async fn stop_service(&self, _name: &str, _timeout_secs: u64) -> Result<()> {
+            unimplemented!()
+        }
+        async fn get_container(&self, _name: &str) -> Result<Container> {
+            unimplemented!()
+        }
+        async fn list_containers(&self) -> Result<Vec<Container>> {
+            unimplemented!()
+        }
+        async fn logs(&self, name: &str, follow: bool, tail: Option<usize>) -> Result<Vec<String>> {
+            self.logs_window(name, follow, None, None, tail).await
+        }
+        async fn logs_window(
+            &self,
+            _name: &str,
+            _follow: bool,
+            since: Option<i64>,
+            _until: Option<i64>,
+            tail: Option<usize>,
+        ) -> Result<Vec<String>> {
+            self.calls.lock().unwrap().push((since, tail));
+            Ok(vec!["line\n".to_string()])
+        }
+        async fn exec(&self, _name: &str, _command: Vec<String>) -> Result<String> {
+            unimplemented!()
+        }
+    }
 
     fn svc(image: &str) -> ServiceConfig {
         ServiceConfig {
@@ -388,9 +900,76 @@ mod tests {
             target_server: None,
             healthcheck: None,
             profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        }
+    }
+
+    fn record(server: &str, name: &str) -> RemoteContainerRecord {
+        RemoteContainerRecord {
+            server: server.to_string(),
+            name: name.to_string(),
+            id: format!("{server}-{name}"),
+            image: "repo/api:latest".to_string(),
+            status: "Up 2 minutes".to_string(),
         }
     }
 
+    #[tokio::test]
+    async fn control_plane_logs_push_tail_down_to_provider() {
+        let provider = RecordingProvider::default();
+        let window = LogWindow {
+            since: Some(1_700_000_000),
+            until: None,
+        };
+        let logs = control_plane_logs(&provider, "api", false, Some(50), window)
+            .await
+            .unwrap();
+        assert_eq!(logs, vec!["line\n".to_string()]);
+        control_plane_logs(&provider, "api", false, None, LogWindow::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            *provider.calls.lock().unwrap(),
+            vec![(Some(1_700_000_000), Some(50)), (None, None),]
+        );
+    }
+
+    #[test]
+    fn merge_remote_inventory_preserves_configured_server_order() {
+        let order = vec![
+            "node-a".to_string(),
+            "node-b".to_string(),
+            "node-c".to_string(),
+        ];
+        // Insert in completion order that differs from the configured order.
+        let mut results = HashMap::new();
+        results.insert("node-c".to_string(), Ok(vec![record("node-c", "worker")]));
+        results.insert(
+            "node-b".to_string(),
+            Err(anyhow::anyhow!("ssh connection refused")),
+        );
+        results.insert(
+            "node-a".to_string(),
+            Ok(vec![record("node-a", "api"), record("node-a", "db")]),
+        );
+
+        let merged = merge_remote_inventory(&order, results);
+        let names = merged
+            .iter()
+            .map(|r| format!("{}/{}", r.server, r.name))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["node-a/api", "node-a/db", "node-c/worker"]);
+    }
+
     #[test]
     fn find_remote_matches_prefix_name() {
         let records = vec![RemoteContainerRecord {
@@ -418,4 +997,44 @@ mod tests {
             .expect("repo match should find container");
         assert_eq!(found.name, "generated-container");
     }
+
+    #[test]
+    fn parses_durations_rfc3339_and_unix_log_times() {
+        let now = 1_714_564_800; // 2024-05-01T12:00:00Z
+        assert_eq!(parse_log_time("30m", now).unwrap(), now - 1800);
+        assert_eq!(parse_log_time("1h30m", now).unwrap(), now - 5400);
+        assert_eq!(parse_log_time("2024-05-01T12:00:00Z", now).unwrap(), now);
+        assert_eq!(
+            parse_log_time("2024-05-01T14:00:00.250+02:00", now).unwrap(),
+            now
+        );
+        assert_eq!(parse_log_time("1700000000", now).unwrap(), 1_700_000_000);
+        assert!(parse_log_time("yesterday", now).is_err());
+
+        let window = LogWindow::parse_at(Some("1h"), Some("2024-05-01T12:00:00Z"), now).unwrap();
+        assert_eq!(
+            window.docker_args(None),
+            format!("--since {} --until {}", now - 3600, now)
+        );
+        assert_eq!(LogWindow::default().docker_args(None), "--tail 200");
+        assert!(LogWindow::parse_at(Some("2024-05-01T12:00:00Z"), Some("2h"), now).is_err());
+    }
+
+    #[test]
+    fn multiplexed_lines_get_aligned_colored_prefixes() {
+        assert_eq!(prefix_line("db", 1, 6, "ready", false), "db     | ready");
+        assert_eq!(prefix_line("worker", 2, 6, "", false), "worker | ");
+
+        let first = prefix_line("api", 0, 3, "GET /", true);
+        assert_eq!(
+            first,
+            format!(
+                "{} GET /",
+                crate::theme::ansi_fg("api |", crate::theme::SERVICE_PALETTE[0])
+            )
+        );
+        let wrapped = prefix_line("api", crate::theme::SERVICE_PALETTE.len(), 3, "x", true);
+        assert_eq!(wrapped, first.replace("GET /", "x"));
+        assert_ne!(prefix_line("api", 1, 3, "x", true), wrapped);
+    }
 }
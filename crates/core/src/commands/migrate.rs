@@ -0,0 +1,120 @@
+use crate::output;
+use airstack_config::migrate::migrate;
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Serialize;
+use std::fs;
+
+#[derive(Debug, Clone, Args)]
+pub struct MigrateArgs {
+    #[arg(
+        long,
+        help = "Write the migrated config back to the file (default: print a diff)"
+    )]
+    pub write: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct MigrateOutput {
+    config_path: String,
+    from_version: u32,
+    to_version: u32,
+    changes: Vec<String>,
+    written: bool,
+    diff: Vec<String>,
+}
+
+pub async fn run(config_path: &str, args: MigrateArgs) -> Result<()> {
+    let original = fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read config file: {}", config_path))?;
+    let value: toml::Value =
+        toml::from_str(&original).context("Failed to parse TOML configuration")?;
+    let (migrated, report) = migrate(value)?;
+
+    let rendered = if report.is_noop() {
+        original.clone()
+    } else {
+        toml::to_string_pretty(&migrated).context("Failed to render migrated config")?
+    };
+    let diff = line_diff(&original, &rendered);
+    let written = args.write && !report.is_noop();
+    if written {
+        fs::write(config_path, &rendered)
+            .with_context(|| format!("Failed to write config file: {}", config_path))?;
+    }
+
+    if output::is_json() {
+        return output::emit_json(&MigrateOutput {
+            config_path: config_path.to_string(),
+            from_version: report.from_version,
+            to_version: report.to_version,
+            changes: report.changes,
+            written,
+            diff,
+        });
+    }
+
+    if report.is_noop() {
+        output::line(format!(
+            "✅ {} is already at config version {}",
+            config_path, report.to_version
+        ));
+        return Ok(());
+    }
+
+    output::line(format!(
+        "🧬 Migrating {} from version {} to {}",
+        config_path, report.from_version, report.to_version
+    ));
+    for change in &report.changes {
+        output::line(format!("- {}", change));
+    }
+    if written {
+        output::line(format!(
+            "✅ wrote {} (comments and key order are not preserved)",
+            config_path
+        ));
+    } else {
+        output::line("");
+        for line in &diff {
+            output::line(line);
+        }
+        output::line("");
+        output::line("ℹ️ re-run with --write to apply");
+    }
+    Ok(())
+}
+
+// Minimal LCS line diff; configs are small enough that O(n*m) is fine.
+fn line_diff(old: &str, new: &str) -> Vec<String> {
+    let a = old.lines().collect::<Vec<_>>();
+    let b = new.lines().collect::<Vec<_>>();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(format!("-{}", a[i]));
+            i += 1;
+        } else {
+            out.push(format!("+{}", b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|line| format!("-{line}")));
+    out.extend(b[j..].iter().map(|line| format!("+{line}")));
+    out
+}
@@ -1,4 +1,5 @@
 pub mod apply;
+pub mod audit;
 pub mod backup;
 pub mod cexec;
 pub mod cli;
@@ -10,8 +11,10 @@ pub mod edge;
 pub mod golive;
 pub mod init;
 pub mod logs;
+pub mod migrate;
 pub mod plan;
 pub mod provider;
+pub mod providers;
 pub mod reconcile;
 pub mod registry;
 pub mod release;
@@ -19,6 +22,7 @@ pub mod runbook;
 pub mod scale;
 pub mod script;
 pub mod secrets;
+pub mod server;
 pub mod ship;
 pub mod ssh;
 pub mod status;
@@ -29,3 +33,4 @@ pub mod tui;
 #[path = "tui_stub.rs"]
 pub mod tui;
 pub mod up;
+pub mod validate;
@@ -1,24 +1,98 @@
+use crate::deploy_runtime::{
+    configure_container_runtime, existing_service_image, existing_service_spec, resolve_target,
+    ExistingServiceSpec,
+};
 use crate::infra_preflight::{check_ssh_key_path, format_validation_error, resolve_server_request};
 use crate::output;
-use airstack_config::AirstackConfig;
+use crate::registry_mirror;
+use crate::state::LocalState;
+use airstack_config::{AirstackConfig, ServiceConfig};
 use airstack_metal::get_provider as get_metal_provider;
-use airstack_metal::CapacityResolveOptions;
+use airstack_metal::{is_managed_server, CapacityResolveOptions, Server};
 use anyhow::{Context, Result};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
+use std::fs;
 
-#[derive(Debug, Serialize)]
-struct PlanAction {
-    resource_type: String,
-    resource: String,
-    action: String,
-    reason: String,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanAction {
+    pub resource_type: String,
+    pub resource: String,
+    pub action: String,
+    pub reason: String,
 }
 
-#[derive(Debug, Serialize)]
-struct PlanOutput {
-    project: String,
-    actions: Vec<PlanAction>,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServicePlanItem {
+    pub service: String,
+    pub action: String,
+    pub current_image: Option<String>,
+    pub desired_image: String,
+    pub changes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanOutput {
+    pub project: String,
+    pub config_hash: String,
+    pub actions: Vec<PlanAction>,
+    pub services: Vec<ServicePlanItem>,
+}
+
+impl PlanOutput {
+    pub fn servers_to_create(&self) -> Vec<String> {
+        self.actions
+            .iter()
+            .filter(|a| a.resource_type == "server" && a.action == "create")
+            .map(|a| a.resource.clone())
+            .collect()
+    }
+
+    pub fn services_to_deploy(&self) -> Vec<String> {
+        self.services
+            .iter()
+            .filter(|s| s.action == "create" || s.action == "update")
+            .map(|s| s.service.clone())
+            .collect()
+    }
+}
+
+// Hash the loaded config (after includes and overlays) so a saved plan can detect drift.
+pub fn config_hash(config: &AirstackConfig) -> Result<String> {
+    let canonical = serde_json::to_string(&serde_json::to_value(config)?)?;
+    let mut hasher = Sha256::new();
+    hasher.update(canonical.as_bytes());
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+pub fn load_plan(path: &str) -> Result<PlanOutput> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read plan file '{}'", path))?;
+    serde_json::from_str(&raw).with_context(|| format!("Failed to parse plan file '{}'", path))
+}
+
+pub fn ensure_plan_matches_config(plan: &PlanOutput, config: &AirstackConfig) -> Result<()> {
+    if plan.project != config.project.name {
+        anyhow::bail!(
+            "plan was created for project '{}' but config is for '{}'",
+            plan.project,
+            config.project.name
+        );
+    }
+    let current = config_hash(config)?;
+    if plan.config_hash != current {
+        anyhow::bail!(
+            "config has changed since the plan was created (plan hash {}, current {}); re-run `airstack plan --output`",
+            short_hash(&plan.config_hash),
+            short_hash(&current)
+        );
+    }
+    Ok(())
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(12)]
 }
 
 pub async fn run(
@@ -26,9 +100,14 @@ pub async fn run(
     include_destroy: bool,
     auto_fallback: bool,
     resolve_capacity: bool,
+    output_path: Option<&str>,
 ) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    registry_mirror::adopt_config(&config);
+    let config_hash = config_hash(&config)?;
     let mut actions = Vec::new();
+    let mut service_items = Vec::new();
 
     if let Some(infra) = &config.infra {
         if let Some(firewall) = &infra.firewall {
@@ -50,6 +129,10 @@ pub async fn run(
                 .push(server.name.clone());
         }
 
+        // Servers airstack recorded in state count as managed even without the marker.
+        let recorded: HashSet<String> = LocalState::load(&config.project.name)
+            .map(|state| state.servers.into_keys().collect())
+            .unwrap_or_default();
         for (provider, desired_names) in by_provider {
             let desired: HashSet<String> = desired_names.into_iter().collect();
             let remote = get_metal_provider(&provider, HashMap::new())
@@ -57,7 +140,7 @@ pub async fn run(
                 .list_servers()
                 .await
                 .unwrap_or_default();
-            let remote_names: HashSet<String> = remote.into_iter().map(|s| s.name).collect();
+            let remote_names: HashSet<String> = remote.iter().map(|s| s.name.clone()).collect();
 
             for name in desired.difference(&remote_names) {
                 actions.push(PlanAction {
@@ -78,12 +161,27 @@ pub async fn run(
             }
 
             if include_destroy {
-                for name in remote_names.difference(&desired) {
+                let (destroy, foreign) = unmanaged_servers(&remote, &desired, &recorded);
+                for name in destroy {
                     actions.push(PlanAction {
                         resource_type: "server".to_string(),
-                        resource: name.clone(),
+                        resource: name,
                         action: "destroy".to_string(),
-                        reason: format!("exists in provider {} but not in config", provider),
+                        reason: format!(
+                            "airstack-managed in provider {} but not in config",
+                            provider
+                        ),
+                    });
+                }
+                for name in foreign {
+                    actions.push(PlanAction {
+                        resource_type: "server".to_string(),
+                        resource: name,
+                        action: "ignore".to_string(),
+                        reason: format!(
+                            "not in config and not tagged airstack-managed in provider {}",
+                            provider
+                        ),
                     });
                 }
             }
@@ -115,13 +213,22 @@ pub async fn run(
     }
 
     if let Some(services) = &config.services {
-        for (name, svc) in services {
+        let mut names = services.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let svc = &services[name];
+            let item = plan_service(&config, name, svc).await;
             actions.push(PlanAction {
                 resource_type: "service".to_string(),
                 resource: name.clone(),
-                action: "deploy".to_string(),
-                reason: format!("ensure image {} is active", svc.image),
+                action: item.action.clone(),
+                reason: if item.changes.is_empty() {
+                    format!("image {} already active", svc.image)
+                } else {
+                    item.changes.join("; ")
+                },
             });
+            service_items.push(item);
             if let Some(vols) = &svc.volumes {
                 for volume in vols {
                     actions.push(PlanAction {
@@ -136,13 +243,26 @@ pub async fn run(
         }
     }
 
+    let plan = PlanOutput {
+        project: config.project.name,
+        config_hash,
+        actions,
+        services: service_items,
+    };
+
+    if let Some(path) = output_path {
+        let body = serde_json::to_string_pretty(&plan)?;
+        fs::write(path, body).with_context(|| format!("Failed to write plan file '{}'", path))?;
+        if !output::is_json() {
+            output::line(format!("💾 plan saved to {}", path));
+        }
+    }
+
     if output::is_json() {
-        output::emit_json(&PlanOutput {
-            project: config.project.name,
-            actions,
-        })?;
+        output::emit_json(&plan)?;
         return Ok(());
     }
+    let actions = plan.actions;
 
     output::line("🧭 Airstack Plan");
     if actions.is_empty() {
@@ -159,3 +279,233 @@ pub async fn run(
 
     Ok(())
 }
+
+// Split provider servers missing from config into airstack-managed ones (safe to
+// destroy) and foreign ones that were never created by airstack.
+fn unmanaged_servers(
+    remote: &[Server],
+    desired: &HashSet<String>,
+    recorded: &HashSet<String>,
+) -> (Vec<String>, Vec<String>) {
+    let mut destroy = Vec::new();
+    let mut foreign = Vec::new();
+    for server in remote.iter().filter(|s| !desired.contains(&s.name)) {
+        if is_managed_server(server, recorded) {
+            destroy.push(server.name.clone());
+        } else {
+            foreign.push(server.name.clone());
+        }
+    }
+    destroy.sort();
+    foreign.sort();
+    (destroy, foreign)
+}
+
+async fn plan_service(config: &AirstackConfig, name: &str, svc: &ServiceConfig) -> ServicePlanItem {
+    let target = match resolve_target(config, svc, false) {
+        Ok(t) => t,
+        Err(e) => return unknown_item(name, svc, format!("target resolution failed: {}", e)),
+    };
+    let current_image = match existing_service_image(&target, name).await {
+        Ok(image) => image,
+        Err(e) => return unknown_item(name, svc, format!("runtime inspect failed: {}", e)),
+    };
+    let spec = if current_image.is_some() {
+        existing_service_spec(&target, name).await.ok().flatten()
+    } else {
+        None
+    };
+    let mirror = registry_mirror::active();
+    classify_service(name, svc, current_image, spec.as_ref(), mirror.as_deref())
+}
+
+fn unknown_item(name: &str, svc: &ServiceConfig, reason: String) -> ServicePlanItem {
+    ServicePlanItem {
+        service: name.to_string(),
+        action: "unknown".to_string(),
+        current_image: None,
+        desired_image: svc.image.clone(),
+        changes: vec![reason],
+    }
+}
+
+fn classify_service(
+    name: &str,
+    svc: &ServiceConfig,
+    current_image: Option<String>,
+    spec: Option<&ExistingServiceSpec>,
+    mirror: Option<&str>,
+) -> ServicePlanItem {
+    let Some(current) = current_image else {
+        return ServicePlanItem {
+            service: name.to_string(),
+            action: "create".to_string(),
+            current_image: None,
+            desired_image: svc.image.clone(),
+            changes: vec!["no running container".to_string()],
+        };
+    };
+
+    let mut changes = Vec::new();
+    if !registry_mirror::images_match(&svc.image, &current, mirror) {
+        changes.push(format!("image {} -> {}", current, svc.image));
+    }
+    if let Some(spec) = spec {
+        let mut desired_ports = svc.ports.clone();
+        desired_ports.sort_unstable();
+        desired_ports.dedup();
+        if desired_ports != spec.ports {
+            changes.push(format!("ports {:?} -> {:?}", spec.ports, desired_ports));
+        }
+        let mut changed_keys = svc
+            .env
+            .iter()
+            .flatten()
+            .filter(|(k, v)| !spec.env.contains(&format!("{}={}", k, v)))
+            .map(|(k, _)| k.clone())
+            .collect::<Vec<_>>();
+        changed_keys.sort();
+        if !changed_keys.is_empty() {
+            changes.push(format!("env changed: {}", changed_keys.join(", ")));
+        }
+    }
+
+    ServicePlanItem {
+        service: name.to_string(),
+        action: if changes.is_empty() { "noop" } else { "update" }.to_string(),
+        current_image: Some(current),
+        desired_image: svc.image.clone(),
+        changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        classify_service, config_hash, ensure_plan_matches_config, unmanaged_servers, PlanOutput,
+    };
+    use crate::deploy_runtime::ExistingServiceSpec;
+    use airstack_config::{AirstackConfig, ServiceConfig};
+
+    fn svc(image: &str) -> ServiceConfig {
+        ServiceConfig {
+            image: image.to_string(),
+            ports: vec![8080],
+            env: None,
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: None,
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        }
+    }
+
+    #[test]
+    fn classifies_matching_differing_and_missing_containers() {
+        let spec = ExistingServiceSpec {
+            ports: vec![8080],
+            env: Vec::new(),
+        };
+        let noop = classify_service(
+            "api",
+            &svc("repo/api:v1"),
+            Some("repo/api:v1".to_string()),
+            Some(&spec),
+            None,
+        );
+        assert_eq!(noop.action, "noop");
+        assert!(noop.changes.is_empty());
+
+        let update = classify_service(
+            "api",
+            &svc("repo/api:v2"),
+            Some("repo/api:v1".to_string()),
+            Some(&spec),
+            None,
+        );
+        assert_eq!(update.action, "update");
+        assert_eq!(update.changes, vec!["image repo/api:v1 -> repo/api:v2"]);
+
+        let create = classify_service("api", &svc("repo/api:v2"), None, None, None);
+        assert_eq!(create.action, "create");
+
+        let mirrored = classify_service(
+            "api",
+            &svc("repo/api:v1"),
+            Some("mirror.corp.example/repo/api:v1".to_string()),
+            Some(&spec),
+            Some("mirror.corp.example"),
+        );
+        assert_eq!(mirrored.action, "noop", "{:?}", mirrored.changes);
+    }
+
+    fn config(image: &str) -> AirstackConfig {
+        toml::from_str(&format!(
+            "[project]\nname = \"demo\"\n\n[services.api]\nimage = \"{image}\"\nports = [8080]\n"
+        ))
+        .expect("config")
+    }
+
+    #[test]
+    fn saved_plan_rejects_config_drift() {
+        let original = config("repo/api:v1");
+        let plan = PlanOutput {
+            project: "demo".to_string(),
+            config_hash: config_hash(&original).expect("hash"),
+            actions: Vec::new(),
+            services: Vec::new(),
+        };
+        assert_eq!(
+            config_hash(&original).expect("hash"),
+            config_hash(&config("repo/api:v1")).expect("hash")
+        );
+        ensure_plan_matches_config(&plan, &original).expect("unchanged config matches");
+
+        let err = ensure_plan_matches_config(&plan, &config("repo/api:v2"))
+            .expect_err("changed config must be rejected");
+        assert!(err.to_string().contains("config has changed"), "{err}");
+    }
+
+    #[test]
+    fn include_destroy_only_targets_managed_servers_missing_from_config() {
+        use airstack_metal::{Server, ServerStatus};
+        use std::collections::HashSet;
+
+        let server = |name: &str, managed: Option<bool>| Server {
+            id: name.to_string(),
+            name: name.to_string(),
+            status: ServerStatus::Running,
+            public_ip: None,
+            private_ip: None,
+            server_type: "cpx21".to_string(),
+            region: "hel1".to_string(),
+            managed,
+        };
+        let remote = vec![
+            server("web-1", Some(true)),
+            server("web-old", Some(true)),
+            server("legacy-db", Some(false)),
+            server("bastion", None),
+            server("api-old", None),
+        ];
+        let desired = HashSet::from(["web-1".to_string()]);
+        let recorded = HashSet::from(["api-old".to_string()]);
+
+        let (destroy, foreign) = unmanaged_servers(&remote, &desired, &recorded);
+        assert_eq!(destroy, vec!["api-old".to_string(), "web-old".to_string()]);
+        assert_eq!(
+            foreign,
+            vec!["bastion".to_string(), "legacy-db".to_string()]
+        );
+    }
+}
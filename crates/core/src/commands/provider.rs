@@ -300,9 +300,13 @@ async fn status_profiles(config_path: &str, args: ProviderProfileStatusArgs) ->
 
         output::line("");
         output::line(format!("=== profile {} ===", selector));
-        if let Err(e) =
-            status::run(config_path, args.detailed, args.probe, false, &args.source).await
-        {
+        let status_args = status::StatusArgs {
+            detailed: args.detailed,
+            probe: args.probe,
+            source: args.source.clone(),
+            ..Default::default()
+        };
+        if let Err(e) = status::run(config_path, status_args).await {
             failures.push(format!("{} -> {}", selector, e));
         }
     }
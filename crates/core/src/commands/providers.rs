@@ -0,0 +1,138 @@
+use crate::output;
+use airstack_config::AirstackConfig;
+use airstack_container::get_provider as get_container_provider;
+use airstack_metal::{get_provider as get_metal_provider, ProviderCapabilities};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Serialize)]
+struct ProviderCapabilityRecord {
+    provider: String,
+    kind: String,
+    available: bool,
+    error: Option<String>,
+    capabilities: Option<ProviderCapabilities>,
+}
+
+pub async fn run(config_path: &str) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let metal_providers = config
+        .infra
+        .as_ref()
+        .map(|i| {
+            i.servers
+                .iter()
+                .map(|s| s.provider.clone())
+                .collect::<BTreeSet<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut records = Vec::new();
+    for provider in metal_providers {
+        let capabilities = get_metal_provider(&provider, HashMap::new()).map(|p| p.capabilities());
+        records.push(capability_record(&provider, "metal", capabilities));
+    }
+    let runtime = config.project.container_runtime();
+    let container = get_container_provider(runtime).map(|_| None);
+    records.push(container_record(runtime, container));
+
+    if output::is_json() {
+        output::emit_json(&records)?;
+        return Ok(());
+    }
+
+    output::line("🔌 Providers");
+    for r in &records {
+        if let Some(err) = &r.error {
+            output::line(format!(
+                "❌ {} ({}): unavailable: {}",
+                r.provider, r.kind, err
+            ));
+            continue;
+        }
+        match &r.capabilities {
+            Some(c) => output::line(format!(
+                "✅ {} ({}): public_ip={} direct_ssh={} provider_ssh={} create={} destroy={} firewall={}",
+                r.provider,
+                r.kind,
+                c.supports_public_ip,
+                c.supports_direct_ssh,
+                c.supports_provider_ssh,
+                c.supports_server_create,
+                c.supports_server_destroy,
+                c.supports_firewall
+            )),
+            None => output::line(format!("✅ {} ({}): available", r.provider, r.kind)),
+        }
+    }
+    Ok(())
+}
+
+fn capability_record(
+    provider: &str,
+    kind: &str,
+    capabilities: Result<ProviderCapabilities>,
+) -> ProviderCapabilityRecord {
+    match capabilities {
+        Ok(c) => ProviderCapabilityRecord {
+            provider: provider.to_string(),
+            kind: kind.to_string(),
+            available: true,
+            error: None,
+            capabilities: Some(c),
+        },
+        Err(e) => ProviderCapabilityRecord {
+            provider: provider.to_string(),
+            kind: kind.to_string(),
+            available: false,
+            error: Some(format!("{:#}", e)),
+            capabilities: None,
+        },
+    }
+}
+
+fn container_record(
+    provider: &str,
+    init: Result<Option<ProviderCapabilities>>,
+) -> ProviderCapabilityRecord {
+    match init {
+        Ok(capabilities) => ProviderCapabilityRecord {
+            provider: provider.to_string(),
+            kind: "container".to_string(),
+            available: true,
+            error: None,
+            capabilities,
+        },
+        Err(e) => capability_record(provider, "container", Err(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capability_record;
+    use airstack_metal::ProviderCapabilities;
+
+    #[test]
+    fn record_reflects_capabilities_or_init_error() {
+        let caps = ProviderCapabilities {
+            supports_public_ip: true,
+            supports_direct_ssh: true,
+            supports_provider_ssh: false,
+            supports_server_create: true,
+            supports_server_destroy: false,
+            supports_firewall: false,
+        };
+        let ok = capability_record("mock", "metal", Ok(caps));
+        assert!(ok.available);
+        assert!(ok.error.is_none());
+        let c = ok.capabilities.expect("capabilities present");
+        assert!(c.supports_public_ip && c.supports_server_create);
+        assert!(!c.supports_server_destroy);
+
+        let failed = capability_record("mock", "metal", Err(anyhow::anyhow!("missing token")));
+        assert!(!failed.available);
+        assert_eq!(failed.error.as_deref(), Some("missing token"));
+        assert!(failed.capabilities.is_none());
+    }
+}
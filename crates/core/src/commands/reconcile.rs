@@ -1,7 +1,25 @@
 use crate::commands::deploy;
+use crate::commands::status::map_container_health;
 use crate::commands::{status, up};
-use anyhow::Result;
+use crate::deploy_runtime::{
+    configure_container_runtime, container_state, replica_containers, resolve_target,
+    start_container,
+};
+use crate::output;
+use crate::service_profiles;
+use crate::state::{HealthState, LocalState, ServiceState};
+use airstack_config::AirstackConfig;
+use airstack_container::ContainerStatus;
+use anyhow::{Context, Result};
 use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+// Consecutive failures double the wait between cycles, capped so a long outage still retries.
+const MAX_BACKOFF_SECS: u64 = 900;
+const MAX_BACKOFF_DOUBLINGS: u32 = 6;
 
 #[derive(Debug, Clone, Args)]
 pub struct ReconcileArgs {
@@ -15,35 +33,401 @@ pub struct ReconcileArgs {
     pub services_only: bool,
     #[arg(long, help = "Alias for --services-only")]
     pub no_infra: bool,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Keep reconciling every SECS seconds until interrupted (--json emits one object per cycle)"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReconcileCycle {
+    cycle: u64,
+    started_at: u64,
+    mode: &'static str,
+    dry_run: bool,
+    ok: bool,
+    error: Option<String>,
+    duration_ms: u128,
+    consecutive_failures: u32,
+    next_run_in_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum HealAction {
+    Restart,
+    Redeploy,
+    None,
+}
+
+#[derive(Debug, Serialize)]
+struct HealRecord {
+    service: String,
+    container: String,
+    observed: String,
+    action: HealAction,
+    ok: bool,
+    detail: Option<String>,
 }
 
 pub async fn run(config_path: &str, args: ReconcileArgs) -> Result<()> {
+    match args.interval {
+        Some(0) => anyhow::bail!("--interval must be at least 1 second"),
+        Some(interval) => run_loop(config_path, &args, interval).await,
+        None => reconcile_once(config_path, &args).await,
+    }
+}
+
+async fn run_loop(config_path: &str, args: &ReconcileArgs, interval: u64) -> Result<()> {
+    let json = output::is_json();
+    let quiet = output::is_quiet();
+    let mode = if args.services_only || args.no_infra {
+        "services-only"
+    } else {
+        "full"
+    };
+    output::line(format!(
+        "🔁 Reconciling every {}s ({}); press Ctrl-C to stop",
+        interval, mode
+    ));
+
+    let mut consecutive_failures = 0u32;
+    for cycle in 1u64.. {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let started = Instant::now();
+        output::line(format!("🔁 reconcile cycle {}", cycle));
+
+        // Inner commands print their own pretty JSON; keep them quiet so each cycle is one NDJSON line.
+        if json {
+            output::configure(false, true);
+        }
+        let result = reconcile_once(config_path, args).await;
+        if json {
+            output::configure(true, quiet);
+        }
+
+        let error = match result {
+            Ok(()) => {
+                consecutive_failures = 0;
+                None
+            }
+            Err(e) => {
+                consecutive_failures += 1;
+                Some(format!("{:#}", e))
+            }
+        };
+        let delay = cycle_delay(interval, consecutive_failures);
+        let record = ReconcileCycle {
+            cycle,
+            started_at,
+            mode,
+            dry_run: args.dry_run,
+            ok: error.is_none(),
+            error,
+            duration_ms: started.elapsed().as_millis(),
+            consecutive_failures,
+            next_run_in_secs: delay.as_secs(),
+        };
+
+        if json {
+            println!("{}", serde_json::to_string(&record)?);
+        } else if let Some(err) = &record.error {
+            output::error_line(format!(
+                "❌ cycle {} failed ({} in a row): {}; retrying in {}s",
+                cycle, consecutive_failures, err, record.next_run_in_secs
+            ));
+        } else {
+            output::line(format!(
+                "✅ cycle {} converged in {}ms; next in {}s",
+                cycle, record.duration_ms, record.next_run_in_secs
+            ));
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = tokio::signal::ctrl_c() => {
+                output::line("🛑 reconcile loop interrupted");
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn cycle_delay(interval: u64, consecutive_failures: u32) -> Duration {
+    if consecutive_failures == 0 {
+        return Duration::from_secs(interval);
+    }
+    let factor = 1u64 << consecutive_failures.min(MAX_BACKOFF_DOUBLINGS);
+    let backoff = interval.saturating_mul(factor).min(MAX_BACKOFF_SECS);
+    Duration::from_secs(backoff.max(interval))
+}
+
+// Stopped/exited containers restart in place; dead or missing ones are redeployed.
+// Running, starting and deliberately paused containers are left alone, as are
+// restart_policy = "no" services and containers that exited cleanly.
+fn heal_action(
+    observed: Option<&ContainerStatus>,
+    exit_code: Option<i64>,
+    restart_policy: Option<&str>,
+) -> HealAction {
+    if restart_policy == Some("no") {
+        return HealAction::None;
+    }
+    let Some(status) = observed else {
+        return HealAction::Redeploy;
+    };
+    if map_container_health(status.clone()) != HealthState::Unhealthy {
+        return HealAction::None;
+    }
+    match status {
+        ContainerStatus::Exited | ContainerStatus::Stopped if exit_code == Some(0) => {
+            HealAction::None
+        }
+        ContainerStatus::Exited | ContainerStatus::Stopped => HealAction::Restart,
+        ContainerStatus::Dead | ContainerStatus::Removing => HealAction::Redeploy,
+        _ => HealAction::None,
+    }
+}
+
+async fn heal_stopped_containers(
+    config_path: &str,
+    args: &ReconcileArgs,
+) -> Result<Vec<HealRecord>> {
+    let mut config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    service_profiles::apply_active(&mut config);
+    let Some(services) = &config.services else {
+        return Ok(Vec::new());
+    };
+    let lock = LocalState::acquire_lock(&config.project.name)?;
+    let state = LocalState::load(&config.project.name)?;
+    let mut names = services.keys().collect::<Vec<_>>();
+    names.sort();
+
+    let mut records = Vec::new();
+    let mut redeploys = BTreeSet::new();
+    for name in names {
+        // Never-deployed services are left to the converge step; paused ones stay paused.
+        let service_state = state.services.get(name);
+        if service_state.is_none_or(ServiceState::is_paused) {
+            continue;
+        }
+        let service = &services[name];
+        let target = match resolve_target(&config, service, args.allow_local_deploy) {
+            Ok(target) => target,
+            Err(e) => {
+                warn!("Skipping container heal for '{}': {}", name, e);
+                continue;
+            }
+        };
+        for container in replica_containers(name, service_state) {
+            let observed = match container_state(&target, &container).await {
+                Ok(observed) => observed,
+                Err(e) => {
+                    warn!("Failed to inspect container '{}': {}", container, e);
+                    continue;
+                }
+            };
+            let action = heal_action(
+                observed
+                    .as_ref()
+                    .map(|(status, _)| ContainerStatus::from_state(status))
+                    .as_ref(),
+                observed.as_ref().and_then(|(_, code)| *code),
+                service.restart_policy.as_deref(),
+            );
+            if action == HealAction::None {
+                continue;
+            }
+            let result = match action {
+                _ if args.dry_run => Ok(()),
+                HealAction::Restart => start_container(&target, &container).await,
+                // Redeploys go through `deploy` after the lock is released so they get
+                // the health gate and state update.
+                HealAction::Redeploy => {
+                    redeploys.insert(name.clone());
+                    Ok(())
+                }
+                HealAction::None => Ok(()),
+            };
+            records.push(HealRecord {
+                service: name.clone(),
+                container,
+                observed: observed
+                    .map(|(status, _)| status)
+                    .unwrap_or_else(|| "missing".to_string()),
+                action,
+                ok: result.is_ok(),
+                detail: match result {
+                    Err(e) => Some(format!("{:#}", e)),
+                    Ok(()) if args.dry_run => Some("dry run".to_string()),
+                    Ok(()) => None,
+                },
+            });
+        }
+    }
+    drop(lock);
+
+    for name in redeploys {
+        let result = deploy::run(
+            config_path,
+            deploy::DeployArgs {
+                push: false,
+                ..deploy::DeployArgs::for_service(&name)
+            },
+            false,
+            args.allow_local_deploy,
+        )
+        .await;
+        for record in records
+            .iter_mut()
+            .filter(|r| r.service == name && r.action == HealAction::Redeploy)
+        {
+            record.ok = result.is_ok();
+            record.detail = result.as_ref().err().map(|e| format!("{:#}", e));
+        }
+    }
+    Ok(records)
+}
+
+fn report_heal(records: &[HealRecord]) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    if output::is_json() {
+        return output::emit_json(&serde_json::json!({ "heal": records }));
+    }
+    for record in records {
+        let action = match record.action {
+            HealAction::Restart => "restart",
+            HealAction::Redeploy => "redeploy",
+            HealAction::None => "none",
+        };
+        if record.ok {
+            output::line(format!(
+                "🩹 {} {} (was {}){}",
+                action,
+                record.container,
+                record.observed,
+                record
+                    .detail
+                    .as_deref()
+                    .map(|d| format!(" [{}]", d))
+                    .unwrap_or_default()
+            ));
+        } else {
+            output::error_line(format!(
+                "❌ {} {} (was {}) failed: {}",
+                action,
+                record.container,
+                record.observed,
+                record.detail.as_deref().unwrap_or("unknown error")
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn reconcile_once(config_path: &str, args: &ReconcileArgs) -> Result<()> {
+    let healed = heal_stopped_containers(config_path, args).await?;
+    report_heal(&healed)?;
+
     if args.services_only || args.no_infra {
         deploy::run(
             config_path,
-            "all",
-            None,
+            deploy::DeployArgs {
+                push: false,
+                ..deploy::DeployArgs::for_service("all")
+            },
+            args.dry_run,
             args.allow_local_deploy,
-            false,
-            false,
-            None,
-            "rolling".to_string(),
-            45,
         )
         .await?;
     } else {
         up::run(
             config_path,
-            None,
-            None,
+            up::UpArgs::for_all(),
             args.dry_run,
             args.allow_local_deploy,
-            false,
-            false,
-            false,
-            false,
         )
         .await?;
     }
-    status::run(config_path, args.detailed, false, false, "auto").await
+    status::run(
+        config_path,
+        status::StatusArgs {
+            detailed: args.detailed,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cycle_delay, heal_action, HealAction, MAX_BACKOFF_SECS};
+    use airstack_container::ContainerStatus;
+    use std::time::Duration;
+
+    #[test]
+    fn heal_decision_table_maps_observed_status_to_action() {
+        let cases = [
+            (Some(ContainerStatus::Running), HealAction::None),
+            (Some(ContainerStatus::Restarting), HealAction::None),
+            (Some(ContainerStatus::Creating), HealAction::None),
+            (Some(ContainerStatus::Paused), HealAction::None),
+            (Some(ContainerStatus::Exited), HealAction::Restart),
+            (Some(ContainerStatus::Stopped), HealAction::Restart),
+            (Some(ContainerStatus::Dead), HealAction::Redeploy),
+            (Some(ContainerStatus::Removing), HealAction::Redeploy),
+            (None, HealAction::Redeploy),
+        ];
+        for (observed, expected) in cases {
+            assert_eq!(
+                heal_action(observed.as_ref(), Some(1), None),
+                expected,
+                "{observed:?}"
+            );
+        }
+        assert_eq!(
+            heal_action(
+                Some(&ContainerStatus::from_state("exited")),
+                Some(137),
+                None
+            ),
+            HealAction::Restart
+        );
+    }
+
+    #[test]
+    fn heal_respects_restart_policy_no_and_clean_exits() {
+        let exited = ContainerStatus::Exited;
+        assert_eq!(heal_action(Some(&exited), Some(0), None), HealAction::None);
+        assert_eq!(
+            heal_action(Some(&exited), Some(1), Some("no")),
+            HealAction::None
+        );
+        assert_eq!(heal_action(None, None, Some("no")), HealAction::None);
+        assert_eq!(
+            heal_action(Some(&exited), Some(1), Some("on-failure")),
+            HealAction::Restart
+        );
+    }
+
+    #[test]
+    fn backoff_escalates_with_consecutive_failures_and_caps() {
+        assert_eq!(cycle_delay(30, 0), Duration::from_secs(30));
+        assert_eq!(cycle_delay(30, 1), Duration::from_secs(60));
+        assert_eq!(cycle_delay(30, 2), Duration::from_secs(120));
+        assert_eq!(cycle_delay(30, 3), Duration::from_secs(240));
+        assert_eq!(cycle_delay(30, 10), Duration::from_secs(MAX_BACKOFF_SECS));
+        // An interval already above the cap is never shortened by backoff.
+        assert_eq!(cycle_delay(3600, 4), Duration::from_secs(3600));
+        assert_eq!(cycle_delay(1, 50), Duration::from_secs(64));
+    }
 }
@@ -1,5 +1,5 @@
 use crate::output;
-use crate::ssh_utils::execute_remote_command;
+use crate::ssh_utils::{execute_remote_command, execute_remote_command_with_stdin};
 use airstack_config::AirstackConfig;
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
@@ -9,6 +9,27 @@ use serde::Serialize;
 pub enum RegistryCommands {
     #[command(about = "Verify remote registry credentials/image pull permissions")]
     Doctor(RegistryDoctorArgs),
+    #[command(about = "Log in to a registry on remote hosts using AIRSTACK_REGISTRY_USER/PASS")]
+    Login(RegistryLoginArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RegistryLoginArgs {
+    #[arg(long, help = "Server name (default: all non-fly servers)")]
+    pub server: Option<String>,
+    #[arg(long, help = "Registry host to authenticate against (e.g. ghcr.io)")]
+    pub registry: String,
+}
+
+const ENV_REGISTRY_USER: &str = "AIRSTACK_REGISTRY_USER";
+const ENV_REGISTRY_PASS: &str = "AIRSTACK_REGISTRY_PASS";
+
+#[derive(Debug, Serialize)]
+struct RegistryLoginRecord {
+    server: String,
+    registry: String,
+    ok: bool,
+    detail: String,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -39,7 +60,79 @@ struct RegistryDoctorRecord {
 pub async fn run(config_path: &str, command: RegistryCommands) -> Result<()> {
     match command {
         RegistryCommands::Doctor(args) => doctor(config_path, args).await,
+        RegistryCommands::Login(args) => login(config_path, args).await,
+    }
+}
+
+fn login_command(registry: &str, username: &str) -> Vec<String> {
+    vec![
+        "docker".to_string(),
+        "login".to_string(),
+        registry.to_string(),
+        "--username".to_string(),
+        username.to_string(),
+        "--password-stdin".to_string(),
+    ]
+}
+
+async fn login(config_path: &str, args: RegistryLoginArgs) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let infra = config
+        .infra
+        .as_ref()
+        .context("No infra.servers configured")?;
+    let username = std::env::var(ENV_REGISTRY_USER)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .with_context(|| format!("{} must be set for registry login", ENV_REGISTRY_USER))?;
+    let password = std::env::var(ENV_REGISTRY_PASS)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .with_context(|| format!("{} must be set for registry login", ENV_REGISTRY_PASS))?;
+
+    let targets = infra
+        .servers
+        .iter()
+        .filter(|s| s.provider != "fly")
+        .filter(|s| args.server.as_ref().is_none_or(|name| &s.name == name))
+        .collect::<Vec<_>>();
+    if targets.is_empty() {
+        anyhow::bail!("No matching non-fly servers for registry login");
+    }
+
+    let command = login_command(&args.registry, &username);
+    let mut rows = Vec::new();
+    for server in targets {
+        let out =
+            execute_remote_command_with_stdin(server, &command, format!("{password}\n").as_bytes())
+                .await?;
+        let detail = if out.status.success() {
+            "login succeeded".to_string()
+        } else {
+            String::from_utf8_lossy(&out.stderr).trim().to_string()
+        };
+        rows.push(RegistryLoginRecord {
+            server: server.name.clone(),
+            registry: args.registry.clone(),
+            ok: out.status.success(),
+            detail,
+        });
     }
+
+    if output::is_json() {
+        output::emit_json(&serde_json::json!({ "results": rows }))?;
+    } else {
+        output::line(format!("🔐 Registry Login ({})", args.registry));
+        for row in &rows {
+            let mark = if row.ok { "✅" } else { "❌" };
+            output::line(format!("{} {} {}", mark, row.server, row.detail));
+        }
+    }
+
+    if rows.iter().any(|r| !r.ok) {
+        anyhow::bail!("Registry login failed on one or more hosts");
+    }
+    Ok(())
 }
 
 async fn doctor(config_path: &str, args: RegistryDoctorArgs) -> Result<()> {
@@ -152,6 +245,7 @@ fn classify_pull_failure(image: &str, stderr: &str) -> (String, Vec<String>) {
     {
         let mut remediation = vec![
             "Authenticate on target host: `echo \"$GHCR_TOKEN\" | docker login ghcr.io -u <github-username> --password-stdin`".to_string(),
+            "Or from CI: set AIRSTACK_REGISTRY_USER/AIRSTACK_REGISTRY_PASS and run `airstack registry login --registry <host>`".to_string(),
         ];
         if image.starts_with("ghcr.io/") {
             remediation.push(
@@ -196,3 +290,25 @@ fn classify_pull_failure(image: &str, stderr: &str) -> (String, Vec<String>) {
         vec!["Inspect raw docker pull stderr for exact root cause".to_string()],
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::login_command;
+
+    #[test]
+    fn login_command_reads_password_from_stdin() {
+        let cmd = login_command("ghcr.io", "ci-bot");
+        assert_eq!(
+            cmd,
+            vec![
+                "docker",
+                "login",
+                "ghcr.io",
+                "--username",
+                "ci-bot",
+                "--password-stdin"
+            ]
+        );
+        assert!(!cmd.iter().any(|arg| arg == "--password" || arg == "-p"));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::output;
-use crate::ssh_utils::{execute_remote_command, resolve_server_public_ip};
+use crate::ssh_utils::{check_ssh_reachable, execute_remote_command, resolve_server_public_ip};
 use crate::state::{HealthState, LocalState, ServiceState};
 use airstack_config::{AirstackConfig, ServerConfig};
 use anyhow::{Context, Result};
@@ -23,6 +23,36 @@ pub struct ReleaseArgs {
     pub remote_build: Option<String>,
     #[arg(long, value_enum, default_value_t = ReleaseFrom::Build, help = "Start release at this phase (build or push)")]
     pub from: ReleaseFrom,
+    #[arg(long, help = "Docker build context directory (default: .)")]
+    pub context_dir: Option<String>,
+    #[arg(long, help = "Dockerfile path passed as `docker build -f`")]
+    pub dockerfile: Option<String>,
+    #[arg(
+        long = "build-arg",
+        value_name = "KEY=VALUE",
+        help = "Docker build arg (repeatable)"
+    )]
+    pub build_arg: Vec<String>,
+    #[arg(
+        long,
+        help = "Build without the layer cache (`docker build --no-cache`)"
+    )]
+    pub no_cache: bool,
+    #[arg(long, help = "Always re-pull base images (`docker build --pull`)")]
+    pub pull: bool,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "PLATFORMS",
+        help = "Build for these platforms via `docker buildx` (e.g. linux/amd64,linux/arm64; several require --push)"
+    )]
+    pub platform: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuildCache {
+    pub no_cache: bool,
+    pub pull: bool,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
@@ -50,19 +80,50 @@ pub async fn run(config_path: &str, args: ReleaseArgs) -> Result<()> {
     let final_image = format!("{}:{}", base_image, tag);
 
     let operation_id = format!("rel-{}-{}", args.service, unix_now());
+    if args.from == ReleaseFrom::Push && args.platform.len() > 1 {
+        anyhow::bail!(
+            "--from push cannot resume a multi-platform build (the manifest list is never loaded locally); rerun from build with --push"
+        );
+    }
+    // buildx pushes multi-platform manifest lists itself as part of the build.
+    let pushed_by_build = args.push && !args.platform.is_empty() && args.from == ReleaseFrom::Build;
     if args.from == ReleaseFrom::Build {
         emit_phase(&operation_id, "build", "start");
+        // Remote builds still stream the build context from this machine, so validate both paths.
+        validate_build_paths(args.context_dir.as_deref(), args.dockerfile.as_deref())?;
+        validate_build_arg_pairs(&args.build_arg)?;
+        let build = build_args(
+            &final_image,
+            args.context_dir.as_deref(),
+            args.dockerfile.as_deref(),
+            &args.build_arg,
+            BuildCache {
+                no_cache: args.no_cache,
+                pull: args.pull,
+            },
+        );
+        let build = if args.platform.is_empty() {
+            build
+        } else {
+            let build = buildx_args(build, &args.platform, args.push)?;
+            preflight_buildx_available()?;
+            build
+        };
         if let Some(server_name) = &args.remote_build {
             let server = resolve_remote_build_server(&config, server_name)?;
             if args.push {
                 preflight_remote_push_requirements(server, &final_image).await?;
             }
-            run_remote_build(server, server_name, &final_image).await?;
+            run_remote_build(server, server_name, &build).await?;
         } else {
             preflight_local_docker_available()?;
-            run_cmd("docker", &["build", "-t", &final_image, "."])?;
+            let build_refs = build.iter().map(String::as_str).collect::<Vec<_>>();
+            run_cmd("docker", &build_refs)?;
         }
         emit_phase(&operation_id, "build", "ok");
+        if pushed_by_build {
+            emit_phase(&operation_id, "push", "ok");
+        }
     } else if args.push {
         if let Some(server_name) = &args.remote_build {
             let server = resolve_remote_build_server(&config, server_name)?;
@@ -75,13 +136,13 @@ pub async fn run(config_path: &str, args: ReleaseArgs) -> Result<()> {
 
     if let Some(server_name) = &args.remote_build {
         let server = resolve_remote_build_server(&config, server_name)?;
-        if args.push {
+        if args.push && !pushed_by_build {
             emit_phase(&operation_id, "push", "start");
             run_remote_push(server, &final_image).await?;
             emit_phase(&operation_id, "push", "ok");
         }
     } else {
-        if args.push {
+        if args.push && !pushed_by_build {
             emit_phase(&operation_id, "push", "start");
             run_cmd("docker", &["push", &final_image])?;
             emit_phase(&operation_id, "push", "ok");
@@ -122,6 +183,7 @@ pub async fn run(config_path: &str, args: ReleaseArgs) -> Result<()> {
         .entry(args.service.clone())
         .and_modify(|s| {
             s.image = final_image.clone();
+            s.image_size_bytes = None;
             s.last_status = Some("Released".to_string());
             s.last_checked_unix = now;
             s.last_error = None;
@@ -140,6 +202,7 @@ pub async fn run(config_path: &str, args: ReleaseArgs) -> Result<()> {
             last_deploy_command: Some(deploy_command.clone()),
             last_deploy_unix: Some(now),
             image_origin: Some(image_origin.to_string()),
+            image_size_bytes: None,
         });
     state.save()?;
 
@@ -150,6 +213,7 @@ pub async fn run(config_path: &str, args: ReleaseArgs) -> Result<()> {
             "pushed": args.push,
             "updated_config": args.update_config,
             "remote_build": args.remote_build,
+            "platforms": args.platform,
             "from": format!("{:?}", args.from).to_ascii_lowercase(),
             "operation_id": operation_id,
             "phases": ["build", if args.push { "push" } else { "skip-push" }],
@@ -198,8 +262,117 @@ pub fn resolve_remote_build_server<'a>(
     Ok(server)
 }
 
-pub async fn run_remote_build(server: &ServerConfig, server_name: &str, image: &str) -> Result<()> {
+fn docker_ssh_host(server: &ServerConfig, ip: &str) -> String {
+    // IPv6-only servers (public_net ipv4 = false) need brackets in the ssh:// URL.
+    let host = if ip.contains(':') && !ip.starts_with('[') {
+        format!("[{}]", ip)
+    } else {
+        ip.to_string()
+    };
+    let port = server.ssh_port();
+    if port == ServerConfig::DEFAULT_SSH_PORT {
+        format!("host=ssh://{}@{}", server.ssh_user(), host)
+    } else {
+        format!("host=ssh://{}@{}:{}", server.ssh_user(), host, port)
+    }
+}
+
+pub fn build_args(
+    image: &str,
+    context_dir: Option<&str>,
+    dockerfile: Option<&str>,
+    build_arg: &[String],
+    cache: BuildCache,
+) -> Vec<String> {
+    let mut args = vec!["build".to_string()];
+    if cache.no_cache {
+        args.push("--no-cache".to_string());
+    }
+    if cache.pull {
+        args.push("--pull".to_string());
+    }
+    args.push("-t".to_string());
+    args.push(image.to_string());
+    if let Some(dockerfile) = dockerfile {
+        args.push("-f".to_string());
+        args.push(dockerfile.to_string());
+    }
+    for pair in build_arg {
+        args.push("--build-arg".to_string());
+        args.push(pair.clone());
+    }
+    args.push(context_dir.unwrap_or(".").to_string());
+    args
+}
+
+// Rewrites plain `docker build` args into `docker buildx build --platform ...`.
+// A single platform is loaded locally unless pushing; manifest lists must be pushed.
+pub fn buildx_args(build: Vec<String>, platforms: &[String], push: bool) -> Result<Vec<String>> {
+    if platforms.len() > 1 && !push {
+        anyhow::bail!(
+            "--platform {} requires --push: multi-platform images cannot be loaded into the local image store",
+            platforms.join(",")
+        );
+    }
+    let mut args = vec![
+        "buildx".to_string(),
+        "build".to_string(),
+        "--platform".to_string(),
+        platforms.join(","),
+        if push { "--push" } else { "--load" }.to_string(),
+    ];
+    args.extend(build.into_iter().skip(1));
+    Ok(args)
+}
+
+pub fn preflight_buildx_available() -> Result<()> {
+    let available = Command::new("docker")
+        .args(["buildx", "version"])
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false);
+    if !available {
+        anyhow::bail!(
+            "--platform needs docker buildx, which was not found. Install the buildx plugin (bundled with Docker Desktop; `docker-buildx-plugin` on Linux) or build without --platform."
+        );
+    }
+    Ok(())
+}
+
+fn validate_build_arg_pairs(build_arg: &[String]) -> Result<()> {
+    for pair in build_arg {
+        let valid = pair.split_once('=').is_some_and(|(key, _)| {
+            !key.is_empty() && !key.chars().any(|c| c.is_whitespace() || c == '=')
+        });
+        if !valid {
+            anyhow::bail!("--build-arg '{}' must be KEY=VALUE", pair);
+        }
+    }
+    Ok(())
+}
+
+fn validate_build_paths(context_dir: Option<&str>, dockerfile: Option<&str>) -> Result<()> {
+    if let Some(dir) = context_dir {
+        if !std::path::Path::new(dir).is_dir() {
+            anyhow::bail!("--context-dir '{}' is not a directory", dir);
+        }
+    }
+    if let Some(file) = dockerfile {
+        if !std::path::Path::new(file).is_file() {
+            anyhow::bail!("--dockerfile '{}' does not exist", file);
+        }
+    }
+    Ok(())
+}
+
+pub async fn run_remote_build(
+    server: &ServerConfig,
+    server_name: &str,
+    build: &[String],
+) -> Result<()> {
     let ip = resolve_server_public_ip(server).await?;
+    // docker's ssh connection helper takes no ssh options, so fail fast here instead of hanging.
+    check_ssh_reachable(server, &ip)?;
     let ctx = format!("airstack-remote-{}-{}", server_name, unix_now());
     run_cmd(
         "docker",
@@ -208,10 +381,12 @@ pub async fn run_remote_build(server: &ServerConfig, server_name: &str, image: &
             "create",
             &ctx,
             "--docker",
-            &format!("host=ssh://root@{}", ip),
+            &docker_ssh_host(server, &ip),
         ],
     )?;
-    let build_result = run_cmd("docker", &["--context", &ctx, "build", "-t", image, "."]);
+    let mut context_args = vec!["--context", ctx.as_str()];
+    context_args.extend(build.iter().map(String::as_str));
+    let build_result = run_cmd("docker", &context_args);
     let cleanup_result = run_cmd("docker", &["context", "rm", "-f", &ctx]);
     if let Err(e) = build_result {
         return Err(e);
@@ -424,7 +599,139 @@ pub fn update_config_image(config_path: &str, service: &str, image: &str) -> Res
 
 #[cfg(test)]
 mod tests {
-    use super::{explicit_registry_host, registry_host_for_login};
+    use super::{
+        build_args, buildx_args, docker_ssh_host, explicit_registry_host, registry_host_for_login,
+        validate_build_arg_pairs, BuildCache,
+    };
+    use airstack_config::ServerConfig;
+
+    #[test]
+    fn docker_ssh_host_brackets_ipv6_addresses() {
+        let mut server = ServerConfig {
+            name: "builder".to_string(),
+            provider: "hetzner".to_string(),
+            region: "nbg1".to_string(),
+            server_type: "cx22".to_string(),
+            ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
+            floating_ip: None,
+            public_net: None,
+            ssh_user: Some("deploy".to_string()),
+            ssh_port: None,
+            labels: None,
+            user_data: None,
+        };
+        assert_eq!(
+            docker_ssh_host(&server, "203.0.113.7"),
+            "host=ssh://deploy@203.0.113.7"
+        );
+        assert_eq!(
+            docker_ssh_host(&server, "2001:db8::1"),
+            "host=ssh://deploy@[2001:db8::1]"
+        );
+        server.ssh_port = Some(2222);
+        assert_eq!(
+            docker_ssh_host(&server, "2001:db8::1"),
+            "host=ssh://deploy@[2001:db8::1]:2222"
+        );
+    }
+
+    #[test]
+    fn buildx_args_for_multiple_platforms_push_manifest_list() {
+        let platforms = vec!["linux/amd64".to_string(), "linux/arm64".to_string()];
+        let build = build_args("repo/api:v1", None, None, &[], BuildCache::default());
+        assert_eq!(
+            buildx_args(build.clone(), &platforms, true).expect("push is set"),
+            vec![
+                "buildx",
+                "build",
+                "--platform",
+                "linux/amd64,linux/arm64",
+                "--push",
+                "-t",
+                "repo/api:v1",
+                "."
+            ]
+        );
+        assert!(buildx_args(build.clone(), &platforms, false).is_err());
+        assert_eq!(
+            buildx_args(build, &platforms[..1], false).expect("single platform loads"),
+            vec![
+                "buildx",
+                "build",
+                "--platform",
+                "linux/amd64",
+                "--load",
+                "-t",
+                "repo/api:v1",
+                "."
+            ]
+        );
+    }
+
+    #[test]
+    fn build_args_include_dockerfile_and_context_dir() {
+        assert_eq!(
+            build_args("repo/api:v1", None, None, &[], BuildCache::default()),
+            vec!["build", "-t", "repo/api:v1", "."]
+        );
+        assert_eq!(
+            build_args(
+                "repo/api:v1",
+                None,
+                None,
+                &[],
+                BuildCache {
+                    no_cache: true,
+                    pull: true
+                }
+            ),
+            vec!["build", "--no-cache", "--pull", "-t", "repo/api:v1", "."]
+        );
+        assert_eq!(
+            build_args(
+                "repo/api:v1",
+                Some("services/api"),
+                Some("services/api/Dockerfile.prod"),
+                &[],
+                BuildCache::default()
+            ),
+            vec![
+                "build",
+                "-t",
+                "repo/api:v1",
+                "-f",
+                "services/api/Dockerfile.prod",
+                "services/api"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_args_preserve_repeated_build_arg_order() {
+        let pairs = vec![
+            "VERSION=1.4.2".to_string(),
+            "GIT_SHA=abc123".to_string(),
+            "EMPTY=".to_string(),
+        ];
+        validate_build_arg_pairs(&pairs).expect("pairs should validate");
+        assert_eq!(
+            build_args("repo/api:v1", None, None, &pairs, BuildCache::default()),
+            vec![
+                "build",
+                "-t",
+                "repo/api:v1",
+                "--build-arg",
+                "VERSION=1.4.2",
+                "--build-arg",
+                "GIT_SHA=abc123",
+                "--build-arg",
+                "EMPTY=",
+                "."
+            ]
+        );
+        assert!(validate_build_arg_pairs(&["VERSION".to_string()]).is_err());
+        assert!(validate_build_arg_pairs(&["=1".to_string()]).is_err());
+    }
 
     #[test]
     fn explicit_registry_host_requires_host_prefix() {
@@ -7,8 +7,9 @@ use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 use tracing::info;
 
+use crate::deploy_runtime::stop_timeout_secs;
 use crate::output;
-use crate::state::{HealthState, LocalState, ServiceState};
+use crate::state::{HealthState, LocalState, ServiceState, PAUSED_STATUS};
 
 #[derive(Debug, Serialize)]
 struct ScaleOutput {
@@ -22,12 +23,9 @@ struct ScaleOutput {
 
 pub async fn run(config_path: &str, service_name: &str, replicas: usize) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let _lock = LocalState::acquire_lock(&config.project.name)?;
     let mut state = LocalState::load(&config.project.name)?;
 
-    if replicas == 0 {
-        anyhow::bail!("Replica count must be at least 1");
-    }
-
     let services = config
         .services
         .context("No services defined in configuration")?;
@@ -41,8 +39,8 @@ pub async fn run(config_path: &str, service_name: &str, replicas: usize) -> Resu
         service_name, replicas
     );
 
-    let container_provider =
-        get_container_provider("docker").context("Failed to initialize Docker provider")?;
+    let container_provider = get_container_provider(config.project.container_runtime())
+        .context("Failed to initialize container runtime provider")?;
 
     let containers = container_provider
         .list_containers()
@@ -52,10 +50,17 @@ pub async fn run(config_path: &str, service_name: &str, replicas: usize) -> Resu
     let existing = detect_service_replicas(service_name, &containers);
     let current_count = existing.len();
 
-    output::line(format!(
-        "📈 Scaling service '{}' from {} to {} replica(s)",
-        service_name, current_count, replicas
-    ));
+    if replicas == 0 {
+        output::line(format!(
+            "⏸️  Pausing service '{}' (stopping {} replica(s))",
+            service_name, current_count
+        ));
+    } else {
+        output::line(format!(
+            "📈 Scaling service '{}' from {} to {} replica(s)",
+            service_name, current_count, replicas
+        ));
+    }
 
     let mut started = Vec::new();
     let mut restarted = Vec::new();
@@ -78,7 +83,12 @@ pub async fn run(config_path: &str, service_name: &str, replicas: usize) -> Resu
             ports: remap_ports(&service.ports, replica)?,
             env: service.env.clone(),
             volumes: service.volumes.clone(),
-            restart_policy: Some("unless-stopped".to_string()),
+            restart_policy: Some(
+                service
+                    .restart_policy
+                    .clone()
+                    .unwrap_or_else(|| "unless-stopped".to_string()),
+            ),
         };
 
         container_provider
@@ -98,7 +108,7 @@ pub async fn run(config_path: &str, service_name: &str, replicas: usize) -> Resu
     for (&replica, container) in existing.iter().rev() {
         if replica > replicas {
             container_provider
-                .stop_service(&container.name)
+                .stop_service(&container.name, stop_timeout_secs(service))
                 .await
                 .with_context(|| format!("Failed to stop replica {}", container.name))?;
             removed.push(container.name.clone());
@@ -119,28 +129,44 @@ pub async fn run(config_path: &str, service_name: &str, replicas: usize) -> Resu
         output::line("🎯 Scale operation completed.");
     }
 
-    state.services.insert(
-        service_name.to_string(),
-        ServiceState {
-            image: service.image.clone(),
-            replicas,
-            containers: (1..=replicas)
-                .map(|r| replica_name(service_name, r))
-                .collect(),
-            health: HealthState::Healthy,
-            last_status: Some("Scaled".to_string()),
-            last_checked_unix: unix_now(),
-            last_error: None,
-            last_deploy_command: None,
-            last_deploy_unix: None,
-            image_origin: None,
-        },
-    );
+    let previous = state.services.get(service_name);
+    let next = scaled_service_state(service_name, &service.image, replicas, previous, unix_now());
+    state.services.insert(service_name.to_string(), next);
     state.save()?;
 
     Ok(())
 }
 
+fn scaled_service_state(
+    service_name: &str,
+    image: &str,
+    replicas: usize,
+    previous: Option<&ServiceState>,
+    now: u64,
+) -> ServiceState {
+    // Pausing keeps the service's deploy provenance so a later scale-up can resume it.
+    let (health, last_status) = if replicas == 0 {
+        (HealthState::Unknown, PAUSED_STATUS)
+    } else {
+        (HealthState::Healthy, "Scaled")
+    };
+    ServiceState {
+        image: image.to_string(),
+        replicas,
+        containers: (1..=replicas)
+            .map(|r| replica_name(service_name, r))
+            .collect(),
+        health,
+        last_status: Some(last_status.to_string()),
+        last_checked_unix: now,
+        last_error: None,
+        last_deploy_command: previous.and_then(|s| s.last_deploy_command.clone()),
+        last_deploy_unix: previous.and_then(|s| s.last_deploy_unix),
+        image_origin: previous.and_then(|s| s.image_origin.clone()),
+        image_size_bytes: previous.and_then(|s| s.image_size_bytes),
+    }
+}
+
 fn unix_now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -168,7 +194,7 @@ fn detect_service_replicas(
     replicas
 }
 
-fn parse_replica_index(service_name: &str, container_name: &str) -> Option<usize> {
+pub fn parse_replica_index(service_name: &str, container_name: &str) -> Option<usize> {
     let prefix = format!("{}-", service_name);
     if !container_name.starts_with(&prefix) {
         return None;
@@ -220,7 +246,8 @@ fn remap_ports(base_ports: &[u16], replica: usize) -> Result<Vec<u16>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_replica_index, remap_ports, replica_name};
+    use super::{parse_replica_index, remap_ports, replica_name, scaled_service_state};
+    use crate::state::HealthState;
 
     #[test]
     fn replica_name_uses_legacy_single_name() {
@@ -236,6 +263,28 @@ mod tests {
         assert_eq!(parse_replica_index("api", "api-a"), None);
     }
 
+    #[test]
+    fn scaling_to_zero_pauses_and_scaling_up_resumes() {
+        let running = scaled_service_state("api", "api:v1", 2, None, 100);
+        assert_eq!(running.containers, vec!["api", "api-2"]);
+        assert!(!running.is_paused());
+
+        let mut deployed = running.clone();
+        deployed.last_deploy_command = Some("deploy".to_string());
+        let paused = scaled_service_state("api", "api:v1", 0, Some(&deployed), 200);
+        assert!(paused.is_paused());
+        assert_eq!(paused.replicas, 0);
+        assert!(paused.containers.is_empty());
+        assert_eq!(paused.health, HealthState::Unknown);
+        assert_eq!(paused.last_deploy_command.as_deref(), Some("deploy"));
+
+        let resumed = scaled_service_state("api", "api:v1", 1, Some(&paused), 300);
+        assert!(!resumed.is_paused());
+        assert_eq!(resumed.containers, vec!["api"]);
+        assert_eq!(resumed.health, HealthState::Healthy);
+        assert_eq!(resumed.last_deploy_command.as_deref(), Some("deploy"));
+    }
+
     #[test]
     fn remap_ports_keeps_first_replica() {
         assert_eq!(remap_ports(&[80, 443], 1).unwrap(), vec![80, 443]);
@@ -1,3 +1,4 @@
+use crate::concurrency::BoundedJoinSet;
 use crate::output;
 use crate::ssh_utils::{execute_remote_command, join_shell_command};
 use crate::state::{LocalState, ScriptRunState};
@@ -8,9 +9,14 @@ use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 use std::fs;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 use uuid::Uuid;
 
+pub const DEFAULT_SCRIPT_CONCURRENCY: usize = 4;
+
 #[derive(Debug, Clone, Subcommand)]
 pub enum ScriptCommands {
     #[command(about = "List configured scripts")]
@@ -39,12 +45,19 @@ pub struct ScriptRunArgs {
     pub explain: bool,
     #[arg(long, help = "Do not execute; show what would run")]
     pub dry_run: bool,
+    #[arg(
+        long,
+        default_value_t = DEFAULT_SCRIPT_CONCURRENCY,
+        help = "Maximum number of servers to run the script on at once"
+    )]
+    pub concurrency: usize,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct ScriptRunOptions {
     pub dry_run: bool,
     pub explain: bool,
+    pub concurrency: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
@@ -96,6 +109,7 @@ pub async fn run_hook_scripts(
                 all_servers: false,
                 explain: options.explain,
                 dry_run: options.dry_run,
+                concurrency: options.concurrency.unwrap_or(DEFAULT_SCRIPT_CONCURRENCY),
             },
             options.clone(),
         )
@@ -189,25 +203,26 @@ async fn run_named_script(
     let hash = load_script_hash(config_path, script)?;
     let script_content = load_script_content(config_path, script)?;
     let mut state = LocalState::load(&config.project.name)?;
-    let mut rows = Vec::new();
     let explain = args.explain || options.explain;
+    let mut slots: Vec<Option<ScriptRunRow>> = Vec::with_capacity(servers.len());
+    let mut pending = Vec::new();
 
     for server in servers {
         let key = script_state_key(&args.name, &server.name);
         let prior = state.script_runs.get(&key).cloned().unwrap_or_default();
         let (action, reason) = planned_action(script, &hash, &prior);
         if action == "skip" {
-            rows.push(ScriptRunRow {
+            slots.push(Some(ScriptRunRow {
                 script: args.name.clone(),
                 server: server.name.clone(),
                 ok: true,
                 skipped: true,
                 detail: reason,
-            });
+            }));
             continue;
         }
         if args.dry_run || options.dry_run {
-            rows.push(ScriptRunRow {
+            slots.push(Some(ScriptRunRow {
                 script: args.name.clone(),
                 server: server.name.clone(),
                 ok: true,
@@ -217,73 +232,78 @@ async fn run_named_script(
                 } else {
                     "dry-run".to_string()
                 },
-            });
+            }));
             continue;
         }
-
-        let shell = script.shell.clone().unwrap_or_else(|| "bash".to_string());
-        let attempts = script
-            .retry
-            .as_ref()
-            .and_then(|r| r.max_attempts)
-            .unwrap_or(1)
-            .max(1);
-        let transient_only = script
-            .retry
-            .as_ref()
-            .and_then(|r| r.transient_only)
-            .unwrap_or(false);
-
-        let mut last_err = None;
-        for attempt in 1..=attempts {
-            let out =
-                execute_script_remote(server, &args.name, script, &shell, &script_content).await;
-            match out {
-                Ok(detail) => {
-                    state.script_runs.insert(
-                        key.clone(),
-                        ScriptRunState {
-                            last_hash: Some(hash.clone()),
-                            last_run_unix: now_unix(),
-                        },
-                    );
-                    rows.push(ScriptRunRow {
-                        script: args.name.clone(),
-                        server: server.name.clone(),
-                        ok: true,
-                        skipped: false,
-                        detail: if explain {
-                            format!("{} ({detail})", script.file)
-                        } else {
-                            detail
-                        },
-                    });
-                    last_err = None;
-                    break;
-                }
-                Err(e) => {
-                    let msg = e.to_string();
-                    last_err = Some(msg.clone());
-                    if !transient_only || is_transient_script_error(&msg) {
-                        if attempt < attempts {
-                            continue;
-                        }
-                    }
-                    break;
+        pending.push((slots.len(), server.clone()));
+        slots.push(None);
+    }
+    let pending_names = pending
+        .iter()
+        .map(|(slot, server)| (*slot, server.name.clone()))
+        .collect::<Vec<_>>();
+
+    let shell = script.shell.clone().unwrap_or_else(|| "bash".to_string());
+    let script_name = args.name.clone();
+    let script_cfg = script.clone();
+    let outcomes = run_bounded(pending, args.concurrency, move |server| {
+        let script_name = script_name.clone();
+        let script_cfg = script_cfg.clone();
+        let shell = shell.clone();
+        let content = script_content.clone();
+        async move {
+            let result =
+                execute_script_with_retry(&server, &script_name, &script_cfg, &shell, &content)
+                    .await;
+            (server.name, result)
+        }
+    })
+    .await;
+
+    for (slot, (server_name, result)) in outcomes {
+        let row = match result {
+            Ok(detail) => {
+                state.script_runs.insert(
+                    script_state_key(&args.name, &server_name),
+                    ScriptRunState {
+                        last_hash: Some(hash.clone()),
+                        last_run_unix: now_unix(),
+                    },
+                );
+                ScriptRunRow {
+                    script: args.name.clone(),
+                    server: server_name,
+                    ok: true,
+                    skipped: false,
+                    detail: if explain {
+                        format!("{} ({detail})", script.file)
+                    } else {
+                        detail
+                    },
                 }
             }
-        }
-
-        if let Some(err) = last_err {
-            rows.push(ScriptRunRow {
+            Err(err) => ScriptRunRow {
                 script: args.name.clone(),
-                server: server.name.clone(),
+                server: server_name,
                 ok: false,
                 skipped: false,
                 detail: err,
+            },
+        };
+        slots[slot] = Some(row);
+    }
+    for (slot, server_name) in pending_names {
+        if slots[slot].is_none() {
+            slots[slot] = Some(ScriptRunRow {
+                script: args.name.clone(),
+                server: server_name,
+                ok: false,
+                skipped: false,
+                detail: "script task aborted unexpectedly".to_string(),
             });
         }
     }
+    let rows = slots.into_iter().flatten().collect::<Vec<_>>();
 
     state.save()?;
 
@@ -307,6 +327,70 @@ async fn run_named_script(
     Ok(())
 }
 
+// Runs `task` for every item with at most `concurrency` in flight, returning
+// results tagged with their slot in the original order.
+async fn run_bounded<T, R, F, Fut>(
+    items: Vec<(usize, T)>,
+    concurrency: usize,
+    task: F,
+) -> Vec<(usize, R)>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = R> + Send + 'static,
+{
+    let mut set = BoundedJoinSet::new(Arc::new(Semaphore::new(concurrency.max(1))));
+    let total = items.len();
+    for (slot, item) in items {
+        let fut = task(item);
+        set.spawn(async move { (slot, fut.await) });
+    }
+    let mut results = Vec::with_capacity(total);
+    while let Some(joined) = set.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+    results.sort_by_key(|(slot, _)| *slot);
+    results
+}
+
+async fn execute_script_with_retry(
+    server: &airstack_config::ServerConfig,
+    script_name: &str,
+    script: &ScriptConfig,
+    shell: &str,
+    content: &str,
+) -> std::result::Result<String, String> {
+    let attempts = script
+        .retry
+        .as_ref()
+        .and_then(|r| r.max_attempts)
+        .unwrap_or(1)
+        .max(1);
+    let transient_only = script
+        .retry
+        .as_ref()
+        .and_then(|r| r.transient_only)
+        .unwrap_or(false);
+
+    let mut last_err = String::new();
+    for attempt in 1..=attempts {
+        match execute_script_remote(server, script_name, script, shell, content).await {
+            Ok(detail) => return Ok(detail),
+            Err(e) => {
+                last_err = e.to_string();
+                if (!transient_only || is_transient_script_error(&last_err)) && attempt < attempts {
+                    continue;
+                }
+                break;
+            }
+        }
+    }
+    Err(last_err)
+}
+
 fn resolve_target_servers<'a>(
     config: &'a AirstackConfig,
     script: &ScriptConfig,
@@ -468,7 +552,7 @@ fn now_unix() -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{planned_action, resolve_target_servers};
+    use super::{planned_action, resolve_target_servers, run_bounded};
     use crate::state::ScriptRunState;
     use airstack_config::{AirstackConfig, InfraConfig, ProjectConfig, ScriptConfig, ServerConfig};
 
@@ -478,6 +562,8 @@ mod tests {
                 name: "demo".to_string(),
                 description: None,
                 deploy_mode: Some("remote".to_string()),
+                container_runtime: None,
+                registry_mirror: None,
             },
             infra: Some(InfraConfig {
                 servers: vec![
@@ -488,6 +574,11 @@ mod tests {
                         server_type: "cpx21".to_string(),
                         ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
                         floating_ip: Some(false),
+                        public_net: None,
+                        ssh_user: None,
+                        ssh_port: None,
+                        labels: None,
+                        user_data: None,
                     },
                     ServerConfig {
                         name: "web-2".to_string(),
@@ -496,14 +587,21 @@ mod tests {
                         server_type: "cpx21".to_string(),
                         ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
                         floating_ip: Some(false),
+                        public_net: None,
+                        ssh_user: None,
+                        ssh_port: None,
+                        labels: None,
+                        user_data: None,
                     },
                 ],
                 firewall: None,
+                volumes: None,
             }),
             services: None,
             edge: None,
             scripts: None,
             hooks: None,
+            version: None,
         }
     }
 
@@ -563,4 +661,52 @@ mod tests {
         let (always_action, _) = planned_action(&script, "abc", &prior);
         assert_eq!(always_action, "run");
     }
+
+    #[tokio::test]
+    async fn run_bounded_limits_in_flight_and_preserves_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let servers = (0..6)
+            .map(|i| (i, format!("web-{}", i + 1)))
+            .collect::<Vec<_>>();
+        let results = run_bounded(servers, 2, |name: String| {
+            let active = active.clone();
+            let peak = peak.clone();
+            async move {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+                name
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let names = results.into_iter().map(|(_, n)| n).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["web-1", "web-2", "web-3", "web-4", "web-5", "web-6"]
+        );
+    }
+
+    // Remote scripts run through ssh_utils::command_output; on a single worker thread two of
+    // them must still overlap rather than run back to back.
+    #[tokio::test(flavor = "current_thread")]
+    async fn run_bounded_overlaps_remote_processes_on_one_worker() {
+        let started = std::time::Instant::now();
+        let results = run_bounded(vec![(0, ()), (1, ())], 2, |_| {
+            let mut cmd = std::process::Command::new("sleep");
+            cmd.arg("0.3");
+            crate::ssh_utils::command_output(cmd)
+        })
+        .await;
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, out)| out.is_ok()));
+        assert!(started.elapsed() < std::time::Duration::from_millis(550));
+    }
 }
@@ -3,6 +3,9 @@ use crate::secrets_store;
 use airstack_config::AirstackConfig;
 use anyhow::{Context, Result};
 use clap::Subcommand;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum SecretsCommands {
@@ -14,9 +17,22 @@ pub enum SecretsCommands {
     List,
     #[command(about = "Delete a secret")]
     Delete { key: String },
+    #[command(about = "Export decrypted secrets to a file (requires --yes)")]
+    Export {
+        #[arg(help = "Output file path")]
+        path: String,
+        #[arg(long, default_value = "env", help = "Output format: env|json")]
+        format: String,
+    },
+    #[command(
+        about = "Re-encrypt all secret stores under a new master key from AIRSTACK_SECRETS_KEY_NEW"
+    )]
+    Rotate,
 }
 
-pub async fn run(config_path: &str, command: SecretsCommands) -> Result<()> {
+const ENV_NEW_KEY: &str = "AIRSTACK_SECRETS_KEY_NEW";
+
+pub async fn run(config_path: &str, command: SecretsCommands, yes: bool) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
     let project = &config.project.name;
 
@@ -64,7 +80,152 @@ pub async fn run(config_path: &str, command: SecretsCommands) -> Result<()> {
                 output::line(format!("✅ secret deleted: {}", key));
             }
         }
+        SecretsCommands::Export { path, format } => {
+            if !yes {
+                anyhow::bail!(
+                    "Refusing to write decrypted secrets to '{}' without --yes",
+                    path
+                );
+            }
+            let values = secrets_store::all(project)?;
+            let content = match format.as_str() {
+                "env" => render_dotenv(&values),
+                "json" => format!("{}\n", serde_json::to_string_pretty(&values)?),
+                _ => anyhow::bail!("Invalid --format '{}'. Expected one of: env|json", format),
+            };
+            write_private_file(Path::new(&path), &content)?;
+            if output::is_json() {
+                output::emit_json(&serde_json::json!({
+                    "ok": true,
+                    "action": "export",
+                    "path": path,
+                    "format": format,
+                    "count": values.len(),
+                }))?;
+            } else {
+                output::line(format!(
+                    "✅ exported {} secret(s) to {} ({})",
+                    values.len(),
+                    path,
+                    format
+                ));
+            }
+        }
+        SecretsCommands::Rotate => {
+            let raw = std::env::var(ENV_NEW_KEY).with_context(|| {
+                format!(
+                    "{} must hold the new 32-byte key (base64 or 64 hex chars)",
+                    ENV_NEW_KEY
+                )
+            })?;
+            let new_key = secrets_store::parse_key(&raw)
+                .with_context(|| format!("Invalid {}", ENV_NEW_KEY))?;
+            let rotated = secrets_store::rotate(new_key)?;
+            if output::is_json() {
+                output::emit_json(&serde_json::json!({
+                    "ok": true,
+                    "action": "rotate",
+                    "projects": rotated,
+                }))?;
+            } else {
+                output::line(format!(
+                    "✅ re-encrypted {} secret store(s) under the new key: {}",
+                    rotated.len(),
+                    rotated.join(", ")
+                ));
+            }
+        }
     }
 
     Ok(())
 }
+
+fn render_dotenv(values: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    for (key, value) in values {
+        out.push_str(&format!("{}={}\n", key, dotenv_quote(value)));
+    }
+    out
+}
+
+fn dotenv_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./:@+,".contains(ch))
+    {
+        return value.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for ch in value.chars() {
+        match ch {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '$' => quoted.push_str("\\$"),
+            '`' => quoted.push_str("\\`"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn write_private_file(path: &Path, content: &str) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to open export file {:?}", path))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // An existing file keeps its old mode on open; tighten it before writing.
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to chmod export file {:?}", path))?;
+    }
+    file.write_all(content.as_bytes())
+        .with_context(|| format!("Failed to write export file {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dotenv_quote, render_dotenv};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn dotenv_leaves_simple_values_unquoted() {
+        assert_eq!(dotenv_quote("abc123"), "abc123");
+        assert_eq!(
+            dotenv_quote("postgres://db:5432/app"),
+            "postgres://db:5432/app"
+        );
+    }
+
+    #[test]
+    fn dotenv_quotes_spaces_newlines_and_specials() {
+        assert_eq!(dotenv_quote("hello world"), "\"hello world\"");
+        assert_eq!(dotenv_quote("line1\nline2"), "\"line1\\nline2\"");
+        assert_eq!(
+            dotenv_quote("say \"hi\" $HOME"),
+            "\"say \\\"hi\\\" \\$HOME\""
+        );
+        assert_eq!(dotenv_quote(""), "\"\"");
+    }
+
+    #[test]
+    fn render_dotenv_emits_sorted_lines() {
+        let values = BTreeMap::from([
+            ("B".to_string(), "two words".to_string()),
+            ("A".to_string(), "1".to_string()),
+        ]);
+        assert_eq!(render_dotenv(&values), "A=1\nB=\"two words\"\n");
+    }
+}
@@ -0,0 +1,148 @@
+use crate::commands::status::map_server_health;
+use crate::output;
+use crate::ssh_utils::lookup_provider_server;
+use crate::state::{LocalState, ServerState};
+use airstack_config::AirstackConfig;
+use airstack_metal::get_provider as get_metal_provider;
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ServerCommands {
+    #[command(about = "Change a server's type in place (reboots the server; requires --yes)")]
+    Resize {
+        #[arg(help = "Server name")]
+        name: String,
+        #[arg(help = "Target provider server type (e.g. cpx31)")]
+        server_type: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct ServerResizeOutput {
+    server: String,
+    from: String,
+    to: String,
+    changed: bool,
+    dry_run: bool,
+    status: Option<String>,
+    public_ip: Option<String>,
+}
+
+pub async fn run(
+    config_path: &str,
+    command: ServerCommands,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    match command {
+        ServerCommands::Resize { name, server_type } => {
+            resize(config_path, &name, &server_type, dry_run, yes).await
+        }
+    }
+}
+
+async fn resize(
+    config_path: &str,
+    name: &str,
+    server_type: &str,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let server = config
+        .infra
+        .as_ref()
+        .and_then(|infra| infra.servers.iter().find(|s| s.name == name))
+        .with_context(|| format!("Server '{}' not found in configuration", name))?;
+
+    let existing = lookup_provider_server(server).await?;
+    let mut report = ServerResizeOutput {
+        server: server.name.clone(),
+        from: existing.server_type.clone(),
+        to: server_type.to_string(),
+        changed: false,
+        dry_run,
+        status: Some(format!("{:?}", existing.status)),
+        public_ip: existing.public_ip.clone(),
+    };
+
+    if existing.server_type == server_type {
+        return emit(&report, "already running the requested type");
+    }
+    if dry_run {
+        return emit(&report, "would power off, resize, and power on");
+    }
+    if !yes {
+        anyhow::bail!(
+            "Refusing to resize '{}' without --yes (the server will be powered off)",
+            server.name
+        );
+    }
+
+    let _lock = LocalState::acquire_lock(&config.project.name)?;
+    let provider = get_metal_provider(&server.provider, HashMap::new())
+        .with_context(|| format!("Failed to initialize {} provider", server.provider))?;
+    if !output::is_json() {
+        output::line(format!(
+            "🔄 resizing {} {} -> {} (server will reboot)",
+            server.name, existing.server_type, server_type
+        ));
+    }
+    let resized = provider
+        .resize_server(&existing.id, server_type)
+        .await
+        .with_context(|| format!("Failed to resize server '{}'", server.name))?;
+
+    let mut state = LocalState::load(&config.project.name)?;
+    state.servers.insert(
+        server.name.clone(),
+        ServerState {
+            provider: server.provider.clone(),
+            id: Some(resized.id.clone()),
+            public_ip: resized.public_ip.clone(),
+            health: map_server_health(resized.status.clone()),
+            last_status: Some(format!("{:?}", resized.status)),
+            last_checked_unix: now_unix(),
+            last_error: None,
+        },
+    );
+    state.save()?;
+
+    report.changed = true;
+    report.status = Some(format!("{:?}", resized.status));
+    report.public_ip = resized.public_ip;
+    emit(&report, "resized")?;
+    if !output::is_json() && server.server_type != server_type {
+        output::line(format!(
+            "ℹ️ update server_type for '{}' in your config to '{}' to avoid drift",
+            server.name, server_type
+        ));
+    }
+    Ok(())
+}
+
+fn emit(report: &ServerResizeOutput, detail: &str) -> Result<()> {
+    if output::is_json() {
+        return output::emit_json(report);
+    }
+    let icon = if report.changed || report.dry_run {
+        "✅"
+    } else {
+        "ℹ️"
+    };
+    output::line(format!(
+        "{} server {}: {} -> {} ({})",
+        icon, report.server, report.from, report.to, detail
+    ));
+    Ok(())
+}
+
+fn now_unix() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -1,10 +1,13 @@
 use crate::commands::edge;
 use crate::commands::release;
 use crate::deploy_runtime::{
-    collect_container_diagnostics, deploy_service_with_strategy, evaluate_service_health,
-    existing_service_image, resolve_target, rollback_service, DeployStrategy,
+    apply_health_timeout, candidate_container_name, collect_container_diagnostics,
+    configure_container_runtime, deploy_service_with_strategy, evaluate_replica_health,
+    existing_service_image, image_size_bytes, replica_containers, resolve_target, rollback_service,
+    CanaryTraffic, CanaryWindow, DeployStrategy,
 };
 use crate::output;
+use crate::registry_mirror;
 use crate::state::{HealthState, LocalState, ServiceState};
 use airstack_config::AirstackConfig;
 use anyhow::{Context, Result};
@@ -33,7 +36,7 @@ pub struct ShipArgs {
     pub allow_local_deploy: bool,
     #[arg(
         long,
-        help = "Deploy strategy: rolling|bluegreen|canary",
+        help = "Deploy strategy: rolling|recreate (stop old first; brief downtime)|bluegreen|canary",
         default_value = "rolling"
     )]
     pub strategy: String,
@@ -43,6 +46,17 @@ pub struct ShipArgs {
         default_value_t = 45
     )]
     pub canary_seconds: u64,
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u8).range(1..=99),
+        help = "Route N% of edge traffic to the candidate during the canary window (strategy=canary)"
+    )]
+    pub canary_percent: Option<u8>,
+    #[arg(
+        long,
+        help = "Overall healthcheck deadline in seconds (overrides healthcheck.overall_timeout_secs)"
+    )]
+    pub health_timeout: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -58,6 +72,9 @@ struct ShipOutput {
 
 pub async fn run(config_path: &str, args: ShipArgs) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    registry_mirror::adopt_config(&config);
+    let _lock = LocalState::acquire_lock(&config.project.name)?;
     let mut state = LocalState::load(&config.project.name)?;
     let services = config
         .services
@@ -75,6 +92,31 @@ pub async fn run(config_path: &str, args: ShipArgs) -> Result<()> {
     let tag = args.tag.clone().unwrap_or(git_sha()?);
     let final_image = format!("{}:{}", base_image, tag);
 
+    let strategy = DeployStrategy::parse(&args.strategy)?;
+    let canary_route = match args.canary_percent {
+        Some(percent) => {
+            if strategy != DeployStrategy::Canary {
+                anyhow::bail!("--canary-percent requires --strategy canary");
+            }
+            let edge_cfg = config
+                .edge
+                .as_ref()
+                .context("--canary-percent requires an [edge] config")?;
+            if !edge::upstream_services(edge_cfg).contains(&args.service) {
+                anyhow::bail!(
+                    "--canary-percent requires '{}' to be an upstream of an edge site",
+                    args.service
+                );
+            }
+            Some(edge::CanaryRoute {
+                service: args.service.clone(),
+                candidate: candidate_container_name(&args.service),
+                percent,
+            })
+        }
+        None => None,
+    };
+
     // Build + push phase
     release::preflight_local_docker_available()?;
     run_cmd("docker", &["build", "-t", &final_image, "."])?;
@@ -83,27 +125,35 @@ pub async fn run(config_path: &str, args: ShipArgs) -> Result<()> {
     }
 
     // Deploy phase
-    let strategy = DeployStrategy::parse(&args.strategy)?;
     let target = resolve_target(&config, service_cfg, args.allow_local_deploy)?;
     let previous_image = existing_service_image(&target, &args.service).await?;
     let mut deploy_cfg = service_cfg.clone();
     deploy_cfg.image = final_image.clone();
+    apply_health_timeout(&mut deploy_cfg, args.health_timeout);
 
     let mut rolled_back = false;
     let mut deployed = deploy_service_with_strategy(
         &target,
+        &config.project.name,
         &args.service,
         &deploy_cfg,
-        service_cfg.healthcheck.as_ref(),
+        deploy_cfg.healthcheck.as_ref(),
         strategy,
-        args.canary_seconds,
+        CanaryWindow {
+            seconds: args.canary_seconds,
+            traffic: canary_route.as_ref().map(|route| CanaryTraffic {
+                shift: Box::pin(edge::apply_canary_route(&config, Some(route))),
+                restore: Box::pin(edge::apply_canary_route(&config, None)),
+            }),
+        },
     )
     .await
     .with_context(|| format!("Failed deploying ship image for '{}'", args.service))?;
 
     if service_cfg.healthcheck.is_some() {
+        let containers = replica_containers(&args.service, state.services.get(&args.service));
         if let Err(err) =
-            evaluate_service_health(&target, &args.service, service_cfg, false, 1, false)
+            evaluate_replica_health(&target, &args.service, &containers, &deploy_cfg, 1, false)
                 .await
                 .and_then(|eval| {
                     if eval.ok {
@@ -116,7 +166,14 @@ pub async fn run(config_path: &str, args: ShipArgs) -> Result<()> {
             deployed.healthy = Some(false);
             let diag = collect_container_diagnostics(&target, &args.service).await;
             if let Some(prev) = &previous_image {
-                let _ = rollback_service(&target, &args.service, prev, service_cfg).await;
+                let _ = rollback_service(
+                    &target,
+                    &config.project.name,
+                    &args.service,
+                    prev,
+                    service_cfg,
+                )
+                .await;
                 rolled_back = true;
                 output::line(format!(
                     "↩️ rollback target for {} -> image {}",
@@ -137,6 +194,7 @@ pub async fn run(config_path: &str, args: ShipArgs) -> Result<()> {
         release::update_config_image(config_path, &args.service, &final_image)?;
     }
 
+    let image_size_bytes = image_size_bytes(&target, &final_image).await;
     let now = unix_now();
     let deploy_command = format!(
         "airstack ship {} --tag {}{}{}",
@@ -154,6 +212,7 @@ pub async fn run(config_path: &str, args: ShipArgs) -> Result<()> {
         .entry(args.service.clone())
         .and_modify(|s| {
             s.image = final_image.clone();
+            s.image_size_bytes = image_size_bytes;
             s.last_status = Some("Shipped".to_string());
             s.last_checked_unix = now;
             s.last_error = None;
@@ -180,13 +239,14 @@ pub async fn run(config_path: &str, args: ShipArgs) -> Result<()> {
             } else {
                 "local-build-only".to_string()
             }),
+            image_size_bytes,
         });
     state.save()?;
 
-    if args.service == "caddy" && config.edge.is_some() {
+    if edge::is_edge_service(&args.service) && config.edge.is_some() {
         edge::apply_from_config(&config)
             .await
-            .with_context(|| "Failed to sync edge config during caddy ship")?;
+            .with_context(|| format!("Failed to sync edge config during {} ship", args.service))?;
     }
 
     if output::is_json() {
@@ -1,11 +1,15 @@
+use crate::concurrency::{self, BoundedJoinSet};
 use crate::output;
 use crate::ssh_utils::{
     execute_remote_command, execute_remote_shell_command, join_shell_command, start_remote_session,
 };
-use airstack_config::AirstackConfig;
+use airstack_config::{AirstackConfig, ServerConfig};
+use airstack_metal::read_public_key;
 use anyhow::{Context, Result};
 use serde::Serialize;
+use std::collections::HashMap;
 use std::path::Path;
+use std::process::Output;
 use tracing::info;
 
 #[derive(Debug, Serialize)]
@@ -18,6 +22,22 @@ struct SshOutput {
     stderr: String,
 }
 
+#[derive(Debug, Serialize)]
+struct SshAllRecord {
+    server: String,
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CopyIdOutput {
+    target: String,
+    public_key_path: String,
+    installed: bool,
+}
+
+#[derive(Debug, Clone)]
 pub struct SshExec {
     pub command: Vec<String>,
     pub cmd: Option<String>,
@@ -47,46 +67,11 @@ pub async fn run(config_path: &str, target: &str, exec: SshExec) -> Result<()> {
 
     output::line(format!("🔌 Connecting to {} via {}", target, endpoint));
 
-    let command_modes = usize::from(!exec.command.is_empty())
-        + usize::from(exec.cmd.is_some())
-        + usize::from(exec.script.is_some());
-    if command_modes > 1 {
-        anyhow::bail!("Use only one execution mode: --cmd, --script, or -- <argv...>");
-    }
+    let command_modes = command_mode_count(&exec)?;
 
     // Add command if specified
     if command_modes == 1 {
-        let (exec_display, output, output_command) = if let Some(cmd) = exec.cmd {
-            let display = format!("sh -lc {}", shell_quote(&cmd));
-            (
-                display.clone(),
-                execute_remote_shell_command(server_config, &display).await?,
-                vec!["sh".to_string(), "-lc".to_string(), cmd],
-            )
-        } else if let Some(script_path) = exec.script {
-            let script = std::fs::read_to_string(&script_path)
-                .with_context(|| format!("Failed to read script '{}'", script_path))?;
-            let script_name = Path::new(&script_path)
-                .file_name()
-                .and_then(|s| s.to_str())
-                .unwrap_or("script");
-            let wrapped = format!(
-                "cat <<'AIRSTACK_SCRIPT' >/tmp/{script_name}.airstack.sh\n{script}\nAIRSTACK_SCRIPT\nchmod +x /tmp/{script_name}.airstack.sh\nsh /tmp/{script_name}.airstack.sh"
-            );
-            let display = format!("sh -lc {}", shell_quote(&wrapped));
-            (
-                display.clone(),
-                execute_remote_shell_command(server_config, &display).await?,
-                vec!["sh".to_string(), "-lc".to_string(), wrapped],
-            )
-        } else {
-            let display = join_shell_command(&exec.command);
-            (
-                display,
-                execute_remote_command(server_config, &exec.command).await?,
-                exec.command.clone(),
-            )
-        };
+        let (exec_display, output, output_command) = execute_exec(server_config, &exec).await?;
 
         output::line(format!("🔧 Executing: {}", exec_display));
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -125,7 +110,7 @@ pub async fn run(config_path: &str, target: &str, exec: SshExec) -> Result<()> {
         }
         // Interactive SSH session
         output::line("🖥️  Starting interactive SSH session...");
-        let code = start_remote_session(server_config, &[]).await?;
+        let code = start_remote_session(server_config, &[], false).await?;
 
         if code != 0 {
             anyhow::bail!("SSH session failed with exit code: {}", code);
@@ -135,6 +120,212 @@ pub async fn run(config_path: &str, target: &str, exec: SshExec) -> Result<()> {
     Ok(())
 }
 
+pub async fn run_all(config_path: &str, exec: SshExec) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let infra = config
+        .infra
+        .context("No infrastructure defined in configuration")?;
+    if command_mode_count(&exec)? == 0 {
+        anyhow::bail!("--all requires a command: --cmd, --script, or -- <argv...>");
+    }
+
+    output::line(format!(
+        "🔌 Executing on {} server(s), at most {} at a time",
+        infra.servers.len(),
+        concurrency::limit()
+    ));
+
+    let mut tasks = BoundedJoinSet::new(concurrency::semaphore());
+    for server in infra.servers.iter().cloned() {
+        let exec = exec.clone();
+        tasks.spawn(async move {
+            let result = execute_exec(&server, &exec).await.map(|(_, out, _)| out);
+            (server.name, result)
+        });
+    }
+
+    let mut results: HashMap<String, Result<Output>> = HashMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (name, result) = joined.context("SSH task panicked")?;
+        results.insert(name, result);
+    }
+
+    let mut records = Vec::new();
+    for server in &infra.servers {
+        let record = match results.remove(&server.name) {
+            Some(Ok(out)) => SshAllRecord {
+                server: server.name.clone(),
+                exit_code: out.status.code().unwrap_or(1),
+                stdout: String::from_utf8_lossy(&out.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&out.stderr).to_string(),
+            },
+            Some(Err(e)) => SshAllRecord {
+                server: server.name.clone(),
+                exit_code: 255,
+                stdout: String::new(),
+                stderr: format!("{:#}", e),
+            },
+            None => continue,
+        };
+        records.push(record);
+    }
+
+    if output::is_json() {
+        output::emit_json(&records)?;
+    } else {
+        for r in &records {
+            if !r.stdout.is_empty() {
+                print!("{}", prefix_lines(&r.server, &r.stdout));
+            }
+            if !r.stderr.is_empty() {
+                output::error_line(prefix_lines(&r.server, &r.stderr).trim_end());
+            }
+        }
+    }
+
+    let failed = records
+        .iter()
+        .filter(|r| r.exit_code != 0)
+        .map(|r| r.server.as_str())
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "SSH command failed on {} server(s): {}",
+            failed.len(),
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+pub async fn copy_id(config_path: &str, target: &str) -> Result<()> {
+    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let infra = config
+        .infra
+        .context("No infrastructure defined in configuration")?;
+    let server_config = infra
+        .servers
+        .iter()
+        .find(|s| s.name == target)
+        .with_context(|| format!("Server '{}' not found in configuration", target))?;
+    if server_config.provider == "fly" {
+        anyhow::bail!(
+            "--copy-id is not supported for provider='fly'; flyctl manages SSH credentials"
+        );
+    }
+
+    let key_path = public_key_path(&server_config.ssh_key);
+    let public_key = read_public_key(&key_path)?;
+    let script = copy_id_script(&public_key)?;
+
+    output::line(format!(
+        "🔑 Installing {} on {} (password or agent auth may be prompted)",
+        key_path, target
+    ));
+    let out =
+        execute_remote_shell_command(server_config, &format!("sh -lc {}", shell_quote(&script)))
+            .await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "Failed to install public key on '{}': {}",
+            target,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    let installed = String::from_utf8_lossy(&out.stdout).trim() == COPY_ID_ADDED;
+
+    if output::is_json() {
+        return output::emit_json(&CopyIdOutput {
+            target: target.to_string(),
+            public_key_path: key_path,
+            installed,
+        });
+    }
+    if installed {
+        output::line(format!("✅ Public key added to {}", target));
+    } else {
+        output::line(format!("✅ Public key already authorized on {}", target));
+    }
+    Ok(())
+}
+
+const COPY_ID_ADDED: &str = "added";
+
+fn public_key_path(ssh_key: &str) -> String {
+    if ssh_key.ends_with(".pub") {
+        ssh_key.to_string()
+    } else {
+        format!("{ssh_key}.pub")
+    }
+}
+
+// grep -qxF keeps re-runs idempotent: the key is appended only when no identical line exists.
+fn copy_id_script(public_key: &str) -> Result<String> {
+    let key = public_key.trim();
+    if key.is_empty() || key.contains('\n') || !key.contains(' ') {
+        anyhow::bail!("SSH public key must be a single '<type> <base64> [comment]' line");
+    }
+    let quoted = shell_quote(key);
+    Ok(format!(
+        "umask 077; mkdir -p ~/.ssh && touch ~/.ssh/authorized_keys && \
+         if grep -qxF {quoted} ~/.ssh/authorized_keys; then echo present; \
+         else printf '%s\\n' {quoted} >> ~/.ssh/authorized_keys && echo {COPY_ID_ADDED}; fi"
+    ))
+}
+
+fn command_mode_count(exec: &SshExec) -> Result<usize> {
+    let command_modes = usize::from(!exec.command.is_empty())
+        + usize::from(exec.cmd.is_some())
+        + usize::from(exec.script.is_some());
+    if command_modes > 1 {
+        anyhow::bail!("Use only one execution mode: --cmd, --script, or -- <argv...>");
+    }
+    Ok(command_modes)
+}
+
+async fn execute_exec(
+    server_config: &ServerConfig,
+    exec: &SshExec,
+) -> Result<(String, Output, Vec<String>)> {
+    if let Some(cmd) = &exec.cmd {
+        let display = format!("sh -lc {}", shell_quote(cmd));
+        Ok((
+            display.clone(),
+            execute_remote_shell_command(server_config, &display).await?,
+            vec!["sh".to_string(), "-lc".to_string(), cmd.clone()],
+        ))
+    } else if let Some(script_path) = &exec.script {
+        let script = std::fs::read_to_string(script_path)
+            .with_context(|| format!("Failed to read script '{}'", script_path))?;
+        let script_name = Path::new(script_path)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("script");
+        let wrapped = format!(
+            "cat <<'AIRSTACK_SCRIPT' >/tmp/{script_name}.airstack.sh\n{script}\nAIRSTACK_SCRIPT\nchmod +x /tmp/{script_name}.airstack.sh\nsh /tmp/{script_name}.airstack.sh"
+        );
+        let display = format!("sh -lc {}", shell_quote(&wrapped));
+        Ok((
+            display.clone(),
+            execute_remote_shell_command(server_config, &display).await?,
+            vec!["sh".to_string(), "-lc".to_string(), wrapped],
+        ))
+    } else {
+        let display = join_shell_command(&exec.command);
+        Ok((
+            display,
+            execute_remote_command(server_config, &exec.command).await?,
+            exec.command.clone(),
+        ))
+    }
+}
+
+fn prefix_lines(server: &str, text: &str) -> String {
+    text.lines()
+        .map(|line| format!("[{}] {}\n", server, line))
+        .collect()
+}
+
 fn shell_quote(value: &str) -> String {
     if value.is_empty() {
         return "''".to_string();
@@ -147,3 +338,43 @@ fn shell_quote(value: &str) -> String {
     }
     format!("'{}'", value.replace('\'', "'\"'\"'"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{copy_id_script, prefix_lines, public_key_path};
+
+    #[test]
+    fn prefixes_every_output_line_with_server_name() {
+        assert_eq!(
+            prefix_lines("web-1", "up 3 days\nload 0.1\n"),
+            "[web-1] up 3 days\n[web-1] load 0.1\n"
+        );
+        assert_eq!(prefix_lines("web-2", "no newline"), "[web-2] no newline\n");
+        assert_eq!(prefix_lines("web-3", ""), "");
+    }
+
+    #[test]
+    fn copy_id_script_appends_only_when_key_is_missing() {
+        let key = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIexample ops@laptop";
+        let script = copy_id_script(&format!("{key}\n")).expect("valid key");
+        let quoted = format!("'{key}'");
+        assert!(script.contains(&format!("grep -qxF {quoted} ~/.ssh/authorized_keys")));
+        assert!(script.contains(&format!(
+            "printf '%s\\n' {quoted} >> ~/.ssh/authorized_keys"
+        )));
+        let grep_at = script.find("grep -qxF").unwrap();
+        let append_at = script.find(">>").unwrap();
+        assert!(grep_at < append_at);
+
+        assert!(copy_id_script("").is_err());
+        assert!(copy_id_script("ssh-ed25519 AAAA\nssh-rsa BBBB").is_err());
+        assert_eq!(
+            public_key_path("~/.ssh/id_ed25519"),
+            "~/.ssh/id_ed25519.pub"
+        );
+        assert_eq!(
+            public_key_path("~/.ssh/id_ed25519.pub"),
+            "~/.ssh/id_ed25519.pub"
+        );
+    }
+}
@@ -2,17 +2,28 @@ use airstack_config::{AirstackConfig, InfraConfig, ServerConfig};
 use airstack_container::get_provider as get_container_provider;
 use airstack_metal::{get_provider as get_metal_provider, Server};
 use anyhow::{Context, Result};
+use clap::Args;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use tokio::process::Command;
-use tokio::task::JoinSet;
+use tokio::sync::Semaphore;
 use tracing::{info, warn};
 
-use crate::deploy_runtime::{evaluate_service_health, preflight_runtime_abi, resolve_target};
+use crate::concurrency::{self, BoundedJoinSet};
+use crate::deploy_runtime::{
+    configure_container_runtime, container_runtime, evaluate_replica_health,
+    evaluate_service_health, preflight_runtime_abi, replica_containers, resolve_target,
+};
 use crate::output;
+use crate::registry_mirror;
+use crate::service_profiles;
 use crate::ssh_utils::execute_remote_command;
 use crate::state::{DriftReport, HealthState, LocalState, ServerState, ServiceState};
 
+const PROVIDER_ONLY_NOTE: &str =
+    "provider mode does not inspect container runtime; use --source ssh|auto|control-plane";
+
 #[derive(Debug, Serialize)]
 struct ServerStatusRecord {
     name: String,
@@ -39,7 +50,24 @@ struct ServiceStatusRecord {
     image_origin: Option<String>,
     ports: Vec<String>,
     active_probe: Option<String>,
+    probe_result: Option<ProbeResult>,
     note: Option<String>,
+    cpu_percent: Option<f64>,
+    mem_usage: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ProbeResult {
+    ok: bool,
+    // False when nothing service-level was actually checked (no healthcheck and no ports).
+    conclusive: bool,
+    detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ContainerStats {
+    cpu_percent: Option<f64>,
+    mem_usage: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -57,12 +85,39 @@ struct StatusOutput {
     project: String,
     description: Option<String>,
     source_mode: String,
+    summary: HealthSummary,
     infrastructure: Vec<ServerStatusRecord>,
     services: Vec<ServiceStatusRecord>,
     remote_containers: Vec<RemoteContainerRecord>,
     drift: DriftReport,
 }
 
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+struct HealthSummary {
+    healthy: usize,
+    degraded: usize,
+    unhealthy: usize,
+    unknown: usize,
+    total: usize,
+}
+
+impl HealthSummary {
+    // Same buckets as the TUI summary; records without a recognised health count as unknown.
+    fn from_labels<'a>(labels: impl IntoIterator<Item = Option<&'a str>>) -> Self {
+        let mut summary = Self::default();
+        for label in labels {
+            match label {
+                Some("healthy") => summary.healthy += 1,
+                Some("degraded") => summary.degraded += 1,
+                Some("unhealthy") => summary.unhealthy += 1,
+                _ => summary.unknown += 1,
+            }
+            summary.total += 1;
+        }
+        summary
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct FlyMachineStatusLine {
     id: String,
@@ -111,6 +166,15 @@ impl SourceMode {
         }
     }
 
+    // control-plane is the fast path: local daemon plus provider APIs, no SSH fan-out.
+    fn probes_remote_inventory(self) -> bool {
+        matches!(self, Self::Auto | Self::Ssh)
+    }
+
+    fn inspects_local_runtime(self) -> bool {
+        matches!(self, Self::Auto | Self::ControlPlane)
+    }
+
     fn as_str(self) -> &'static str {
         match self {
             Self::Auto => "auto",
@@ -121,22 +185,83 @@ impl SourceMode {
     }
 }
 
-pub async fn run(
-    config_path: &str,
-    detailed: bool,
-    probe: bool,
-    provenance: bool,
-    source: &str,
-) -> Result<()> {
-    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+#[derive(Debug, Clone, Args)]
+pub struct StatusArgs {
+    #[arg(long, help = "Show detailed status")]
+    pub detailed: bool,
+    #[arg(long, help = "Run active health probes for services")]
+    pub probe: bool,
+    #[arg(long, help = "Include image/deploy provenance fields in status output")]
+    pub provenance: bool,
+    #[arg(
+        long,
+        help = "Status source-of-truth mode: auto|provider|ssh|control-plane",
+        default_value = "auto"
+    )]
+    pub source: String,
+    #[arg(long, help = "Only report the named infra server")]
+    pub server: Option<String>,
+    #[arg(long, help = "Only report the named service")]
+    pub service: Option<String>,
+    #[arg(
+        long,
+        help = "Print one line per server/service using a template like '{name} {status} {public_ip}'"
+    )]
+    pub format: Option<String>,
+}
+
+impl Default for StatusArgs {
+    fn default() -> Self {
+        Self {
+            detailed: false,
+            probe: false,
+            provenance: false,
+            source: "auto".to_string(),
+            server: None,
+            service: None,
+            format: None,
+        }
+    }
+}
+
+pub async fn run(config_path: &str, args: StatusArgs) -> Result<()> {
+    let StatusArgs {
+        detailed,
+        probe,
+        provenance,
+        ..
+    } = args;
+    let source = args.source.as_str();
+    let server_filter = args.server.as_deref();
+    let service_filter = args.service.as_deref();
+    let format = args.format.as_deref();
+    // Templated lines replace the human report; main runs `--format` in quiet mode.
+    if let Some(template) = format {
+        if output::is_json() {
+            anyhow::bail!("--format cannot be used with --json");
+        }
+        format_record(template, &BTreeMap::new())?;
+    }
+    let mut config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    registry_mirror::adopt_config(&config);
+    let profiled = service_profiles::apply_active(&mut config);
+    let scoped = server_filter.is_some() || service_filter.is_some() || profiled;
+    scope_config(&mut config, server_filter, service_filter)?;
     let mut state = LocalState::load(&config.project.name)?;
-    let drift = state.detect_drift(&config);
+    // A scoped config would report every filtered-out resource as drift.
+    let drift = if scoped {
+        DriftReport::default()
+    } else {
+        state.detect_drift(&config)
+    };
     let source_mode = SourceMode::parse(source)?;
 
     info!("Checking status for project: {}", config.project.name);
 
     let mut infra_records = Vec::new();
     let mut service_records = Vec::new();
+    let permits = concurrency::semaphore();
 
     if !output::is_json() {
         output::line("📊 Airstack Status Report");
@@ -153,7 +278,7 @@ pub async fn run(
             output::line("🏗️  Infrastructure Status:");
         }
 
-        let provider_servers = fetch_provider_servers(infra).await;
+        let provider_servers = fetch_provider_servers(infra, permits.clone()).await;
 
         for server in &infra.servers {
             match provider_servers.get(&server.provider) {
@@ -209,6 +334,8 @@ pub async fn run(
                             private_ip: found_server.private_ip.clone(),
                             server_type: Some(found_server.server_type.clone()),
                             region: Some(found_server.region.clone()),
+                            // Matched by its configured name, so it is airstack's with or
+                            // without the provider's managed marker.
                             note: None,
                         });
                     } else {
@@ -302,9 +429,10 @@ pub async fn run(
     }
 
     let mut remote_containers = Vec::new();
+    let mut remote_stats: HashMap<String, HashMap<String, ContainerStats>> = HashMap::new();
     if let Some(infra) = &config.infra {
-        let mut probe_set = JoinSet::new();
-        if source_mode == SourceMode::Auto || source_mode == SourceMode::Ssh {
+        let mut probe_set = BoundedJoinSet::new(permits.clone());
+        if source_mode.probes_remote_inventory() {
             for server_cfg in &infra.servers {
                 let cfg = server_cfg.clone();
                 probe_set.spawn(async move {
@@ -331,6 +459,28 @@ pub async fn run(
             }
         }
 
+        if detailed && source_mode.probes_remote_inventory() {
+            let mut stats_set = BoundedJoinSet::new(permits.clone());
+            for server_cfg in infra.servers.iter().filter(|s| s.provider != "fly") {
+                let cfg = server_cfg.clone();
+                stats_set.spawn(async move {
+                    let result = collect_remote_stats(&cfg).await;
+                    (cfg.name, result)
+                });
+            }
+            while let Some(joined) = stats_set.join_next().await {
+                match joined {
+                    Ok((server_name, Ok(stats))) => {
+                        remote_stats.insert(server_name, stats);
+                    }
+                    Ok((server_name, Err(e))) => {
+                        warn!("Container stats failed for {}: {}", server_name, e);
+                    }
+                    Err(e) => warn!("Container stats task failed to join: {}", e),
+                }
+            }
+        }
+
         // Preserve configured server order for stable output.
         for server_cfg in &infra.servers {
             if let Some(result) = probe_results.remove(&server_cfg.name) {
@@ -352,12 +502,19 @@ pub async fn run(
             output::line("🚀 Services Status:");
         }
 
-        let local_container_provider =
-            if source_mode == SourceMode::Auto || source_mode == SourceMode::ControlPlane {
-                get_container_provider("docker").ok()
-            } else {
-                None
-            };
+        let local_container_provider = if source_mode.inspects_local_runtime() {
+            get_container_provider(config.project.container_runtime()).ok()
+        } else {
+            None
+        };
+        let local_stats_map = if detailed && local_container_provider.is_some() {
+            collect_local_stats().await.unwrap_or_else(|e| {
+                warn!("Local container stats failed: {}", e);
+                HashMap::new()
+            })
+        } else {
+            HashMap::new()
+        };
         let mut local_observed: HashMap<String, (String, String)> = HashMap::new();
         if let Some(container_provider) = &local_container_provider {
             for service_name in services.keys() {
@@ -371,11 +528,13 @@ pub async fn run(
         }
 
         for (service_name, service_config) in services {
-            let active_probe = if probe {
-                Some(run_active_probe(&config, service_name, service_config).await)
+            let probe_result = if probe {
+                let containers = replica_containers(service_name, state.services.get(service_name));
+                Some(run_active_probe(&config, service_name, &containers, service_config).await)
             } else {
                 None
             };
+            let active_probe = probe_result.as_ref().map(|p| p.detail.clone());
             if source_mode == SourceMode::Provider {
                 let checked_at = unix_now();
                 service_records.push(ServiceStatusRecord {
@@ -402,10 +561,37 @@ pub async fn run(
                         .and_then(|s| s.image_origin.clone()),
                     ports: Vec::new(),
                     active_probe: active_probe.clone(),
-                    note: Some(
-                        "provider mode does not inspect container runtime; use --source ssh|auto|control-plane"
-                            .to_string(),
-                    ),
+                    probe_result: probe_result.clone(),
+                    note: Some(PROVIDER_ONLY_NOTE.to_string()),
+                    cpu_percent: None,
+                    mem_usage: None,
+                });
+                continue;
+            }
+
+            if let Some(paused) = state.services.get(service_name).filter(|s| s.is_paused()) {
+                if !output::is_json() {
+                    output::line(format!("   ⏸️  {} (paused, 0 replicas)", service_name));
+                }
+                service_records.push(ServiceStatusRecord {
+                    name: service_name.clone(),
+                    status: "paused".to_string(),
+                    cached_health: Some(HealthState::Unknown.as_str().to_string()),
+                    cached_last_checked_unix: Some(paused.last_checked_unix),
+                    image: None,
+                    config_image: Some(service_config.image.clone()),
+                    last_deploy_command: paused.last_deploy_command.clone(),
+                    last_deploy_unix: paused.last_deploy_unix,
+                    image_origin: paused.image_origin.clone(),
+                    ports: Vec::new(),
+                    active_probe: active_probe.clone(),
+                    probe_result: probe_result.clone(),
+                    note: Some(format!(
+                        "scaled to zero; run 'airstack scale {} <n>' to resume",
+                        service_name
+                    )),
+                    cpu_percent: None,
+                    mem_usage: None,
                 });
                 continue;
             }
@@ -414,12 +600,13 @@ pub async fn run(
                 find_remote_for_service(service_name, service_config, &remote_containers)
             {
                 let checked_at = unix_now();
-                let mut health = map_remote_container_health(&remote.status);
-                if let Some(probe_text) = &active_probe {
-                    if !probe_indicates_service_ok(probe_text) {
-                        health = HealthState::Degraded;
-                    }
-                }
+                let remote_stats = remote_stats
+                    .get(&remote.server)
+                    .and_then(|m| m.get(&remote.name));
+                let health = probed_health(
+                    map_remote_container_health(&remote.status),
+                    probe_result.as_ref(),
+                );
                 state.services.insert(
                     service_name.clone(),
                     ServiceState {
@@ -442,6 +629,10 @@ pub async fn run(
                             .services
                             .get(service_name)
                             .and_then(|s| s.image_origin.clone()),
+                        image_size_bytes: state
+                            .services
+                            .get(service_name)
+                            .and_then(|s| s.image_size_bytes),
                     },
                 );
 
@@ -458,6 +649,9 @@ pub async fn run(
                         if let Some(probe_status) = &active_probe {
                             output::line(format!("      Probe: {}", probe_status));
                         }
+                        if let Some(stats) = remote_stats {
+                            output::line(format!("      {}", format_stats_line(stats)));
+                        }
                     }
                 }
 
@@ -482,10 +676,13 @@ pub async fn run(
                         .and_then(|s| s.image_origin.clone()),
                     ports: remote.ports.clone(),
                     active_probe: active_probe.clone(),
+                    probe_result: probe_result.clone(),
                     note: local_observed
                         .get(service_name)
                         .and_then(|(local_image, local_status)| {
-                            if local_image != &remote.image || local_status != &remote.status {
+                            if !registry_mirror::same_image(local_image, &remote.image)
+                                || local_status != &remote.status
+                            {
                                 Some(format!(
                                     "mismatch: ssh={} [{}] local={} [{}]",
                                     remote.image, remote.status, local_image, local_status
@@ -495,6 +692,8 @@ pub async fn run(
                             }
                         })
                         .or_else(|| Some(format!("remote container on {}", remote.server))),
+                    cpu_percent: remote_stats.and_then(|s| s.cpu_percent),
+                    mem_usage: remote_stats.map(|s| s.mem_usage.clone()),
                 });
                 continue;
             }
@@ -502,13 +701,21 @@ pub async fn run(
             if let Some(container_provider) = &local_container_provider {
                 match container_provider.get_container(service_name).await {
                     Ok(container) => {
-                        let status_text = format!("{:?}", container.status);
-                        let mut cached_health = map_container_health(container.status.clone());
-                        if let Some(probe_text) = &active_probe {
-                            if !probe_indicates_service_ok(probe_text) {
-                                cached_health = HealthState::Degraded;
+                        let local_stats = local_stats_map.get(service_name);
+                        if detailed && !output::is_json() {
+                            if let Some(stats) = local_stats {
+                                output::line(format!(
+                                    "   • {} {}",
+                                    service_name,
+                                    format_stats_line(stats)
+                                ));
                             }
                         }
+                        let status_text = format!("{:?}", container.status);
+                        let cached_health = probed_health(
+                            map_container_health(container.status.clone()),
+                            probe_result.as_ref(),
+                        );
                         let checked_at = unix_now();
                         let replicas = state
                             .services
@@ -543,6 +750,10 @@ pub async fn run(
                                     .services
                                     .get(service_name)
                                     .and_then(|s| s.image_origin.clone()),
+                                image_size_bytes: state
+                                    .services
+                                    .get(service_name)
+                                    .and_then(|s| s.image_size_bytes),
                             },
                         );
 
@@ -575,7 +786,10 @@ pub async fn run(
                                 })
                                 .collect(),
                             active_probe: active_probe.clone(),
+                            probe_result: probe_result.clone(),
                             note: Some("local docker daemon".to_string()),
+                            cpu_percent: local_stats.and_then(|s| s.cpu_percent),
+                            mem_usage: local_stats.map(|s| s.mem_usage.clone()),
                         });
                     }
                     Err(_) => {
@@ -612,6 +826,10 @@ pub async fn run(
                                     .services
                                     .get(service_name)
                                     .and_then(|s| s.image_origin.clone()),
+                                image_size_bytes: state
+                                    .services
+                                    .get(service_name)
+                                    .and_then(|s| s.image_size_bytes),
                             },
                         );
 
@@ -636,7 +854,10 @@ pub async fn run(
                                 .and_then(|s| s.image_origin.clone()),
                             ports: Vec::new(),
                             active_probe: active_probe.clone(),
+                            probe_result: probe_result.clone(),
                             note: Some("container not found".to_string()),
+                            cpu_percent: None,
+                            mem_usage: None,
                         });
                     }
                 }
@@ -663,7 +884,10 @@ pub async fn run(
                         .and_then(|s| s.image_origin.clone()),
                     ports: Vec::new(),
                     active_probe: active_probe.clone(),
+                    probe_result: probe_result.clone(),
                     note: Some("container provider init failed".to_string()),
+                    cpu_percent: None,
+                    mem_usage: None,
                 });
             }
         }
@@ -719,11 +943,31 @@ pub async fn run(
 
     state.save()?;
 
-    if output::is_json() {
+    if let Some(template) = format {
+        for record in &infra_records {
+            println!(
+                "{}",
+                format_record(template, &server_format_fields(record))?
+            );
+        }
+        for record in &service_records {
+            println!(
+                "{}",
+                format_record(template, &service_format_fields(record))?
+            );
+        }
+    } else if output::is_json() {
+        let summary = HealthSummary::from_labels(
+            infra_records
+                .iter()
+                .map(|r| r.cached_health.as_deref())
+                .chain(service_records.iter().map(|r| r.cached_health.as_deref())),
+        );
         output::emit_json(&StatusOutput {
             project: config.project.name,
             description: config.project.description,
             source_mode: source_mode.as_str().to_string(),
+            summary,
             infrastructure: infra_records,
             services: service_records,
             remote_containers,
@@ -835,6 +1079,74 @@ fn parse_remote_container_lines(
     Ok(items)
 }
 
+const DOCKER_STATS_FORMAT: &str = "{{.Name}}\t{{.CPUPerc}}\t{{.MemUsage}}";
+
+async fn collect_remote_stats(
+    server_cfg: &ServerConfig,
+) -> Result<HashMap<String, ContainerStats>> {
+    let runtime = container_runtime();
+    let script = format!(
+        "{} stats --no-stream --format '{}'",
+        runtime, DOCKER_STATS_FORMAT
+    );
+    let out =
+        execute_remote_command(server_cfg, &["sh".to_string(), "-lc".to_string(), script]).await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "{} stats failed: {}",
+            runtime,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(parse_stats_lines(&String::from_utf8_lossy(&out.stdout)))
+}
+
+async fn collect_local_stats() -> Result<HashMap<String, ContainerStats>> {
+    let runtime = container_runtime();
+    let out = Command::new(&runtime)
+        .args(["stats", "--no-stream", "--format", DOCKER_STATS_FORMAT])
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {} stats", runtime))?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "{} stats failed: {}",
+            runtime,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(parse_stats_lines(&String::from_utf8_lossy(&out.stdout)))
+}
+
+fn parse_stats_lines(stdout: &str) -> HashMap<String, ContainerStats> {
+    let mut stats = HashMap::new();
+    for line in stdout.lines().filter(|l| !l.trim().is_empty()) {
+        let parts = line.splitn(3, '\t').map(str::trim).collect::<Vec<_>>();
+        if parts.len() < 3 || parts[0].is_empty() {
+            continue;
+        }
+        stats.insert(
+            parts[0].to_string(),
+            ContainerStats {
+                cpu_percent: parts[1].trim_end_matches('%').parse::<f64>().ok(),
+                mem_usage: parts[2].to_string(),
+            },
+        );
+    }
+    stats
+}
+
+fn format_stats_line(stats: &ContainerStats) -> String {
+    format!(
+        "cpu: {}% mem: {}",
+        stats
+            .cpu_percent
+            .map(|c| format!("{:.2}", c))
+            .unwrap_or_else(|| "?".to_string()),
+        stats.mem_usage
+    )
+}
+
 fn find_remote_for_service<'a>(
     service_name: &str,
     service_cfg: &airstack_config::ServiceConfig,
@@ -941,8 +1253,9 @@ async fn inspect_fly_workloads_for_server(
 
 async fn fetch_provider_servers(
     infra: &InfraConfig,
+    permits: Arc<Semaphore>,
 ) -> HashMap<String, Result<Vec<Server>, String>> {
-    let mut lookup_set = JoinSet::new();
+    let mut lookup_set = BoundedJoinSet::new(permits);
     let mut providers = std::collections::HashSet::new();
 
     for server in &infra.servers {
@@ -977,7 +1290,7 @@ async fn fetch_provider_servers(
     by_provider
 }
 
-fn map_server_health(status: airstack_metal::ServerStatus) -> HealthState {
+pub(crate) fn map_server_health(status: airstack_metal::ServerStatus) -> HealthState {
     use airstack_metal::ServerStatus;
 
     match status {
@@ -989,7 +1302,7 @@ fn map_server_health(status: airstack_metal::ServerStatus) -> HealthState {
     }
 }
 
-fn map_container_health(status: airstack_container::ContainerStatus) -> HealthState {
+pub(crate) fn map_container_health(status: airstack_container::ContainerStatus) -> HealthState {
     use airstack_container::ContainerStatus;
 
     match status {
@@ -1017,28 +1330,37 @@ fn map_remote_container_health(status: &str) -> HealthState {
 async fn run_active_probe(
     config: &AirstackConfig,
     service_name: &str,
+    containers: &[String],
     service_cfg: &airstack_config::ServiceConfig,
-) -> String {
-    match resolve_target(config, service_cfg, true) {
+) -> ProbeResult {
+    let conclusive =
+        service_cfg.healthcheck.is_some() || should_run_default_network_probe(service_cfg);
+    let detail = match resolve_target(config, service_cfg, true) {
         Ok(target) => {
             let abi = match preflight_runtime_abi(&target, service_name, service_cfg).await {
                 Ok(_) => "ok".to_string(),
                 Err(e) => format!("fail({})", e),
             };
 
-            let mut service_result =
-                match evaluate_service_health(&target, service_name, service_cfg, false, 1, false)
-                    .await
-                {
-                    Ok(eval) => {
-                        if eval.ok {
-                            "configured=ok".to_string()
-                        } else {
-                            format!("configured=fail({})", eval.detail)
-                        }
+            let mut service_result = match evaluate_replica_health(
+                &target,
+                service_name,
+                containers,
+                service_cfg,
+                1,
+                false,
+            )
+            .await
+            {
+                Ok(eval) => {
+                    if eval.ok {
+                        "configured=ok".to_string()
+                    } else {
+                        format!("configured=fail({})", eval.detail)
                     }
-                    Err(e) => format!("configured=error({})", e),
-                };
+                }
+                Err(e) => format!("configured=error({})", e),
+            };
 
             if should_run_default_network_probe(service_cfg) {
                 let default_probe = default_network_probe(&target, service_name, service_cfg).await;
@@ -1047,6 +1369,25 @@ async fn run_active_probe(
             format!("abi={abi}; service={service_result}")
         }
         Err(e) => format!("target-error: {}", e),
+    };
+    ProbeResult {
+        ok: probe_indicates_service_ok(&detail),
+        conclusive,
+        detail,
+    }
+}
+
+// An active probe is fresher evidence than container state: a passing conclusive probe
+// reports healthy, and a failing one degrades a container that otherwise looks fine.
+fn probed_health(container_health: HealthState, probe: Option<&ProbeResult>) -> HealthState {
+    match probe {
+        None => container_health,
+        Some(p) if !p.ok => match container_health {
+            HealthState::Unhealthy => HealthState::Unhealthy,
+            _ => HealthState::Degraded,
+        },
+        Some(p) if p.conclusive => HealthState::Healthy,
+        Some(_) => container_health,
     }
 }
 
@@ -1076,18 +1417,22 @@ async fn default_network_probe(
         interval_secs: Some(1),
         retries: Some(1),
         timeout_secs: Some(3),
+        overall_timeout_secs: None,
         http: Some(airstack_config::HttpHealthcheckConfig {
             url: None,
             path: Some("/health".to_string()),
             port: Some(port),
             expected_status: Some(200),
             timeout_secs: Some(3),
+            method: None,
+            headers: None,
         }),
         tcp: None,
         any: None,
         all: None,
+        quorum: None,
     });
-    match evaluate_service_health(target, service_name, &http_probe, false, 1, false).await {
+    match evaluate_service_health(target, service_name, service_name, &http_probe, 1, false).await {
         Ok(eval) if eval.ok => "http-ok".to_string(),
         _ => {
             let mut tcp_probe = service_cfg.clone();
@@ -1096,16 +1441,21 @@ async fn default_network_probe(
                 interval_secs: Some(1),
                 retries: Some(1),
                 timeout_secs: Some(3),
+                overall_timeout_secs: None,
                 http: None,
                 tcp: Some(airstack_config::TcpHealthcheckConfig {
                     host: Some("127.0.0.1".to_string()),
                     port,
                     timeout_secs: Some(3),
+                    from_host: Some(true),
                 }),
                 any: None,
                 all: None,
+                quorum: None,
             });
-            match evaluate_service_health(target, service_name, &tcp_probe, false, 1, false).await {
+            match evaluate_service_health(target, service_name, service_name, &tcp_probe, 1, false)
+                .await
+            {
                 Ok(eval) if eval.ok => "tcp-ok".to_string(),
                 Ok(eval) => format!("tcp-fail({})", eval.detail),
                 Err(e) => format!("tcp-error({})", e),
@@ -1114,6 +1464,139 @@ async fn default_network_probe(
     }
 }
 
+fn scope_config(
+    config: &mut AirstackConfig,
+    server_filter: Option<&str>,
+    service_filter: Option<&str>,
+) -> Result<()> {
+    let mut server_filter = server_filter.map(str::to_string);
+    if let Some(name) = service_filter {
+        let services = config.services.get_or_insert_with(HashMap::new);
+        let Some(service) = services.remove(name) else {
+            anyhow::bail!("Unknown service '{}' (not found in configuration)", name);
+        };
+        if server_filter.is_none() {
+            server_filter = service.target_server.clone();
+        }
+        services.clear();
+        services.insert(name.to_string(), service);
+    }
+    if let Some(name) = server_filter {
+        let known = config
+            .infra
+            .as_ref()
+            .is_some_and(|infra| infra.servers.iter().any(|s| s.name == name));
+        if !known {
+            anyhow::bail!("Unknown server '{}' (not found in infra.servers)", name);
+        }
+        if let Some(infra) = &mut config.infra {
+            infra.servers.retain(|s| s.name == name);
+        }
+    }
+    Ok(())
+}
+
+const FORMAT_KEYS: &[&str] = &[
+    "kind",
+    "name",
+    "status",
+    "health",
+    "public_ip",
+    "private_ip",
+    "server_type",
+    "region",
+    "image",
+    "ports",
+    "cpu_percent",
+    "mem_usage",
+    "note",
+];
+
+fn server_format_fields(record: &ServerStatusRecord) -> BTreeMap<&'static str, String> {
+    let mut fields = BTreeMap::from([
+        ("kind", "server".to_string()),
+        ("name", record.name.clone()),
+        ("status", record.status.clone()),
+    ]);
+    let optional = [
+        ("health", &record.cached_health),
+        ("public_ip", &record.public_ip),
+        ("private_ip", &record.private_ip),
+        ("server_type", &record.server_type),
+        ("region", &record.region),
+        ("note", &record.note),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            fields.insert(key, value.clone());
+        }
+    }
+    fields
+}
+
+fn service_format_fields(record: &ServiceStatusRecord) -> BTreeMap<&'static str, String> {
+    let mut fields = BTreeMap::from([
+        ("kind", "service".to_string()),
+        ("name", record.name.clone()),
+        ("status", record.status.clone()),
+    ]);
+    if !record.ports.is_empty() {
+        fields.insert("ports", record.ports.join(","));
+    }
+    if let Some(cpu) = record.cpu_percent {
+        fields.insert("cpu_percent", format!("{cpu:.2}"));
+    }
+    let optional = [
+        ("health", &record.cached_health),
+        ("image", &record.image),
+        ("mem_usage", &record.mem_usage),
+        ("note", &record.note),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            fields.insert(key, value.clone());
+        }
+    }
+    fields
+}
+
+fn format_record(template: &str, fields: &BTreeMap<&'static str, String>) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut key = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => key.push(ch),
+                        None => anyhow::bail!("Unterminated placeholder '{{{}' in --format", key),
+                    }
+                }
+                if !FORMAT_KEYS.contains(&key.as_str()) {
+                    anyhow::bail!(
+                        "Unknown placeholder '{{{}}}' in --format (valid keys: {})",
+                        key,
+                        FORMAT_KEYS.join(", ")
+                    );
+                }
+                rendered.push_str(fields.get(key.as_str()).map(String::as_str).unwrap_or("-"));
+            }
+            other => rendered.push(other),
+        }
+    }
+    Ok(rendered)
+}
+
 fn unix_now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -1124,3 +1607,192 @@ fn unix_now() -> u64 {
 fn probe_indicates_service_ok(text: &str) -> bool {
     !text.contains("fail(") && !text.contains("error(") && !text.contains("target-error")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_record, parse_stats_lines, probed_health, scope_config, ContainerStats,
+        HealthSummary, ProbeResult, SourceMode,
+    };
+    use crate::state::HealthState;
+    use airstack_config::{
+        AirstackConfig, InfraConfig, ProjectConfig, ServerConfig, ServiceConfig,
+    };
+    use std::collections::{BTreeMap, HashMap};
+
+    fn config() -> AirstackConfig {
+        let server = |name: &str| ServerConfig {
+            name: name.to_string(),
+            provider: "hetzner".to_string(),
+            region: "hel1".to_string(),
+            server_type: "cpx21".to_string(),
+            ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
+            floating_ip: None,
+            public_net: None,
+            ssh_user: None,
+            ssh_port: None,
+            labels: None,
+            user_data: None,
+        };
+        AirstackConfig {
+            project: ProjectConfig {
+                name: "demo".to_string(),
+                description: None,
+                deploy_mode: None,
+                container_runtime: None,
+                registry_mirror: None,
+            },
+            infra: Some(InfraConfig {
+                servers: vec![server("web-1"), server("web-2")],
+                firewall: None,
+                volumes: None,
+            }),
+            services: Some(HashMap::from([(
+                "api".to_string(),
+                ServiceConfig {
+                    image: "repo/api:v1".to_string(),
+                    ports: vec![8080],
+                    env: None,
+                    volumes: None,
+                    depends_on: None,
+                    target_server: Some("web-2".to_string()),
+                    healthcheck: None,
+                    profile: None,
+                    secrets: None,
+                    stop_timeout_secs: None,
+                    deploy_timeout_secs: None,
+                    verify_digest: None,
+                    env_file: None,
+                    restart_policy: None,
+                    log_max_size: None,
+                    log_max_file: None,
+                    command: None,
+                    entrypoint: None,
+                },
+            )])),
+            edge: None,
+            scripts: None,
+            hooks: None,
+            version: None,
+        }
+    }
+
+    #[test]
+    fn docker_stats_line_parses_into_cpu_and_mem() {
+        let stats = parse_stats_lines("api\t0.52%\t12.3MiB / 1.944GiB\nworker\t--\t0B / 0B\n\n");
+        assert_eq!(
+            stats.get("api"),
+            Some(&ContainerStats {
+                cpu_percent: Some(0.52),
+                mem_usage: "12.3MiB / 1.944GiB".to_string(),
+            })
+        );
+        assert_eq!(stats["worker"].cpu_percent, None);
+        assert_eq!(stats.len(), 2);
+    }
+
+    #[test]
+    fn format_record_substitutes_known_placeholders() {
+        let fields = BTreeMap::from([
+            ("name", "web-1".to_string()),
+            ("status", "Running".to_string()),
+        ]);
+        let line = format_record("{name} {status} {public_ip} {{raw}}", &fields).expect("valid");
+        assert_eq!(line, "web-1 Running - {raw}");
+    }
+
+    #[test]
+    fn format_record_rejects_unknown_placeholders() {
+        let err = format_record("{name} {uptime}", &BTreeMap::new()).expect_err("unknown key");
+        let message = err.to_string();
+        assert!(
+            message.contains("'{uptime}'"),
+            "unexpected error: {message}"
+        );
+        assert!(message.contains("valid keys: kind, name, status"));
+        assert!(format_record("{name", &BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn unknown_service_filter_is_an_error() {
+        let mut cfg = config();
+        let err = scope_config(&mut cfg, None, Some("worker")).expect_err("unknown service");
+        assert!(
+            err.to_string().contains("Unknown service 'worker'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn service_filter_scopes_servers_to_its_target() {
+        let mut cfg = config();
+        scope_config(&mut cfg, None, Some("api")).expect("known service");
+        let servers = &cfg.infra.as_ref().expect("infra").servers;
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "web-2");
+        assert!(scope_config(&mut config(), Some("web-9"), None).is_err());
+    }
+
+    #[test]
+    fn control_plane_source_skips_remote_inventory_probes() {
+        let control_plane = SourceMode::parse("control-plane").unwrap();
+        assert!(!control_plane.probes_remote_inventory());
+        assert!(control_plane.inspects_local_runtime());
+
+        assert!(SourceMode::Auto.probes_remote_inventory());
+        assert!(SourceMode::Ssh.probes_remote_inventory());
+        assert!(!SourceMode::Ssh.inspects_local_runtime());
+        assert!(!SourceMode::Provider.probes_remote_inventory());
+    }
+
+    #[test]
+    fn probe_result_overrides_container_derived_health() {
+        let probe = |ok, conclusive| ProbeResult {
+            ok,
+            conclusive,
+            detail: String::new(),
+        };
+        assert_eq!(
+            probed_health(HealthState::Unknown, Some(&probe(true, true))),
+            HealthState::Healthy
+        );
+        assert_eq!(
+            probed_health(HealthState::Degraded, Some(&probe(true, true))),
+            HealthState::Healthy
+        );
+        assert_eq!(
+            probed_health(HealthState::Healthy, Some(&probe(false, true))),
+            HealthState::Degraded
+        );
+        assert_eq!(
+            probed_health(HealthState::Unhealthy, Some(&probe(false, true))),
+            HealthState::Unhealthy
+        );
+        // Nothing service-level was checked, so the container view stands.
+        assert_eq!(
+            probed_health(HealthState::Unknown, Some(&probe(true, false))),
+            HealthState::Unknown
+        );
+        assert_eq!(
+            probed_health(HealthState::Healthy, None),
+            HealthState::Healthy
+        );
+    }
+
+    #[test]
+    fn health_summary_counts_mixed_records() {
+        let infra = [Some("healthy"), Some("unhealthy")];
+        let services = [Some("healthy"), Some("degraded"), None, Some("unknown")];
+        let summary = HealthSummary::from_labels(infra.into_iter().chain(services));
+        assert_eq!(
+            summary,
+            HealthSummary {
+                healthy: 2,
+                degraded: 1,
+                unhealthy: 1,
+                unknown: 2,
+                total: 6,
+            }
+        );
+    }
+}
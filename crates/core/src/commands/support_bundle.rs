@@ -1,16 +1,44 @@
+use crate::commands::logs::LogWindow;
+use crate::output;
 use airstack_config::AirstackConfig;
 use anyhow::{Context, Result};
 use clap::Args;
 use serde::Serialize;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug, Clone, Args)]
 pub struct SupportBundleArgs {
     #[arg(long, help = "Output directory for bundle")]
     pub out_dir: Option<String>,
+    #[arg(
+        long,
+        help = "Directory for the airstack-support-<project>-<unix>.tar.gz archive (default: current dir)"
+    )]
+    pub output_dir: Option<String>,
+    #[arg(
+        long,
+        default_value_t = true,
+        action = clap::ArgAction::Set,
+        help = "Mask password/token/secret/key values in captured output (--redact=false to disable)"
+    )]
+    pub redact: bool,
+    #[arg(
+        long,
+        help = "Collect service logs since a duration ago (30m, 1h30m), RFC3339 time, or unix time"
+    )]
+    pub since: Option<String>,
+    #[arg(
+        long,
+        help = "Collect service logs until a duration ago, RFC3339 time, or unix time"
+    )]
+    pub until: Option<String>,
 }
 
+const REDACTED: &str = "[REDACTED]";
+const SENSITIVE_KEY_MARKERS: &[&str] = &["password", "passwd", "token", "secret", "key"];
+
 #[derive(Debug, Serialize)]
 struct BundleRun {
     name: String,
@@ -25,14 +53,50 @@ struct BundleRun {
 struct BundleManifest {
     project: String,
     created_unix: u64,
+    redacted: bool,
+    log_window: Option<BundleLogWindow>,
     runs: Vec<BundleRun>,
 }
 
+#[derive(Debug, Serialize)]
+struct BundleOutput {
+    bundle_dir: String,
+    archive: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BundleLogWindow {
+    since: Option<String>,
+    until: Option<String>,
+    since_unix: Option<i64>,
+    until_unix: Option<i64>,
+}
+
+fn bundle_log_window(args: &SupportBundleArgs, window: LogWindow) -> Option<BundleLogWindow> {
+    if window.is_open() {
+        return None;
+    }
+    Some(BundleLogWindow {
+        since: args.since.clone(),
+        until: args.until.clone(),
+        since_unix: window.since,
+        until_unix: window.until,
+    })
+}
+
 pub async fn run(config_path: &str, args: SupportBundleArgs) -> Result<()> {
     let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
-    let bundle_dir = args
-        .out_dir
-        .unwrap_or_else(|| format!("support-bundle-{}", unix_now()));
+    let window = LogWindow::parse(args.since.as_deref(), args.until.as_deref())?;
+    let created_unix = unix_now();
+    let output_dir = PathBuf::from(args.output_dir.as_deref().unwrap_or("."));
+    fs::create_dir_all(&output_dir)
+        .with_context(|| format!("Failed to create output dir {}", output_dir.display()))?;
+    let bundle_dir = args.out_dir.clone().unwrap_or_else(|| {
+        output_dir
+            .join(format!("support-bundle-{}", created_unix))
+            .to_string_lossy()
+            .to_string()
+    });
     fs::create_dir_all(&bundle_dir)
         .with_context(|| format!("Failed to create bundle dir {}", bundle_dir))?;
 
@@ -40,6 +104,7 @@ pub async fn run(config_path: &str, args: SupportBundleArgs) -> Result<()> {
     runs.push(run_capture(
         "status",
         &bundle_dir,
+        args.redact,
         &[
             "--config",
             config_path,
@@ -54,6 +119,7 @@ pub async fn run(config_path: &str, args: SupportBundleArgs) -> Result<()> {
     runs.push(run_capture(
         "go-live",
         &bundle_dir,
+        args.redact,
         &[
             "--config",
             config_path,
@@ -67,6 +133,7 @@ pub async fn run(config_path: &str, args: SupportBundleArgs) -> Result<()> {
     runs.push(run_capture(
         "edge-diagnose",
         &bundle_dir,
+        args.redact,
         &["--config", config_path, "--json", "edge", "diagnose"],
     )?);
 
@@ -78,6 +145,7 @@ pub async fn run(config_path: &str, args: SupportBundleArgs) -> Result<()> {
     runs.push(run_capture(
         "registry-doctor",
         &bundle_dir,
+        args.redact,
         &[
             "--config",
             config_path,
@@ -91,17 +159,22 @@ pub async fn run(config_path: &str, args: SupportBundleArgs) -> Result<()> {
 
     if let Some(services) = &config.services {
         for service in services.keys() {
+            let log_args = log_capture_args(config_path, service, window);
+            let log_args = log_args.iter().map(String::as_str).collect::<Vec<_>>();
             runs.push(run_capture(
                 &format!("logs-{}", service),
                 &bundle_dir,
-                &["--config", config_path, "logs", service, "--tail", "200"],
+                args.redact,
+                &log_args,
             )?);
         }
     }
 
     let manifest = BundleManifest {
-        project: config.project.name,
-        created_unix: unix_now(),
+        project: config.project.name.clone(),
+        created_unix,
+        redacted: args.redact,
+        log_window: bundle_log_window(&args, window),
         runs,
     };
     fs::write(
@@ -110,11 +183,77 @@ pub async fn run(config_path: &str, args: SupportBundleArgs) -> Result<()> {
     )
     .with_context(|| format!("Failed to write manifest in {}", bundle_dir))?;
 
-    println!("✅ support bundle created at {}", bundle_dir);
+    let archive = output_dir.join(archive_name(&config.project.name, created_unix));
+    write_archive(Path::new(&bundle_dir), &archive)?;
+    let archive = archive.to_string_lossy().to_string();
+
+    if output::is_json() {
+        output::emit_json(&BundleOutput {
+            bundle_dir,
+            archive,
+        })?;
+    } else {
+        output::line(format!("✅ support bundle created at {}", bundle_dir));
+        output::line(format!("📦 archive: {}", archive));
+    }
+    Ok(())
+}
+
+fn archive_name(project: &str, created_unix: u64) -> String {
+    format!(
+        "airstack-support-{}-{}.tar.gz",
+        sanitize(project),
+        created_unix
+    )
+}
+
+fn write_archive(bundle_dir: &Path, archive: &Path) -> Result<()> {
+    let parent = bundle_dir
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = bundle_dir
+        .file_name()
+        .with_context(|| format!("Invalid bundle dir {}", bundle_dir.display()))?;
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(archive)
+        .arg("-C")
+        .arg(parent)
+        .arg(name)
+        .status()
+        .context("Failed to run tar for support bundle archive")?;
+    if !status.success() {
+        anyhow::bail!(
+            "tar failed to write support bundle archive {} ({})",
+            archive.display(),
+            status
+        );
+    }
     Ok(())
 }
 
-fn run_capture(name: &str, bundle_dir: &str, args: &[&str]) -> Result<BundleRun> {
+fn log_capture_args(config_path: &str, service: &str, window: LogWindow) -> Vec<String> {
+    let mut args = vec![
+        "--config".to_string(),
+        config_path.to_string(),
+        "logs".to_string(),
+        service.to_string(),
+    ];
+    // Pass resolved unix times so every service is bounded by the same absolute window.
+    if let Some(since) = window.since {
+        args.extend(["--since".to_string(), since.to_string()]);
+    }
+    if let Some(until) = window.until {
+        args.extend(["--until".to_string(), until.to_string()]);
+    }
+    if window.is_open() {
+        args.extend(["--tail".to_string(), "200".to_string()]);
+    }
+    args
+}
+
+fn run_capture(name: &str, bundle_dir: &str, redact: bool, args: &[&str]) -> Result<BundleRun> {
     let exe = std::env::current_exe().context("Failed to resolve current executable")?;
     let out = Command::new(exe)
         .args(args)
@@ -123,8 +262,19 @@ fn run_capture(name: &str, bundle_dir: &str, args: &[&str]) -> Result<BundleRun>
 
     let stdout_file = format!("{}/{}.stdout.log", bundle_dir, sanitize(name));
     let stderr_file = format!("{}/{}.stderr.log", bundle_dir, sanitize(name));
-    fs::write(&stdout_file, &out.stdout)?;
-    fs::write(&stderr_file, &out.stderr)?;
+    if redact {
+        fs::write(
+            &stdout_file,
+            redact_sensitive(&String::from_utf8_lossy(&out.stdout)),
+        )?;
+        fs::write(
+            &stderr_file,
+            redact_sensitive(&String::from_utf8_lossy(&out.stderr)),
+        )?;
+    } else {
+        fs::write(&stdout_file, &out.stdout)?;
+        fs::write(&stderr_file, &out.stderr)?;
+    }
 
     Ok(BundleRun {
         name: name.to_string(),
@@ -136,6 +286,105 @@ fn run_capture(name: &str, bundle_dir: &str, args: &[&str]) -> Result<BundleRun>
     })
 }
 
+pub fn redact_sensitive(text: &str) -> String {
+    text.split_inclusive('\n').map(redact_line).collect()
+}
+
+fn redact_line(line: &str) -> String {
+    let line = redact_bearer_tokens(line);
+    let bytes = line.as_bytes();
+    let mut out = String::with_capacity(line.len());
+    let mut copied = 0;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let sep = bytes[idx];
+        if sep != b'=' && sep != b':' {
+            idx += 1;
+            continue;
+        }
+        if !is_sensitive_key(key_before(&line[..idx])) {
+            idx += 1;
+            continue;
+        }
+        let (start, end) = value_span(&line, idx + 1, sep == b':');
+        if start < end && &line[start..end] != REDACTED {
+            out.push_str(&line[copied..start]);
+            out.push_str(REDACTED);
+            copied = end;
+        }
+        idx = end.max(idx + 1);
+    }
+    out.push_str(&line[copied..]);
+    out
+}
+
+fn redact_bearer_tokens(line: &str) -> String {
+    let lower = line.to_ascii_lowercase();
+    let mut out = String::with_capacity(line.len());
+    let mut copied = 0;
+    let mut search = 0;
+    while let Some(pos) = lower[search..].find("bearer ") {
+        let start = search + pos + "bearer ".len();
+        let end = line[start..]
+            .find(|c: char| c.is_whitespace() || "\"',".contains(c))
+            .map(|off| start + off)
+            .unwrap_or(line.len());
+        if start < end {
+            out.push_str(&line[copied..start]);
+            out.push_str(REDACTED);
+            copied = end;
+        }
+        search = end.max(start);
+    }
+    out.push_str(&line[copied..]);
+    out
+}
+
+fn key_before(prefix: &str) -> &str {
+    let trimmed = prefix.trim_end().trim_end_matches(['"', '\'']);
+    let start = trimmed
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || "_-.".contains(c)))
+        .map(|pos| pos + 1)
+        .unwrap_or(0);
+    &trimmed[start..]
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    !key.is_empty()
+        && SENSITIVE_KEY_MARKERS
+            .iter()
+            .any(|marker| key.contains(marker))
+}
+
+fn value_span(line: &str, from: usize, to_line_end: bool) -> (usize, usize) {
+    let rest = &line[from..];
+    let start = from + (rest.len() - rest.trim_start_matches([' ', '\t']).len());
+    match line[start..].chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let inner = start + 1;
+            let end = line[inner..]
+                .find(quote)
+                .map(|off| inner + off)
+                .unwrap_or_else(|| line.trim_end().len().max(inner));
+            (inner, end)
+        }
+        Some(_) => {
+            let stops: &[char] = if to_line_end {
+                &[',', '}', ']', '\r', '\n']
+            } else {
+                &[' ', '\t', '"', '\'', ',', '}', ']', '\r', '\n']
+            };
+            let end = line[start..]
+                .find(stops)
+                .map(|off| start + off)
+                .unwrap_or(line.len());
+            (start, line[start..end].trim_end().len() + start)
+        }
+        None => (start, start),
+    }
+}
+
 fn sanitize(value: &str) -> String {
     value
         .chars()
@@ -155,3 +404,93 @@ fn unix_now() -> u64 {
         .map(|d| d.as_secs())
         .unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        archive_name, bundle_log_window, log_capture_args, redact_sensitive, BundleManifest,
+        SupportBundleArgs,
+    };
+    use crate::commands::logs::LogWindow;
+
+    #[test]
+    fn redacts_env_assignments_and_json_values() {
+        assert_eq!(
+            redact_sensitive("POSTGRES_PASSWORD=foo\nPOSTGRES_USER=app\n"),
+            "POSTGRES_PASSWORD=[REDACTED]\nPOSTGRES_USER=app\n"
+        );
+        assert_eq!(
+            redact_sensitive(
+                r#"{"Env": ["API_TOKEN=abc123", "PORT=8080"], "secret_key": "s3cr3t"}"#
+            ),
+            r#"{"Env": ["API_TOKEN=[REDACTED]", "PORT=8080"], "secret_key": "[REDACTED]"}"#
+        );
+    }
+
+    #[test]
+    fn redacts_bearer_authorization_headers() {
+        assert_eq!(
+            redact_sensitive("Authorization: Bearer xyz\n"),
+            "Authorization: Bearer [REDACTED]\n"
+        );
+        assert_eq!(
+            redact_sensitive("curl -H 'authorization: bearer xyz' http://app:8080/health"),
+            "curl -H 'authorization: bearer [REDACTED]' http://app:8080/health"
+        );
+        assert_eq!(redact_sensitive("status: running"), "status: running");
+    }
+
+    #[test]
+    fn archive_name_embeds_project_and_timestamp() {
+        assert_eq!(
+            archive_name("demo", 1_714_566_600),
+            "airstack-support-demo-1714566600.tar.gz"
+        );
+        assert_eq!(
+            archive_name("my app/prod", 42),
+            "airstack-support-my_app_prod-42.tar.gz"
+        );
+    }
+
+    #[test]
+    fn manifest_records_requested_log_window() {
+        let args = SupportBundleArgs {
+            out_dir: None,
+            output_dir: None,
+            redact: true,
+            since: Some("30m".to_string()),
+            until: Some("2024-05-01T12:00:00Z".to_string()),
+        };
+        let now = 1_714_566_600;
+        let window =
+            LogWindow::parse_at(args.since.as_deref(), args.until.as_deref(), now).unwrap();
+        let manifest = BundleManifest {
+            project: "demo".to_string(),
+            created_unix: now as u64,
+            redacted: true,
+            log_window: bundle_log_window(&args, window),
+            runs: Vec::new(),
+        };
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["log_window"]["since"], "30m");
+        assert_eq!(json["log_window"]["until"], "2024-05-01T12:00:00Z");
+        assert_eq!(json["log_window"]["since_unix"], now - 1800);
+        assert_eq!(json["log_window"]["until_unix"], 1_714_564_800);
+        assert_eq!(
+            log_capture_args("airstack.toml", "api", window)[4..],
+            [
+                "--since".to_string(),
+                (now - 1800).to_string(),
+                "--until".to_string(),
+                "1714564800".to_string()
+            ]
+        );
+
+        let open = SupportBundleArgs {
+            since: None,
+            until: None,
+            ..args
+        };
+        assert!(bundle_log_window(&open, LogWindow::default()).is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::BTreeSet;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use airstack_config::AirstackConfig;
 use anyhow::{Context, Result};
@@ -38,6 +38,7 @@ const STONE_MUTED: PackedRgba = rgb(theme::GRAY_500);
 const STEEL_MAIN: PackedRgba = rgb(theme::STEEL_300);
 const STEEL_BRIGHT: PackedRgba = rgb(theme::STEEL_200);
 const TEXT_MAIN: PackedRgba = rgb(theme::WHITE_100);
+const ERROR_RED: PackedRgba = rgb(theme::RED_400);
 const SPINNER_FRAMES: &[&str] = &["|", "/", "-", "\\"];
 
 const fn rgb(c: theme::Rgb) -> PackedRgba {
@@ -88,6 +89,7 @@ struct TuiServer {
     cached_health: HealthState,
     cached_last_status: Option<String>,
     cached_last_checked_unix: u64,
+    cached_last_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -387,6 +389,7 @@ fn load_summary(config_path: &str) -> Result<TuiSummary> {
                         cached_health: cached.map(|s| s.health).unwrap_or(HealthState::Unknown),
                         cached_last_status: cached.and_then(|s| s.last_status.clone()),
                         cached_last_checked_unix: cached.map(|s| s.last_checked_unix).unwrap_or(0),
+                        cached_last_error: cached.and_then(|s| s.last_error.clone()),
                     }
                 })
                 .collect::<Vec<_>>()
@@ -616,7 +619,7 @@ fn render_workspace(
 
     let content = match selected_view {
         0 => render_dashboard_view(summary, &description),
-        1 => render_servers_view(summary),
+        1 => render_servers_view(summary, unix_now()),
         2 => render_services_view(summary),
         3 => render_logs_view(summary),
         4 => render_scaling_view(summary),
@@ -627,9 +630,23 @@ fn render_workspace(
         _ => "Workspace".to_string(),
     };
 
-    Paragraph::new(content)
+    Paragraph::new(content.clone())
         .style(Style::new().fg(TEXT_MAIN).bg(STONE_PANEL))
         .render(workspace, frame);
+
+    for (row, line) in content.lines().enumerate() {
+        if row >= workspace.height as usize {
+            break;
+        }
+        if line.starts_with(SERVER_ERROR_PREFIX) {
+            Paragraph::new(line.to_string())
+                .style(Style::new().fg(ERROR_RED).bg(STONE_PANEL))
+                .render(
+                    Rect::new(workspace.x, workspace.y + row as u16, workspace.width, 1),
+                    frame,
+                );
+        }
+    }
 }
 
 fn render_dashboard_view(summary: &TuiSummary, description: &str) -> String {
@@ -653,7 +670,29 @@ fn render_dashboard_view(summary: &TuiSummary, description: &str) -> String {
     )
 }
 
-fn render_servers_view(summary: &TuiSummary) -> String {
+const SERVER_ERROR_PREFIX: &str = "  error: ";
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn format_relative_time(now_unix: u64, then_unix: u64) -> String {
+    if then_unix == 0 {
+        return "never".to_string();
+    }
+    let elapsed = now_unix.saturating_sub(then_unix);
+    match elapsed {
+        0..=59 => format!("{}s ago", elapsed),
+        60..=3_599 => format!("{}m ago", elapsed / 60),
+        3_600..=86_399 => format!("{}h ago", elapsed / 3_600),
+        _ => format!("{}d ago", elapsed / 86_400),
+    }
+}
+
+fn render_servers_view(summary: &TuiSummary, now_unix: u64) -> String {
     let mut lines = vec!["server inventory".to_string(), String::new()];
 
     if summary.servers.is_empty() {
@@ -682,10 +721,14 @@ fn render_servers_view(summary: &TuiSummary) -> String {
             }
             if let Some(status) = &server.cached_last_status {
                 lines.push(format!(
-                    "  last_status: {} @ {}",
-                    status, server.cached_last_checked_unix
+                    "  last_status: {} (checked {})",
+                    status,
+                    format_relative_time(now_unix, server.cached_last_checked_unix)
                 ));
             }
+            if let Some(error) = &server.cached_last_error {
+                lines.push(format!("{}{}", SERVER_ERROR_PREFIX, error));
+            }
             lines.push(String::new());
         }
     }
@@ -1083,6 +1126,7 @@ mod tests {
                 cached_health: HealthState::Healthy,
                 cached_last_status: Some("Running".to_string()),
                 cached_last_checked_unix: 1_700_000_100,
+                cached_last_error: Some("ssh timeout".to_string()),
             }],
             services: vec![
                 TuiService {
@@ -1116,6 +1160,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn relative_time_covers_seconds_minutes_and_hours() {
+        let now = 1_700_100_000;
+        assert_eq!(format_relative_time(now, now), "0s ago");
+        assert_eq!(format_relative_time(now, now - 12), "12s ago");
+        assert_eq!(format_relative_time(now, now - 125), "2m ago");
+        assert_eq!(format_relative_time(now, now - 3 * 3_600 - 5), "3h ago");
+        assert_eq!(format_relative_time(now, now - 2 * 86_400), "2d ago");
+        assert_eq!(format_relative_time(now, 0), "never");
+        assert_eq!(format_relative_time(now, now + 30), "0s ago");
+    }
+
+    #[test]
+    fn servers_view_shows_last_error_and_check_age() {
+        let view = render_servers_view(&sample_summary(), 1_700_000_112);
+        assert!(
+            view.contains("last_status: Running (checked 12s ago)"),
+            "{view}"
+        );
+        assert!(view.lines().any(|l| l == "  error: ssh timeout"), "{view}");
+    }
+
     #[test]
     fn parse_view_index_handles_case_insensitive_names() {
         assert_eq!(parse_view_index("dashboard"), Some(0));
@@ -1,31 +1,39 @@
-use airstack_config::AirstackConfig;
+use airstack_config::{AirstackConfig, EdgeConfig, InfraConfig, ServerConfig, ServiceConfig};
 use airstack_metal::{
-    get_provider as get_metal_provider, CreateServerRequest, FirewallRuleSpec, FirewallSpec,
-    ServerStatus,
+    get_provider as get_metal_provider, BlockVolumeSpec, CreateServerRequest, FirewallRuleSpec,
+    FirewallSpec, ProviderCapabilities, ServerStatus,
 };
 use anyhow::{Context, Result};
+use clap::Args;
 use serde::Serialize;
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::future::Future;
 use std::time::Duration;
 use tracing::{info, warn};
 
 use crate::commands::edge;
 use crate::commands::script::{run_hook_scripts, ScriptRunOptions};
-use crate::dependencies::deployment_order;
+use crate::dependencies::deployment_order_subset;
 use crate::deploy_runtime::{
-    collect_container_diagnostics, deploy_service, evaluate_service_health, existing_service_image,
-    resolve_target, rollback_service,
+    apply_health_timeout, collect_container_diagnostics, configure_container_runtime,
+    deploy_service_bounded, evaluate_replica_health, evaluate_service_health,
+    existing_service_image, image_size_bytes, replica_containers, resolve_target, rollback_service,
 };
 use crate::infra_preflight::{
-    check_ssh_key_path, format_validation_error, is_permanent_provider_error,
+    check_ssh_key_path, create_request, format_validation_error, is_permanent_provider_error,
     resolve_server_request,
 };
 use crate::output;
+use crate::registry_mirror;
 use crate::retry::{retry_with_backoff_classified, RetryDecision};
+use crate::service_profiles;
 use crate::ssh_utils::execute_remote_command;
-use crate::state::{HealthState, LocalState, ServerState, ServiceState};
+use crate::state::{HealthState, LocalState, ServerState, ServiceState, VolumeState};
 use airstack_metal::CapacityResolveOptions;
 
+const EDGE_READINESS_TIMEOUT_SECS: u64 = 120;
+
 #[derive(Debug, Serialize)]
 struct UpServerRecord {
     name: String,
@@ -42,30 +50,184 @@ struct UpServiceRecord {
     container_id: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct UpVolumeRecord {
+    name: String,
+    server: String,
+    action: String,
+    id: Option<String>,
+    linux_device: Option<String>,
+    mount_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct UpEvent {
+    timestamp_unix: u64,
+    phase: String,
+    target: String,
+    status: String,
+    detail: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct UpOutput {
     project: String,
     dry_run: bool,
     servers: Vec<UpServerRecord>,
+    volumes: Vec<UpVolumeRecord>,
     services: Vec<UpServiceRecord>,
+    events: Vec<UpEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<UpTimedOut>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpTimedOut {
+    pub phase: String,
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Display for UpTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "up timed out after {}s during phase '{}'",
+            self.timeout_secs, self.phase
+        )
+    }
+}
+
+impl std::error::Error for UpTimedOut {}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CapabilityGate {
+    attach_floating_ip: bool,
+    attach_firewall: bool,
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpScope {
+    Full,
+    InfraOnly,
+    SkipInfra,
+}
+
+impl UpScope {
+    pub fn from_flags(infra_only: bool, skip_infra: bool) -> Result<Self> {
+        match (infra_only, skip_infra) {
+            (true, true) => anyhow::bail!("--infra-only and --skip-infra cannot be used together"),
+            (true, false) => Ok(Self::InfraOnly),
+            (false, true) => Ok(Self::SkipInfra),
+            (false, false) => Ok(Self::Full),
+        }
+    }
+
+    fn provisions_infra(self) -> bool {
+        self != Self::SkipInfra
+    }
+
+    fn deploys_services(self) -> bool {
+        self != Self::InfraOnly
+    }
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct UpArgs {
+    #[arg(long, help = "Target environment")]
+    pub target: Option<String>,
+    #[arg(long, help = "Infrastructure provider")]
+    pub provider: Option<String>,
+    #[arg(
+        long,
+        help = "Deploy services locally and skip infrastructure provisioning"
+    )]
+    pub local: bool,
+    #[arg(
+        long,
+        help = "Bootstrap runtime dependencies (Docker) on remote servers"
+    )]
+    pub bootstrap_runtime: bool,
+    #[arg(long, help = "Allow provider-aware fallback to default valid region")]
+    pub auto_fallback: bool,
+    #[arg(long, help = "Resolve server region/type capacity automatically")]
+    pub resolve_capacity: bool,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Only deploy these services (comma-separated) plus their dependencies"
+    )]
+    pub only: Vec<String>,
+    // Not a flag: `apply <server>` narrows provisioning to the servers it targets.
+    #[arg(skip)]
+    pub only_servers: Vec<String>,
+    #[arg(
+        long,
+        conflicts_with_all = ["skip_infra", "local", "only"],
+        help = "Provision infrastructure only; skip service deploys"
+    )]
+    pub infra_only: bool,
+    #[arg(
+        long,
+        help = "Skip infrastructure provisioning; deploy services to existing servers"
+    )]
+    pub skip_infra: bool,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Abort provisioning+deploy after SECS seconds, saving partial state"
+    )]
+    pub timeout: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Overall healthcheck deadline in seconds for each deployed service (overrides healthcheck.overall_timeout_secs)"
+    )]
+    pub health_timeout: Option<u64>,
+}
+
+impl UpArgs {
+    // Programmatic runs (apply, reconcile, the interactive cli) use the CLI defaults.
+    pub fn for_all() -> Self {
+        Self::default()
+    }
 }
 
 pub async fn run(
     config_path: &str,
-    _target: Option<String>,
-    _provider: Option<String>,
+    args: UpArgs,
     dry_run: bool,
     allow_local_deploy: bool,
-    force_local: bool,
-    bootstrap_runtime: bool,
-    auto_fallback: bool,
-    resolve_capacity: bool,
 ) -> Result<()> {
-    let config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    let scope = UpScope::from_flags(args.infra_only, args.skip_infra)?;
+    let UpArgs {
+        local: force_local,
+        bootstrap_runtime,
+        auto_fallback,
+        resolve_capacity,
+        only,
+        only_servers,
+        timeout,
+        health_timeout,
+        ..
+    } = args;
+    let mut config = AirstackConfig::load(config_path).context("Failed to load configuration")?;
+    configure_container_runtime(config.project.container_runtime());
+    service_profiles::apply_active(&mut config);
+    apply_service_health_timeouts(&mut config, health_timeout);
+    registry_mirror::adopt_config(&config);
+    let service_order = match &config.services {
+        Some(services) => deployment_order_subset(services, &only)?,
+        None if !only.is_empty() => anyhow::bail!("--only requires services in configuration"),
+        None => Vec::new(),
+    };
     let mut deploy_config = config.clone();
     if force_local {
         deploy_config.project.deploy_mode = Some("local".to_string());
     }
+    let _lock = LocalState::acquire_lock(&config.project.name)?;
     let mut state = LocalState::load(&config.project.name)?;
 
     info!(
@@ -78,7 +240,9 @@ pub async fn run(
     }
 
     let mut server_records = Vec::new();
+    let mut volume_records = Vec::new();
     let mut service_records = Vec::new();
+    let mut events = Vec::new();
 
     if force_local && !output::is_json() {
         output::line(
@@ -86,173 +250,156 @@ pub async fn run(
         );
     }
 
-    if !force_local {
-        if let Some(infra) = &config.infra {
-            if let Some(hooks) = &config.hooks {
-                if let Some(pre_provision) = &hooks.pre_provision {
-                    output::line("🔧 running pre_provision hooks");
-                    run_hook_scripts(
-                        config_path,
-                        pre_provision,
-                        ScriptRunOptions {
-                            dry_run,
-                            explain: false,
-                        },
-                    )
-                    .await
-                    .context("pre_provision hook execution failed")?;
-                }
-            }
-            let mut firewall_ids: HashMap<String, String> = HashMap::new();
-            for server in &infra.servers {
-                info!("Planning server: {} ({})", server.name, server.server_type);
-                check_ssh_key_path(server)?;
-                let preflight = resolve_server_request(
-                    server,
-                    CapacityResolveOptions {
-                        auto_fallback,
-                        resolve_capacity,
-                    },
-                )
-                .await?;
-                if !preflight.validation.valid {
-                    anyhow::bail!("{}", format_validation_error(server, &preflight));
-                }
+    match scope {
+        UpScope::InfraOnly => output::line("ℹ️ infra-only: skipping service deploys"),
+        UpScope::SkipInfra => output::line("ℹ️ skip-infra: deploying services to existing servers"),
+        UpScope::Full => {}
+    }
 
-                if dry_run {
-                    server_records.push(UpServerRecord {
-                        name: server.name.clone(),
-                        provider: server.provider.clone(),
-                        action: "plan-create".to_string(),
-                        id: None,
-                        public_ip: None,
-                    });
-                    output::line(format!(
-                        "Would create server {} ({}, {})",
-                        server.name, server.server_type, preflight.request.region
-                    ));
-                    continue;
+    let phase = Cell::new("pre-provision");
+    let flow = async {
+        if !force_local {
+            if let Some(infra) = config.infra.as_ref().filter(|_| scope.provisions_infra()) {
+                if let Some(hooks) = &config.hooks {
+                    if let Some(pre_provision) = &hooks.pre_provision {
+                        output::line("🔧 running pre_provision hooks");
+                        run_hook_scripts(
+                            config_path,
+                            pre_provision,
+                            ScriptRunOptions {
+                                dry_run,
+                                explain: false,
+                                concurrency: None,
+                            },
+                        )
+                        .await
+                        .context("pre_provision hook execution failed")?;
+                    }
                 }
+                let mut firewall_ids: HashMap<String, String> = HashMap::new();
+                phase.set("server-create");
+                for server in infra
+                    .servers
+                    .iter()
+                    .filter(|s| only_servers.is_empty() || only_servers.contains(&s.name))
+                {
+                    info!("Planning server: {} ({})", server.name, server.server_type);
+                    check_ssh_key_path(server)?;
+                    let preflight = resolve_server_request(
+                        server,
+                        CapacityResolveOptions {
+                            auto_fallback,
+                            resolve_capacity,
+                        },
+                    )
+                    .await?;
+                    if !preflight.validation.valid {
+                        anyhow::bail!("{}", format_validation_error(server, &preflight));
+                    }
 
-                let provider_config = HashMap::new();
-                let metal_provider = get_metal_provider(&server.provider, provider_config)
-                    .with_context(|| {
-                        format!("Failed to initialize {} provider", server.provider)
-                    })?;
+                    let provider_config = HashMap::new();
+                    let metal_provider = get_metal_provider(&server.provider, provider_config)
+                        .with_context(|| {
+                            format!("Failed to initialize {} provider", server.provider)
+                        })?;
+                    let gate = gate_capabilities(
+                        server,
+                        &metal_provider.capabilities(),
+                        infra.firewall.is_some(),
+                    )?;
+                    for warning in &gate.warnings {
+                        output::line(format!("⚠️  {}", warning));
+                        events.push(up_event(
+                            "capability",
+                            &server.name,
+                            "skipped",
+                            Some(warning.clone()),
+                        ));
+                    }
 
-                let existing = metal_provider
-                    .list_servers()
-                    .await
-                    .unwrap_or_default()
-                    .into_iter()
-                    .find(|s| s.name == server.name);
-
-                if let Some(existing_server) = existing {
-                    let existing_id = existing_server.id.clone();
-                    let existing_ip = existing_server.public_ip.clone();
-                    let existing_status = existing_server.status.clone();
-                    output::line(format!(
-                        "✅ Server already exists: {} ({})",
-                        existing_server.name, existing_server.id
-                    ));
-                    server_records.push(UpServerRecord {
-                        name: existing_server.name.clone(),
-                        provider: server.provider.clone(),
-                        action: "unchanged".to_string(),
-                        id: Some(existing_id.clone()),
-                        public_ip: existing_ip.clone(),
-                    });
-                    state.servers.insert(
-                        server.name.clone(),
-                        ServerState {
+                    if dry_run {
+                        server_records.push(UpServerRecord {
+                            name: server.name.clone(),
                             provider: server.provider.clone(),
-                            id: Some(existing_id),
-                            public_ip: existing_ip,
-                            health: map_server_health(existing_status.clone()),
-                            last_status: Some(format!("{:?}", existing_status)),
-                            last_checked_unix: unix_now(),
-                            last_error: None,
-                        },
-                    );
-                    if let Some(firewall) = &infra.firewall {
-                        let spec = to_firewall_spec(firewall);
-                        if let Some(fw_id) = ensure_firewall_attached(
-                            &*metal_provider,
-                            &server.provider,
-                            &existing_server.id,
-                            &spec,
-                            &mut firewall_ids,
-                        )
-                        .await?
-                        {
+                            action: "plan-create".to_string(),
+                            id: None,
+                            public_ip: None,
+                        });
+                        output::line(format!(
+                            "Would create server {} ({}, {})",
+                            server.name, server.server_type, preflight.request.region
+                        ));
+                        events.push(up_event(
+                            "server-create",
+                            &server.name,
+                            "planned",
+                            Some(preflight.request.region.clone()),
+                        ));
+                        for volume in volumes_for_server(infra, &server.name) {
                             output::line(format!(
-                                "🛡️ Firewall '{}' attached to {}",
-                                fw_id, server.name
+                                "Would attach volume {} ({}GB) to {}",
+                                volume.name, volume.size_gb, server.name
                             ));
+                            volume_records.push(UpVolumeRecord {
+                                name: volume.name.clone(),
+                                server: server.name.clone(),
+                                action: "plan-attach".to_string(),
+                                id: None,
+                                linux_device: None,
+                                mount_path: None,
+                            });
                         }
+                        continue;
                     }
-                    continue;
-                }
 
-                let request = CreateServerRequest {
-                    name: server.name.clone(),
-                    server_type: server.server_type.clone(),
-                    region: preflight.request.region.clone(),
-                    ssh_key: server.ssh_key.clone(),
-                    attach_floating_ip: server.floating_ip.unwrap_or(false),
-                };
+                    let existing = metal_provider
+                        .list_servers()
+                        .await
+                        .unwrap_or_default()
+                        .into_iter()
+                        .find(|s| s.name == server.name);
 
-                match retry_with_backoff_classified(
-                    3,
-                    Duration::from_millis(300),
-                    &format!("create server '{}'", server.name),
-                    |err| {
-                        if is_permanent_provider_error(err) {
-                            RetryDecision::Stop
-                        } else {
-                            RetryDecision::Retry
-                        }
-                    },
-                    |_| metal_provider.create_server(request.clone()),
-                )
-                .await
-                {
-                    Ok(created_server) => {
-                        let created_id = created_server.id.clone();
-                        let created_ip = created_server.public_ip.clone();
-                        let created_status = created_server.status.clone();
+                    if let Some(existing_server) = existing {
+                        let existing_id = existing_server.id.clone();
+                        let existing_ip = existing_server.public_ip.clone();
+                        let existing_status = existing_server.status.clone();
                         output::line(format!(
-                            "✅ Created server: {} ({})",
-                            created_server.name, created_server.id
+                            "✅ Server already exists: {} ({})",
+                            existing_server.name, existing_server.id
+                        ));
+                        events.push(up_event(
+                            "server-create",
+                            &server.name,
+                            "unchanged",
+                            Some(existing_id.clone()),
                         ));
-                        if let Some(ip) = &created_server.public_ip {
-                            output::line(format!("   Public IP: {}", ip));
-                        }
                         server_records.push(UpServerRecord {
-                            name: created_server.name.clone(),
+                            name: existing_server.name.clone(),
                             provider: server.provider.clone(),
-                            action: "created".to_string(),
-                            id: Some(created_id.clone()),
-                            public_ip: created_ip.clone(),
+                            action: "unchanged".to_string(),
+                            id: Some(existing_id.clone()),
+                            public_ip: existing_ip.clone(),
                         });
                         state.servers.insert(
                             server.name.clone(),
                             ServerState {
                                 provider: server.provider.clone(),
-                                id: Some(created_id),
-                                public_ip: created_ip,
-                                health: map_server_health(created_status.clone()),
-                                last_status: Some(format!("{:?}", created_status)),
+                                id: Some(existing_id),
+                                public_ip: existing_ip,
+                                health: map_server_health(existing_status.clone()),
+                                last_status: Some(format!("{:?}", existing_status)),
                                 last_checked_unix: unix_now(),
                                 last_error: None,
                             },
                         );
-                        if let Some(firewall) = &infra.firewall {
+                        if let Some(firewall) =
+                            infra.firewall.as_ref().filter(|_| gate.attach_firewall)
+                        {
                             let spec = to_firewall_spec(firewall);
                             if let Some(fw_id) = ensure_firewall_attached(
                                 &*metal_provider,
                                 &server.provider,
-                                &created_server.id,
+                                &existing_server.id,
                                 &spec,
                                 &mut firewall_ids,
                             )
@@ -262,187 +409,513 @@ pub async fn run(
                                     "🛡️ Firewall '{}' attached to {}",
                                     fw_id, server.name
                                 ));
+                                events.push(up_event(
+                                    "firewall-attach",
+                                    &server.name,
+                                    "attached",
+                                    Some(fw_id),
+                                ));
                             }
                         }
+                        attach_server_volumes(
+                            &*metal_provider,
+                            infra,
+                            &server.name,
+                            &existing_server.id,
+                            &mut state,
+                            &mut volume_records,
+                            &mut events,
+                        )
+                        .await?;
+                        continue;
                     }
-                    Err(e) => {
-                        warn!("Failed to create server {}: {}", server.name, e);
-                        return Err(e);
-                    }
-                }
-            }
 
-            if let Some(hooks) = &config.hooks {
-                if let Some(post_provision) = &hooks.post_provision {
-                    output::line("🔧 running post_provision hooks");
-                    run_hook_scripts(
-                        config_path,
-                        post_provision,
-                        ScriptRunOptions {
-                            dry_run,
-                            explain: false,
+                    let request = CreateServerRequest {
+                        region: preflight.request.region.clone(),
+                        attach_floating_ip: gate.attach_floating_ip,
+                        ..create_request(server)?
+                    };
+
+                    match retry_with_backoff_classified(
+                        3,
+                        Duration::from_millis(300),
+                        &format!("create server '{}'", server.name),
+                        |err| {
+                            if is_permanent_provider_error(err) {
+                                RetryDecision::Stop
+                            } else {
+                                RetryDecision::Retry
+                            }
                         },
+                        |_| metal_provider.create_server(request.clone()),
                     )
                     .await
-                    .context("post_provision hook execution failed")?;
+                    {
+                        Ok(created_server) => {
+                            let created_id = created_server.id.clone();
+                            let created_ip = created_server.public_ip.clone();
+                            let created_status = created_server.status.clone();
+                            output::line(format!(
+                                "✅ Created server: {} ({})",
+                                created_server.name, created_server.id
+                            ));
+                            if let Some(ip) = &created_server.public_ip {
+                                output::line(format!("   Public IP: {}", ip));
+                            }
+                            events.push(up_event(
+                                "server-create",
+                                &server.name,
+                                "created",
+                                Some(created_id.clone()),
+                            ));
+                            server_records.push(UpServerRecord {
+                                name: created_server.name.clone(),
+                                provider: server.provider.clone(),
+                                action: "created".to_string(),
+                                id: Some(created_id.clone()),
+                                public_ip: created_ip.clone(),
+                            });
+                            state.servers.insert(
+                                server.name.clone(),
+                                ServerState {
+                                    provider: server.provider.clone(),
+                                    id: Some(created_id),
+                                    public_ip: created_ip,
+                                    health: map_server_health(created_status.clone()),
+                                    last_status: Some(format!("{:?}", created_status)),
+                                    last_checked_unix: unix_now(),
+                                    last_error: None,
+                                },
+                            );
+                            if let Some(firewall) =
+                                infra.firewall.as_ref().filter(|_| gate.attach_firewall)
+                            {
+                                let spec = to_firewall_spec(firewall);
+                                if let Some(fw_id) = ensure_firewall_attached(
+                                    &*metal_provider,
+                                    &server.provider,
+                                    &created_server.id,
+                                    &spec,
+                                    &mut firewall_ids,
+                                )
+                                .await?
+                                {
+                                    output::line(format!(
+                                        "🛡️ Firewall '{}' attached to {}",
+                                        fw_id, server.name
+                                    ));
+                                    events.push(up_event(
+                                        "firewall-attach",
+                                        &server.name,
+                                        "attached",
+                                        Some(fw_id),
+                                    ));
+                                }
+                            }
+                            attach_server_volumes(
+                                &*metal_provider,
+                                infra,
+                                &server.name,
+                                &created_server.id,
+                                &mut state,
+                                &mut volume_records,
+                                &mut events,
+                            )
+                            .await?;
+                        }
+                        Err(e) => {
+                            warn!("Failed to create server {}: {}", server.name, e);
+                            events.push(up_event(
+                                "server-create",
+                                &server.name,
+                                "failed",
+                                Some(e.to_string()),
+                            ));
+                            return Err(e);
+                        }
+                    }
                 }
-            }
-        }
 
-        if bootstrap_runtime && !dry_run {
-            if let Some(infra) = &config.infra {
-                output::line("🧰 bootstrapping runtime dependencies (docker)");
-                for server in &infra.servers {
-                    ensure_runtime_bootstrap(server).await.with_context(|| {
-                        format!(
-                            "runtime bootstrap failed for server '{}'; retry with 'airstack ssh {} -- <cmd>'",
-                            server.name, server.name
+                phase.set("post-provision");
+                if let Some(hooks) = &config.hooks {
+                    if let Some(post_provision) = &hooks.post_provision {
+                        output::line("🔧 running post_provision hooks");
+                        run_hook_scripts(
+                            config_path,
+                            post_provision,
+                            ScriptRunOptions {
+                                dry_run,
+                                explain: false,
+                                concurrency: None,
+                            },
                         )
-                    })?;
+                        .await
+                        .context("post_provision hook execution failed")?;
+                    }
                 }
             }
-        }
-    }
-
-    if let Some(services) = &config.services {
-        let order = deployment_order(services, None)?;
 
-        for service_name in order {
-            let service = services.get(&service_name).with_context(|| {
-                format!("Service '{}' not found in configuration", service_name)
-            })?;
+            if bootstrap_runtime && !dry_run {
+                phase.set("runtime-bootstrap");
+                if let Some(infra) = &config.infra {
+                    output::line("🧰 bootstrapping runtime dependencies (docker)");
+                    for server in infra
+                        .servers
+                        .iter()
+                        .filter(|s| only_servers.is_empty() || only_servers.contains(&s.name))
+                    {
+                        ensure_runtime_bootstrap(server).await.with_context(|| {
+                            format!(
+                                "runtime bootstrap failed for server '{}'; retry with 'airstack ssh {} -- <cmd>'",
+                                server.name, server.name
+                            )
+                        })?;
+                    }
+                }
+            }
+        }
 
-            if dry_run {
-                output::line(format!(
-                    "Would deploy service {} -> {}",
-                    service_name, service.image
-                ));
-                service_records.push(UpServiceRecord {
-                    name: service_name,
-                    image: service.image.clone(),
-                    container_id: None,
-                });
-                continue;
+        if let Some(services) = config
+            .services
+            .as_ref()
+            .filter(|_| scope.deploys_services())
+        {
+            if !only.is_empty() {
+                output::line(format!("🎯 deploying subset: {}", service_order.join(", ")));
             }
+            let mut edge_pending = false;
+            phase.set("service-deploy");
 
-            let runtime_target =
-                resolve_target(&deploy_config, service, allow_local_deploy || force_local)?;
-            let previous_image = existing_service_image(&runtime_target, &service_name).await?;
-            let deployed = match deploy_service(&runtime_target, &service_name, service).await {
-                Ok(v) => v,
-                Err(e) => {
-                    let diag = collect_container_diagnostics(&runtime_target, &service_name).await;
-                    return Err(e).with_context(|| {
-                        format!(
-                            "Failed to deploy service {}. diagnostics: {}",
-                            service_name, diag
-                        )
+            for service_name in service_order {
+                let service = services.get(&service_name).with_context(|| {
+                    format!("Service '{}' not found in configuration", service_name)
+                })?;
+
+                if dry_run {
+                    output::line(format!(
+                        "Would deploy service {} -> {}",
+                        service_name, service.image
+                    ));
+                    events.push(up_event(
+                        "service-deploy",
+                        &service_name,
+                        "planned",
+                        Some(service.image.clone()),
+                    ));
+                    service_records.push(UpServiceRecord {
+                        name: service_name,
+                        image: service.image.clone(),
+                        container_id: None,
                     });
+                    continue;
                 }
-            };
 
-            if service.healthcheck.is_some() {
-                if let Err(err) = evaluate_service_health(
+                let runtime_target =
+                    resolve_target(&deploy_config, service, allow_local_deploy || force_local)?;
+                let previous_image = existing_service_image(&runtime_target, &service_name).await?;
+                let deployed = match deploy_service_bounded(
                     &runtime_target,
+                    &config.project.name,
                     &service_name,
                     service,
-                    false,
-                    1,
-                    false,
                 )
                 .await
-                .and_then(|eval| {
-                    if eval.ok {
-                        Ok(())
-                    } else {
-                        anyhow::bail!("{}", eval.detail)
+                {
+                    Ok(v) => v,
+                    Err(e) => {
+                        events.push(up_event(
+                            "service-deploy",
+                            &service_name,
+                            "failed",
+                            Some(e.to_string()),
+                        ));
+                        let diag =
+                            collect_container_diagnostics(&runtime_target, &service_name).await;
+                        return Err(e).with_context(|| {
+                            format!(
+                                "Failed to deploy service {}. diagnostics: {}",
+                                service_name, diag
+                            )
+                        });
                     }
-                }) {
-                    let diag = collect_container_diagnostics(&runtime_target, &service_name).await;
-                    if let Some(prev) = &previous_image {
-                        let _ =
-                            rollback_service(&runtime_target, &service_name, prev, service).await;
-                        output::line(format!(
-                            "↩️ rollback target for {} -> image {}",
-                            service_name, prev
+                };
+
+                events.push(up_event(
+                    "service-deploy",
+                    &service_name,
+                    "deployed",
+                    Some(deployed.id.clone()),
+                ));
+
+                if service.healthcheck.is_some() {
+                    let containers =
+                        replica_containers(&service_name, state.services.get(&service_name));
+                    if let Err(err) = evaluate_replica_health(
+                        &runtime_target,
+                        &service_name,
+                        &containers,
+                        service,
+                        1,
+                        false,
+                    )
+                    .await
+                    .and_then(|eval| {
+                        if eval.ok {
+                            Ok(())
+                        } else {
+                            anyhow::bail!("{}", eval.detail)
+                        }
+                    }) {
+                        events.push(up_event(
+                            "healthcheck",
+                            &service_name,
+                            "failed",
+                            Some(err.to_string()),
                         ));
+                        let diag =
+                            collect_container_diagnostics(&runtime_target, &service_name).await;
+                        if let Some(prev) = &previous_image {
+                            let _ = rollback_service(
+                                &runtime_target,
+                                &config.project.name,
+                                &service_name,
+                                prev,
+                                service,
+                            )
+                            .await;
+                            output::line(format!(
+                                "↩️ rollback target for {} -> image {}",
+                                service_name, prev
+                            ));
+                        }
+                        return Err(err).with_context(|| {
+                            format!(
+                                "Healthcheck gate failed for service '{}' (rolled back if possible). diagnostics: {}",
+                                service_name, diag
+                            )
+                        });
                     }
-                    return Err(err).with_context(|| {
-                        format!(
-                            "Healthcheck gate failed for service '{}' (rolled back if possible). diagnostics: {}",
-                            service_name, diag
-                        )
-                    });
+                    events.push(up_event("healthcheck", &service_name, "passed", None));
                 }
-            }
 
-            output::line(format!(
-                "✅ Deployed service: {} ({})",
-                service_name, deployed.id
-            ));
-            service_records.push(UpServiceRecord {
-                name: service_name.clone(),
-                image: service.image.clone(),
-                container_id: Some(deployed.id.clone()),
-            });
-            state.services.insert(
-                service_name.clone(),
-                ServiceState {
+                output::line(format!(
+                    "✅ Deployed service: {} ({})",
+                    service_name, deployed.id
+                ));
+                let image_size_bytes = image_size_bytes(&runtime_target, &service.image).await;
+                service_records.push(UpServiceRecord {
+                    name: service_name.clone(),
                     image: service.image.clone(),
-                    replicas: 1,
-                    containers: vec![service_name.clone()],
-                    health: map_container_health_text(&deployed.status),
-                    last_status: Some(deployed.status),
-                    last_checked_unix: unix_now(),
-                    last_error: None,
-                    last_deploy_command: Some(format!("airstack up {}", service_name)),
-                    last_deploy_unix: Some(unix_now()),
-                    image_origin: None,
-                },
-            );
+                    container_id: Some(deployed.id.clone()),
+                });
+                state.services.insert(
+                    service_name.clone(),
+                    ServiceState {
+                        image: service.image.clone(),
+                        replicas: 1,
+                        containers: vec![service_name.clone()],
+                        health: map_container_health_text(&deployed.status),
+                        last_status: Some(deployed.status),
+                        last_checked_unix: unix_now(),
+                        last_error: None,
+                        last_deploy_command: Some(format!("airstack up {}", service_name)),
+                        last_deploy_unix: Some(unix_now()),
+                        image_origin: None,
+                        image_size_bytes,
+                    },
+                );
 
-            if service_name == "caddy" && config.edge.is_some() {
+                if edge::is_edge_service(&service_name) && config.edge.is_some() {
+                    edge_pending = true;
+                }
+            }
+
+            if let Some(edge_cfg) = config.edge.as_ref().filter(|_| edge_pending) {
+                phase.set("edge-sync");
+                wait_for_edge_upstreams(
+                    &deploy_config,
+                    services,
+                    edge_cfg,
+                    allow_local_deploy || force_local,
+                    &mut events,
+                )
+                .await?;
                 edge::apply_from_config(&config)
                     .await
-                    .with_context(|| "Failed to sync edge config during caddy deploy")?;
-                output::line("✅ edge config reconciled during caddy deploy");
+                    .context("Failed to sync edge config during up")?;
+                output::line("✅ edge config reconciled after upstreams became healthy");
             }
-        }
 
-        if !force_local {
-            if let Some(hooks) = &config.hooks {
-                if let Some(post_deploy) = &hooks.post_deploy {
-                    output::line("🔧 running post_deploy hooks");
-                    run_hook_scripts(
-                        config_path,
-                        post_deploy,
-                        ScriptRunOptions {
-                            dry_run,
-                            explain: false,
-                        },
-                    )
-                    .await
-                    .context("post_deploy hook execution failed")?;
+            if !force_local {
+                if let Some(hooks) = &config.hooks {
+                    if let Some(post_deploy) = &hooks.post_deploy {
+                        phase.set("post-deploy");
+                        output::line("🔧 running post_deploy hooks");
+                        run_hook_scripts(
+                            config_path,
+                            post_deploy,
+                            ScriptRunOptions {
+                                dry_run,
+                                explain: false,
+                                concurrency: None,
+                            },
+                        )
+                        .await
+                        .context("post_deploy hook execution failed")?;
+                    }
                 }
             }
         }
-    }
-
-    if !dry_run {
+        Ok(())
+    };
+    let result = with_up_deadline(timeout, &phase, flow).await;
+    if let Err(err) = &result {
+        if err.downcast_ref::<UpTimedOut>().is_some() {
+            events.push(up_event(
+                "timeout",
+                phase.get(),
+                "expired",
+                Some(err.to_string()),
+            ));
+            if !dry_run {
+                state.save()?;
+            }
+            output::line(format!(
+                "⏱️ up timed out during {}; partial state saved",
+                phase.get()
+            ));
+        }
+    } else if !dry_run {
         state.save()?;
     }
 
+    // Failed runs still emit the payload so --json consumers see the failure events.
     if output::is_json() {
         output::emit_json(&UpOutput {
             project: config.project.name,
             dry_run,
             servers: server_records,
+            volumes: volume_records,
             services: service_records,
+            events,
+            error: result.as_ref().err().map(|err| format!("{:#}", err)),
+            timeout: result
+                .as_ref()
+                .err()
+                .and_then(|err| err.downcast_ref::<UpTimedOut>())
+                .cloned(),
         })?;
-    } else {
+    } else if result.is_ok() {
         output::line("🎉 Up operation completed.");
     }
 
+    result
+}
+
+// Unsupported optional features are skipped with a warning; only a missing create capability is fatal.
+fn gate_capabilities(
+    server: &ServerConfig,
+    caps: &ProviderCapabilities,
+    firewall_requested: bool,
+) -> Result<CapabilityGate> {
+    if !caps.supports_server_create {
+        anyhow::bail!(
+            "provider '{}' cannot create servers; remove server '{}' from infra or switch providers",
+            server.provider,
+            server.name
+        );
+    }
+    let mut gate = CapabilityGate {
+        attach_floating_ip: server.floating_ip.unwrap_or(false),
+        attach_firewall: firewall_requested,
+        warnings: Vec::new(),
+    };
+    if gate.attach_floating_ip && !caps.supports_public_ip {
+        gate.attach_floating_ip = false;
+        gate.warnings.push(format!(
+            "server '{}': floating_ip is not supported by provider '{}'; skipping",
+            server.name, server.provider
+        ));
+    }
+    if gate.attach_firewall && !caps.supports_firewall {
+        gate.attach_firewall = false;
+        gate.warnings.push(format!(
+            "server '{}': infra.firewall is not supported by provider '{}'; skipping",
+            server.name, server.provider
+        ));
+    }
+    Ok(gate)
+}
+
+fn apply_service_health_timeouts(config: &mut AirstackConfig, health_secs: Option<u64>) {
+    for service in config.services.iter_mut().flat_map(|s| s.values_mut()) {
+        apply_health_timeout(service, health_secs);
+    }
+}
+
+// Dropping the flow on expiry releases its borrows, so records captured so far stay usable.
+async fn with_up_deadline(
+    timeout_secs: Option<u64>,
+    phase: &Cell<&'static str>,
+    flow: impl Future<Output = Result<()>>,
+) -> Result<()> {
+    let Some(secs) = timeout_secs else {
+        return flow.await;
+    };
+    match tokio::time::timeout(Duration::from_secs(secs), flow).await {
+        Ok(result) => result,
+        Err(_) => Err(UpTimedOut {
+            phase: phase.get().to_string(),
+            timeout_secs: secs,
+        }
+        .into()),
+    }
+}
+
+async fn wait_for_edge_upstreams(
+    config: &AirstackConfig,
+    services: &HashMap<String, ServiceConfig>,
+    edge_cfg: &EdgeConfig,
+    allow_local_deploy: bool,
+    events: &mut Vec<UpEvent>,
+) -> Result<()> {
+    for upstream in edge::upstream_services(edge_cfg) {
+        let Some(service) = services.get(&upstream) else {
+            continue;
+        };
+        if service.healthcheck.is_none() {
+            events.push(up_event(
+                "edge-readiness",
+                &upstream,
+                "skipped",
+                Some("no healthcheck configured".to_string()),
+            ));
+            continue;
+        }
+        let mut gated = service.clone();
+        apply_health_timeout(&mut gated, Some(EDGE_READINESS_TIMEOUT_SECS));
+        let target = resolve_target(config, &gated, allow_local_deploy)?;
+        output::line(format!(
+            "⏳ waiting for edge upstream {} to become healthy",
+            upstream
+        ));
+        let eval = evaluate_service_health(&target, &upstream, &upstream, &gated, 1, false).await?;
+        if !eval.ok {
+            events.push(up_event(
+                "edge-readiness",
+                &upstream,
+                "failed",
+                Some(eval.detail.clone()),
+            ));
+            anyhow::bail!(
+                "Edge readiness gate: upstream '{}' did not become healthy within {}s: {}",
+                upstream,
+                EDGE_READINESS_TIMEOUT_SECS,
+                eval.detail
+            );
+        }
+        events.push(up_event("edge-readiness", &upstream, "healthy", None));
+    }
     Ok(())
 }
 
@@ -484,7 +957,117 @@ async fn ensure_firewall_attached(
     Ok(Some(fw_id))
 }
 
-async fn ensure_runtime_bootstrap(server: &airstack_config::ServerConfig) -> Result<()> {
+fn volumes_for_server<'a>(
+    infra: &'a InfraConfig,
+    server_name: &'a str,
+) -> impl Iterator<Item = &'a airstack_config::InfraVolumeConfig> {
+    infra
+        .volumes
+        .iter()
+        .flatten()
+        .filter(move |v| v.server.as_deref() == Some(server_name))
+}
+
+async fn attach_server_volumes(
+    provider: &dyn airstack_metal::MetalProvider,
+    infra: &InfraConfig,
+    server_name: &str,
+    server_id: &str,
+    state: &mut LocalState,
+    records: &mut Vec<UpVolumeRecord>,
+    events: &mut Vec<UpEvent>,
+) -> Result<()> {
+    for volume in volumes_for_server(infra, server_name) {
+        let spec = BlockVolumeSpec {
+            name: volume.name.clone(),
+            size_gb: volume.size_gb,
+            region: volume.region.clone(),
+            format: volume.format.clone(),
+        };
+        let Some(ensured) = provider
+            .ensure_volume(&spec)
+            .await
+            .with_context(|| format!("Failed to ensure volume '{}'", volume.name))?
+        else {
+            warn!(
+                "Provider for server {} does not support block volumes; skipping '{}'",
+                server_name, volume.name
+            );
+            continue;
+        };
+        let (action, attached) = match ensured.server_id.as_deref() {
+            Some(id) if id == server_id => ("unchanged", ensured),
+            Some(other) => {
+                let holder = provider
+                    .get_server(other)
+                    .await
+                    .map(|s| s.name)
+                    .unwrap_or_else(|_| other.to_string());
+                anyhow::bail!(
+                    "volume '{}' is attached to {}; detach it there before attaching it to {}",
+                    volume.name,
+                    holder,
+                    server_name
+                );
+            }
+            None => {
+                provider
+                    .attach_volume_to_server(&ensured.id, server_id)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to attach volume '{}' to {}",
+                            volume.name, server_name
+                        )
+                    })?;
+                // A freshly created volume only gets its device once it is attached.
+                let refreshed = provider
+                    .find_volume(&volume.name)
+                    .await
+                    .with_context(|| format!("Failed to look up volume '{}'", volume.name))?;
+                ("attached", refreshed.unwrap_or(ensured))
+            }
+        };
+        let device = attached
+            .linux_device
+            .clone()
+            .unwrap_or_else(|| "unknown device".to_string());
+        let location = match &attached.mount_path {
+            Some(path) => format!("{} (mounted at {})", device, path),
+            None => device,
+        };
+        output::line(format!(
+            "💾 Volume '{}' attached to {} at {}",
+            volume.name, server_name, location
+        ));
+        events.push(up_event(
+            "volume-attach",
+            server_name,
+            action,
+            Some(format!("{} {}", volume.name, location)),
+        ));
+        state.volumes.insert(
+            volume.name.clone(),
+            VolumeState {
+                server: server_name.to_string(),
+                id: attached.id.clone(),
+                linux_device: attached.linux_device.clone(),
+                mount_path: attached.mount_path.clone(),
+            },
+        );
+        records.push(UpVolumeRecord {
+            name: volume.name.clone(),
+            server: server_name.to_string(),
+            action: action.to_string(),
+            id: Some(attached.id),
+            linux_device: attached.linux_device,
+            mount_path: attached.mount_path,
+        });
+    }
+    Ok(())
+}
+
+pub async fn ensure_runtime_bootstrap(server: &airstack_config::ServerConfig) -> Result<()> {
     let script = r#"
 if command -v docker >/dev/null 2>&1; then
   exit 0
@@ -550,9 +1133,247 @@ fn map_container_health_text(status: &str) -> HealthState {
     }
 }
 
+fn up_event(phase: &str, target: &str, status: &str, detail: Option<String>) -> UpEvent {
+    UpEvent {
+        timestamp_unix: unix_now(),
+        phase: phase.to_string(),
+        target: target.to_string(),
+        status: status.to_string(),
+        detail,
+    }
+}
+
 fn unix_now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_secs())
         .unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_service_health_timeouts, gate_capabilities, up_event, with_up_deadline, UpEvent,
+        UpOutput, UpScope, UpTimedOut,
+    };
+    use crate::state::{HealthState, LocalState, ServerState};
+    use airstack_config::{AirstackConfig, ServerConfig};
+    use airstack_metal::ProviderCapabilities;
+    use std::cell::Cell;
+
+    #[test]
+    fn health_timeout_caps_every_service_healthcheck() {
+        let mut config: AirstackConfig = toml::from_str(
+            r#"
+[project]
+name = "demo"
+
+[services.api]
+image = "repo/api:v1"
+ports = [8080]
+healthcheck = { command = ["true"], overall_timeout_secs = 300 }
+
+[services.worker]
+image = "repo/worker:v1"
+ports = []
+"#,
+        )
+        .expect("config");
+        apply_service_health_timeouts(&mut config, Some(30));
+        let services = config.services.as_ref().expect("services");
+        let api = services["api"].healthcheck.as_ref().expect("healthcheck");
+        assert_eq!(api.overall_timeout_secs, Some(30));
+        assert!(services["worker"].healthcheck.is_none());
+
+        apply_service_health_timeouts(&mut config, None);
+        let api = config.services.expect("services")["api"]
+            .healthcheck
+            .clone();
+        assert_eq!(api.expect("healthcheck").overall_timeout_secs, Some(30));
+    }
+
+    #[test]
+    fn up_scope_rejects_conflicting_flags() {
+        assert_eq!(
+            UpScope::from_flags(false, false).expect("default"),
+            UpScope::Full
+        );
+        assert_eq!(
+            UpScope::from_flags(true, false).expect("infra only"),
+            UpScope::InfraOnly
+        );
+        assert_eq!(
+            UpScope::from_flags(false, true).expect("skip infra"),
+            UpScope::SkipInfra
+        );
+        let err = UpScope::from_flags(true, true).expect_err("both flags must be rejected");
+        assert!(err.to_string().contains("cannot be used together"));
+        assert!(!UpScope::InfraOnly.deploys_services());
+        assert!(!UpScope::SkipInfra.provisions_infra());
+    }
+
+    #[test]
+    fn up_events_serialize_with_expected_fields() {
+        let events: Vec<UpEvent> = vec![
+            up_event("server-create", "web-1", "created", Some("42".to_string())),
+            up_event(
+                "firewall-attach",
+                "web-1",
+                "attached",
+                Some("fw-1".to_string()),
+            ),
+            up_event("healthcheck", "api", "passed", None),
+        ];
+        let value = serde_json::to_value(&events).expect("serialize events");
+        let first = value[0].as_object().expect("event object");
+        let mut keys = first.keys().map(String::as_str).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec!["detail", "phase", "status", "target", "timestamp_unix"]
+        );
+        assert_eq!(value[0]["phase"], "server-create");
+        assert_eq!(value[1]["detail"], "fw-1");
+        assert_eq!(value[2]["target"], "api");
+        assert!(value[2]["detail"].is_null());
+    }
+
+    #[test]
+    fn up_output_reports_the_error_only_for_failed_runs() {
+        let failed = UpOutput {
+            project: "demo".to_string(),
+            dry_run: false,
+            servers: Vec::new(),
+            volumes: Vec::new(),
+            services: Vec::new(),
+            events: vec![up_event(
+                "server-create",
+                "web-1",
+                "failed",
+                Some("quota exceeded".to_string()),
+            )],
+            error: Some("quota exceeded".to_string()),
+            timeout: None,
+        };
+        let value = serde_json::to_value(&failed).expect("serialize output");
+        assert_eq!(value["error"], "quota exceeded");
+        assert_eq!(value["events"][0]["status"], "failed");
+
+        let succeeded = UpOutput {
+            error: None,
+            events: Vec::new(),
+            ..failed
+        };
+        let value = serde_json::to_value(&succeeded).expect("serialize output");
+        assert!(value.get("error").is_none());
+        assert!(value.get("timeout").is_none());
+    }
+
+    #[tokio::test]
+    async fn up_output_reports_the_expired_phase_on_timeout() {
+        let phase = Cell::new("service-deploy");
+        let flow = async {
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+            Ok(())
+        };
+        let err = with_up_deadline(Some(0), &phase, flow)
+            .await
+            .expect_err("deadline should expire");
+        let output = UpOutput {
+            project: "demo".to_string(),
+            dry_run: false,
+            servers: Vec::new(),
+            volumes: Vec::new(),
+            services: Vec::new(),
+            events: vec![up_event(
+                "timeout",
+                phase.get(),
+                "expired",
+                Some(err.to_string()),
+            )],
+            error: Some(format!("{:#}", err)),
+            timeout: err.downcast_ref::<UpTimedOut>().cloned(),
+        };
+        let value = serde_json::to_value(&output).expect("serialize output");
+        assert_eq!(value["timeout"]["phase"], "service-deploy");
+        assert_eq!(value["timeout"]["timeout_secs"], 0);
+        assert_eq!(value["events"][0]["status"], "expired");
+        assert!(value["error"]
+            .as_str()
+            .is_some_and(|e| e.contains("during phase 'service-deploy'")));
+    }
+
+    #[tokio::test]
+    async fn up_deadline_expires_without_losing_recorded_state() {
+        let mut state = LocalState::default();
+        let phase = Cell::new("pre-provision");
+        let flow = async {
+            phase.set("server-create");
+            state.servers.insert(
+                "web-1".to_string(),
+                ServerState {
+                    provider: "hetzner".to_string(),
+                    id: Some("42".to_string()),
+                    public_ip: None,
+                    health: HealthState::Unknown,
+                    last_status: None,
+                    last_checked_unix: 0,
+                    last_error: None,
+                },
+            );
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+            Ok(())
+        };
+
+        let err = with_up_deadline(Some(0), &phase, flow)
+            .await
+            .expect_err("deadline should expire");
+        let timed_out = err.downcast_ref::<UpTimedOut>().expect("UpTimedOut error");
+        assert_eq!(timed_out.phase, "server-create");
+        assert_eq!(timed_out.timeout_secs, 0);
+        assert_eq!(state.servers["web-1"].id.as_deref(), Some("42"));
+        assert!(with_up_deadline(None, &phase, async { Ok(()) })
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn floating_ip_attachment_is_gated_on_public_ip_support() {
+        let server = ServerConfig {
+            name: "web-1".to_string(),
+            provider: "fly".to_string(),
+            region: "iad".to_string(),
+            server_type: "shared-cpu-1x".to_string(),
+            ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
+            floating_ip: Some(true),
+            public_net: None,
+            ssh_user: None,
+            ssh_port: None,
+            labels: None,
+            user_data: None,
+        };
+        let mut caps = ProviderCapabilities {
+            supports_public_ip: false,
+            supports_direct_ssh: false,
+            supports_provider_ssh: true,
+            supports_server_create: true,
+            supports_server_destroy: true,
+            supports_firewall: false,
+        };
+
+        let gate = gate_capabilities(&server, &caps, true).expect("gate");
+        assert!(!gate.attach_floating_ip);
+        assert!(!gate.attach_firewall);
+        assert_eq!(gate.warnings.len(), 2);
+        assert!(gate.warnings[0].contains("floating_ip"));
+
+        caps.supports_public_ip = true;
+        caps.supports_firewall = true;
+        let gate = gate_capabilities(&server, &caps, true).expect("gate");
+        assert!(gate.attach_floating_ip && gate.attach_firewall);
+        assert!(gate.warnings.is_empty());
+
+        caps.supports_server_create = false;
+        assert!(gate_capabilities(&server, &caps, false).is_err());
+    }
+}
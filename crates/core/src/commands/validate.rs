@@ -0,0 +1,267 @@
+use crate::output;
+use crate::service_profiles;
+use airstack_config::AirstackConfig;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+struct ValidationFinding {
+    severity: String,
+    check: String,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateOutput {
+    config_path: String,
+    ok: bool,
+    errors: usize,
+    warnings: usize,
+    findings: Vec<ValidationFinding>,
+}
+
+pub async fn run(config_path: &str) -> Result<()> {
+    let findings = match AirstackConfig::load(config_path) {
+        Ok(config) => {
+            let base = Path::new(config_path)
+                .parent()
+                .unwrap_or_else(|| Path::new("."));
+            let mut findings = collect_findings(&config, base);
+            findings.extend(profile_findings(
+                &config,
+                service_profiles::active().as_deref(),
+            ));
+            findings
+        }
+        Err(e) => vec![finding("error", "schema", format!("{:#}", e))],
+    };
+
+    let errors = findings.iter().filter(|f| f.severity == "error").count();
+    let warnings = findings.len() - errors;
+    let payload = ValidateOutput {
+        config_path: config_path.to_string(),
+        ok: errors == 0,
+        errors,
+        warnings,
+        findings,
+    };
+
+    if output::is_json() {
+        output::emit_json(&payload)?;
+    } else {
+        for f in &payload.findings {
+            let mark = if f.severity == "error" {
+                "❌"
+            } else {
+                "⚠️"
+            };
+            output::line(format!("{} [{}] {}", mark, f.check, f.message));
+        }
+        if payload.ok {
+            output::line(format!(
+                "✅ {} is valid ({} warning(s))",
+                payload.config_path, payload.warnings
+            ));
+        } else {
+            output::line(format!(
+                "❌ {} has {} error(s), {} warning(s)",
+                payload.config_path, payload.errors, payload.warnings
+            ));
+        }
+    }
+
+    if !payload.ok {
+        anyhow::bail!("configuration validation failed");
+    }
+    Ok(())
+}
+
+fn collect_findings(config: &AirstackConfig, config_dir: &Path) -> Vec<ValidationFinding> {
+    let mut findings = Vec::new();
+    let server_names: HashSet<&str> = config
+        .infra
+        .as_ref()
+        .map(|i| i.servers.iter().map(|s| s.name.as_str()).collect())
+        .unwrap_or_default();
+
+    if let Some(services) = &config.services {
+        let mut names = services.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let svc = &services[name];
+            if let Some(target) = &svc.target_server {
+                if !server_names.contains(target.as_str()) {
+                    findings.push(finding(
+                        "error",
+                        "target_server",
+                        format!("service '{}' targets unknown server '{}'", name, target),
+                    ));
+                }
+            }
+            for dep in svc.depends_on.iter().flatten() {
+                if !services.contains_key(dep) {
+                    findings.push(finding(
+                        "error",
+                        "depends_on",
+                        format!("service '{}' depends on unknown service '{}'", name, dep),
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(edge) = &config.edge {
+        for site in &edge.sites {
+            let known = config
+                .services
+                .as_ref()
+                .is_some_and(|s| s.contains_key(&site.upstream_service));
+            if !known {
+                findings.push(finding(
+                    "warning",
+                    "edge",
+                    format!(
+                        "edge site '{}' upstream_service '{}' is not a configured service",
+                        site.host, site.upstream_service
+                    ),
+                ));
+            }
+        }
+    }
+
+    if let Some(scripts) = &config.scripts {
+        let mut names = scripts.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let script = &scripts[name];
+            let path = config_dir.join(&script.file);
+            if !path.is_file() {
+                findings.push(finding(
+                    "error",
+                    "script_file",
+                    format!("script '{}' file not found: {}", name, path.display()),
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+fn profile_findings(config: &AirstackConfig, profile: Option<&str>) -> Vec<ValidationFinding> {
+    let Some(profile) = profile else {
+        return Vec::new();
+    };
+    let known = config
+        .services
+        .as_ref()
+        .map(service_profiles::known_profiles)
+        .unwrap_or_default();
+    if known.contains(profile) {
+        return Vec::new();
+    }
+    let known = if known.is_empty() {
+        "none defined".to_string()
+    } else {
+        known.into_iter().collect::<Vec<_>>().join(", ")
+    };
+    vec![finding(
+        "warning",
+        "profile",
+        format!(
+            "profile '{}' is not used by any service (known: {}); only profile-less services will be selected",
+            profile, known
+        ),
+    )]
+}
+
+fn finding(severity: &str, check: &str, message: String) -> ValidationFinding {
+    ValidationFinding {
+        severity: severity.to_string(),
+        check: check.to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collect_findings;
+    use airstack_config::{
+        AirstackConfig, InfraConfig, ProjectConfig, ServerConfig, ServiceConfig,
+    };
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn config_with_target(target: &str) -> AirstackConfig {
+        AirstackConfig {
+            project: ProjectConfig {
+                name: "demo".to_string(),
+                description: None,
+                deploy_mode: None,
+                container_runtime: None,
+                registry_mirror: None,
+            },
+            infra: Some(InfraConfig {
+                servers: vec![ServerConfig {
+                    name: "web-1".to_string(),
+                    provider: "hetzner".to_string(),
+                    region: "hel1".to_string(),
+                    server_type: "cpx21".to_string(),
+                    ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
+                    floating_ip: None,
+                    public_net: None,
+                    ssh_user: None,
+                    ssh_port: None,
+                    labels: None,
+                    user_data: None,
+                }],
+                firewall: None,
+                volumes: None,
+            }),
+            services: Some(HashMap::from([(
+                "api".to_string(),
+                ServiceConfig {
+                    image: "repo/api:v1".to_string(),
+                    ports: vec![8080],
+                    env: None,
+                    volumes: None,
+                    depends_on: None,
+                    target_server: Some(target.to_string()),
+                    healthcheck: None,
+                    profile: None,
+                    secrets: None,
+                    stop_timeout_secs: None,
+                    deploy_timeout_secs: None,
+                    verify_digest: None,
+                    env_file: None,
+                    restart_policy: None,
+                    log_max_size: None,
+                    log_max_file: None,
+                    command: None,
+                    entrypoint: None,
+                },
+            )])),
+            edge: None,
+            scripts: None,
+            hooks: None,
+            version: None,
+        }
+    }
+
+    #[test]
+    fn dangling_target_server_is_an_error() {
+        let findings = collect_findings(&config_with_target("web-9"), Path::new("."));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, "error");
+        assert_eq!(findings[0].check, "target_server");
+        assert!(findings[0].message.contains("web-9"));
+    }
+
+    #[test]
+    fn resolvable_target_server_has_no_findings() {
+        let findings = collect_findings(&config_with_target("web-1"), Path::new("."));
+        assert!(findings.is_empty(), "unexpected findings: {findings:?}");
+    }
+}
@@ -0,0 +1,90 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::{JoinError, JoinSet};
+
+const ENV_PARALLEL: &str = "AIRSTACK_PARALLEL";
+pub const DEFAULT_PARALLEL: usize = 8;
+
+pub fn configure(parallel: usize) {
+    std::env::set_var(ENV_PARALLEL, parallel.max(1).to_string());
+}
+
+pub fn limit() -> usize {
+    std::env::var(ENV_PARALLEL)
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_PARALLEL)
+}
+
+pub fn semaphore() -> Arc<Semaphore> {
+    Arc::new(Semaphore::new(limit()))
+}
+
+pub struct BoundedJoinSet<T> {
+    set: JoinSet<T>,
+    permits: Arc<Semaphore>,
+}
+
+impl<T: Send + 'static> BoundedJoinSet<T> {
+    pub fn new(permits: Arc<Semaphore>) -> Self {
+        Self {
+            set: JoinSet::new(),
+            permits,
+        }
+    }
+
+    pub fn spawn<F>(&mut self, task: F)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let permits = self.permits.clone();
+        self.set.spawn(async move {
+            let _permit = permits
+                .acquire_owned()
+                .await
+                .expect("concurrency semaphore is never closed");
+            task.await
+        });
+    }
+
+    pub async fn join_next(&mut self) -> Option<Result<T, JoinError>> {
+        self.set.join_next().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundedJoinSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+
+    #[tokio::test]
+    async fn bounded_join_never_exceeds_permits() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut set = BoundedJoinSet::new(Arc::new(Semaphore::new(3)));
+        for _ in 0..20 {
+            let active = active.clone();
+            let peak = peak.clone();
+            set.spawn(async move {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        let mut joined = 0;
+        while let Some(result) = set.join_next().await {
+            result.expect("task should not panic");
+            joined += 1;
+        }
+        assert_eq!(joined, 20);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(peak.load(Ordering::SeqCst) >= 1);
+    }
+}
@@ -37,6 +37,49 @@ pub fn deployment_order(
     Ok(ordered)
 }
 
+pub fn deployment_order_subset(
+    services: &HashMap<String, ServiceConfig>,
+    only: &[String],
+) -> Result<Vec<String>> {
+    if only.is_empty() {
+        return deployment_order(services, None);
+    }
+
+    let unknown: Vec<&str> = only
+        .iter()
+        .filter(|name| !services.contains_key(name.as_str()))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        anyhow::bail!(
+            "Unknown service(s) not found in configuration: {}",
+            unknown.join(", ")
+        );
+    }
+
+    let mut ordered = Vec::new();
+    let mut visiting = HashSet::new();
+    let mut visited = HashSet::new();
+    for root in only {
+        visit(root, services, &mut visiting, &mut visited, &mut ordered)?;
+    }
+    Ok(ordered)
+}
+
+// Reverse deployment order: dependents are torn down before what they depend on.
+// A non-empty `only` keeps just those services rather than their dependencies.
+pub fn teardown_order(
+    services: &HashMap<String, ServiceConfig>,
+    only: &[String],
+) -> Result<Vec<String>> {
+    let mut ordered = deployment_order_subset(services, only)?;
+    ordered.reverse();
+    if !only.is_empty() {
+        ordered.retain(|name| only.contains(name));
+    }
+    Ok(ordered)
+}
+
 fn visit(
     service: &str,
     services: &HashMap<String, ServiceConfig>,
@@ -81,7 +124,7 @@ fn visit(
 
 #[cfg(test)]
 mod tests {
-    use super::deployment_order;
+    use super::{deployment_order, deployment_order_subset, teardown_order};
     use airstack_config::ServiceConfig;
     use std::collections::HashMap;
 
@@ -95,6 +138,16 @@ mod tests {
             target_server: None,
             healthcheck: None,
             profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
         }
     }
 
@@ -109,6 +162,47 @@ mod tests {
         assert_eq!(order, vec!["db", "api", "web"]);
     }
 
+    #[test]
+    fn subset_expands_to_transitive_dependencies() {
+        let mut services = HashMap::new();
+        services.insert("db".to_string(), svc(None));
+        services.insert("cache".to_string(), svc(Some(vec!["db"])));
+        services.insert("api".to_string(), svc(Some(vec!["cache"])));
+        services.insert("web".to_string(), svc(Some(vec!["api"])));
+        services.insert("worker".to_string(), svc(None));
+
+        let order = deployment_order_subset(&services, &["api".to_string()]).unwrap();
+        assert_eq!(order, vec!["db", "cache", "api"]);
+
+        let order =
+            deployment_order_subset(&services, &["worker".to_string(), "cache".to_string()])
+                .unwrap();
+        assert_eq!(order, vec!["worker", "db", "cache"]);
+
+        let err = deployment_order_subset(&services, &["api".to_string(), "nope".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("nope"), "{err}");
+    }
+
+    #[test]
+    fn teardown_order_removes_dependents_before_dependencies() {
+        let mut services = HashMap::new();
+        services.insert("db".to_string(), svc(None));
+        services.insert("api".to_string(), svc(Some(vec!["db"])));
+        services.insert("web".to_string(), svc(Some(vec!["api"])));
+        services.insert("cache".to_string(), svc(None));
+
+        let all = teardown_order(&services, &[]).expect("teardown order");
+        let pos = |name: &str| all.iter().position(|s| s == name).unwrap();
+        assert_eq!(all.len(), 4);
+        assert!(pos("web") < pos("api"));
+        assert!(pos("api") < pos("db"));
+
+        let scoped = teardown_order(&services, &["db".to_string(), "web".to_string()])
+            .expect("scoped teardown order");
+        assert_eq!(scoped, vec!["web".to_string(), "db".to_string()]);
+    }
+
     #[test]
     fn detects_cycles() {
         let mut services = HashMap::new();
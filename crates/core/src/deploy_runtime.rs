@@ -1,17 +1,59 @@
-use crate::ssh_utils::{execute_remote_command, join_shell_command};
+use crate::output;
+use crate::registry_mirror;
+use crate::retry::{retry_with_backoff_classified, RetryDecision};
+use crate::secrets_store;
+use crate::ssh_utils::{
+    execute_remote_command_with_stdin, join_shell_command, remote_shell_process, shell_escape,
+};
+use crate::state::{LocalState, ServiceState, VolumeState};
 use airstack_config::{
-    AirstackConfig, HealthcheckConfig, HttpHealthcheckConfig, ServerConfig, ServiceConfig,
-    TcpHealthcheckConfig,
+    image_digest, AirstackConfig, HealthcheckConfig, HttpHealthcheckConfig, ServerConfig,
+    ServiceConfig, TcpHealthcheckConfig, VolumeSpec, INFRA_VOLUME_PREFIX,
 };
 use anyhow::{Context, Result};
 use serde::Serialize;
-use std::process::Output;
-use tokio::time::{sleep, Duration};
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::process::{Output, Stdio};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::time::{sleep, Duration, Instant};
+
+pub const DEFAULT_STOP_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_RESTART_POLICY: &str = "unless-stopped";
+const REMOTE_ENV_FILE_DIR: &str = "/var/lib/airstack/env";
+const DOCKER_RETRY_ATTEMPTS: usize = 3;
+const DOCKER_RETRY_DELAY: Duration = Duration::from_millis(500);
+const ENV_FORCE_PULL: &str = "AIRSTACK_FORCE_PULL";
+const ENV_CONTAINER_RUNTIME: &str = "AIRSTACK_CONTAINER_RUNTIME";
+
+pub fn configure_force_pull(enabled: bool) {
+    std::env::set_var(ENV_FORCE_PULL, if enabled { "1" } else { "0" });
+}
+
+fn force_pull() -> bool {
+    std::env::var(ENV_FORCE_PULL).is_ok_and(|v| v == "1")
+}
+
+pub fn configure_container_runtime(runtime: &str) {
+    std::env::set_var(ENV_CONTAINER_RUNTIME, runtime);
+}
+
+// Binary every run/pull/rm/inspect script invokes; project.container_runtime picks it.
+pub fn container_runtime() -> String {
+    runtime_binary(std::env::var(ENV_CONTAINER_RUNTIME).ok().as_deref()).to_string()
+}
+
+fn runtime_binary(configured: Option<&str>) -> &'static str {
+    match configured {
+        Some("podman") => "podman",
+        _ => "docker",
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum RuntimeTarget {
     Local,
-    Remote(ServerConfig),
+    Remote(Box<ServerConfig>),
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -25,6 +67,24 @@ pub struct RuntimeDeployResult {
     pub healthy: Option<bool>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployTimedOut {
+    pub service: String,
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Display for DeployTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "deploy of service '{}' timed out after {}s (deploy_timeout_secs)",
+            self.service, self.timeout_secs
+        )
+    }
+}
+
+impl std::error::Error for DeployTimedOut {}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct HealthProbeRecord {
     pub profile: String,
@@ -45,6 +105,7 @@ pub struct HealthEvaluation {
 #[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
 pub enum DeployStrategy {
     Rolling,
+    Recreate,
     BlueGreen,
     Canary,
 }
@@ -53,10 +114,11 @@ impl DeployStrategy {
     pub fn parse(input: &str) -> Result<Self> {
         match input {
             "rolling" => Ok(Self::Rolling),
+            "recreate" => Ok(Self::Recreate),
             "bluegreen" => Ok(Self::BlueGreen),
             "canary" => Ok(Self::Canary),
             _ => anyhow::bail!(
-                "Invalid deploy strategy '{}'. Expected one of: rolling|bluegreen|canary",
+                "Invalid deploy strategy '{}'. Expected one of: rolling|recreate|bluegreen|canary",
                 input
             ),
         }
@@ -107,7 +169,7 @@ pub fn resolve_target(
                     "Remote service deploy to provider='fly' is not supported via docker runtime. Use Fly-native deploy flow"
                 );
             }
-            Ok(RuntimeTarget::Remote(server))
+            Ok(RuntimeTarget::Remote(Box::new(server)))
         }
         _ => anyhow::bail!(
             "Invalid deploy mode '{}'. Expected local|remote",
@@ -119,7 +181,10 @@ pub fn resolve_target(
 pub async fn existing_service_image(target: &RuntimeTarget, name: &str) -> Result<Option<String>> {
     let output = run_shell(
         target,
-        &format!("docker inspect -f '{{{{.Config.Image}}}}' {name} 2>/dev/null || true"),
+        &format!(
+            "{rt} inspect -f '{{{{.Config.Image}}}}' {name} 2>/dev/null || true",
+            rt = container_runtime()
+        ),
     )
     .await?;
 
@@ -135,23 +200,133 @@ pub async fn existing_service_image(target: &RuntimeTarget, name: &str) -> Resul
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExistingServiceSpec {
+    pub ports: Vec<u16>,
+    pub env: Vec<String>,
+}
+
+pub async fn existing_service_spec(
+    target: &RuntimeTarget,
+    name: &str,
+) -> Result<Option<ExistingServiceSpec>> {
+    let template = "{{range $p, $b := .HostConfig.PortBindings}}{{$p}} {{end}}{{println}}{{range .Config.Env}}{{println .}}{{end}}";
+    let output = run_shell(
+        target,
+        &format!(
+            "{rt} inspect -f {} {} 2>/dev/null || true",
+            shell_quote(template),
+            shell_quote(name),
+            rt = container_runtime(),
+        ),
+    )
+    .await?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(parse_existing_service_spec(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+fn parse_existing_service_spec(raw: &str) -> Option<ExistingServiceSpec> {
+    if raw.trim().is_empty() {
+        return None;
+    }
+    let mut lines = raw.lines();
+    let mut ports = lines
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|p| p.split('/').next()?.parse::<u16>().ok())
+        .collect::<Vec<_>>();
+    ports.sort_unstable();
+    let env = lines
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some(ExistingServiceSpec { ports, env })
+}
+
 pub async fn deploy_service(
     target: &RuntimeTarget,
+    project: &str,
     name: &str,
     service: &ServiceConfig,
 ) -> Result<RuntimeDeployResult> {
-    preflight_image_access(target, &service.image).await?;
+    let mut mirrored = service.clone();
+    mirrored.image = registry_mirror::resolve(&service.image);
+    if let Some(volumes) = mirrored.volumes.as_mut() {
+        if volumes
+            .iter()
+            .any(|v| v.trim().starts_with(INFRA_VOLUME_PREFIX))
+        {
+            let server = match target {
+                RuntimeTarget::Remote(server) => Some(server.name.as_str()),
+                RuntimeTarget::Local => None,
+            };
+            resolve_infra_volumes(volumes, &LocalState::load(project)?.volumes, server)
+                .with_context(|| format!("Failed to resolve volumes for service '{}'", name))?;
+        }
+    }
+    let service = &mirrored;
+    let secrets = load_service_secrets(project, name, service)?;
+    pull_service_image(target, service).await?;
     preflight_runtime_abi(target, name, service).await?;
     validate_remote_volumes(target, name, service).await?;
 
+    let env_file_path = match &service.env_file {
+        Some(path) => Some(stage_env_file(target, name, path).await?),
+        None => None,
+    };
+    let run_parts = docker_run_args(name, service, &secrets, env_file_path.as_deref());
+
+    let ensure_dirs = ensure_bind_mount_sources_script(&bind_mount_host_sources(service));
+
+    let script = format!(
+        "{ensure_dirs}{} {}",
+        replace_container_script(name, stop_timeout_secs(service)),
+        join_run_command(&run_parts)
+    );
+
+    let run_out = retry_with_backoff_classified(
+        DOCKER_RETRY_ATTEMPTS,
+        DOCKER_RETRY_DELAY,
+        &format!("run service '{}'", name),
+        classify_docker_error,
+        |_| async {
+            let out = run_shell(target, &script).await?;
+            if !out.status.success() {
+                let stderr = String::from_utf8_lossy(&out.stderr);
+                anyhow::bail!("Failed to deploy service '{}': {}", name, stderr.trim());
+            }
+            Ok(out)
+        },
+    )
+    .await?;
+
+    let launched_id = String::from_utf8_lossy(&run_out.stdout).trim().to_string();
+    inspect_service(target, name, Some(launched_id)).await
+}
+
+fn docker_run_args(
+    name: &str,
+    service: &ServiceConfig,
+    secrets: &BTreeMap<String, String>,
+    env_file: Option<&str>,
+) -> Vec<String> {
     let mut run_parts = vec![
-        "docker".to_string(),
+        container_runtime(),
         "run".to_string(),
         "-d".to_string(),
         "--name".to_string(),
         name.to_string(),
         "--restart".to_string(),
-        "unless-stopped".to_string(),
+        service
+            .restart_policy
+            .clone()
+            .unwrap_or_else(|| DEFAULT_RESTART_POLICY.to_string()),
     ];
 
     for port in &service.ports {
@@ -159,11 +334,15 @@ pub async fn deploy_service(
         run_parts.push(format!("{}:{}", port, port));
     }
 
-    if let Some(env) = &service.env {
-        for (key, value) in env {
-            run_parts.push("-e".to_string());
-            run_parts.push(format!("{}={}", key, value));
-        }
+    // docker applies -e after --env-file, so inline env overrides file values.
+    if let Some(path) = env_file {
+        run_parts.push("--env-file".to_string());
+        run_parts.push(path.to_string());
+    }
+
+    for (key, value) in container_env(service, secrets) {
+        run_parts.push("-e".to_string());
+        run_parts.push(format!("{}={}", key, value));
     }
 
     if let Some(vols) = &service.volumes {
@@ -173,27 +352,300 @@ pub async fn deploy_service(
         }
     }
 
+    if let Some(size) = &service.log_max_size {
+        run_parts.push("--log-opt".to_string());
+        run_parts.push(format!("max-size={}", size));
+    }
+    if let Some(files) = service.log_max_file {
+        run_parts.push("--log-opt".to_string());
+        run_parts.push(format!("max-file={}", files));
+    }
+
+    // docker's --entrypoint takes one executable; the rest of the entrypoint leads the command.
+    let (entrypoint, entrypoint_args) = match service.entrypoint.as_deref() {
+        Some([exe, args @ ..]) => (Some(exe), args),
+        _ => (None, &[][..]),
+    };
+    if let Some(exe) = entrypoint {
+        run_parts.push("--entrypoint".to_string());
+        run_parts.push(exe.clone());
+    }
+
     run_parts.push(service.image.clone());
+    run_parts.extend(entrypoint_args.iter().cloned());
+    run_parts.extend(service.command.iter().flatten().cloned());
+    run_parts
+}
 
-    let script = format!(
-        "docker rm -f {name} >/dev/null 2>&1 || true; \
+// Remote env files travel over ssh stdin so their secrets never show up in `ps`.
+async fn stage_env_file(target: &RuntimeTarget, name: &str, local_path: &str) -> Result<String> {
+    match target {
+        RuntimeTarget::Local => Ok(local_path.to_string()),
+        RuntimeTarget::Remote(server_cfg) => {
+            let content = std::fs::read(local_path)
+                .with_context(|| format!("Failed to read env_file: {}", local_path))?;
+            let remote_path = format!("{}/{}.env", REMOTE_ENV_FILE_DIR, name);
+            let out = execute_remote_command_with_stdin(
+                server_cfg,
+                &[
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    env_file_upload_script(&remote_path),
+                ],
+                &content,
+            )
+            .await?;
+            if !out.status.success() {
+                anyhow::bail!(
+                    "Failed to upload env_file for '{}': {}",
+                    name,
+                    summarize_process_failure(&out)
+                );
+            }
+            Ok(remote_path)
+        }
+    }
+}
+
+fn env_file_upload_script(remote_path: &str) -> String {
+    format!(
+        "umask 077 && mkdir -p {dir} && cat > {path} && chmod 600 {path}",
+        dir = REMOTE_ENV_FILE_DIR,
+        path = shell_quote(remote_path)
+    )
+}
+
+fn classify_docker_error(err: &anyhow::Error) -> RetryDecision {
+    if is_transient_docker_error(err) {
+        RetryDecision::Retry
+    } else {
+        RetryDecision::Stop
+    }
+}
+
+fn is_transient_docker_error(err: &anyhow::Error) -> bool {
+    let msg = format!("{:#}", err).to_ascii_lowercase();
+    let permanent = [
+        "manifest unknown",
+        "manifest for",
+        "not found",
+        "no such image",
+        "invalid reference format",
+        "unauthorized",
+        "denied",
+        "authentication required",
+        "container runtime not found",
+    ];
+    if permanent.iter().any(|p| msg.contains(p)) {
+        return false;
+    }
+    let transient = [
+        "timeout",
+        "timed out",
+        "connection reset",
+        "connection refused",
+        "tls handshake",
+        "temporary failure",
+        "network is unreachable",
+        "no route to host",
+        "unexpected eof",
+        "toomanyrequests",
+        "500 internal server error",
+        "502 bad gateway",
+        "503 service unavailable",
+        "504 gateway timeout",
+    ];
+    transient.iter().any(|t| msg.contains(t))
+}
+
+pub fn stop_timeout_secs(service: &ServiceConfig) -> u64 {
+    service
+        .stop_timeout_secs
+        .unwrap_or(DEFAULT_STOP_TIMEOUT_SECS)
+}
+
+fn replace_container_script(name: &str, stop_timeout_secs: u64) -> String {
+    format!(
+        "if {rt} container inspect {name} >/dev/null 2>&1; then \
+           {rt} stop -t {stop_timeout_secs} {name} >/dev/null 2>&1 || true; \
+         fi; \
+         {rt} rm -f {name} >/dev/null 2>&1 || true; \
          for i in 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20; do \
-           docker container inspect {name} >/dev/null 2>&1 || break; \
-           docker rm -f {name} >/dev/null 2>&1 || true; \
+           {rt} container inspect {name} >/dev/null 2>&1 || break; \
+           {rt} rm -f {name} >/dev/null 2>&1 || true; \
            sleep 0.2; \
-         done; \
-         {}",
-        join_shell_command(&run_parts)
+         done;",
+        rt = container_runtime(),
+    )
+}
+
+pub async fn stop_service_gracefully(
+    target: &RuntimeTarget,
+    name: &str,
+    stop_timeout_secs: u64,
+) -> Result<()> {
+    let out = run_shell(
+        target,
+        &format!(
+            "{rt} container inspect {name} >/dev/null 2>&1 || exit 0; {rt} stop -t {stop_timeout_secs} {name} >/dev/null",
+            name = shell_quote(name), rt = container_runtime(),
+        ),
+    )
+    .await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "Failed to stop service '{}': {}",
+            name,
+            summarize_process_failure(&out)
+        );
+    }
+    Ok(())
+}
+
+// Status and exit code; None when the container does not exist on the target.
+pub async fn container_state(
+    target: &RuntimeTarget,
+    name: &str,
+) -> Result<Option<(String, Option<i64>)>> {
+    let out = run_shell(
+        target,
+        &format!(
+            "{rt} container inspect -f '{{{{.State.Status}}}}|{{{{.State.ExitCode}}}}' {} 2>/dev/null || true",
+            shell_quote(name),
+            rt = container_runtime(),
+        ),
+    )
+    .await?;
+    let line = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let (status, exit_code) = line.split_once('|').unwrap_or((&line, ""));
+    Ok(Some((status.to_string(), exit_code.trim().parse().ok())))
+}
+
+pub async fn start_container(target: &RuntimeTarget, name: &str) -> Result<()> {
+    let out = run_shell(
+        target,
+        &format!(
+            "{rt} start {} >/dev/null",
+            shell_quote(name),
+            rt = container_runtime()
+        ),
+    )
+    .await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "Failed to start container '{}': {}",
+            name,
+            summarize_process_failure(&out)
+        );
+    }
+    Ok(())
+}
+
+// Without `-v`, anonymous volumes outlive the container; only --delete-volumes passes it.
+pub fn docker_rm_args(name: &str, remove_volumes: bool) -> Vec<String> {
+    let mut args = vec![container_runtime(), "rm".to_string(), "-f".to_string()];
+    if remove_volumes {
+        args.push("-v".to_string());
+    }
+    args.push(name.to_string());
+    args
+}
+
+pub async fn remove_service_container(
+    target: &RuntimeTarget,
+    name: &str,
+    stop_timeout_secs: u64,
+    remove_volumes: bool,
+) -> Result<()> {
+    let quoted = shell_quote(name);
+    let script = format!(
+        "if {rt} container inspect {quoted} >/dev/null 2>&1; then \
+           {rt} stop -t {stop_timeout_secs} {quoted} >/dev/null 2>&1 || true; \
+           {rm} >/dev/null 2>&1 || true; \
+         fi; \
+         {} ! {rt} container inspect {quoted} >/dev/null 2>&1",
+        replace_container_script(&quoted, stop_timeout_secs),
+        rm = join_shell_command(&docker_rm_args(name, remove_volumes)),
+        rt = container_runtime(),
     );
+    let out = run_shell(target, &script).await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "Failed to remove container '{}': {}",
+            name,
+            summarize_process_failure(&out)
+        );
+    }
+    Ok(())
+}
+
+pub async fn list_container_names(target: &RuntimeTarget) -> Result<Vec<String>> {
+    let out = run_shell(
+        target,
+        &format!("{} ps -a --format '{{{{.Names}}}}'", container_runtime()),
+    )
+    .await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "Failed to list containers: {}",
+            summarize_process_failure(&out)
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
 
-    let run_out = run_shell(target, &script).await?;
-    if !run_out.status.success() {
-        let stderr = String::from_utf8_lossy(&run_out.stderr);
-        anyhow::bail!("Failed to deploy service '{}': {}", name, stderr.trim());
+fn load_service_secrets(
+    project: &str,
+    name: &str,
+    service: &ServiceConfig,
+) -> Result<BTreeMap<String, String>> {
+    let Some(allowlist) = service.secrets.as_ref().filter(|s| !s.is_empty()) else {
+        return Ok(BTreeMap::new());
+    };
+    let all = secrets_store::all(project)?;
+    let missing = allowlist
+        .iter()
+        .filter(|key| !all.contains_key(*key))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "Service '{}' references unknown secret(s): {}. Set them with 'airstack secrets set'.",
+            name,
+            missing.join(", ")
+        );
     }
+    Ok(filter_allowed_secrets(allowlist, all))
+}
 
-    let launched_id = String::from_utf8_lossy(&run_out.stdout).trim().to_string();
-    inspect_service(target, name, Some(launched_id)).await
+fn filter_allowed_secrets(
+    allowlist: &[String],
+    all: BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    all.into_iter()
+        .filter(|(key, _)| allowlist.contains(key))
+        .collect()
+}
+
+fn container_env(
+    service: &ServiceConfig,
+    secrets: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut env = secrets.clone();
+    if let Some(service_env) = &service.env {
+        for (key, value) in service_env {
+            env.insert(key.clone(), value.clone());
+        }
+    }
+    env
 }
 
 pub async fn preflight_runtime_abi(
@@ -221,7 +673,7 @@ pub async fn preflight_runtime_abi(
 
     let executable = hc.command[0].clone();
     let probe_parts = vec![
-        "docker".to_string(),
+        container_runtime(),
         "run".to_string(),
         "--rm".to_string(),
         "--entrypoint".to_string(),
@@ -264,23 +716,182 @@ pub async fn preflight_runtime_abi(
     );
 }
 
+// The deploy runs on its own task because the shell/SSH calls underneath block; a hung
+// `docker pull` would otherwise never yield back to the timer.
+pub async fn with_deploy_timeout<T, F>(service: &str, timeout_secs: u64, fut: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T>> + Send + 'static,
+{
+    let mut handle = tokio::spawn(fut);
+    match tokio::time::timeout(Duration::from_secs(timeout_secs), &mut handle).await {
+        Ok(joined) => joined.context("deploy task panicked")?,
+        Err(_) => {
+            // Wait for the cancelled task to drop so its kill_on_drop children are
+            // gone before the caller starts a rollback against the same container.
+            handle.abort();
+            let _ = handle.await;
+            Err(DeployTimedOut {
+                service: service.to_string(),
+                timeout_secs,
+            }
+            .into())
+        }
+    }
+}
+
+async fn pull_service_image(target: &RuntimeTarget, service: &ServiceConfig) -> Result<()> {
+    let image = registry_mirror::resolve(&service.image);
+    retry_with_backoff_classified(
+        DOCKER_RETRY_ATTEMPTS,
+        DOCKER_RETRY_DELAY,
+        &format!("pull image '{}'", image),
+        classify_docker_error,
+        |_| preflight_image_access(target, &image, service.verify_digest.unwrap_or(false)),
+    )
+    .await
+}
+
+async fn rollback_candidate_image(
+    target: &RuntimeTarget,
+    name: &str,
+    service: &ServiceConfig,
+) -> Option<String> {
+    existing_service_image(target, name)
+        .await
+        .ok()
+        .flatten()
+        .filter(|image| !registry_mirror::same_image(&service.image, image))
+}
+
+pub async fn deploy_service_bounded(
+    target: &RuntimeTarget,
+    project: &str,
+    name: &str,
+    service: &ServiceConfig,
+) -> Result<RuntimeDeployResult> {
+    if service.deploy_timeout_secs.is_none() {
+        return deploy_service(target, project, name, service).await;
+    }
+    let previous_image = rollback_candidate_image(target, name, service).await;
+    deploy_service_bounded_from(target, project, name, service, previous_image).await
+}
+
+async fn deploy_service_bounded_from(
+    target: &RuntimeTarget,
+    project: &str,
+    name: &str,
+    service: &ServiceConfig,
+    previous_image: Option<String>,
+) -> Result<RuntimeDeployResult> {
+    let Some(timeout_secs) = service.deploy_timeout_secs else {
+        return deploy_service(target, project, name, service).await;
+    };
+    let deploy = {
+        let (target, project, name, service) = (
+            target.clone(),
+            project.to_string(),
+            name.to_string(),
+            service.clone(),
+        );
+        async move { deploy_service(&target, &project, &name, &service).await }
+    };
+    let err = match with_deploy_timeout(name, timeout_secs, deploy).await {
+        Ok(result) => return Ok(result),
+        Err(err) => err,
+    };
+    if err.downcast_ref::<DeployTimedOut>().is_none() {
+        return Err(err);
+    }
+
+    Err(roll_back_after(target, project, name, service, previous_image, err).await)
+}
+
+// Redeploys `previous_image` after a failed deploy and returns the original error with
+// the rollback outcome attached.
+async fn roll_back_after(
+    target: &RuntimeTarget,
+    project: &str,
+    name: &str,
+    service: &ServiceConfig,
+    previous_image: Option<String>,
+    err: anyhow::Error,
+) -> anyhow::Error {
+    let Some(prev) = previous_image else {
+        return err;
+    };
+    match rollback_service(target, project, name, &prev, service).await {
+        Ok(()) => err.context(format!("rolled back '{}' to image {}", name, prev)),
+        Err(rollback_err) => err.context(format!(
+            "rollback of '{}' to image {} also failed: {}",
+            name, prev, rollback_err
+        )),
+    }
+}
+
+pub type CanaryHook<'a> = std::pin::Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+// Edge hooks for a canary: `shift` runs once the candidate is healthy, `restore` runs
+// after promotion (or on failure) before the candidate container is removed.
+pub struct CanaryTraffic<'a> {
+    pub shift: CanaryHook<'a>,
+    pub restore: CanaryHook<'a>,
+}
+
+pub struct CanaryWindow<'a> {
+    pub seconds: u64,
+    pub traffic: Option<CanaryTraffic<'a>>,
+}
+
+impl CanaryWindow<'_> {
+    pub fn seconds(seconds: u64) -> Self {
+        Self {
+            seconds,
+            traffic: None,
+        }
+    }
+}
+
+pub fn candidate_container_name(name: &str) -> String {
+    format!("{}__candidate", name)
+}
+
 pub async fn deploy_service_with_strategy(
     target: &RuntimeTarget,
+    project: &str,
     name: &str,
     service: &ServiceConfig,
     healthcheck: Option<&HealthcheckConfig>,
     strategy: DeployStrategy,
-    canary_seconds: u64,
+    canary: CanaryWindow<'_>,
 ) -> Result<RuntimeDeployResult> {
     match strategy {
-        DeployStrategy::Rolling => deploy_service(target, name, service).await,
+        DeployStrategy::Rolling => deploy_service_bounded(target, project, name, service).await,
+        DeployStrategy::Recreate => {
+            // Accepts downtime: the old container is stopped (honouring stop_timeout_secs) and
+            // confirmed gone before the new one starts, so the two versions never overlap.
+            // The image is pulled and the rollback image captured first, so a bad
+            // reference fails while the old container is still serving. Once the old
+            // container is gone, any failure puts the previous image back.
+            let previous_image = existing_service_image(target, name).await.ok().flatten();
+            pull_service_image(target, service).await?;
+            remove_service_container(target, name, stop_timeout_secs(service), false)
+                .await
+                .with_context(|| format!("Failed to stop '{}' before recreate", name))?;
+            match deploy_service_bounded_from(target, project, name, service, None).await {
+                Ok(result) => Ok(result),
+                Err(err) => {
+                    Err(roll_back_after(target, project, name, service, previous_image, err).await)
+                }
+            }
+        }
         DeployStrategy::BlueGreen | DeployStrategy::Canary => {
             // Candidate runs without host port bindings to avoid conflicts while validating the new image.
-            let candidate_name = format!("{}__candidate", name);
+            let candidate_name = candidate_container_name(name);
             let mut candidate = service.clone();
             candidate.ports = Vec::new();
 
-            let _ = deploy_service(target, &candidate_name, &candidate).await?;
+            let _ = deploy_service_bounded(target, project, &candidate_name, &candidate).await?;
 
             if let Some(hc) = healthcheck {
                 let mut health_service = service.clone();
@@ -288,8 +899,8 @@ pub async fn deploy_service_with_strategy(
                 if let Err(err) = evaluate_service_health(
                     target,
                     &candidate_name,
+                    &candidate_name,
                     &health_service,
-                    false,
                     1,
                     false,
                 )
@@ -303,7 +914,11 @@ pub async fn deploy_service_with_strategy(
                 }) {
                     let _ = run_shell(
                         target,
-                        &format!("docker rm -f {} >/dev/null 2>&1 || true", candidate_name),
+                        &format!(
+                            "{rt} rm -f {} >/dev/null 2>&1 || true",
+                            candidate_name,
+                            rt = container_runtime()
+                        ),
                     )
                     .await;
                     return Err(err).with_context(|| {
@@ -315,25 +930,58 @@ pub async fn deploy_service_with_strategy(
                 }
             }
 
-            if strategy == DeployStrategy::Canary && canary_seconds > 0 {
-                sleep(Duration::from_secs(canary_seconds)).await;
+            let mut restore = None;
+            if strategy == DeployStrategy::Canary {
+                if let Some(traffic) = canary.traffic {
+                    if let Err(e) = traffic.shift.await {
+                        let _ = traffic.restore.await;
+                        let _ = run_shell(
+                            target,
+                            &format!(
+                                "{rt} rm -f {} >/dev/null 2>&1 || true",
+                                candidate_name,
+                                rt = container_runtime()
+                            ),
+                        )
+                        .await;
+                        return Err(e).context("Failed to route canary traffic at the edge");
+                    }
+                    restore = Some(traffic.restore);
+                }
+                if canary.seconds > 0 {
+                    sleep(Duration::from_secs(canary.seconds)).await;
+                }
             }
 
-            let promoted = match deploy_service(target, name, service).await {
+            let promoted = deploy_service_bounded(target, project, name, service).await;
+            let restored = match restore {
+                Some(hook) => hook.await,
+                None => Ok(()),
+            };
+            let promoted = match promoted {
                 Ok(v) => v,
                 Err(e) => {
                     let _ = run_shell(
                         target,
-                        &format!("docker rm -f {} >/dev/null 2>&1 || true", candidate_name),
+                        &format!(
+                            "{rt} rm -f {} >/dev/null 2>&1 || true",
+                            candidate_name,
+                            rt = container_runtime()
+                        ),
                     )
                     .await;
                     return Err(e);
                 }
             };
+            restored.context("Failed to restore edge routing after canary promotion")?;
 
             let _ = run_shell(
                 target,
-                &format!("docker rm -f {} >/dev/null 2>&1 || true", candidate_name),
+                &format!(
+                    "{rt} rm -f {} >/dev/null 2>&1 || true",
+                    candidate_name,
+                    rt = container_runtime()
+                ),
             )
             .await;
 
@@ -344,13 +992,14 @@ pub async fn deploy_service_with_strategy(
 
 pub async fn rollback_service(
     target: &RuntimeTarget,
+    project: &str,
     name: &str,
     previous_image: &str,
     service: &ServiceConfig,
 ) -> Result<()> {
     let mut rollback_cfg = service.clone();
     rollback_cfg.image = previous_image.to_string();
-    let _ = deploy_service(target, name, &rollback_cfg).await?;
+    let _ = deploy_service(target, project, name, &rollback_cfg).await?;
     Ok(())
 }
 
@@ -369,8 +1018,18 @@ pub async fn run_healthcheck(
         target_server: None,
         healthcheck: Some(healthcheck.clone()),
         profile: None,
+        secrets: None,
+        stop_timeout_secs: None,
+        deploy_timeout_secs: None,
+        verify_digest: None,
+        env_file: None,
+        restart_policy: None,
+        log_max_size: None,
+        log_max_file: None,
+        command: None,
+        entrypoint: None,
     };
-    let evaluation = evaluate_service_health(target, name, &service, false, 1, false).await?;
+    let evaluation = evaluate_service_health(target, name, name, &service, 1, false).await?;
     if evaluation.ok {
         Ok(())
     } else {
@@ -400,35 +1059,175 @@ pub async fn run_http_health_probe(
     );
 }
 
-pub async fn evaluate_service_health(
+pub fn replica_containers(service_name: &str, state: Option<&ServiceState>) -> Vec<String> {
+    match state {
+        Some(state) if !state.containers.is_empty() => state.containers.clone(),
+        _ => vec![service_name.to_string()],
+    }
+}
+
+fn replica_quorum_met(passed: usize, total: usize, quorum: Option<usize>) -> bool {
+    let required = quorum.unwrap_or(total).clamp(1, total.max(1));
+    passed >= required
+}
+
+pub async fn evaluate_replica_health(
     target: &RuntimeTarget,
     service_name: &str,
+    containers: &[String],
     service: &ServiceConfig,
-    explain: bool,
     stability_runs: u32,
     jitter: bool,
 ) -> Result<HealthEvaluation> {
-    let Some(healthcheck) = &service.healthcheck else {
-        return Ok(HealthEvaluation {
-            ok: true,
-            detail: "missing healthcheck (skipped)".to_string(),
-            records: Vec::new(),
-        });
+    if containers.len() <= 1 {
+        let container = containers
+            .first()
+            .map(String::as_str)
+            .unwrap_or(service_name);
+        return evaluate_service_health(
+            target,
+            service_name,
+            container,
+            service,
+            stability_runs,
+            jitter,
+        )
+        .await;
+    }
+
+    let mut passed = 0;
+    let mut failures = Vec::new();
+    let mut records = Vec::new();
+    for container in containers {
+        let replica_service = replica_probe_service(service, service_name, container)?;
+        let eval = evaluate_service_health(
+            target,
+            service_name,
+            container,
+            &replica_service,
+            stability_runs,
+            jitter,
+        )
+        .await?;
+        if eval.ok {
+            passed += 1;
+        } else {
+            failures.push(format!("{container}: {}", eval.detail));
+        }
+        records.extend(eval.records.into_iter().map(|mut record| {
+            record.profile = format!("{container}/{}", record.profile);
+            record
+        }));
+    }
+
+    let total = containers.len();
+    let quorum = service.healthcheck.as_ref().and_then(|hc| hc.quorum);
+    let ok = replica_quorum_met(passed, total, quorum);
+    let required = quorum.unwrap_or(total).clamp(1, total);
+    let detail = if ok {
+        format!("Healthcheck passed for {passed}/{total} replicas of '{service_name}' (required {required})")
+    } else {
+        format!(
+            "Healthcheck failed for '{service_name}': {passed}/{total} replicas healthy (required {required}); {}",
+            failures.join("; ")
+        )
+    };
+    Ok(HealthEvaluation {
+        ok,
+        detail,
+        records,
+    })
+}
+
+// Replicas publish their ports shifted by replica index (`api-3` maps 8080 to 8082),
+// so host-side probes must follow the replica's own port instead of the base one.
+fn replica_probe_service(
+    service: &ServiceConfig,
+    service_name: &str,
+    container_name: &str,
+) -> Result<ServiceConfig> {
+    let offset = container_name
+        .strip_prefix(&format!("{service_name}-"))
+        .and_then(|suffix| suffix.parse::<u16>().ok())
+        .filter(|replica| *replica > 1)
+        .map(|replica| replica - 1)
+        .unwrap_or(0);
+    let mut service = service.clone();
+    if offset == 0 {
+        return Ok(service);
+    }
+    let base_port = service.ports.first().copied();
+    if let Some(hc) = service.healthcheck.as_mut() {
+        shift_probe_ports(hc, base_port, offset)
+            .with_context(|| format!("Cannot derive probe port for replica '{container_name}'"))?;
+    }
+    Ok(service)
+}
+
+fn shift_probe_ports(
+    hc: &mut HealthcheckConfig,
+    base_port: Option<u16>,
+    offset: u16,
+) -> Result<()> {
+    if let Some(http) = hc.http.as_mut() {
+        if http.url.is_none() {
+            if let Some(port) = http.port.or(base_port) {
+                http.port = Some(port.checked_add(offset).context("port overflow")?);
+            }
+        }
+    }
+    if let Some(tcp) = hc.tcp.as_mut() {
+        if tcp.from_host != Some(false) {
+            tcp.port = tcp.port.checked_add(offset).context("port overflow")?;
+        }
+    }
+    for child in hc
+        .all
+        .iter_mut()
+        .flatten()
+        .chain(hc.any.iter_mut().flatten())
+    {
+        shift_probe_ports(child, base_port, offset)?;
+    }
+    Ok(())
+}
+
+pub async fn evaluate_service_health(
+    target: &RuntimeTarget,
+    service_name: &str,
+    container_name: &str,
+    service: &ServiceConfig,
+    stability_runs: u32,
+    jitter: bool,
+) -> Result<HealthEvaluation> {
+    let Some(healthcheck) = &service.healthcheck else {
+        return Ok(HealthEvaluation {
+            ok: true,
+            detail: "missing healthcheck (skipped)".to_string(),
+            records: Vec::new(),
+        });
     };
 
     let runs = stability_runs.max(1);
     let mut run_summaries = Vec::new();
     let mut all_records = Vec::new();
     let mut overall_ok = true;
+    let overall_timeout = healthcheck.overall_timeout_secs;
+    let deadline = overall_timeout.map(|secs| Instant::now() + Duration::from_secs(secs));
 
     for run_idx in 1..=runs {
+        if deadline_passed(deadline) {
+            overall_ok = false;
+            break;
+        }
         let mut records = Vec::new();
         let ok = evaluate_profile(
             target,
-            service_name,
+            container_name,
             service,
             healthcheck,
             "root",
+            deadline,
             &mut records,
         )
         .await?;
@@ -436,9 +1235,9 @@ pub async fn evaluate_service_health(
             overall_ok = false;
         }
         run_summaries.push(format!("run#{run_idx}:{}", if ok { "ok" } else { "fail" }));
-        if explain {
-            all_records.extend(records);
-        }
+        // Records are always kept so --json output carries every probe; callers
+        // decide whether to show them in human output.
+        all_records.extend(records);
         if jitter && run_idx < runs {
             let pause_ms = ((run_idx as u64 * 137) % 400) + 100;
             sleep(Duration::from_millis(pause_ms)).await;
@@ -447,6 +1246,8 @@ pub async fn evaluate_service_health(
 
     let detail = if overall_ok {
         format!("Healthcheck passed for service '{service_name}'")
+    } else if let Some(secs) = overall_timeout.filter(|_| deadline_passed(deadline)) {
+        format!("Healthcheck for service '{service_name}' timed out after {secs}s")
     } else {
         format!(
             "Healthcheck failed for service '{service_name}': {}",
@@ -463,19 +1264,31 @@ pub async fn evaluate_service_health(
 
 fn evaluate_profile<'a>(
     target: &'a RuntimeTarget,
-    service_name: &'a str,
+    container_name: &'a str,
     service: &'a ServiceConfig,
     hc: &'a HealthcheckConfig,
     profile_name: &'a str,
+    deadline: Option<Instant>,
     records: &'a mut Vec<HealthProbeRecord>,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<bool>> + Send + 'a>> {
     Box::pin(async move {
         if let Some(all_profiles) = &hc.all {
             let mut ok = true;
             for (idx, child) in all_profiles.iter().enumerate() {
+                if deadline_passed(deadline) {
+                    return Ok(false);
+                }
                 let child_name = format!("{profile_name}.all[{idx}]");
-                if !evaluate_profile(target, service_name, service, child, &child_name, records)
-                    .await?
+                if !evaluate_profile(
+                    target,
+                    container_name,
+                    service,
+                    child,
+                    &child_name,
+                    deadline,
+                    records,
+                )
+                .await?
                 {
                     ok = false;
                 }
@@ -486,9 +1299,20 @@ fn evaluate_profile<'a>(
         if let Some(any_profiles) = &hc.any {
             let mut ok = false;
             for (idx, child) in any_profiles.iter().enumerate() {
+                if deadline_passed(deadline) {
+                    break;
+                }
                 let child_name = format!("{profile_name}.any[{idx}]");
-                if evaluate_profile(target, service_name, service, child, &child_name, records)
-                    .await?
+                if evaluate_profile(
+                    target,
+                    container_name,
+                    service,
+                    child,
+                    &child_name,
+                    deadline,
+                    records,
+                )
+                .await?
                 {
                     ok = true;
                 }
@@ -496,37 +1320,75 @@ fn evaluate_profile<'a>(
             return Ok(ok);
         }
 
+        if hc.command.is_empty() && hc.http.is_none() && hc.tcp.is_none() {
+            anyhow::bail!(
+                "No executable health profile for service '{}'",
+                container_name
+            );
+        }
+
         let retries = hc.retries.unwrap_or(10);
         let interval = Duration::from_secs(hc.interval_secs.unwrap_or(5));
-        let mut last_record = None;
-
-        for _ in 0..retries {
-            let record = if !hc.command.is_empty() {
-                execute_command_probe(target, service_name, &hc.command, profile_name).await?
+        run_probe_loop(retries, interval, deadline, records, || async {
+            if !hc.command.is_empty() {
+                execute_command_probe(target, container_name, &hc.command, profile_name).await
             } else if let Some(http) = &hc.http {
-                execute_http_probe(target, service_name, service, hc, http, profile_name).await?
+                execute_http_probe(target, container_name, service, hc, http, profile_name).await
             } else if let Some(tcp) = &hc.tcp {
-                execute_tcp_probe(target, hc, tcp, profile_name).await?
+                execute_tcp_probe(target, container_name, hc, tcp, profile_name).await
             } else {
-                anyhow::bail!(
-                    "No executable health profile for service '{}'",
-                    service_name
-                );
-            };
-            let ok = record.ok;
-            last_record = Some(record.clone());
-            records.push(record);
-            if ok {
-                return Ok(true);
+                unreachable!("health profile presence checked above")
             }
-            sleep(interval).await;
-        }
+        })
+        .await
+    })
+}
 
-        if let Some(last) = last_record {
-            records.push(last);
+async fn run_probe_loop<F, Fut>(
+    retries: u32,
+    interval: Duration,
+    deadline: Option<Instant>,
+    records: &mut Vec<HealthProbeRecord>,
+    mut probe: F,
+) -> Result<bool>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<HealthProbeRecord>>,
+{
+    let mut last_record = None;
+
+    for _ in 0..retries {
+        if deadline_passed(deadline) {
+            break;
         }
-        Ok(false)
-    })
+        let record = probe().await?;
+        let ok = record.ok;
+        last_record = Some(record.clone());
+        records.push(record);
+        if ok {
+            return Ok(true);
+        }
+        let pause = match deadline {
+            Some(d) => interval.min(d.saturating_duration_since(Instant::now())),
+            None => interval,
+        };
+        sleep(pause).await;
+    }
+
+    if let Some(last) = last_record {
+        records.push(last);
+    }
+    Ok(false)
+}
+
+fn deadline_passed(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|d| Instant::now() >= d)
+}
+
+pub fn apply_health_timeout(service: &mut ServiceConfig, timeout_secs: Option<u64>) {
+    if let (Some(secs), Some(hc)) = (timeout_secs, service.healthcheck.as_mut()) {
+        hc.overall_timeout_secs = Some(secs);
+    }
 }
 
 async fn execute_command_probe(
@@ -536,7 +1398,7 @@ async fn execute_command_probe(
     profile_name: &str,
 ) -> Result<HealthProbeRecord> {
     let mut parts = vec![
-        "docker".to_string(),
+        container_runtime(),
         "exec".to_string(),
         service_name.to_string(),
     ];
@@ -567,9 +1429,7 @@ async fn execute_http_probe(
         format!("http://127.0.0.1:{port}{path}")
     };
 
-    let script = format!(
-        "code=$(curl -sS -o /dev/null -w '%{{http_code}}' --max-time {timeout} {url} || true); [ \"$code\" = \"{expected}\" ]"
-    );
+    let script = http_probe_script(http, &url, timeout, expected);
     let out = run_shell(target, &script).await?;
     Ok(to_probe_record(
         profile_name,
@@ -578,24 +1438,71 @@ async fn execute_http_probe(
     ))
 }
 
+fn http_probe_script(
+    http: &HttpHealthcheckConfig,
+    url: &str,
+    timeout: u64,
+    expected: u16,
+) -> String {
+    let method = http.method.as_deref().unwrap_or("GET").to_ascii_uppercase();
+    // `-X HEAD` still waits for a response body that never comes; `--head` doesn't.
+    let mut request_args = if method == "HEAD" {
+        "--head".to_string()
+    } else {
+        format!("-X {method}")
+    };
+    let headers: BTreeMap<_, _> = http.headers.iter().flatten().collect();
+    for (name, value) in headers {
+        request_args.push_str(" -H ");
+        request_args.push_str(&shell_quote(&format!("{name}: {value}")));
+    }
+    format!(
+        "code=$(curl -sS -o /dev/null -w '%{{http_code}}' {request_args} --max-time {timeout} {url} || true); [ \"$code\" = \"{expected}\" ]"
+    )
+}
+
 async fn execute_tcp_probe(
     target: &RuntimeTarget,
+    container_name: &str,
     hc: &HealthcheckConfig,
     tcp: &TcpHealthcheckConfig,
     profile_name: &str,
 ) -> Result<HealthProbeRecord> {
     let timeout = tcp.timeout_secs.or(hc.timeout_secs).unwrap_or(5);
-    let host = tcp.host.clone().unwrap_or_else(|| "127.0.0.1".to_string());
-    let script = format!(
-        "nc -z -w {timeout} {host} {port}",
-        timeout = timeout,
-        host = shell_quote(&host),
-        port = tcp.port
-    );
+    let script = tcp_probe_script(container_name, tcp, timeout);
     let out = run_shell(target, &script).await?;
     Ok(to_probe_record(profile_name, script, out))
 }
 
+fn tcp_probe_script(container_name: &str, tcp: &TcpHealthcheckConfig, timeout: u64) -> String {
+    let host = tcp.host.clone().unwrap_or_else(|| "127.0.0.1".to_string());
+    match tcp.from_host {
+        // Probe the published port from the runtime host; fall back to bash /dev/tcp when nc is absent.
+        Some(true) => format!(
+            "if command -v nc >/dev/null 2>&1; then nc -z -w {timeout} {host} {port}; else timeout {timeout} bash -c {dev_tcp}; fi",
+            host = shell_quote(&host),
+            port = tcp.port,
+            dev_tcp = shell_quote(&format!("</dev/tcp/{host}/{}", tcp.port)),
+        ),
+        Some(false) => join_shell_command(&[
+            container_runtime(),
+            "exec".to_string(),
+            container_name.to_string(),
+            "nc".to_string(),
+            "-z".to_string(),
+            "-w".to_string(),
+            timeout.to_string(),
+            host,
+            tcp.port.to_string(),
+        ]),
+        None => format!(
+            "nc -z -w {timeout} {host} {port}",
+            host = shell_quote(&host),
+            port = tcp.port
+        ),
+    }
+}
+
 fn to_probe_record(profile_name: &str, command: String, out: Output) -> HealthProbeRecord {
     HealthProbeRecord {
         profile: profile_name.to_string(),
@@ -607,21 +1514,46 @@ fn to_probe_record(profile_name: &str, command: String, out: Output) -> HealthPr
     }
 }
 
-pub async fn preflight_image_access(target: &RuntimeTarget, image: &str) -> Result<()> {
-    let docker_check = run_shell(target, "command -v docker >/dev/null 2>&1").await?;
-    if !docker_check.status.success() {
+pub async fn preflight_image_access(
+    target: &RuntimeTarget,
+    image: &str,
+    verify_digest: bool,
+) -> Result<()> {
+    let image = &registry_mirror::resolve(image);
+    let runtime = container_runtime();
+    let runtime_check = run_shell(target, &format!("command -v {runtime} >/dev/null 2>&1")).await?;
+    if !runtime_check.status.success() {
         anyhow::bail!(
-            "Image preflight failed for '{}': docker runtime not found on target host. Use `airstack up --bootstrap-runtime` or run `airstack ssh <server> -- 'apt-get update && apt-get install -y docker.io && systemctl enable --now docker'`.",
+            "Image preflight failed for '{}': container runtime not found on target host ({runtime}). Use `airstack up --bootstrap-runtime` or run `airstack ssh <server> -- 'apt-get update && apt-get install -y docker.io && systemctl enable --now docker'`.",
             image
         );
     }
 
-    let script = format!(
-        "docker image inspect {img} >/dev/null 2>&1 || docker pull {img}",
-        img = shell_quote(image)
-    );
-    let out = run_shell(target, &script).await?;
+    let script = image_pull_script(image, force_pull());
+    let out = if output::is_json() || output::is_quiet() {
+        run_shell(target, &script).await?
+    } else {
+        let mut progress = PullProgress::default();
+        run_shell_streaming(target, &script, |line| {
+            if let Some(step) = progress.observe(line) {
+                output::line(format!("   ⬇️ pulling {}: {}", image, step));
+            }
+        })
+        .await?
+    };
     if out.status.success() {
+        if verify_digest {
+            verify_image_digest(target, image).await?;
+        }
+        if !output::is_json() {
+            if let Some(size) = image_size_bytes(target, image).await {
+                output::line(format!(
+                    "📦 image {} ready ({})",
+                    image,
+                    format_image_size(size)
+                ));
+            }
+        }
         return Ok(());
     }
 
@@ -640,6 +1572,122 @@ pub async fn preflight_image_access(target: &RuntimeTarget, image: &str) -> Resu
     );
 }
 
+// Counts layers from `docker pull`'s non-TTY output ("<layer>: Pull complete").
+#[derive(Debug, Default)]
+struct PullProgress {
+    layers: BTreeSet<String>,
+    complete: usize,
+}
+
+impl PullProgress {
+    fn observe(&mut self, line: &str) -> Option<String> {
+        let (layer, status) = line.trim().split_once(": ")?;
+        match status.trim() {
+            "Pulling fs layer" => {
+                self.layers.insert(layer.to_string());
+                None
+            }
+            "Already exists" => {
+                self.layers.insert(layer.to_string());
+                self.complete += 1;
+                None
+            }
+            "Pull complete" => {
+                self.layers.insert(layer.to_string());
+                self.complete += 1;
+                Some(format!(
+                    "{}/{} layers",
+                    self.complete.min(self.layers.len()),
+                    self.layers.len()
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+pub async fn image_size_bytes(target: &RuntimeTarget, image: &str) -> Option<u64> {
+    let image = registry_mirror::resolve(image);
+    let out = run_shell(target, &image_size_script(&image)).await.ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    parse_image_size(&String::from_utf8_lossy(&out.stdout))
+}
+
+fn image_size_script(image: &str) -> String {
+    format!(
+        "{rt} image inspect --format '{{{{.Size}}}}' {}",
+        shell_quote(image),
+        rt = container_runtime(),
+    )
+}
+
+fn parse_image_size(stdout: &str) -> Option<u64> {
+    stdout.trim().lines().next()?.trim().parse().ok()
+}
+
+fn format_image_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+}
+
+// A locally present image normally short-circuits the pull; `--pull` refreshes it anyway.
+pub fn image_pull_script(image: &str, force: bool) -> String {
+    let img = shell_quote(image);
+    if force {
+        format!("{rt} pull {img}", rt = container_runtime())
+    } else {
+        format!(
+            "{rt} image inspect {img} >/dev/null 2>&1 || {rt} pull {img}",
+            rt = container_runtime()
+        )
+    }
+}
+
+async fn verify_image_digest(target: &RuntimeTarget, image: &str) -> Result<()> {
+    let expected = image_digest(image).with_context(|| {
+        format!(
+            "Digest verification requested but image '{}' is not pinned by digest",
+            image
+        )
+    })?;
+    let out = run_shell(
+        target,
+        &format!(
+            "{rt} image inspect --format '{{{{json .RepoDigests}}}}' {}",
+            shell_quote(image),
+            rt = container_runtime(),
+        ),
+    )
+    .await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "Digest verification failed for '{}': {}",
+            image,
+            summarize_process_failure(&out)
+        );
+    }
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    if !repo_digests_match(&stdout, expected)? {
+        anyhow::bail!(
+            "Digest verification failed for '{}': pulled image RepoDigests {} do not include {}",
+            image,
+            stdout.trim(),
+            expected
+        );
+    }
+    Ok(())
+}
+
+fn repo_digests_match(inspect_output: &str, expected: &str) -> Result<bool> {
+    let digests: Vec<String> = serde_json::from_str(inspect_output.trim())
+        .context("Failed to parse RepoDigests from docker image inspect")?;
+    Ok(digests
+        .iter()
+        .filter_map(|d| d.rsplit_once('@').map(|(_, digest)| digest))
+        .any(|digest| digest == expected))
+}
+
 async fn validate_remote_volumes(
     target: &RuntimeTarget,
     service_name: &str,
@@ -654,57 +1702,143 @@ async fn validate_remote_volumes(
 
     let mut missing_paths = Vec::new();
     for volume in volumes {
-        let Some((source, _dest)) = parse_volume_mapping(volume) else {
+        let Ok(spec) = VolumeSpec::parse(volume) else {
             continue;
         };
-        let is_bind_like = source.starts_with('/')
-            || source.starts_with("./")
-            || source.starts_with("../")
-            || source.starts_with("~/")
-            || source.contains('/');
-        if !is_bind_like {
+        if !spec.is_bind_mount() {
             continue;
         }
-        if !source.starts_with('/') {
+        if !spec.source.starts_with('/') && !spec.source.starts_with("~/") {
             anyhow::bail!(
                 "Remote volume mapping '{}' for service '{}' uses a local/relative source path '{}'. Airstack does not auto-sync local files to remote hosts. Use absolute remote paths or bootstrap/scripts to copy files first.",
                 volume,
                 service_name,
-                source
+                spec.source
             );
         }
-        let check = run_shell(target, &format!("test -e {}", shell_quote(source))).await?;
+        let check = run_shell(target, &format!("test -e {}", host_path_word(&spec.source))).await?;
         if !check.status.success() {
-            missing_paths.push(source.to_string());
+            missing_paths.push(spec.source);
         }
     }
 
+    // Existing sources are mounted as they are; only missing ones become directories.
     if !missing_paths.is_empty() {
-        anyhow::bail!(
-            "Remote volume preflight failed for service '{}': missing remote source path(s): {}",
+        output::line(format!(
+            "⚠️ service '{}': remote source path(s) {} missing; creating them as directories",
             service_name,
             missing_paths.join(", ")
-        );
+        ));
     }
     Ok(())
 }
 
-fn parse_volume_mapping(mapping: &str) -> Option<(&str, &str)> {
-    let mut parts = mapping.splitn(3, ':');
-    let source = parts.next()?.trim();
-    let dest = parts.next()?.trim();
-    if source.is_empty() || dest.is_empty() {
-        return None;
+// `infra-volume:<name>` sources become the mount path `up` recorded when it attached the volume.
+fn resolve_infra_volumes(
+    volumes: &mut [String],
+    recorded: &BTreeMap<String, VolumeState>,
+    server: Option<&str>,
+) -> Result<()> {
+    for volume in volumes.iter_mut() {
+        let Ok(spec) = VolumeSpec::parse(volume) else {
+            continue;
+        };
+        let Some(infra_volume) = spec.infra_volume() else {
+            continue;
+        };
+        let Some(server) = server else {
+            anyhow::bail!(
+                "infra volume '{}' can only be mounted on a remote server",
+                infra_volume
+            );
+        };
+        let attached = recorded.get(infra_volume).with_context(|| {
+            format!(
+                "infra volume '{}' is not attached yet; run `airstack up` first",
+                infra_volume
+            )
+        })?;
+        if attached.server != server {
+            anyhow::bail!(
+                "infra volume '{}' is attached to {}, not {}",
+                infra_volume,
+                attached.server,
+                server
+            );
+        }
+        let path = attached.mount_path.as_deref().with_context(|| {
+            format!(
+                "infra volume '{}' has no known mount path on {}",
+                infra_volume, server
+            )
+        })?;
+        *volume = match &spec.mode {
+            Some(mode) => format!("{}:{}:{}", path, spec.target, mode),
+            None => format!("{}:{}", path, spec.target),
+        };
+    }
+    Ok(())
+}
+
+fn bind_mount_host_sources(service: &ServiceConfig) -> Vec<String> {
+    service
+        .volumes
+        .iter()
+        .flatten()
+        .filter_map(|v| VolumeSpec::parse(v).ok())
+        .filter(VolumeSpec::is_bind_mount)
+        .map(|spec| spec.source)
+        .collect()
+}
+
+// Whether a source is a file or a directory is up to the host: anything that already
+// exists is left alone and only missing sources are created as directories.
+fn ensure_bind_mount_sources_script(sources: &[String]) -> String {
+    sources
+        .iter()
+        .map(|source| {
+            let path = host_path_word(source);
+            format!("{{ test -e {path} || mkdir -p {path}; }} && ")
+        })
+        .collect()
+}
+
+pub async fn missing_bind_mount_dirs(
+    target: &RuntimeTarget,
+    service: &ServiceConfig,
+) -> Result<Vec<String>> {
+    let mut missing = Vec::new();
+    for source in bind_mount_host_sources(service) {
+        let check = run_shell(target, &format!("test -e {}", host_path_word(&source))).await?;
+        if !check.status.success() {
+            missing.push(source);
+        }
+    }
+    Ok(missing)
+}
+
+pub async fn create_bind_mount_dirs(target: &RuntimeTarget, dirs: &[String]) -> Result<()> {
+    let mkdir = std::iter::once("mkdir -p".to_string())
+        .chain(dirs.iter().map(|dir| host_path_word(dir)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let out = run_shell(target, &mkdir).await?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "failed to create host dirs {}: {}",
+            dirs.join(", "),
+            summarize_process_failure(&out)
+        );
     }
-    Some((source, dest))
+    Ok(())
 }
 
 pub async fn collect_container_diagnostics(target: &RuntimeTarget, name: &str) -> String {
     let inspect = run_shell(
         target,
         &format!(
-            "docker inspect -f '{{{{.State.Status}}}}|{{{{.State.ExitCode}}}}|{{{{.State.Error}}}}|{{{{.RestartCount}}}}' {} 2>/dev/null || true",
-            shell_quote(name)
+            "{rt} inspect -f '{{{{.State.Status}}}}|{{{{.State.ExitCode}}}}|{{{{.State.Error}}}}|{{{{.RestartCount}}}}' {} 2>/dev/null || true",
+            shell_quote(name), rt = container_runtime(),
         ),
     )
     .await
@@ -714,7 +1848,11 @@ pub async fn collect_container_diagnostics(target: &RuntimeTarget, name: &str) -
 
     let logs = run_shell(
         target,
-        &format!("docker logs --tail 40 {} 2>&1 || true", shell_quote(name)),
+        &format!(
+            "{rt} logs --tail 40 {} 2>&1 || true",
+            shell_quote(name),
+            rt = container_runtime()
+        ),
     )
     .await
     .ok()
@@ -724,8 +1862,8 @@ pub async fn collect_container_diagnostics(target: &RuntimeTarget, name: &str) -
     let mounts = run_shell(
         target,
         &format!(
-            "docker inspect -f '{{{{range .Mounts}}}}{{{{.Source}}}}:{{{{.Destination}}}} {{end}}' {} 2>/dev/null || true",
-            shell_quote(name)
+            "{rt} inspect -f '{{{{range .Mounts}}}}{{{{.Source}}}}:{{{{.Destination}}}} {{end}}' {} 2>/dev/null || true",
+            shell_quote(name), rt = container_runtime(),
         ),
     )
     .await
@@ -736,8 +1874,8 @@ pub async fn collect_container_diagnostics(target: &RuntimeTarget, name: &str) -
     let env_keys = run_shell(
         target,
         &format!(
-            "docker inspect -f '{{{{range .Config.Env}}}}{{{{println .}}}}{{{{end}}}}' {} 2>/dev/null | cut -d= -f1 | sort -u | tr '\\n' ',' | sed 's/,$//' || true",
-            shell_quote(name)
+            "{rt} inspect -f '{{{{range .Config.Env}}}}{{{{println .}}}}{{{{end}}}}' {} 2>/dev/null | cut -d= -f1 | sort -u | tr '\\n' ',' | sed 's/,$//' || true",
+            shell_quote(name), rt = container_runtime(),
         ),
     )
     .await
@@ -782,7 +1920,7 @@ async fn inspect_service(
     let inspect = run_shell(
         target,
         &format!(
-            "docker inspect -f '{{{{.Id}}}}|{{{{.Config.Image}}}}|{{{{.State.Status}}}}' {inspect_id} 2>/dev/null || true"
+            "{rt} inspect -f '{{{{.Id}}}}|{{{{.Config.Image}}}}|{{{{.State.Status}}}}' {inspect_id} 2>/dev/null || true", rt = container_runtime(),
         ),
     )
     .await?;
@@ -793,7 +1931,7 @@ async fn inspect_service(
         let by_name = run_shell(
             target,
             &format!(
-                "docker inspect -f '{{{{.Id}}}}|{{{{.Config.Image}}}}|{{{{.State.Status}}}}' {name} 2>/dev/null || true"
+                "{rt} inspect -f '{{{{.Id}}}}|{{{{.Config.Image}}}}|{{{{.State.Status}}}}' {name} 2>/dev/null || true", rt = container_runtime(),
             ),
         )
         .await?;
@@ -808,7 +1946,10 @@ async fn inspect_service(
     let mut result = parse_inspect_line(&line, detected_by)?;
     let ports_out = run_shell(
         target,
-        &format!("docker ps -a --filter name=^/{name}$ --format '{{{{.Ports}}}}' | head -n 1"),
+        &format!(
+            "{rt} ps -a --filter name=^/?{name}$ --format '{{{{.Ports}}}}' | head -n 1",
+            rt = container_runtime()
+        ),
     )
     .await?;
     if ports_out.status.success() {
@@ -846,36 +1987,107 @@ fn parse_inspect_line(line: &str, detected_by: &str) -> Result<RuntimeDeployResu
     })
 }
 
+// Runs on tokio's process API with kill_on_drop so a deploy timeout that cancels
+// the caller also terminates the docker/ssh child instead of leaking it.
 async fn run_shell(target: &RuntimeTarget, script: &str) -> Result<Output> {
-    match target {
+    let command = match target {
         RuntimeTarget::Local => {
-            let out = std::process::Command::new("sh")
-                .arg("-lc")
-                .arg(script)
-                .output()
-                .context("Failed to execute local shell command")?;
-            Ok(out)
+            let mut command = std::process::Command::new("sh");
+            command.arg("-lc").arg(script);
+            command
         }
         RuntimeTarget::Remote(server_cfg) => {
-            execute_remote_command(
+            remote_shell_process(
                 server_cfg,
-                &["sh".to_string(), "-lc".to_string(), script.to_string()],
+                &join_shell_command(&["sh".to_string(), "-lc".to_string(), script.to_string()]),
             )
-            .await
+            .await?
+        }
+    };
+    let mut command = tokio::process::Command::from(command);
+    command.stdin(Stdio::null()).kill_on_drop(true);
+    command
+        .output()
+        .await
+        .context("Failed to execute shell command")
+}
+
+// Like `run_shell`, but hands each stdout line to `on_line` as it arrives.
+async fn run_shell_streaming(
+    target: &RuntimeTarget,
+    script: &str,
+    mut on_line: impl FnMut(&str),
+) -> Result<Output> {
+    let command = match target {
+        RuntimeTarget::Local => {
+            let mut command = std::process::Command::new("sh");
+            command.arg("-lc").arg(script);
+            command
         }
+        RuntimeTarget::Remote(server_cfg) => {
+            remote_shell_process(
+                server_cfg,
+                &join_shell_command(&["sh".to_string(), "-lc".to_string(), script.to_string()]),
+            )
+            .await?
+        }
+    };
+    let mut command = tokio::process::Command::from(command);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command.spawn().context("Failed to start shell command")?;
+    let stdout = child
+        .stdout
+        .take()
+        .context("Failed to capture shell command output")?;
+    let mut lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        on_line(&line);
     }
+    child
+        .wait_with_output()
+        .await
+        .context("Failed to wait for shell command")
 }
 
 fn shell_quote(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\"'\"'"))
 }
 
+// Quoting a leading `~/` would stop the shell expanding it, so it becomes "$HOME"/ instead.
+fn host_path_word(value: &str) -> String {
+    match value.strip_prefix("~/") {
+        Some(rest) => format!("\"$HOME\"/{}", shell_escape(rest)),
+        None => shell_escape(value),
+    }
+}
+
+// Only host-side paths (-v sources, --env-file) expand `~/`; the image's own command
+// and args reach the container untouched.
+fn join_run_command(parts: &[String]) -> String {
+    let mut words = Vec::with_capacity(parts.len());
+    let mut host_path_next = false;
+    for part in parts {
+        words.push(if host_path_next {
+            host_path_word(part)
+        } else {
+            shell_escape(part)
+        });
+        host_path_next = matches!(part.as_str(), "-v" | "--volume" | "--env-file");
+    }
+    words.join(" ")
+}
+
 async fn image_architecture(target: &RuntimeTarget, image: &str) -> Result<String> {
     let out = run_shell(
         target,
         &format!(
-            "docker image inspect -f '{{{{.Architecture}}}}' {} 2>/dev/null || true",
-            shell_quote(image)
+            "{rt} image inspect -f '{{{{.Architecture}}}}' {} 2>/dev/null || true",
+            shell_quote(image),
+            rt = container_runtime(),
         ),
     )
     .await?;
@@ -940,8 +2152,126 @@ fn limit_output(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::summarize_process_failure;
+    use super::{
+        bind_mount_host_sources, container_env, docker_rm_args, docker_run_args,
+        ensure_bind_mount_sources_script, env_file_upload_script, evaluate_service_health,
+        filter_allowed_secrets, host_path_word, http_probe_script, image_pull_script,
+        image_size_script, is_transient_docker_error, join_run_command,
+        parse_existing_service_spec, parse_image_size, replace_container_script,
+        replica_containers, replica_probe_service, replica_quorum_met, repo_digests_match,
+        resolve_infra_volumes, run_probe_loop, run_shell, runtime_binary,
+        summarize_process_failure, tcp_probe_script, with_deploy_timeout, DeployStrategy,
+        DeployTimedOut, HealthProbeRecord, PullProgress, RuntimeTarget,
+    };
+    use crate::state::VolumeState;
+    use airstack_config::{HttpHealthcheckConfig, ServiceConfig, TcpHealthcheckConfig};
+    use std::collections::{BTreeMap, HashMap};
     use std::process::Command;
+    use tokio::time::{Duration, Instant};
+
+    #[test]
+    fn tcp_probe_script_selects_host_or_container_path() {
+        let tcp = |from_host| TcpHealthcheckConfig {
+            host: None,
+            port: 5432,
+            timeout_secs: None,
+            from_host,
+        };
+
+        let host = tcp_probe_script("db", &tcp(Some(true)), 3);
+        assert!(host.contains("nc -z -w 3 '127.0.0.1' 5432"), "{host}");
+        assert!(host.contains("</dev/tcp/127.0.0.1/5432"), "{host}");
+        assert!(!host.contains("docker exec"));
+
+        let container = tcp_probe_script("db", &tcp(Some(false)), 3);
+        assert_eq!(container, "docker exec db nc -z -w 3 127.0.0.1 5432");
+
+        assert_eq!(
+            tcp_probe_script("db", &tcp(None), 3),
+            "nc -z -w 3 '127.0.0.1' 5432"
+        );
+    }
+
+    #[test]
+    fn env_file_upload_reads_contents_from_stdin() {
+        assert_eq!(
+            env_file_upload_script("/var/lib/airstack/env/api.env"),
+            "umask 077 && mkdir -p /var/lib/airstack/env && cat > '/var/lib/airstack/env/api.env' && chmod 600 '/var/lib/airstack/env/api.env'"
+        );
+    }
+
+    #[test]
+    fn replica_quorum_defaults_to_all_replicas() {
+        assert!(replica_quorum_met(3, 3, None));
+        assert!(!replica_quorum_met(2, 3, None));
+        assert!(replica_quorum_met(2, 3, Some(2)));
+        assert!(!replica_quorum_met(1, 3, Some(2)));
+        // Quorum is clamped to the replica set so a stale config can't demand more than exist.
+        assert!(replica_quorum_met(2, 2, Some(5)));
+        assert!(!replica_quorum_met(0, 2, Some(0)));
+        assert_eq!(replica_containers("api", None), vec!["api"]);
+    }
+
+    #[test]
+    fn replicas_probe_their_own_published_ports() {
+        let service = ServiceConfig {
+            image: "api:v1".to_string(),
+            ports: vec![8080],
+            env: None,
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: Some(airstack_config::HealthcheckConfig {
+                command: Vec::new(),
+                interval_secs: None,
+                retries: None,
+                timeout_secs: None,
+                overall_timeout_secs: None,
+                http: Some(HttpHealthcheckConfig {
+                    url: None,
+                    path: None,
+                    port: None,
+                    expected_status: None,
+                    timeout_secs: None,
+                    method: None,
+                    headers: None,
+                }),
+                tcp: Some(TcpHealthcheckConfig {
+                    host: None,
+                    port: 9090,
+                    timeout_secs: None,
+                    from_host: None,
+                }),
+                any: None,
+                all: None,
+                quorum: None,
+            }),
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        };
+        let probe_ports = |container: &str| {
+            let hc = replica_probe_service(&service, "api", container)
+                .expect("replica service")
+                .healthcheck
+                .expect("healthcheck");
+            (
+                hc.http.and_then(|http| http.port),
+                hc.tcp.map(|tcp| tcp.port),
+            )
+        };
+        assert_eq!(probe_ports("api"), (None, Some(9090)));
+        assert_eq!(probe_ports("api-2"), (Some(8081), Some(9091)));
+        assert_eq!(probe_ports("api-3"), (Some(8082), Some(9092)));
+    }
 
     #[test]
     fn summarize_failure_includes_stderr_when_present() {
@@ -966,4 +2296,559 @@ mod tests {
         assert!(summary.contains("exit=3"));
         assert!(summary.contains("stdout=nope"));
     }
+
+    #[test]
+    fn parse_existing_spec_reads_ports_and_env() {
+        let spec = parse_existing_service_spec("8080/tcp 443/tcp \nPATH=/usr/bin\nRUST_LOG=info\n")
+            .expect("spec should parse");
+        assert_eq!(spec.ports, vec![443, 8080]);
+        assert_eq!(spec.env, vec!["PATH=/usr/bin", "RUST_LOG=info"]);
+        assert!(parse_existing_service_spec("  \n").is_none());
+    }
+
+    #[test]
+    fn bind_mount_sources_skip_named_volumes_and_keep_existing_paths() {
+        let service = ServiceConfig {
+            image: "postgres:15".to_string(),
+            ports: vec![5432],
+            env: None,
+            volumes: Some(vec![
+                "./data:/var/lib/postgresql/data".to_string(),
+                "/opt/app/state:/state".to_string(),
+                "pgdata:/var/lib/postgresql".to_string(),
+                "/etc/app/config.yml:/config.yml:ro".to_string(),
+            ]),
+            depends_on: None,
+            target_server: None,
+            healthcheck: None,
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        };
+        let sources = bind_mount_host_sources(&service);
+        assert_eq!(
+            sources,
+            vec![
+                "./data".to_string(),
+                "/opt/app/state".to_string(),
+                "/etc/app/config.yml".to_string()
+            ]
+        );
+        assert_eq!(
+            ensure_bind_mount_sources_script(&["/etc/caddy/Caddyfile".to_string()]),
+            "{ test -e /etc/caddy/Caddyfile || mkdir -p /etc/caddy/Caddyfile; } && "
+        );
+        assert_eq!(ensure_bind_mount_sources_script(&[]), "");
+    }
+
+    #[test]
+    fn infra_volume_sources_resolve_to_the_recorded_mount_path() {
+        let recorded = BTreeMap::from([(
+            "pg".to_string(),
+            VolumeState {
+                server: "db-1".to_string(),
+                id: "5".to_string(),
+                linux_device: Some("/dev/disk/by-id/scsi-0HC_Volume_5".to_string()),
+                mount_path: Some("/mnt/HC_Volume_5".to_string()),
+            },
+        )]);
+        let mut volumes = vec![
+            "infra-volume:pg:/var/lib/postgresql/data:rw".to_string(),
+            "/etc/app:/config:ro".to_string(),
+        ];
+        resolve_infra_volumes(&mut volumes, &recorded, Some("db-1")).expect("resolves");
+        assert_eq!(
+            volumes,
+            vec![
+                "/mnt/HC_Volume_5:/var/lib/postgresql/data:rw".to_string(),
+                "/etc/app:/config:ro".to_string()
+            ]
+        );
+
+        let mut other = vec!["infra-volume:pg:/data".to_string()];
+        let err = resolve_infra_volumes(&mut other, &recorded, Some("web-1"))
+            .expect_err("volume lives on another server");
+        assert!(err.to_string().contains("attached to db-1, not web-1"));
+        let err = resolve_infra_volumes(&mut other, &BTreeMap::new(), Some("db-1"))
+            .expect_err("volume not attached yet");
+        assert!(err.to_string().contains("run `airstack up` first"));
+        assert!(resolve_infra_volumes(&mut other, &recorded, None).is_err());
+    }
+
+    #[test]
+    fn home_relative_host_paths_expand_instead_of_quoting_the_tilde() {
+        assert_eq!(host_path_word("~/data dir"), "\"$HOME\"/'data dir'");
+        assert_eq!(host_path_word("/opt/app"), "/opt/app");
+        let run = [
+            "run",
+            "-v",
+            "~/data:/var/lib/data",
+            "--env-file",
+            "~/api.env",
+            "app:v1",
+            "~/x",
+        ]
+        .map(String::from);
+        assert_eq!(
+            join_run_command(&run),
+            "run -v \"$HOME\"/data:/var/lib/data --env-file \"$HOME\"/api.env app:v1 '~/x'"
+        );
+    }
+
+    #[test]
+    fn container_env_filters_secrets_and_prefers_service_env() {
+        let all = BTreeMap::from([
+            ("DB_PASSWORD".to_string(), "s3cret".to_string()),
+            ("API_TOKEN".to_string(), "tok".to_string()),
+            ("UNRELATED".to_string(), "nope".to_string()),
+        ]);
+        let allowed =
+            filter_allowed_secrets(&["DB_PASSWORD".to_string(), "API_TOKEN".to_string()], all);
+        assert!(!allowed.contains_key("UNRELATED"));
+
+        let service = ServiceConfig {
+            image: "repo/api:v1".to_string(),
+            ports: vec![],
+            env: Some(HashMap::from([
+                ("API_TOKEN".to_string(), "from-config".to_string()),
+                ("RUST_LOG".to_string(), "info".to_string()),
+            ])),
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: None,
+            profile: None,
+            secrets: Some(vec!["DB_PASSWORD".to_string(), "API_TOKEN".to_string()]),
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        };
+        let env = container_env(&service, &allowed);
+        assert_eq!(env.get("DB_PASSWORD").map(String::as_str), Some("s3cret"));
+        assert_eq!(
+            env.get("API_TOKEN").map(String::as_str),
+            Some("from-config")
+        );
+        assert_eq!(env.get("RUST_LOG").map(String::as_str), Some("info"));
+        assert_eq!(env.len(), 3);
+    }
+
+    #[test]
+    fn replace_script_stops_with_timeout_before_remove() {
+        let script = replace_container_script("api", 30);
+        let stop = script
+            .find("docker stop -t 30 api")
+            .expect("script should stop with timeout");
+        let rm = script
+            .find("docker rm -f api")
+            .expect("script should remove");
+        assert!(stop < rm, "stop must precede rm: {script}");
+    }
+
+    #[test]
+    fn podman_projects_swap_the_runtime_binary() {
+        assert_eq!(runtime_binary(Some("podman")), "podman");
+        assert_eq!(runtime_binary(Some("docker")), "docker");
+        assert_eq!(runtime_binary(None), "docker");
+    }
+
+    #[test]
+    fn docker_rm_keeps_volumes_unless_asked() {
+        assert_eq!(
+            docker_rm_args("api", false),
+            vec!["docker", "rm", "-f", "api"]
+        );
+        assert_eq!(
+            docker_rm_args("api-2", true),
+            vec!["docker", "rm", "-f", "-v", "api-2"]
+        );
+    }
+
+    #[test]
+    fn pulled_image_size_parses_from_inspect_output() {
+        assert_eq!(
+            image_size_script("repo/api:v1"),
+            "docker image inspect --format '{{.Size}}' 'repo/api:v1'"
+        );
+        assert_eq!(parse_image_size("187654321\n"), Some(187_654_321));
+        assert_eq!(parse_image_size(""), None);
+        assert_eq!(parse_image_size("Error: No such image"), None);
+
+        let mut progress = PullProgress::default();
+        assert_eq!(progress.observe("a1: Pulling fs layer"), None);
+        assert_eq!(progress.observe("b2: Already exists"), None);
+        assert_eq!(
+            progress.observe("a1: Pull complete"),
+            Some("2/2 layers".to_string())
+        );
+        assert_eq!(progress.observe("Status: Downloaded newer image"), None);
+    }
+
+    #[test]
+    fn force_pull_skips_local_image_short_circuit() {
+        assert_eq!(
+            image_pull_script("repo/api:v1", false),
+            "docker image inspect 'repo/api:v1' >/dev/null 2>&1 || docker pull 'repo/api:v1'"
+        );
+        assert_eq!(
+            image_pull_script("repo/api:v1", true),
+            "docker pull 'repo/api:v1'"
+        );
+    }
+
+    #[test]
+    fn deploy_strategy_parses_recreate_and_rejects_unknown() {
+        assert_eq!(
+            DeployStrategy::parse("recreate").unwrap(),
+            DeployStrategy::Recreate
+        );
+        assert_eq!(
+            DeployStrategy::parse("rolling").unwrap(),
+            DeployStrategy::Rolling
+        );
+        let err = DeployStrategy::parse("blue-green").expect_err("unknown strategy must fail");
+        assert!(err
+            .to_string()
+            .contains("rolling|recreate|bluegreen|canary"));
+    }
+
+    #[tokio::test]
+    async fn deploy_timeout_aborts_a_deploy_that_never_completes() {
+        let err = with_deploy_timeout("api", 1, std::future::pending::<anyhow::Result<()>>())
+            .await
+            .expect_err("a pending deploy must time out");
+        assert_eq!(
+            err.downcast_ref::<DeployTimedOut>(),
+            Some(&DeployTimedOut {
+                service: "api".to_string(),
+                timeout_secs: 1,
+            })
+        );
+        assert!(err.to_string().contains("timed out after 1s"));
+
+        let ok = with_deploy_timeout("api", 1, async { Ok(7) })
+            .await
+            .unwrap();
+        assert_eq!(ok, 7);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn deploy_timeout_kills_the_running_shell_child() {
+        let pid_file =
+            std::env::temp_dir().join(format!("airstack-timeout-{}", std::process::id()));
+        let script = format!("echo $$ > {}; exec sleep 30", pid_file.display());
+        let err = with_deploy_timeout("api", 1, async move {
+            run_shell(&RuntimeTarget::Local, &script).await.map(|_| ())
+        })
+        .await
+        .expect_err("sleep must outlive the deploy timeout");
+        assert!(err.downcast_ref::<DeployTimedOut>().is_some());
+
+        let pid = std::fs::read_to_string(&pid_file).expect("pid file");
+        let _ = std::fs::remove_file(&pid_file);
+        // SIGKILL lands asynchronously and the child may linger as a zombie until
+        // tokio reaps it, so poll briefly for either outcome.
+        let mut stat = String::new();
+        for _ in 0..50 {
+            stat =
+                std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+            if stat.is_empty() || stat.contains(") Z ") {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("sleep child survived the deploy timeout: {stat}");
+    }
+
+    #[tokio::test]
+    async fn probe_loop_stops_at_deadline_with_retries_remaining() {
+        let mut records = Vec::new();
+        let mut attempts = 0u32;
+        let deadline = Instant::now() + Duration::from_millis(50);
+        let ok = run_probe_loop(
+            100,
+            Duration::from_millis(20),
+            Some(deadline),
+            &mut records,
+            || {
+                attempts += 1;
+                async {
+                    Ok(HealthProbeRecord {
+                        profile: "root".to_string(),
+                        command: "false".to_string(),
+                        ok: false,
+                        exit_code: Some(1),
+                        stdout: String::new(),
+                        stderr: String::new(),
+                    })
+                }
+            },
+        )
+        .await
+        .expect("probe loop should not error");
+
+        assert!(!ok);
+        assert!(attempts < 100, "loop ignored deadline: {attempts} attempts");
+        assert!(Instant::now() >= deadline);
+    }
+
+    #[tokio::test]
+    async fn health_records_collected_without_explain() {
+        let service = ServiceConfig {
+            image: "nginx:1.27".to_string(),
+            ports: Vec::new(),
+            env: None,
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: Some(airstack_config::HealthcheckConfig {
+                command: vec!["true".to_string()],
+                interval_secs: Some(0),
+                retries: Some(1),
+                timeout_secs: None,
+                overall_timeout_secs: None,
+                http: None,
+                tcp: None,
+                any: None,
+                all: None,
+                quorum: None,
+            }),
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        };
+        let eval = evaluate_service_health(
+            &RuntimeTarget::Local,
+            "api",
+            "airstack-test-no-such-container",
+            &service,
+            1,
+            false,
+        )
+        .await
+        .expect("health evaluation should not error");
+
+        assert!(!eval.ok);
+        assert!(!eval.records.is_empty());
+        assert_eq!(eval.records[0].profile, "root");
+    }
+
+    #[test]
+    fn repo_digests_match_compares_inspect_output() {
+        let digest = format!("sha256:{}", "b".repeat(64));
+        let inspect = format!("[\"ghcr.io/acme/api@{digest}\"]\n");
+        assert!(repo_digests_match(&inspect, &digest).expect("parses"));
+
+        let other = format!("sha256:{}", "c".repeat(64));
+        assert!(!repo_digests_match(&inspect, &other).expect("parses"));
+        assert!(!repo_digests_match("[]", &digest).expect("parses"));
+        assert!(repo_digests_match("not json", &digest).is_err());
+    }
+
+    #[test]
+    fn transient_docker_errors_are_classified() {
+        let transient = [
+            "Image preflight failed for 'repo/api:v1': Get \"https://registry-1.docker.io/v2/\": net/http: TLS handshake timeout.",
+            "error pulling image: received unexpected HTTP status: 503 Service Unavailable",
+            "read tcp 10.0.0.2:443: connection reset by peer",
+        ];
+        for msg in transient {
+            assert!(
+                is_transient_docker_error(&anyhow::anyhow!(msg.to_string())),
+                "expected transient: {msg}"
+            );
+        }
+
+        let permanent = [
+            "Image preflight failed for 'repo/api:v9': manifest unknown: manifest unknown.",
+            "pull access denied for repo/private, repository does not exist",
+            "invalid reference format",
+            "container exited with code 1",
+        ];
+        for msg in permanent {
+            assert!(
+                !is_transient_docker_error(&anyhow::anyhow!(msg.to_string())),
+                "expected permanent: {msg}"
+            );
+        }
+    }
+
+    #[test]
+    fn run_args_put_env_file_before_inline_env() {
+        let service = ServiceConfig {
+            image: "repo/api:v1".to_string(),
+            ports: vec![8080],
+            env: Some(HashMap::from([(
+                "LOG_LEVEL".to_string(),
+                "debug".to_string(),
+            )])),
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: None,
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: Some("/srv/app/api.env".to_string()),
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        };
+
+        let args = docker_run_args("api", &service, &BTreeMap::new(), Some("/srv/app/api.env"));
+        let env_file_idx = args
+            .iter()
+            .position(|a| a == "--env-file")
+            .expect("--env-file present");
+        assert_eq!(args[env_file_idx + 1], "/srv/app/api.env");
+        let inline_idx = args.iter().position(|a| a == "-e").expect("-e present");
+        assert_eq!(args[inline_idx + 1], "LOG_LEVEL=debug");
+        assert!(
+            env_file_idx < inline_idx,
+            "inline env must follow --env-file so it wins: {args:?}"
+        );
+        let restart_idx = args.iter().position(|a| a == "--restart").unwrap();
+        assert_eq!(args[restart_idx + 1], "unless-stopped");
+
+        let mut on_failure = service.clone();
+        on_failure.restart_policy = Some("on-failure:3".to_string());
+        let args = docker_run_args("api", &on_failure, &BTreeMap::new(), None);
+        let restart_idx = args.iter().position(|a| a == "--restart").unwrap();
+        assert_eq!(args[restart_idx + 1], "on-failure:3");
+    }
+
+    #[test]
+    fn run_args_include_log_rotation_only_when_configured() {
+        let mut service = ServiceConfig {
+            image: "repo/api:v1".to_string(),
+            ports: Vec::new(),
+            env: None,
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: None,
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        };
+        let args = docker_run_args("api", &service, &BTreeMap::new(), None);
+        assert!(!args.iter().any(|a| a == "--log-opt"), "{args:?}");
+
+        service.log_max_size = Some("10m".to_string());
+        service.log_max_file = Some(3);
+        let args = docker_run_args("api", &service, &BTreeMap::new(), None);
+        let log_opts = args
+            .windows(2)
+            .filter(|w| w[0] == "--log-opt")
+            .map(|w| w[1].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(log_opts, vec!["max-size=10m", "max-file=3"]);
+        assert_eq!(args.last().map(String::as_str), Some("repo/api:v1"));
+    }
+
+    #[test]
+    fn run_args_place_command_after_image_and_set_entrypoint() {
+        let mut service = ServiceConfig {
+            image: "repo/api:v1".to_string(),
+            ports: Vec::new(),
+            env: None,
+            volumes: None,
+            depends_on: None,
+            target_server: None,
+            healthcheck: None,
+            profile: None,
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        };
+        service.command = Some(vec!["serve".to_string(), "--port=8080".to_string()]);
+        let args = docker_run_args("api", &service, &BTreeMap::new(), None);
+        assert!(!args.iter().any(|a| a == "--entrypoint"), "{args:?}");
+        let image = args.iter().position(|a| a == &service.image).unwrap();
+        assert_eq!(args[image + 1..], ["serve", "--port=8080"]);
+
+        service.entrypoint = Some(vec!["/bin/sh".to_string(), "-c".to_string()]);
+        service.command = Some(vec!["exec /app/server".to_string()]);
+        let args = docker_run_args("api", &service, &BTreeMap::new(), None);
+        let image = args.iter().position(|a| a == &service.image).unwrap();
+        let entry = args.iter().position(|a| a == "--entrypoint").unwrap();
+        assert!(entry < image);
+        assert_eq!(args[entry + 1], "/bin/sh");
+        assert_eq!(args[image + 1..], ["-c", "exec /app/server"]);
+    }
+
+    #[test]
+    fn http_probe_script_includes_method_and_headers() {
+        let http = HttpHealthcheckConfig {
+            url: None,
+            path: Some("/ready".to_string()),
+            port: Some(8080),
+            expected_status: Some(204),
+            timeout_secs: None,
+            method: Some("head".to_string()),
+            headers: Some(HashMap::from([
+                ("X-Probe".to_string(), "airstack".to_string()),
+                ("Authorization".to_string(), "Bearer t0k".to_string()),
+            ])),
+        };
+        let script = http_probe_script(&http, "http://127.0.0.1:8080/ready", 5, 204);
+        assert!(script.contains("-w '%{http_code}' --head -H "), "{script}");
+        assert!(!script.contains("-X HEAD"), "{script}");
+        assert!(
+            script.contains("-H 'Authorization: Bearer t0k' -H 'X-Probe: airstack'"),
+            "{script}"
+        );
+        assert!(script.ends_with("[ \"$code\" = \"204\" ]"), "{script}");
+
+        let default_get = HttpHealthcheckConfig {
+            method: None,
+            headers: None,
+            ..http
+        };
+        let script = http_probe_script(&default_get, "http://127.0.0.1:8080/ready", 5, 200);
+        assert!(script.contains("-X GET --max-time 5"), "{script}");
+        assert!(!script.contains(" -H "), "{script}");
+    }
 }
@@ -1,28 +1,80 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 pub fn load_airstack_env() {
     for path in env_candidates(None) {
-        if path.exists() {
-            let _ = dotenvy::from_path(&path);
+        if load_layered(&path) {
             return;
         }
     }
 
     // Backward-compatible fallback: allow project-local .env when no global env file exists.
-    let _ = dotenvy::dotenv();
+    if !load_layered(Path::new(".env")) {
+        let _ = dotenvy::dotenv();
+    }
 }
 
 pub fn load_airstack_env_for_config(config_path: &str) {
     let config = Path::new(config_path);
     for path in env_candidates(Some(config)) {
-        if path.exists() {
-            let _ = dotenvy::from_path(&path);
+        if load_layered(&path) {
             return;
         }
     }
 
     // Backward-compatible fallback: allow project-local .env when no known env file exists.
-    let _ = dotenvy::dotenv();
+    if !load_layered(Path::new(".env")) {
+        let _ = dotenvy::dotenv();
+    }
+}
+
+fn load_layered(path: &Path) -> bool {
+    let env_name = std::env::var("AIRSTACK_ENV").ok();
+    load_layered_with(path, env_name.as_deref())
+}
+
+// Loads `<path>`, `<path>.local`, then `<path>.<AIRSTACK_ENV>` like the config overlays:
+// later files override earlier ones, but variables already set in the process win.
+fn load_layered_with(path: &Path, env_name: Option<&str>) -> bool {
+    let Some(values) = layered_values(path, env_name) else {
+        return false;
+    };
+    for (key, value) in values {
+        if std::env::var_os(&key).is_none() {
+            std::env::set_var(key, value);
+        }
+    }
+    true
+}
+
+fn layered_values(path: &Path, env_name: Option<&str>) -> Option<BTreeMap<String, String>> {
+    let mut found = false;
+    let mut values = BTreeMap::new();
+    for layer in env_layers(path, env_name) {
+        let Ok(iter) = dotenvy::from_path_iter(&layer) else {
+            continue;
+        };
+        found = true;
+        for (key, value) in iter.flatten() {
+            values.insert(key, value);
+        }
+    }
+    found.then_some(values)
+}
+
+fn env_layers(path: &Path, env_name: Option<&str>) -> Vec<PathBuf> {
+    let mut layers = vec![path.to_path_buf(), suffixed(path, "local")];
+    if let Some(name) = env_name.map(str::trim).filter(|n| !n.is_empty()) {
+        layers.push(suffixed(path, name));
+    }
+    layers
+}
+
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
 }
 
 fn env_candidates(config_path: Option<&Path>) -> Vec<PathBuf> {
@@ -56,9 +108,45 @@ fn env_candidates(config_path: Option<&Path>) -> Vec<PathBuf> {
 
 #[cfg(test)]
 mod tests {
-    use super::env_candidates;
+    use super::{env_candidates, load_layered_with};
     use std::path::Path;
 
+    #[test]
+    fn layered_env_files_override_in_order_but_not_process_env() {
+        let dir = std::env::temp_dir().join(format!("airstack-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("temp dir");
+        std::fs::write(
+            dir.join(".env"),
+            "AIRSTACK_LAYER_TEST_BASE=base\nAIRSTACK_LAYER_TEST_SHARED=base\nAIRSTACK_LAYER_TEST_PRESET=file\n",
+        )
+        .expect("write .env");
+        std::fs::write(
+            dir.join(".env.staging"),
+            "AIRSTACK_LAYER_TEST_SHARED=staging\nAIRSTACK_LAYER_TEST_PRESET=staging\n",
+        )
+        .expect("write .env.staging");
+        std::env::set_var("AIRSTACK_LAYER_TEST_PRESET", "process");
+
+        assert!(load_layered_with(&dir.join(".env"), Some("staging")));
+        assert_eq!(
+            std::env::var("AIRSTACK_LAYER_TEST_BASE").as_deref(),
+            Ok("base")
+        );
+        assert_eq!(
+            std::env::var("AIRSTACK_LAYER_TEST_SHARED").as_deref(),
+            Ok("staging")
+        );
+        assert_eq!(
+            std::env::var("AIRSTACK_LAYER_TEST_PRESET").as_deref(),
+            Ok("process")
+        );
+        assert!(!load_layered_with(
+            &dir.join("missing.env"),
+            Some("staging")
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn env_candidates_include_standard_global_locations() {
         let candidates = env_candidates(None);
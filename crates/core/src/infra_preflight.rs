@@ -1,8 +1,8 @@
 use crate::ssh_utils::resolve_identity_path;
 use airstack_config::ServerConfig;
 use airstack_metal::{
-    get_provider as get_metal_provider, CapacityResolveOptions, CreateRequestValidation,
-    CreateServerRequest,
+    get_provider as get_metal_provider, merge_server_labels, CapacityResolveOptions,
+    CreateRequestValidation, CreateServerRequest,
 };
 use anyhow::{Context, Result};
 use std::collections::HashMap;
@@ -13,19 +13,37 @@ pub struct ServerPreflight {
     pub validation: CreateRequestValidation,
 }
 
+pub fn create_request(server: &ServerConfig) -> Result<CreateServerRequest> {
+    let public_net = server.public_net.unwrap_or_default();
+    let user_data = server
+        .user_data
+        .as_deref()
+        .map(|path| {
+            std::fs::read_to_string(path).with_context(|| {
+                format!("Failed to read user_data for '{}': {}", server.name, path)
+            })
+        })
+        .transpose()?;
+    Ok(CreateServerRequest {
+        name: server.name.clone(),
+        server_type: server.server_type.clone(),
+        region: server.region.clone(),
+        ssh_key: server.ssh_key.clone(),
+        attach_floating_ip: server.floating_ip.unwrap_or(false),
+        enable_ipv4: public_net.ipv4,
+        enable_ipv6: public_net.ipv6,
+        labels: merge_server_labels(server.labels.as_ref()),
+        user_data,
+    })
+}
+
 pub async fn resolve_server_request(
     server: &ServerConfig,
     opts: CapacityResolveOptions,
 ) -> Result<ServerPreflight> {
     let provider = get_metal_provider(&server.provider, HashMap::new())
         .with_context(|| format!("Failed to initialize provider '{}'", server.provider))?;
-    let request = CreateServerRequest {
-        name: server.name.clone(),
-        server_type: server.server_type.clone(),
-        region: server.region.clone(),
-        ssh_key: server.ssh_key.clone(),
-        attach_floating_ip: server.floating_ip.unwrap_or(false),
-    };
+    let request = create_request(server)?;
     let resolved = provider.resolve_create_request(&request, opts).await?;
     let validation = provider.validate_create_request(&resolved).await?;
     Ok(ServerPreflight {
@@ -89,3 +107,37 @@ pub fn is_permanent_provider_error(err: &anyhow::Error) -> bool {
         || msg.contains("unauthorized")
         || msg.contains("authentication")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::create_request;
+    use airstack_config::{PublicNetConfig, ServerConfig};
+
+    #[test]
+    fn create_request_carries_public_net_from_config() {
+        let mut server = ServerConfig {
+            name: "web-1".to_string(),
+            provider: "hetzner".to_string(),
+            region: "hel1".to_string(),
+            server_type: "cpx21".to_string(),
+            ssh_key: "~/.ssh/id_ed25519.pub".to_string(),
+            floating_ip: None,
+            public_net: None,
+            ssh_user: None,
+            ssh_port: None,
+            labels: None,
+            user_data: None,
+        };
+        let request = create_request(&server).expect("request builds");
+        assert!(request.enable_ipv4);
+        assert!(!request.enable_ipv6);
+
+        server.public_net = Some(PublicNetConfig {
+            ipv4: false,
+            ipv6: true,
+        });
+        let request = create_request(&server).expect("request builds");
+        assert!(!request.enable_ipv4);
+        assert!(request.enable_ipv6);
+    }
+}
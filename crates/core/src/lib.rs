@@ -1,11 +1,14 @@
 pub mod commands;
+pub mod concurrency;
 pub mod dependencies;
 pub mod deploy_runtime;
 pub mod infra_preflight;
 pub mod output;
 pub mod provider_profiles;
+pub mod registry_mirror;
 pub mod retry;
 pub mod secrets_store;
+pub mod service_profiles;
 pub mod ssh_utils;
 pub mod state;
 pub mod theme;
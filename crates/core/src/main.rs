@@ -1,18 +1,21 @@
 use airstack_config::AirstackConfig;
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
 mod commands;
+mod concurrency;
 mod dependencies;
 mod deploy_runtime;
 mod env_loader;
 mod infra_preflight;
 mod output;
 mod provider_profiles;
+mod registry_mirror;
 mod retry;
 mod secrets_store;
+mod service_profiles;
 mod ssh_utils;
 mod state;
 mod theme;
@@ -52,6 +55,31 @@ pub struct Cli {
     #[arg(long, global = true, help = "Suppress human-readable output")]
     quiet: bool,
 
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        help = "Log output format (independent of --json command output)"
+    )]
+    log_format: LogFormat,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = concurrency::DEFAULT_PARALLEL,
+        help = "Maximum concurrent provider/SSH lookups"
+    )]
+    parallel: usize,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = ssh_utils::DEFAULT_SSH_TIMEOUT_SECS,
+        help = "SSH connect timeout and keepalive interval in seconds"
+    )]
+    ssh_timeout: u64,
+
     #[arg(
         long,
         global = true,
@@ -72,6 +100,27 @@ pub struct Cli {
         help = "Provider profile override for this run (<provider>:<profile>)"
     )]
     provider_profile: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Only act on services with this profile (plus profile-less services)"
+    )]
+    profile: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "HOST",
+        help = "Pull Docker Hub images through this registry mirror (overrides project.registry_mirror)"
+    )]
+    registry_mirror: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
 }
 
 #[derive(Subcommand)]
@@ -84,71 +133,47 @@ enum Commands {
         provider: Option<String>,
         #[arg(long, help = "Preset template (e.g., clickhouse)")]
         preset: Option<String>,
-    },
-    #[command(about = "Provision infrastructure and deploy services")]
-    Up {
-        #[arg(long, help = "Target environment")]
-        target: Option<String>,
-        #[arg(long, help = "Infrastructure provider")]
-        provider: Option<String>,
-        #[arg(
-            long,
-            help = "Deploy services locally and skip infrastructure provisioning"
-        )]
-        local: bool,
         #[arg(
             long,
-            help = "Bootstrap runtime dependencies (Docker) on remote servers"
+            help = "Starter config template: web|web+db|fly|minimal (default: web+db)"
         )]
-        bootstrap_runtime: bool,
-        #[arg(long, help = "Allow provider-aware fallback to default valid region")]
-        auto_fallback: bool,
-        #[arg(long, help = "Resolve server region/type capacity automatically")]
-        resolve_capacity: bool,
+        template: Option<String>,
     },
+    #[command(about = "Provision infrastructure and deploy services")]
+    Up(commands::up::UpArgs),
     #[command(about = "Destroy infrastructure")]
     Destroy {
         #[arg(long, help = "Target environment")]
         target: Option<String>,
         #[arg(long, help = "Force destruction without confirmation")]
         force: bool,
-    },
-    #[command(about = "Deploy a specific service")]
-    Deploy {
-        #[arg(help = "Service name")]
-        service: String,
-        #[arg(long, help = "Target server")]
-        target: Option<String>,
-        #[arg(long, help = "Build latest local code into image before deploy")]
-        latest_code: bool,
         #[arg(
             long,
-            default_value_t = true,
-            help = "Push image when using --latest-code"
+            value_delimiter = ',',
+            help = "Remove these services' containers (dependents first) instead of infrastructure"
         )]
-        push: bool,
-        #[arg(long, help = "Tag override for --latest-code")]
-        tag: Option<String>,
+        service: Vec<String>,
         #[arg(
             long,
-            help = "Deploy strategy: rolling|bluegreen|canary",
-            default_value = "rolling"
+            conflicts_with = "delete_volumes",
+            help = "Detach provider volumes and keep container volumes (default)"
         )]
-        strategy: String,
+        keep_volumes: bool,
         #[arg(
             long,
-            help = "Canary observation window in seconds (strategy=canary)",
-            default_value_t = 45
+            help = "Also delete provider volumes and anonymous container volumes (docker rm -v)"
         )]
-        canary_seconds: u64,
+        delete_volumes: bool,
     },
+    #[command(about = "Deploy a specific service")]
+    Deploy(commands::deploy::DeployArgs),
     #[command(about = "Execute a command inside a container on a remote server")]
     #[command(
-        after_help = "Example: airstack cexec <server> <container> -- <command>\nExample: airstack cexec <server> --container <container> -- <command>"
+        after_help = "Example: airstack cexec <server> <container> -- <command>\nExample: airstack cexec <server> --container <container> -- <command>\nExample: airstack cexec --service api -- ps aux"
     )]
     Cexec {
-        #[arg(help = "Server name")]
-        server: String,
+        #[arg(help = "Server name (defaults to the service's target server with --service)")]
+        server: Option<String>,
         #[arg(help = "Container name")]
         container: Option<String>,
         #[arg(
@@ -156,12 +181,25 @@ enum Commands {
             help = "Container name (named form to avoid positional ordering mistakes)"
         )]
         container_name: Option<String>,
+        #[arg(
+            long,
+            visible_alias = "all-containers",
+            conflicts_with_all = ["container", "container_name", "tty"],
+            help = "Run the command in every replica container of this service"
+        )]
+        service: Option<String>,
         #[arg(help = "Command to execute in container", last = true)]
         command: Vec<String>,
         #[arg(long, help = "Execute this shell command string in the container")]
         cmd: Option<String>,
         #[arg(long, help = "Run a local script file in the container via shell")]
         script: Option<String>,
+        #[arg(
+            long,
+            short = 't',
+            help = "Attach an interactive TTY session (docker exec -it)"
+        )]
+        tty: bool,
     },
     #[command(
         about = "Legacy build command (deprecated; use release/ship)",
@@ -177,8 +215,14 @@ enum Commands {
     Scale {
         #[arg(help = "Service name")]
         service: String,
-        #[arg(help = "Target number of replicas")]
-        replicas: usize,
+        #[arg(
+            help = "Target number of replicas (0 pauses the service)",
+            required_unless_present = "to_zero",
+            conflicts_with = "to_zero"
+        )]
+        replicas: Option<usize>,
+        #[arg(long, help = "Stop all replicas but keep service state (same as 0)")]
+        to_zero: bool,
     },
     #[command(about = "Launch lightweight interactive CLI menus")]
     Cli,
@@ -196,35 +240,42 @@ enum Commands {
         command: commands::script::ScriptCommands,
     },
     #[command(about = "Show status of infrastructure and services")]
-    Status {
-        #[arg(long, help = "Show detailed status")]
-        detailed: bool,
-        #[arg(long, help = "Run active health probes for services")]
-        probe: bool,
-        #[arg(long, help = "Include image/deploy provenance fields in status output")]
-        provenance: bool,
-        #[arg(
-            long,
-            help = "Status source-of-truth mode: auto|provider|ssh|control-plane",
-            default_value = "auto"
-        )]
-        source: String,
-    },
+    Status(commands::status::StatusArgs),
     #[command(about = "SSH into a server")]
     Ssh {
-        #[arg(help = "Server name")]
-        target: String,
+        #[arg(
+            help = "Server name",
+            required_unless_present = "all",
+            conflicts_with = "all"
+        )]
+        target: Option<String>,
+        #[arg(long, help = "Run the command on all infra servers concurrently")]
+        all: bool,
         #[arg(help = "Command to execute", last = true)]
         command: Vec<String>,
         #[arg(long, help = "Execute this shell command string on the remote host")]
         cmd: Option<String>,
         #[arg(long, help = "Run a local script file on the remote host via shell")]
         script: Option<String>,
+        #[arg(
+            long,
+            help = "Append the server's ssh_key public key to the remote authorized_keys",
+            requires = "target",
+            conflicts_with_all = ["all", "command", "cmd", "script"]
+        )]
+        copy_id: bool,
     },
     #[command(about = "Show logs for a service")]
     Logs {
-        #[arg(help = "Service name")]
-        service: String,
+        #[arg(help = "Service name", required_unless_present = "services")]
+        service: Option<String>,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            conflicts_with = "service",
+            help = "Tail several services at once with prefixed, interleaved output (comma-separated)"
+        )]
+        services: Vec<String>,
         #[arg(long, short = 'f', help = "Follow log output")]
         follow: bool,
         #[arg(long, help = "Number of lines to show")]
@@ -235,29 +286,61 @@ enum Commands {
             default_value = "auto"
         )]
         source: String,
+        #[arg(
+            long,
+            help = "Show logs since a duration ago (30m, 1h30m), RFC3339 time, or unix time"
+        )]
+        since: Option<String>,
+        #[arg(
+            long,
+            help = "Show logs until a duration ago, RFC3339 time, or unix time"
+        )]
+        until: Option<String>,
     },
     #[command(about = "Preview planned infra/service actions")]
     Plan {
-        #[arg(long, help = "Include destroy actions for unmanaged resources")]
+        #[arg(
+            long,
+            help = "Include destroy actions for airstack-managed servers missing from config"
+        )]
         include_destroy: bool,
         #[arg(long, help = "Allow provider-aware fallback to default valid region")]
         auto_fallback: bool,
         #[arg(long, help = "Resolve server region/type capacity automatically")]
         resolve_capacity: bool,
+        #[arg(long, help = "Write the computed plan as JSON to this file")]
+        output: Option<String>,
     },
     #[command(about = "Apply desired infrastructure and services")]
-    Apply,
+    Apply {
+        #[arg(
+            long,
+            help = "Apply only the actions from a saved plan (fails if config changed)"
+        )]
+        plan: Option<String>,
+        #[arg(
+            long,
+            help = "Converge only this server or service (plus its dependencies); prefix with server: or service: to disambiguate"
+        )]
+        target: Option<String>,
+    },
     #[command(about = "Edge reverse-proxy workflows")]
     Edge {
         #[command(subcommand)]
         command: commands::edge::EdgeCommands,
     },
     #[command(about = "Run production safety checks")]
-    Doctor,
+    Doctor(commands::doctor::DoctorArgs),
+    #[command(about = "Validate configuration references without side effects")]
+    Validate,
+    #[command(about = "Upgrade an older config file to the current schema version")]
+    Migrate(commands::migrate::MigrateArgs),
+    #[command(about = "Show capabilities of configured providers")]
+    Providers,
     #[command(about = "Validate full go-live readiness across infra/image/edge/health")]
     GoLive(commands::golive::GoLiveArgs),
     #[command(about = "Check image drift between config and running runtime")]
-    Drift,
+    Drift(commands::drift::DriftArgs),
     #[command(about = "Registry credential diagnostics")]
     Registry {
         #[command(subcommand)]
@@ -272,6 +355,11 @@ enum Commands {
         #[command(subcommand)]
         command: commands::secrets::SecretsCommands,
     },
+    #[command(about = "Manage provider servers in place")]
+    Server {
+        #[command(subcommand)]
+        command: commands::server::ServerCommands,
+    },
     #[command(about = "Managed backup lifecycle commands")]
     Backup {
         #[command(subcommand)]
@@ -288,6 +376,32 @@ enum Commands {
     Ship(commands::ship::ShipArgs),
     #[command(about = "Collect status/log/diagnostic artifacts for bug reports")]
     SupportBundle(commands::support_bundle::SupportBundleArgs),
+    #[command(about = "Show the audit log of mutating operations")]
+    Audit(commands::audit::AuditArgs),
+}
+
+impl Commands {
+    // Commands that change infrastructure, runtime, or secrets get an audit log entry.
+    fn audit_name(&self) -> Option<&'static str> {
+        Some(match self {
+            Commands::Up(_) => "up",
+            Commands::Destroy { .. } => "destroy",
+            Commands::Deploy(_) => "deploy",
+            Commands::Cexec { .. } => "cexec",
+            Commands::Scale { .. } => "scale",
+            Commands::Script { .. } => "script",
+            Commands::Apply { .. } => "apply",
+            Commands::Edge { .. } => "edge",
+            Commands::Drift(args) if args.fix => "drift",
+            Commands::Reconcile(_) => "reconcile",
+            Commands::Secrets { .. } => "secrets",
+            Commands::Server { .. } => "server",
+            Commands::Backup { .. } => "backup",
+            Commands::Release(_) => "release",
+            Commands::Ship(_) => "ship",
+            _ => return None,
+        })
+    }
 }
 
 #[tokio::main]
@@ -300,6 +414,10 @@ async fn main() -> Result<()> {
     }
     provider_profiles::apply_profiles_for_run(cli.provider_profile.as_deref())?;
     output::configure(cli.json, cli.quiet);
+    concurrency::configure(cli.parallel);
+    ssh_utils::configure_timeout(cli.ssh_timeout);
+    service_profiles::configure(cli.profile.as_deref());
+    registry_mirror::configure(cli.registry_mirror.as_deref());
 
     let level = if cli.verbose {
         Level::DEBUG
@@ -309,17 +427,17 @@ async fn main() -> Result<()> {
         Level::WARN
     };
 
-    let subscriber = FmtSubscriber::builder()
+    let builder = FmtSubscriber::builder()
         .with_max_level(level)
         .with_target(false)
         .with_thread_ids(false)
         .with_thread_names(false)
         .with_file(false)
-        .with_line_number(false)
-        .compact()
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber)?;
+        .with_line_number(false);
+    match cli.log_format {
+        LogFormat::Text => tracing::subscriber::set_global_default(builder.compact().finish())?,
+        LogFormat::Json => tracing::subscriber::set_global_default(builder.json().finish())?,
+    }
 
     info!("Airstack CLI v{}", env!("CARGO_PKG_VERSION"));
 
@@ -333,66 +451,83 @@ async fn main() -> Result<()> {
     };
     env_loader::load_airstack_env_for_config(&config_path);
 
+    let audit = cli.command.audit_name().and_then(|command| {
+        let project = AirstackConfig::load(&config_path).ok()?.project.name;
+        Some(state::AuditRecorder::start(
+            &project,
+            command,
+            state::summarize_args(std::env::args().skip(1)),
+        ))
+    });
+    let result = dispatch(cli, &config_path).await;
+    if let Some(audit) = audit {
+        audit.finish(&result);
+    }
+    result
+}
+
+async fn dispatch(cli: Cli, config_path: &str) -> Result<()> {
+    let config_path = config_path.to_string();
     match cli.command {
         Commands::Init {
             name,
             provider,
             preset,
-        } => commands::init::run(name, provider, preset, &config_path).await,
-        Commands::Up {
+            template,
+        } => commands::init::run(name, provider, preset, template, &config_path).await,
+        Commands::Up(args) => {
+            commands::up::run(&config_path, args, cli.dry_run, cli.allow_local_deploy).await
+        }
+        Commands::Destroy {
             target,
-            provider,
-            local,
-            bootstrap_runtime,
-            auto_fallback,
-            resolve_capacity,
+            force,
+            service,
+            keep_volumes: _,
+            delete_volumes,
         } => {
-            commands::up::run(
+            commands::destroy::run(
                 &config_path,
                 target,
-                provider,
+                force || cli.yes,
+                service,
                 cli.dry_run,
-                cli.allow_local_deploy,
-                local,
-                bootstrap_runtime,
-                auto_fallback,
-                resolve_capacity,
+                delete_volumes,
             )
             .await
         }
-        Commands::Destroy { target, force } => {
-            commands::destroy::run(&config_path, target, force || cli.yes).await
-        }
-        Commands::Deploy {
-            service,
-            target,
-            latest_code,
-            push,
-            tag,
-            strategy,
-            canary_seconds,
-        } => {
-            commands::deploy::run(
-                &config_path,
-                &service,
-                target,
-                cli.allow_local_deploy,
-                latest_code,
-                push,
-                tag,
-                strategy,
-                canary_seconds,
-            )
-            .await
+        Commands::Deploy(args) => {
+            commands::deploy::run(&config_path, args, cli.dry_run, cli.allow_local_deploy).await
         }
         Commands::Cexec {
             server,
             container,
             container_name,
+            service,
             command,
             cmd,
             script,
+            tty,
         } => {
+            let exec = commands::cexec::ContainerExec {
+                command,
+                cmd,
+                script,
+                tty,
+            };
+            if let Some(service) = service {
+                return commands::cexec::run_service(
+                    &config_path,
+                    server.as_deref(),
+                    &service,
+                    exec,
+                )
+                .await;
+            }
+            let server = server.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Missing server name. Usage: airstack cexec <server> <container> -- <command>"
+                )
+            })?;
             let resolved_container = container_name
                 .or(container)
                 .ok_or_else(|| {
@@ -400,79 +535,119 @@ async fn main() -> Result<()> {
                         "Missing container name. Usage: airstack cexec <server> <container> -- <command>\nOr: airstack cexec <server> --container <container> -- <command>"
                     )
                 })?;
-            commands::cexec::run(
-                &config_path,
-                &server,
-                &resolved_container,
-                commands::cexec::ContainerExec {
-                    command,
-                    cmd,
-                    script,
-                },
-            )
-            .await
-        }
-        Commands::Scale { service, replicas } => {
-            commands::scale::run(&config_path, &service, replicas).await
+            commands::cexec::run(&config_path, &server, &resolved_container, exec).await
         }
+        Commands::Scale {
+            service,
+            replicas,
+            to_zero: _,
+        } => commands::scale::run(&config_path, &service, replicas.unwrap_or(0)).await,
         Commands::Cli => commands::cli::run(&config_path).await,
         Commands::Tui { view } => commands::tui::run(&config_path, view).await,
         Commands::Script { command } => commands::script::run(&config_path, command).await,
-        Commands::Status {
-            detailed,
-            probe,
-            provenance,
-            source,
-        } => commands::status::run(&config_path, detailed, probe, provenance, &source).await,
+        Commands::Status(args) => {
+            if args.format.is_some() && !cli.json {
+                output::configure(false, true);
+            }
+            commands::status::run(&config_path, args).await
+        }
         Commands::Ssh {
             target,
+            all,
             command,
             cmd,
             script,
+            copy_id,
         } => {
-            commands::ssh::run(
-                &config_path,
-                &target,
-                commands::ssh::SshExec {
-                    command,
-                    cmd,
-                    script,
-                },
-            )
-            .await
+            let exec = commands::ssh::SshExec {
+                command,
+                cmd,
+                script,
+            };
+            match target {
+                Some(target) if copy_id => commands::ssh::copy_id(&config_path, &target).await,
+                None if copy_id => {
+                    anyhow::bail!("--copy-id needs a server: airstack ssh <server> --copy-id")
+                }
+                Some(target) if !all => commands::ssh::run(&config_path, &target, exec).await,
+                _ => commands::ssh::run_all(&config_path, exec).await,
+            }
         }
         Commands::Logs {
             service,
+            services,
             follow,
             tail,
             source,
-        } => commands::logs::run(&config_path, &service, follow, tail, &source).await,
+            since,
+            until,
+        } => {
+            let window = commands::logs::LogWindow::parse(since.as_deref(), until.as_deref())?;
+            match service {
+                Some(service) => {
+                    commands::logs::run(&config_path, &service, follow, tail, &source, window).await
+                }
+                None => {
+                    commands::logs::run_multi(
+                        &config_path,
+                        &services,
+                        follow,
+                        tail,
+                        &source,
+                        window,
+                    )
+                    .await
+                }
+            }
+        }
         Commands::Plan {
             include_destroy,
             auto_fallback,
             resolve_capacity,
+            output,
         } => {
             commands::plan::run(
                 &config_path,
                 include_destroy,
                 auto_fallback,
                 resolve_capacity,
+                output.as_deref(),
+            )
+            .await
+        }
+        Commands::Apply { plan, target } => {
+            commands::apply::run(
+                &config_path,
+                cli.allow_local_deploy,
+                plan.as_deref(),
+                target.as_deref(),
             )
             .await
         }
-        Commands::Apply => commands::apply::run(&config_path, cli.allow_local_deploy).await,
         Commands::Edge { command } => commands::edge::run(&config_path, command).await,
-        Commands::Doctor => commands::doctor::run(&config_path).await,
+        Commands::Doctor(args) => commands::doctor::run(&config_path, args, cli.dry_run).await,
+        Commands::Validate => commands::validate::run(&config_path).await,
+        Commands::Migrate(args) => commands::migrate::run(&config_path, args).await,
+        Commands::Providers => commands::providers::run(&config_path).await,
         Commands::GoLive(args) => commands::golive::run(&config_path, args).await,
-        Commands::Drift => commands::drift::run(&config_path).await,
+        Commands::Drift(args) => {
+            commands::drift::run(&config_path, args, cli.dry_run, cli.allow_local_deploy).await
+        }
         Commands::Registry { command } => commands::registry::run(&config_path, command).await,
         Commands::Reconcile(mut args) => {
             args.allow_local_deploy = cli.allow_local_deploy;
             commands::reconcile::run(&config_path, args).await
         }
         Commands::Runbook => commands::runbook::run(&config_path).await,
-        Commands::Secrets { command } => commands::secrets::run(&config_path, command).await,
-        Commands::Backup { command } => commands::backup::run(&config_path, command).await,
+        Commands::Secrets { command } => {
+            commands::secrets::run(&config_path, command, cli.yes).await
+        }
+        Commands::Server { command } => {
+            commands::server::run(&config_path, command, cli.dry_run, cli.yes).await
+        }
+        Commands::Backup { command } => {
+            commands::backup::run(&config_path, command, cli.dry_run).await
+        }
         Commands::Provider { command } => commands::provider::run(&config_path, command).await,
         Commands::Release(args) => commands::release::run(&config_path, args).await,
         Commands::Ship(mut args) => {
@@ -492,5 +667,76 @@ async fn main() -> Result<()> {
             anyhow::bail!("{migration}");
         }
         Commands::SupportBundle(args) => commands::support_bundle::run(&config_path, args).await,
+        Commands::Audit(args) => commands::audit::run(&config_path, args).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cli, LogFormat};
+    use clap::Parser;
+
+    #[test]
+    fn log_format_flag_parses_to_enum() {
+        let cli = Cli::try_parse_from(["airstack", "status"]).expect("default parses");
+        assert_eq!(cli.log_format, LogFormat::Text);
+        let cli = Cli::try_parse_from(["airstack", "--log-format", "json", "status"])
+            .expect("json parses");
+        assert_eq!(cli.log_format, LogFormat::Json);
+        let cli = Cli::try_parse_from(["airstack", "status", "--log-format", "text"])
+            .expect("global flag parses after subcommand");
+        assert_eq!(cli.log_format, LogFormat::Text);
+        assert!(Cli::try_parse_from(["airstack", "--log-format", "xml", "status"]).is_err());
+    }
+
+    #[test]
+    fn backup_restore_accepts_its_own_dry_run_flag() {
+        let cli = Cli::try_parse_from([
+            "airstack",
+            "backup",
+            "restore",
+            "--id",
+            "db-1",
+            "--destination",
+            "/srv/db",
+            "--dry-run",
+        ])
+        .expect("restore --dry-run parses");
+        assert!(matches!(
+            cli.command,
+            super::Commands::Backup {
+                command: super::commands::backup::BackupCommands::Restore { dry_run: true, .. }
+            }
+        ));
+    }
+
+    #[test]
+    fn up_flags_parse_into_up_args() {
+        let cli =
+            Cli::try_parse_from(["airstack", "up", "--only", "api,worker", "--timeout", "60"])
+                .expect("up --only parses");
+        let super::Commands::Up(args) = cli.command else {
+            panic!("expected up");
+        };
+        assert_eq!(args.only, vec!["api".to_string(), "worker".to_string()]);
+        assert_eq!(args.timeout, Some(60));
+        assert!(args.only_servers.is_empty());
+        assert!(Cli::try_parse_from(["airstack", "up", "--infra-only", "--only", "api"]).is_err());
+    }
+
+    #[test]
+    fn ssh_copy_id_requires_a_target() {
+        assert!(Cli::try_parse_from(["airstack", "ssh", "--copy-id"]).is_err());
+        assert!(Cli::try_parse_from(["airstack", "ssh", "--all", "--copy-id"]).is_err());
+        let cli = Cli::try_parse_from(["airstack", "ssh", "web-1", "--copy-id"])
+            .expect("ssh <server> --copy-id parses");
+        assert!(matches!(
+            cli.command,
+            super::Commands::Ssh {
+                copy_id: true,
+                target: Some(_),
+                ..
+            }
+        ));
     }
 }
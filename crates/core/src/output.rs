@@ -41,3 +41,29 @@ pub fn emit_json<T: Serialize>(value: &T) -> Result<()> {
     println!("{}", serde_json::to_string_pretty(value)?);
     Ok(())
 }
+
+// NDJSON: one compact object per line, for commands that stream events before their result.
+pub fn emit_json_line<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", json_line(value)?);
+    Ok(())
+}
+
+fn json_line<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_line;
+
+    #[test]
+    fn json_lines_stay_on_a_single_line() {
+        let value = serde_json::json!({ "phase": "run", "detail": { "lines": ["a\nb"] } });
+        let line = json_line(&value).expect("serialize");
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&line).expect("parse"),
+            value
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use airstack_config::AirstackConfig;
+
+const ENV_REGISTRY_MIRROR: &str = "AIRSTACK_REGISTRY_MIRROR";
+const DOCKER_HUB_HOSTS: &[&str] = &["docker.io", "index.docker.io", "registry-1.docker.io"];
+
+pub fn configure(mirror: Option<&str>) {
+    if let Some(mirror) = mirror.map(normalize_host).filter(|m| !m.is_empty()) {
+        std::env::set_var(ENV_REGISTRY_MIRROR, mirror);
+    }
+}
+
+// --registry-mirror (or AIRSTACK_REGISTRY_MIRROR) wins over project.registry_mirror.
+pub fn adopt_config(config: &AirstackConfig) {
+    if active().is_none() {
+        configure(config.project.registry_mirror.as_deref());
+    }
+}
+
+pub fn active() -> Option<String> {
+    std::env::var(ENV_REGISTRY_MIRROR)
+        .ok()
+        .map(|m| normalize_host(&m))
+        .filter(|m| !m.is_empty())
+}
+
+pub fn resolve(image: &str) -> String {
+    match active() {
+        Some(mirror) => rewrite_image(image, &mirror),
+        None => image.to_string(),
+    }
+}
+
+// Deploys run the mirrored reference, so config and running images only differ if
+// they still differ after both go through the active mirror.
+pub fn same_image(configured: &str, running: &str) -> bool {
+    images_match(configured, running, active().as_deref())
+}
+
+pub fn images_match(configured: &str, running: &str, mirror: Option<&str>) -> bool {
+    match mirror {
+        Some(mirror) => rewrite_image(configured, mirror) == rewrite_image(running, mirror),
+        None => configured == running,
+    }
+}
+
+// Only Docker Hub references move to the mirror; anything with an explicit registry host is kept.
+pub fn rewrite_image(image: &str, mirror: &str) -> String {
+    let mirror = normalize_host(mirror);
+    if mirror.is_empty() {
+        return image.to_string();
+    }
+    let path = match image.split_once('/') {
+        Some((host, rest)) if DOCKER_HUB_HOSTS.contains(&host) => rest,
+        Some((host, _)) if is_registry_host(host) => return image.to_string(),
+        Some(_) => image,
+        None => return format!("{mirror}/library/{image}"),
+    };
+    if path.contains('/') {
+        format!("{mirror}/{path}")
+    } else {
+        format!("{mirror}/library/{path}")
+    }
+}
+
+fn is_registry_host(component: &str) -> bool {
+    component.contains('.') || component.contains(':') || component == "localhost"
+}
+
+fn normalize_host(value: &str) -> String {
+    let value = value.trim();
+    let value = value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"))
+        .unwrap_or(value);
+    value.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{images_match, rewrite_image};
+
+    #[test]
+    fn rewrites_only_docker_hub_references() {
+        let mirror = "mirror.corp.example:5000";
+        assert_eq!(
+            rewrite_image("nginx:latest", mirror),
+            "mirror.corp.example:5000/library/nginx:latest"
+        );
+        assert_eq!(
+            rewrite_image("docker.io/library/redis", mirror),
+            "mirror.corp.example:5000/library/redis"
+        );
+        assert_eq!(
+            rewrite_image("bitnami/postgresql:16", mirror),
+            "mirror.corp.example:5000/bitnami/postgresql:16"
+        );
+        assert_eq!(rewrite_image("ghcr.io/org/app", mirror), "ghcr.io/org/app");
+        assert_eq!(
+            rewrite_image("localhost:5000/app:dev", mirror),
+            "localhost:5000/app:dev"
+        );
+        assert_eq!(
+            rewrite_image("nginx:latest", "https://mirror.corp.example/"),
+            "mirror.corp.example/library/nginx:latest"
+        );
+    }
+
+    #[test]
+    fn mirrored_running_image_matches_its_config_reference() {
+        let mirror = Some("mirror.corp.example:5000");
+        let running = "mirror.corp.example:5000/library/nginx:1.27";
+        assert!(images_match("nginx:1.27", running, mirror));
+        assert!(images_match(
+            "docker.io/library/nginx:1.27",
+            running,
+            mirror
+        ));
+        assert!(!images_match("nginx:1.28", running, mirror));
+        assert!(!images_match("nginx:1.27", running, None));
+        assert!(images_match(
+            "ghcr.io/org/app:v1",
+            "ghcr.io/org/app:v1",
+            mirror
+        ));
+    }
+}
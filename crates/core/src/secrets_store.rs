@@ -6,7 +6,13 @@ use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const SECRET_FILE_SUFFIX: &str = ".secrets.enc";
+const KEY_FILE: &str = "master.key";
+const STAGED_KEY_FILE: &str = "master.key.new";
+const ROTATING_SUFFIX: &str = ".rotating";
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct SecretBlob {
@@ -39,24 +45,194 @@ pub fn delete(project: &str, key: &str) -> Result<bool> {
     Ok(existed)
 }
 
+pub fn all(project: &str) -> Result<BTreeMap<String, String>> {
+    Ok(load_map(project)?.values)
+}
+
 pub fn list(project: &str) -> Result<Vec<String>> {
     let map = load_map(project)?;
     Ok(map.values.keys().cloned().collect())
 }
 
+// The master key is shared by every project store, so rotation re-encrypts all of them.
+pub fn rotate(new_key: [u8; 32]) -> Result<Vec<String>> {
+    let dir = secrets_dir()?;
+    let projects = stage_rotation(&dir, new_key)?;
+    commit_rotation(&dir)?;
+    Ok(projects)
+}
+
+// Every store is decrypted up front and re-encrypted into `<store>.rotating`; the staged key
+// is written last, so its presence means the staged stores are complete and the rotation can
+// be rolled forward after a crash.
+fn stage_rotation(dir: &Path, new_key: [u8; 32]) -> Result<Vec<String>> {
+    let old_key = load_or_create_key_in(dir)?;
+    if old_key == new_key {
+        anyhow::bail!("New secrets key is identical to the current key");
+    }
+    // Leftovers of a rotation that crashed before its key was staged never took effect.
+    for (path, name) in dir_entries(dir)? {
+        if name.ends_with(ROTATING_SUFFIX) || name.ends_with(".tmp") {
+            fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+        }
+    }
+
+    let mut stores = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to list {:?}", dir))? {
+        let path = entry?.path();
+        let Some(project) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(SECRET_FILE_SUFFIX))
+        else {
+            continue;
+        };
+        let map = decrypt_blob_with(&read_blob(&path)?, &old_key)
+            .with_context(|| format!("Failed to decrypt secrets for project '{}'", project))?;
+        stores.push((project.to_string(), path, map));
+    }
+    stores.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (_, path, map) in &stores {
+        let blob = encrypt_map_with(map, &new_key)?;
+        write_private(
+            &with_suffix(path, ROTATING_SUFFIX)?,
+            serde_json::to_string_pretty(&blob)?.as_bytes(),
+        )?;
+    }
+    write_private(&dir.join(STAGED_KEY_FILE), &new_key)?;
+    sync_dir(dir)?;
+
+    Ok(stores.into_iter().map(|(project, _, _)| project).collect())
+}
+
+// Idempotent, so an interrupted commit is finished by simply running it again; files another
+// process already moved into place are skipped.
+fn commit_rotation(dir: &Path) -> Result<()> {
+    for (path, name) in dir_entries(dir)? {
+        if let Some(live) = name.strip_suffix(ROTATING_SUFFIX) {
+            if live.ends_with(SECRET_FILE_SUFFIX) {
+                rename_if_present(&path, &dir.join(live))?;
+            }
+        }
+    }
+    rename_if_present(&dir.join(STAGED_KEY_FILE), &dir.join(KEY_FILE))?;
+    sync_dir(dir)?;
+
+    // Backups from earlier rotations still decrypt under a retired key.
+    for (path, name) in dir_entries(dir)? {
+        let backup = name
+            .strip_suffix(".bak")
+            .is_some_and(|n| n == KEY_FILE || n.ends_with(SECRET_FILE_SUFFIX));
+        if backup {
+            fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+        }
+    }
+    Ok(())
+}
+
+// Staged stores without a staged key are ignored: that rotation never committed.
+fn recover_rotation(dir: &Path) -> Result<()> {
+    if !dir.join(STAGED_KEY_FILE).exists() {
+        return Ok(());
+    }
+    warn!(
+        "Completing an interrupted secrets key rotation in {:?}",
+        dir
+    );
+    commit_rotation(dir)
+}
+
+fn rename_if_present(from: &Path, to: &Path) -> Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result.with_context(|| format!("Failed to replace {:?}", to)),
+    }
+}
+
+fn dir_entries(dir: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to list {:?}", dir))? {
+        let path = entry?.path();
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            let name = name.to_string();
+            entries.push((path, name));
+        }
+    }
+    Ok(entries)
+}
+
+fn sync_dir(dir: &Path) -> Result<()> {
+    #[cfg(unix)]
+    fs::File::open(dir)
+        .and_then(|d| d.sync_all())
+        .with_context(|| format!("Failed to sync {:?}", dir))?;
+    Ok(())
+}
+
+pub fn parse_key(value: &str) -> Result<[u8; 32]> {
+    let value = value.trim();
+    let bytes = if value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        (0..64)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .context("Invalid hex secrets key")?
+    } else {
+        B64.decode(value.as_bytes())
+            .context("Secrets key must be 32 bytes encoded as base64 or 64 hex characters")?
+    };
+    bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| anyhow::anyhow!("Secrets key must be 32 bytes, got {}", b.len()))
+}
+
 fn load_map(project: &str) -> Result<SecretMap> {
     let path = secret_file(project)?;
     if !path.exists() {
         return Ok(SecretMap::default());
     }
 
-    let blob: SecretBlob = serde_json::from_str(
-        &fs::read_to_string(&path)
+    decrypt_blob(&read_blob(&path)?)
+}
+
+fn read_blob(path: &Path) -> Result<SecretBlob> {
+    serde_json::from_str(
+        &fs::read_to_string(path)
             .with_context(|| format!("Failed to read secret file {:?}", path))?,
     )
-    .with_context(|| format!("Failed to parse secret blob {:?}", path))?;
+    .with_context(|| format!("Failed to parse secret blob {:?}", path))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .with_context(|| format!("Invalid secrets path {:?}", path))?;
+    Ok(path.with_file_name(format!("{file_name}{suffix}")))
+}
 
-    decrypt_blob(&blob)
+// Writes a 0600 file via a synced `.tmp` sibling and a rename, so `path` is never half-written.
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let staged = with_suffix(path, ".tmp")?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    {
+        use std::io::Write;
+        let mut file = options
+            .open(&staged)
+            .with_context(|| format!("Failed to open {:?}", staged))?;
+        file.write_all(contents)
+            .and_then(|_| file.sync_all())
+            .with_context(|| format!("Failed to write {:?}", staged))?;
+    }
+    fs::rename(&staged, path).with_context(|| format!("Failed to replace {:?}", path))?;
+    Ok(())
 }
 
 fn save_map(project: &str, map: &SecretMap) -> Result<()> {
@@ -72,8 +248,11 @@ fn save_map(project: &str, map: &SecretMap) -> Result<()> {
 }
 
 fn encrypt_map(map: &SecretMap) -> Result<SecretBlob> {
-    let key = load_or_create_key()?;
-    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+    encrypt_map_with(map, &load_or_create_key()?)
+}
+
+fn encrypt_map_with(map: &SecretMap, key: &[u8; 32]) -> Result<SecretBlob> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
 
     let mut nonce = [0u8; 24];
     OsRng.fill_bytes(&mut nonce);
@@ -93,9 +272,15 @@ fn decrypt_blob(blob: &SecretBlob) -> Result<SecretMap> {
     if blob.nonce_b64.is_empty() && blob.ciphertext_b64.is_empty() {
         return Ok(SecretMap::default());
     }
+    decrypt_blob_with(blob, &load_or_create_key()?)
+}
+
+fn decrypt_blob_with(blob: &SecretBlob, key: &[u8; 32]) -> Result<SecretMap> {
+    if blob.nonce_b64.is_empty() && blob.ciphertext_b64.is_empty() {
+        return Ok(SecretMap::default());
+    }
 
-    let key = load_or_create_key()?;
-    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
 
     let nonce = B64
         .decode(blob.nonce_b64.as_bytes())
@@ -119,16 +304,17 @@ fn secrets_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
-fn key_file() -> Result<PathBuf> {
-    Ok(secrets_dir()?.join("master.key"))
-}
-
 fn secret_file(project: &str) -> Result<PathBuf> {
-    Ok(secrets_dir()?.join(format!("{}.secrets.enc", project)))
+    Ok(secrets_dir()?.join(format!("{}{}", project, SECRET_FILE_SUFFIX)))
 }
 
 fn load_or_create_key() -> Result<[u8; 32]> {
-    let path = key_file()?;
+    load_or_create_key_in(&secrets_dir()?)
+}
+
+fn load_or_create_key_in(dir: &Path) -> Result<[u8; 32]> {
+    recover_rotation(dir)?;
+    let path = dir.join(KEY_FILE);
 
     if path.exists() {
         let bytes =
@@ -156,8 +342,13 @@ fn load_or_create_key() -> Result<[u8; 32]> {
 
 #[cfg(test)]
 mod tests {
-    use super::{decrypt_blob, encrypt_map, SecretMap};
+    use super::{
+        decrypt_blob, decrypt_blob_with, encrypt_map, encrypt_map_with, load_or_create_key_in,
+        parse_key, read_blob, stage_rotation, write_private, SecretMap, KEY_FILE, ROTATING_SUFFIX,
+        STAGED_KEY_FILE,
+    };
     use std::collections::BTreeMap;
+    use std::fs;
 
     #[test]
     fn encrypt_decrypt_round_trip() {
@@ -168,4 +359,79 @@ mod tests {
         let out = decrypt_blob(&blob).expect("decrypt should succeed");
         assert_eq!(out.values.get("TOKEN").unwrap(), "abc123");
     }
+
+    #[test]
+    fn rotation_re_encrypts_under_new_key() {
+        let old_key = [7u8; 32];
+        let new_key = parse_key(&"ab".repeat(32)).expect("hex key should parse");
+        let map = SecretMap {
+            values: BTreeMap::from([("DB_PASSWORD".to_string(), "s3cret".to_string())]),
+        };
+
+        let old_blob = encrypt_map_with(&map, &old_key).expect("encrypt under old key");
+        let plain = decrypt_blob_with(&old_blob, &old_key).expect("decrypt under old key");
+        let new_blob = encrypt_map_with(&plain, &new_key).expect("encrypt under new key");
+
+        let rotated = decrypt_blob_with(&new_blob, &new_key).expect("decrypt under new key");
+        assert_eq!(rotated.values.get("DB_PASSWORD").unwrap(), "s3cret");
+        assert!(decrypt_blob_with(&new_blob, &old_key).is_err());
+        assert_ne!(new_blob.ciphertext_b64, old_blob.ciphertext_b64);
+        assert!(parse_key("too-short").is_err());
+    }
+
+    #[test]
+    fn interrupted_rotation_rolls_forward_after_the_staged_key_and_back_before_it() {
+        let dir = std::env::temp_dir().join(format!("airstack-secrets-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create dir");
+        let old_key = [7u8; 32];
+        let new_key = [9u8; 32];
+        write_private(&dir.join(KEY_FILE), &old_key).expect("write key");
+        for project in ["api", "web"] {
+            let map = SecretMap {
+                values: BTreeMap::from([("TOKEN".to_string(), project.to_string())]),
+            };
+            let blob = encrypt_map_with(&map, &old_key).expect("encrypt");
+            let json = serde_json::to_string(&blob).expect("serialize");
+            write_private(&dir.join(format!("{project}.secrets.enc")), json.as_bytes())
+                .expect("write store");
+        }
+        fs::write(dir.join("master.key.bak"), old_key).expect("write stale backup");
+        let stores = |key: &[u8; 32]| {
+            ["api", "web"].map(|p| {
+                decrypt_blob_with(
+                    &read_blob(&dir.join(format!("{p}.secrets.enc"))).unwrap(),
+                    key,
+                )
+                .map(|m| m.values["TOKEN"].clone())
+                .ok()
+            })
+        };
+
+        // Crash while staging: without the staged key the old key and stores stay live.
+        stage_rotation(&dir, new_key).expect("stage");
+        fs::remove_file(dir.join(STAGED_KEY_FILE)).expect("drop staged key");
+        assert_eq!(load_or_create_key_in(&dir).expect("load"), old_key);
+        assert_eq!(stores(&old_key), [Some("api".into()), Some("web".into())]);
+
+        // Crash after one store was swapped in: the rotation is rolled forward.
+        stage_rotation(&dir, new_key).expect("stage");
+        fs::rename(
+            dir.join(format!("api.secrets.enc{ROTATING_SUFFIX}")),
+            dir.join("api.secrets.enc"),
+        )
+        .expect("partial commit");
+        assert_eq!(load_or_create_key_in(&dir).expect("load"), new_key);
+        assert_eq!(stores(&new_key), [Some("api".into()), Some("web".into())]);
+
+        let mut left = fs::read_dir(&dir)
+            .expect("list")
+            .map(|e| e.expect("entry").file_name().into_string().expect("utf8"))
+            .collect::<Vec<_>>();
+        left.sort();
+        assert_eq!(
+            left,
+            vec!["api.secrets.enc", "master.key", "web.secrets.enc"]
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,138 @@
+use airstack_config::{AirstackConfig, ServiceConfig};
+use std::collections::{BTreeSet, HashMap};
+
+const ENV_PROFILE: &str = "AIRSTACK_SERVICE_PROFILE";
+
+pub fn configure(profile: Option<&str>) {
+    match profile.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(profile) => std::env::set_var(ENV_PROFILE, profile),
+        None => std::env::remove_var(ENV_PROFILE),
+    }
+}
+
+pub fn active() -> Option<String> {
+    std::env::var(ENV_PROFILE).ok().filter(|p| !p.is_empty())
+}
+
+pub fn known_profiles(services: &HashMap<String, ServiceConfig>) -> BTreeSet<String> {
+    services
+        .values()
+        .filter_map(|svc| svc.profile.clone())
+        .collect()
+}
+
+// Keeps profile-less services, services in `profile`, and anything those depend on.
+pub fn select_services(
+    services: &HashMap<String, ServiceConfig>,
+    profile: &str,
+) -> BTreeSet<String> {
+    let mut selected = BTreeSet::new();
+    let mut pending = services
+        .iter()
+        .filter(|(_, svc)| svc.profile.as_deref().is_none_or(|p| p == profile))
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    while let Some(name) = pending.pop() {
+        if !selected.insert(name.clone()) {
+            continue;
+        }
+        if let Some(svc) = services.get(&name) {
+            pending.extend(
+                svc.depends_on
+                    .iter()
+                    .flatten()
+                    .filter(|dep| services.contains_key(*dep))
+                    .cloned(),
+            );
+        }
+    }
+    selected
+}
+
+pub fn apply_active(config: &mut AirstackConfig) -> bool {
+    let Some(profile) = active() else {
+        return false;
+    };
+    if let Some(services) = config.services.as_mut() {
+        let selected = select_services(services, &profile);
+        services.retain(|name, _| selected.contains(name));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{known_profiles, select_services};
+    use airstack_config::ServiceConfig;
+    use std::collections::HashMap;
+
+    fn svc(profile: Option<&str>, depends_on: &[&str]) -> ServiceConfig {
+        ServiceConfig {
+            image: "repo/app:v1".to_string(),
+            ports: vec![],
+            env: None,
+            volumes: None,
+            depends_on: (!depends_on.is_empty())
+                .then(|| depends_on.iter().map(|d| d.to_string()).collect()),
+            target_server: None,
+            healthcheck: None,
+            profile: profile.map(str::to_string),
+            secrets: None,
+            stop_timeout_secs: None,
+            deploy_timeout_secs: None,
+            verify_digest: None,
+            env_file: None,
+            restart_policy: None,
+            log_max_size: None,
+            log_max_file: None,
+            command: None,
+            entrypoint: None,
+        }
+    }
+
+    #[test]
+    fn db_profile_selects_only_db_profiled_services() {
+        let services = HashMap::from([
+            ("postgres".to_string(), svc(Some("db"), &[])),
+            ("redis".to_string(), svc(Some("db"), &[])),
+            ("api".to_string(), svc(Some("web"), &["postgres"])),
+            ("worker".to_string(), svc(Some("jobs"), &[])),
+        ]);
+        assert_eq!(
+            select_services(&services, "db")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["postgres", "redis"]
+        );
+        assert_eq!(
+            select_services(&services, "web")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["api", "postgres"]
+        );
+        assert_eq!(
+            known_profiles(&services).into_iter().collect::<Vec<_>>(),
+            vec!["db", "jobs", "web"]
+        );
+    }
+
+    #[test]
+    fn profile_less_services_are_always_selected() {
+        let services = HashMap::from([
+            ("postgres".to_string(), svc(Some("db"), &[])),
+            ("proxy".to_string(), svc(None, &[])),
+        ]);
+        assert_eq!(
+            select_services(&services, "db")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["postgres", "proxy"]
+        );
+        assert_eq!(
+            select_services(&services, "web")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["proxy"]
+        );
+    }
+}
@@ -2,17 +2,36 @@ use airstack_config::ServerConfig;
 use airstack_metal::{get_provider as get_metal_provider, Server};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
+
+const ENV_SSH_TIMEOUT: &str = "AIRSTACK_SSH_TIMEOUT";
+pub const DEFAULT_SSH_TIMEOUT_SECS: u64 = 15;
+
+pub fn configure_timeout(secs: u64) {
+    std::env::set_var(ENV_SSH_TIMEOUT, secs.max(1).to_string());
+}
+
+pub fn timeout_secs() -> u64 {
+    std::env::var(ENV_SSH_TIMEOUT)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_SSH_TIMEOUT_SECS)
+}
 
 #[derive(Debug, Clone)]
 pub struct SshCommandOptions<'a> {
     pub user: &'a str,
+    pub port: Option<u16>,
     pub batch_mode: bool,
     pub connect_timeout_secs: Option<u64>,
+    pub server_alive_interval_secs: Option<u64>,
     pub strict_host_key_checking: &'a str,
     pub user_known_hosts_file: Option<&'a str>,
     pub log_level: &'a str,
+    pub force_tty: bool,
 }
 
 pub fn build_ssh_command(
@@ -28,6 +47,10 @@ pub fn build_ssh_command(
     if let Some(timeout) = options.connect_timeout_secs {
         ssh_cmd.args(["-o", &format!("ConnectTimeout={timeout}")]);
     }
+    if let Some(interval) = options.server_alive_interval_secs {
+        ssh_cmd.args(["-o", &format!("ServerAliveInterval={interval}")]);
+        ssh_cmd.args(["-o", "ServerAliveCountMax=3"]);
+    }
     ssh_cmd.args([
         "-o",
         &format!("StrictHostKeyChecking={}", options.strict_host_key_checking),
@@ -36,16 +59,56 @@ pub fn build_ssh_command(
         ssh_cmd.args(["-o", &format!("UserKnownHostsFile={path}")]);
     }
     ssh_cmd.args(["-o", &format!("LogLevel={}", options.log_level)]);
+    if options.force_tty {
+        ssh_cmd.arg("-t");
+    }
 
     if let Some(identity_path) = resolve_identity_path(ssh_key)? {
         ssh_cmd.args(["-i", &identity_path.to_string_lossy()]);
     }
+    if let Some(port) = options.port.filter(|port| *port != 22) {
+        ssh_cmd.args(["-p", &port.to_string()]);
+    }
 
     ssh_cmd.arg(format!("{}@{}", options.user, ip));
     Ok(ssh_cmd)
 }
 
-fn shell_escape(arg: &str) -> String {
+fn remote_options(user: &str, port: Option<u16>, force_tty: bool) -> SshCommandOptions<'_> {
+    let timeout = timeout_secs();
+    SshCommandOptions {
+        user,
+        port,
+        batch_mode: false,
+        connect_timeout_secs: Some(timeout),
+        server_alive_interval_secs: Some(timeout),
+        strict_host_key_checking: "no",
+        user_known_hosts_file: Some("/dev/null"),
+        log_level: "ERROR",
+        force_tty,
+    }
+}
+
+pub fn check_ssh_reachable(server_cfg: &ServerConfig, ip: &str) -> Result<()> {
+    let mut options = remote_options(server_cfg.ssh_user(), Some(server_cfg.ssh_port()), false);
+    options.batch_mode = true;
+    let out = build_ssh_command(&server_cfg.ssh_key, ip, &options)?
+        .arg("true")
+        .output()
+        .context("Failed to execute SSH reachability check")?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "Server '{}' ({}) is not reachable over SSH within {}s: {}",
+            server_cfg.name,
+            ip,
+            options.connect_timeout_secs.unwrap_or_default(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+pub fn shell_escape(arg: &str) -> String {
     if arg.is_empty() {
         return "''".to_string();
     }
@@ -98,6 +161,19 @@ pub async fn execute_remote_shell_command(
     server_cfg: &ServerConfig,
     command: &str,
 ) -> Result<Output> {
+    command_output(remote_shell_process(server_cfg, command).await?).await
+}
+
+// ssh/flyctl block for as long as the remote command runs, so keep them off the async workers.
+pub async fn command_output(mut cmd: Command) -> Result<Output> {
+    tokio::task::spawn_blocking(move || cmd.output())
+        .await
+        .context("SSH command task failed")?
+        .context("Failed to execute SSH command")
+}
+
+// Builds (without running) the ssh/flyctl invocation so callers can stream its output.
+pub async fn remote_shell_process(server_cfg: &ServerConfig, command: &str) -> Result<Command> {
     if server_cfg.provider == "fly" {
         let (app, machine) = resolve_fly_target(server_cfg).await?;
 
@@ -112,30 +188,62 @@ pub async fn execute_remote_shell_command(
         }
         fly_cmd.arg("--command");
         fly_cmd.arg(command);
-
-        return fly_cmd
-            .output()
-            .context("Failed to execute Fly SSH command");
+        return Ok(fly_cmd);
     }
 
     let ip = resolve_server_public_ip(server_cfg).await?;
     let mut ssh_cmd = build_ssh_command(
         &server_cfg.ssh_key,
         &ip,
-        &SshCommandOptions {
-            user: "root",
-            batch_mode: false,
-            connect_timeout_secs: None,
-            strict_host_key_checking: "no",
-            user_known_hosts_file: Some("/dev/null"),
-            log_level: "ERROR",
-        },
+        &remote_options(server_cfg.ssh_user(), Some(server_cfg.ssh_port()), false),
     )?;
     ssh_cmd.arg(command);
-    ssh_cmd.output().context("Failed to execute SSH command")
+    Ok(ssh_cmd)
+}
+
+pub async fn execute_remote_command_with_stdin(
+    server_cfg: &ServerConfig,
+    command: &[String],
+    stdin: &[u8],
+) -> Result<Output> {
+    if server_cfg.provider == "fly" {
+        anyhow::bail!(
+            "piping stdin to remote commands is not supported for provider='fly' (server '{}')",
+            server_cfg.name
+        );
+    }
+
+    let ip = resolve_server_public_ip(server_cfg).await?;
+    let mut ssh_cmd = build_ssh_command(
+        &server_cfg.ssh_key,
+        &ip,
+        &remote_options(server_cfg.ssh_user(), Some(server_cfg.ssh_port()), false),
+    )?;
+    ssh_cmd.arg(join_shell_command(command));
+    let mut child = ssh_cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute SSH command")?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open SSH stdin")?
+        .write_all(stdin)
+        .context("Failed to write SSH stdin")?;
+    child
+        .wait_with_output()
+        .context("Failed to wait for SSH command")
 }
 
-pub async fn start_remote_session(server_cfg: &ServerConfig, command: &[String]) -> Result<i32> {
+// `force_tty` requests `ssh -t`; only interactive container exec needs it, since a
+// forced tty on streaming commands like `logs --follow` mangles their output.
+pub async fn start_remote_session(
+    server_cfg: &ServerConfig,
+    command: &[String],
+    force_tty: bool,
+) -> Result<i32> {
     if server_cfg.provider == "fly" {
         let (app, machine) = resolve_fly_target(server_cfg).await?;
 
@@ -162,14 +270,11 @@ pub async fn start_remote_session(server_cfg: &ServerConfig, command: &[String])
     let mut ssh_cmd = build_ssh_command(
         &server_cfg.ssh_key,
         &ip,
-        &SshCommandOptions {
-            user: "root",
-            batch_mode: false,
-            connect_timeout_secs: None,
-            strict_host_key_checking: "no",
-            user_known_hosts_file: Some("/dev/null"),
-            log_level: "ERROR",
-        },
+        &remote_options(
+            server_cfg.ssh_user(),
+            Some(server_cfg.ssh_port()),
+            force_tty,
+        ),
     )?;
     if !command.is_empty() {
         ssh_cmd.arg(join_shell_command(command));
@@ -232,8 +337,8 @@ pub fn resolve_identity_path(ssh_key: &str) -> Result<Option<PathBuf>> {
 #[cfg(test)]
 mod tests {
     use super::{
-        build_ssh_command, join_shell_command, parse_fly_server_id, resolve_identity_path,
-        SshCommandOptions,
+        build_ssh_command, command_output, join_shell_command, parse_fly_server_id, remote_options,
+        resolve_identity_path, SshCommandOptions,
     };
     use std::fs;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -276,11 +381,14 @@ mod tests {
             "203.0.113.10",
             &SshCommandOptions {
                 user: "root",
+                port: None,
                 batch_mode: true,
                 connect_timeout_secs: Some(7),
+                server_alive_interval_secs: None,
                 strict_host_key_checking: "accept-new",
                 user_known_hosts_file: None,
                 log_level: "ERROR",
+                force_tty: false,
             },
         )
         .expect("command build should succeed");
@@ -300,6 +408,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_ssh_command_uses_custom_user_and_port() {
+        let cmd = build_ssh_command(
+            "",
+            "203.0.113.10",
+            &SshCommandOptions {
+                user: "ubuntu",
+                port: Some(2222),
+                batch_mode: false,
+                connect_timeout_secs: None,
+                server_alive_interval_secs: None,
+                strict_host_key_checking: "no",
+                user_known_hosts_file: None,
+                log_level: "ERROR",
+                force_tty: false,
+            },
+        )
+        .expect("command build should succeed");
+
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|v| v.to_string_lossy().to_string())
+            .collect();
+
+        let port_idx = args
+            .iter()
+            .position(|a| a == "-p")
+            .expect("port flag should be present");
+        assert_eq!(args[port_idx + 1], "2222");
+        assert_eq!(args.last().map(String::as_str), Some("ubuntu@203.0.113.10"));
+    }
+
+    #[test]
+    fn remote_options_apply_default_connect_timeout_and_keepalive() {
+        let cmd = build_ssh_command("", "203.0.113.10", &remote_options("root", None, false))
+            .expect("command build should succeed");
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|v| v.to_string_lossy().to_string())
+            .collect();
+
+        let joined = args.join(" ");
+        assert!(joined.contains("-o ConnectTimeout=15"), "args: {args:?}");
+        assert!(
+            joined.contains("-o ServerAliveInterval=15"),
+            "args: {args:?}"
+        );
+    }
+
     #[test]
     fn join_shell_command_quotes_arguments() {
         let cmd = join_shell_command(&[
@@ -316,4 +473,18 @@ mod tests {
         assert_eq!(parsed.0, "my-app");
         assert_eq!(parsed.1.as_deref(), Some("abc123"));
     }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn command_output_does_not_block_the_runtime() {
+        let sleep = || {
+            let mut cmd = std::process::Command::new("sleep");
+            cmd.arg("0.3");
+            command_output(cmd)
+        };
+        let started = std::time::Instant::now();
+        let (a, b) = tokio::join!(sleep(), sleep());
+        assert!(a.expect("first sleep").status.success());
+        assert!(b.expect("second sleep").status.success());
+        assert!(started.elapsed() < std::time::Duration::from_millis(550));
+    }
 }
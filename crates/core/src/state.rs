@@ -2,7 +2,9 @@ use airstack_config::AirstackConfig;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct LocalState {
@@ -12,6 +14,8 @@ pub struct LocalState {
     pub services: BTreeMap<String, ServiceState>,
     #[serde(default)]
     pub script_runs: BTreeMap<String, ScriptRunState>,
+    #[serde(default)]
+    pub volumes: BTreeMap<String, VolumeState>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -68,6 +72,16 @@ pub struct ServiceState {
     pub last_deploy_unix: Option<u64>,
     #[serde(default)]
     pub image_origin: Option<String>,
+    #[serde(default)]
+    pub image_size_bytes: Option<u64>,
+}
+
+pub const PAUSED_STATUS: &str = "Paused";
+
+impl ServiceState {
+    pub fn is_paused(&self) -> bool {
+        self.replicas == 0 && self.last_status.as_deref() == Some(PAUSED_STATUS)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -78,6 +92,14 @@ pub struct DriftReport {
     pub extra_services_in_cache: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VolumeState {
+    pub server: String,
+    pub id: String,
+    pub linux_device: Option<String>,
+    pub mount_path: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ScriptRunState {
     pub last_hash: Option<String>,
@@ -86,7 +108,10 @@ pub struct ScriptRunState {
 
 impl LocalState {
     pub fn load(project_name: &str) -> Result<Self> {
-        let path = state_file_path(project_name)?;
+        Self::load_from(&state_file_path(project_name)?, project_name)
+    }
+
+    fn load_from(path: &Path, project_name: &str) -> Result<Self> {
         if !path.exists() {
             return Ok(LocalState {
                 project: project_name.to_string(),
@@ -95,10 +120,24 @@ impl LocalState {
             });
         }
 
-        let content = std::fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read local state file: {}", path.display()))?;
-        let mut state: LocalState =
-            serde_json::from_str(&content).context("Failed to parse local state JSON")?;
+        let mut state = match read_state_file(path) {
+            Ok(state) => state,
+            Err(primary_err) => {
+                let backup = backup_path(path);
+                match read_state_file(&backup) {
+                    Ok(state) => {
+                        warn!(
+                            "Local state file {} is unreadable ({:#}); recovered from backup {}",
+                            path.display(),
+                            primary_err,
+                            backup.display()
+                        );
+                        state
+                    }
+                    Err(_) => return Err(primary_err),
+                }
+            }
+        };
         if state.project.is_empty() {
             state.project = project_name.to_string();
         }
@@ -106,8 +145,12 @@ impl LocalState {
     }
 
     pub fn save(&mut self) -> Result<()> {
-        self.updated_at_unix = now_unix();
         let path = state_file_path(&self.project)?;
+        self.save_to(&path)
+    }
+
+    fn save_to(&mut self, path: &Path) -> Result<()> {
+        self.updated_at_unix = now_unix();
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).with_context(|| {
                 format!(
@@ -116,11 +159,31 @@ impl LocalState {
                 )
             })?;
         }
-        std::fs::write(&path, serde_json::to_string_pretty(self)?)
-            .with_context(|| format!("Failed to write local state file: {}", path.display()))?;
+
+        if read_state_file(path).is_ok() {
+            let _ = std::fs::copy(path, backup_path(path));
+        }
+
+        let tmp = path.with_extension(format!("json.tmp-{}", std::process::id()));
+        {
+            let mut file = std::fs::File::create(&tmp)
+                .with_context(|| format!("Failed to create temp state file: {}", tmp.display()))?;
+            file.write_all(serde_json::to_string_pretty(self)?.as_bytes())
+                .and_then(|_| file.sync_all())
+                .with_context(|| format!("Failed to write temp state file: {}", tmp.display()))?;
+        }
+        if let Err(e) = std::fs::rename(&tmp, path) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e)
+                .with_context(|| format!("Failed to write local state file: {}", path.display()));
+        }
         Ok(())
     }
 
+    pub fn acquire_lock(project_name: &str) -> Result<StateLock> {
+        acquire_lock_at(&lock_file_path(project_name)?)
+    }
+
     pub fn detect_drift(&self, config: &AirstackConfig) -> DriftReport {
         let desired_servers = config
             .infra
@@ -162,6 +225,222 @@ impl LocalState {
     }
 }
 
+#[derive(Debug)]
+pub struct StateLock {
+    path: PathBuf,
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn acquire_lock_at(path: &Path) -> Result<StateLock> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create lock directory: {}", parent.display()))?;
+    }
+
+    for _ in 0..2 {
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+        {
+            Ok(mut file) => {
+                let _ = writeln!(file, "{}", std::process::id());
+                return Ok(StateLock {
+                    path: path.to_path_buf(),
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let holder = std::fs::read_to_string(path)
+                    .ok()
+                    .and_then(|s| s.trim().parse::<u32>().ok());
+                if holder.is_some_and(|pid| !process_alive(pid)) {
+                    let _ = std::fs::remove_file(path);
+                    continue;
+                }
+                anyhow::bail!(
+                    "another airstack operation is in progress (pid {}, lock {}). If no other operation is running, remove the lock file and retry.",
+                    holder
+                        .map(|p| p.to_string())
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    path.display()
+                );
+            }
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to create lock file: {}", path.display()))
+            }
+        }
+    }
+    anyhow::bail!(
+        "another airstack operation is in progress (lock {})",
+        path.display()
+    )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub command: String,
+    pub args_summary: String,
+    pub result: String,
+    pub duration_ms: u64,
+}
+
+pub const AUDIT_STARTED: &str = "started";
+
+pub struct AuditRecorder {
+    path: Option<PathBuf>,
+    command: String,
+    args_summary: String,
+    timestamp: u64,
+    started: std::time::Instant,
+}
+
+impl AuditRecorder {
+    // Logs a "started" line up front so a crash or Ctrl-C mid-operation still leaves a trace.
+    pub fn start(project: &str, command: &str, args_summary: String) -> Self {
+        let path = audit_log_path(project)
+            .map_err(|e| warn!("Failed to resolve audit log path: {}", e))
+            .ok();
+        Self::start_at(path, command, args_summary)
+    }
+
+    fn start_at(path: Option<PathBuf>, command: &str, args_summary: String) -> Self {
+        let recorder = Self {
+            path,
+            command: command.to_string(),
+            args_summary,
+            timestamp: now_unix(),
+            started: std::time::Instant::now(),
+        };
+        recorder.append(AUDIT_STARTED.to_string(), 0);
+        recorder
+    }
+
+    pub fn finish<T>(self, result: &Result<T>) {
+        let result = match result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        self.append(result, self.started.elapsed().as_millis() as u64);
+    }
+
+    fn append(&self, result: String, duration_ms: u64) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let entry = AuditEntry {
+            timestamp: self.timestamp,
+            command: self.command.clone(),
+            args_summary: self.args_summary.clone(),
+            result,
+            duration_ms,
+        };
+        // The audit trail must never turn a successful operation into a failure.
+        if let Err(e) = append_audit_entry(path, &entry) {
+            warn!("Failed to append audit log entry: {}", e);
+        }
+    }
+}
+
+pub fn read_audit_log(project_name: &str) -> Result<Vec<AuditEntry>> {
+    read_audit_entries(&audit_log_path(project_name)?)
+}
+
+pub fn audit_log_path(project_name: &str) -> Result<PathBuf> {
+    let base = dirs::home_dir()
+        .context("Could not resolve home directory for audit log")?
+        .join(".airstack");
+    Ok(base
+        .join(sanitize_project_key(project_name))
+        .join("audit.jsonl"))
+}
+
+// Secret values passed on the command line must not end up in the log.
+pub fn summarize_args<I: IntoIterator<Item = String>>(args: I) -> String {
+    let args = args.into_iter().collect::<Vec<_>>();
+    let secret_value = args
+        .windows(2)
+        .position(|w| w[0] == "secrets" && w[1] == "set")
+        .map(|i| i + 3);
+    args.iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            if Some(i) == secret_value {
+                "***".to_string()
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn append_audit_entry(path: &Path, entry: &AuditEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create audit directory: {}", parent.display()))?;
+    }
+    let mut line = serde_json::to_string(entry).context("Failed to serialize audit entry")?;
+    line.push('\n');
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open audit log: {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to append audit log: {}", path.display()))
+}
+
+fn read_audit_entries(path: &Path) -> Result<Vec<AuditEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read audit log: {}", path.display()))?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping malformed audit log line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+fn process_alive(pid: u32) -> bool {
+    let proc_root = Path::new("/proc");
+    if !proc_root.is_dir() {
+        return true;
+    }
+    proc_root.join(pid.to_string()).exists()
+}
+
+fn lock_file_path(project_name: &str) -> Result<PathBuf> {
+    let base = dirs::home_dir()
+        .context("Could not resolve home directory for local state")?
+        .join(".airstack");
+    Ok(base.join(format!("{}.lock", sanitize_project_key(project_name))))
+}
+
+fn read_state_file(path: &Path) -> Result<LocalState> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read local state file: {}", path.display()))?;
+    serde_json::from_str(&content).context("Failed to parse local state JSON")
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
 fn now_unix() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -195,3 +474,127 @@ fn sanitize_project_key(project_name: &str) -> String {
         sanitized
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        acquire_lock_at, append_audit_entry, backup_path, read_audit_entries, summarize_args,
+        AuditEntry, AuditRecorder, LocalState, AUDIT_STARTED,
+    };
+
+    #[test]
+    fn second_lock_acquisition_fails_while_held() {
+        let path = std::env::temp_dir().join(format!(
+            "airstack-lock-test-{}-{}.lock",
+            std::process::id(),
+            super::now_unix()
+        ));
+        let first = acquire_lock_at(&path).expect("first lock acquired");
+        let err = acquire_lock_at(&path).expect_err("second lock should fail");
+        assert!(
+            err.to_string()
+                .contains("another airstack operation is in progress"),
+            "unexpected error: {err}"
+        );
+
+        drop(first);
+        let again = acquire_lock_at(&path).expect("lock re-acquired after release");
+        drop(again);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn truncated_state_recovers_from_backup() {
+        let dir = std::env::temp_dir().join(format!(
+            "airstack-state-test-{}-{}",
+            std::process::id(),
+            super::now_unix()
+        ));
+        let path = dir.join("demo.json");
+
+        let mut state = LocalState::load_from(&path, "demo").expect("empty state");
+        state.services.insert(
+            "api".to_string(),
+            super::ServiceState {
+                image: "repo/api:v1".to_string(),
+                replicas: 1,
+                containers: vec!["api".to_string()],
+                health: super::HealthState::Healthy,
+                last_status: None,
+                last_checked_unix: 0,
+                last_error: None,
+                last_deploy_command: None,
+                last_deploy_unix: None,
+                image_origin: None,
+                image_size_bytes: None,
+            },
+        );
+        state.save_to(&path).expect("first save");
+        state.save_to(&path).expect("second save writes backup");
+        assert!(backup_path(&path).exists());
+
+        let full = std::fs::read_to_string(&path).expect("read primary");
+        std::fs::write(&path, &full[..full.len() / 2]).expect("truncate primary");
+
+        let recovered = LocalState::load_from(&path, "demo").expect("recovered from backup");
+        assert_eq!(recovered.project, "demo");
+        assert!(recovered.services.contains_key("api"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn sequential_operations_append_audit_lines() {
+        let dir = std::env::temp_dir().join(format!(
+            "airstack-audit-test-{}-{}",
+            std::process::id(),
+            super::now_unix()
+        ));
+        let path = dir.join("demo").join("audit.jsonl");
+        for (command, result) in [("deploy", "ok"), ("destroy", "error: boom")] {
+            let entry = AuditEntry {
+                timestamp: super::now_unix(),
+                command: command.to_string(),
+                args_summary: format!("{command} --yes"),
+                result: result.to_string(),
+                duration_ms: 5,
+            };
+            append_audit_entry(&path, &entry).expect("append audit entry");
+        }
+
+        let raw = std::fs::read_to_string(&path).expect("read audit log");
+        let lines = raw.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            let value: serde_json::Value = serde_json::from_str(line).expect("well-formed json");
+            for key in [
+                "timestamp",
+                "command",
+                "args_summary",
+                "result",
+                "duration_ms",
+            ] {
+                assert!(value.get(key).is_some(), "missing {key} in {line}");
+            }
+        }
+        let entries = read_audit_entries(&path).expect("parse audit log");
+        assert_eq!(entries[0].command, "deploy");
+        assert_eq!(entries[1].result, "error: boom");
+
+        let recorder = AuditRecorder::start_at(Some(path.clone()), "up", "up".to_string());
+        let started = read_audit_entries(&path).expect("parse audit log");
+        assert_eq!(started.len(), 3);
+        assert_eq!(started[2].result, AUDIT_STARTED);
+        recorder.finish(&Ok::<(), anyhow::Error>(()));
+        let finished = read_audit_entries(&path).expect("parse audit log");
+        assert_eq!(finished.len(), 4);
+        assert_eq!(finished[3].command, "up");
+        assert_eq!(finished[3].result, "ok");
+
+        assert_eq!(
+            summarize_args(["secrets", "set", "DB_PASS", "hunter2"].map(String::from)),
+            "secrets set DB_PASS ***"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -12,8 +12,16 @@ pub const GRAY_500: Rgb = (149, 161, 172);
 pub const STEEL_300: Rgb = (161, 194, 220);
 pub const STEEL_200: Rgb = (206, 226, 242);
 pub const OCEAN_400: Rgb = (102, 167, 214);
+pub const GREEN_400: Rgb = (152, 195, 121);
+pub const AMBER_400: Rgb = (229, 192, 123);
+pub const VIOLET_400: Rgb = (198, 120, 221);
+pub const TEAL_400: Rgb = (86, 182, 194);
+// Distinct per-service prefixes for multiplexed log output.
+pub const SERVICE_PALETTE: [Rgb; 5] = [OCEAN_400, GREEN_400, AMBER_400, VIOLET_400, TEAL_400];
 #[cfg(feature = "tui")]
 pub const WHITE_100: Rgb = (224, 229, 233);
+#[cfg(feature = "tui")]
+pub const RED_400: Rgb = (224, 108, 117);
 
 pub fn ansi_fg(text: impl AsRef<str>, rgb: Rgb) -> String {
     let (r, g, b) = rgb;
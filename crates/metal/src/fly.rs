@@ -3,6 +3,7 @@ use crate::{
     ProviderCapabilities, Server, ServerStatus,
 };
 use anyhow::{Context, Result};
+use ring::rand::{SecureRandom, SystemRandom};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::process::Command as StdCommand;
@@ -54,8 +55,81 @@ struct FlyIp {
     ip_type: String,
 }
 
+const TRANSIENT_FLYCTL_MARKERS: &[&str] = &[
+    "timeout",
+    "timed out",
+    "deadline exceeded",
+    "connection reset",
+    "connection refused",
+    "connection closed",
+    "broken pipe",
+    "unexpected eof",
+    "temporary failure",
+    "no such host",
+    "tls handshake",
+    "network is unreachable",
+    "too many requests",
+    "rate limit",
+    "bad gateway",
+    "service unavailable",
+    "gateway timeout",
+    "internal server error",
+];
+
+const DETERMINISTIC_FLYCTL_MARKERS: &[&str] = &[
+    "not found",
+    "could not find app",
+    "unauthorized",
+    "not authorized",
+    "authentication",
+    "access token",
+    "no access token",
+    "permission denied",
+    "forbidden",
+    "invalid",
+];
+
+pub fn is_transient_flyctl_error(stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    if DETERMINISTIC_FLYCTL_MARKERS
+        .iter()
+        .any(|marker| stderr.contains(marker))
+    {
+        return false;
+    }
+    TRANSIENT_FLYCTL_MARKERS
+        .iter()
+        .any(|marker| stderr.contains(marker))
+        || has_5xx_status(&stderr)
+}
+
+// Matches "status code: 503", "status 502" and similar, but not a bare number elsewhere in stderr.
+fn has_5xx_status(stderr: &str) -> bool {
+    stderr.match_indices("status").any(|(idx, _)| {
+        let rest = stderr[idx + "status".len()..].trim_start();
+        let rest = rest.strip_prefix("code").unwrap_or(rest);
+        let code = rest.trim_start_matches([' ', ':', '=']).as_bytes();
+        code.len() >= 3
+            && code[0] == b'5'
+            && code[1..3].iter().all(u8::is_ascii_digit)
+            && code.get(3).is_none_or(|b| !b.is_ascii_digit())
+    })
+}
+
+fn flyctl_retry_delay(attempt: u32) -> Duration {
+    let base_ms = 500u64 << attempt.min(4);
+    let mut bytes = [0u8; 2];
+    let jitter_ms = match SystemRandom::new().fill(&mut bytes) {
+        Ok(()) => u64::from(u16::from_le_bytes(bytes)) % (base_ms / 2 + 1),
+        Err(_) => 0,
+    };
+    Duration::from_millis(base_ms + jitter_ms)
+}
+
 impl FlyProvider {
     const DEFAULT_REGION: &'static str = "iad";
+    const FLYCTL_RETRIES: u32 = 3;
+    const FLYCTL_TIMEOUT: Duration = Duration::from_secs(60);
     pub fn new(config: HashMap<String, String>) -> Result<Self> {
         let token = config
             .get("api_token")
@@ -87,24 +161,73 @@ impl FlyProvider {
         })
     }
 
-    async fn run_flyctl(&self, args: &[&str]) -> Result<std::process::Output> {
+    // Returns None when the local timeout expires; the child is killed rather than left running.
+    async fn flyctl_output(&self, args: &[&str]) -> Result<Option<std::process::Output>> {
         let mut cmd = Command::new("flyctl");
-        cmd.args(args);
+        cmd.args(args).kill_on_drop(true);
         if let Some(token) = &self.token {
             cmd.env("FLY_ACCESS_TOKEN", token);
             cmd.env("FLY_API_TOKEN", token);
         }
-
         debug!("flyctl {}", args.join(" "));
-        let output = timeout(Duration::from_secs(60), cmd.output())
-            .await
-            .context("flyctl command timed out")?
-            .context("Failed to execute flyctl command")?;
-        Ok(output)
+        match timeout(Self::FLYCTL_TIMEOUT, cmd.output()).await {
+            Ok(output) => output.map(Some).context("Failed to execute flyctl command"),
+            Err(_) => Ok(None),
+        }
+    }
+
+    // Single attempt: mutating commands (apps create, machine run/destroy, ips allocate) may have
+    // taken effect even when flyctl reports an error, so they are never repeated.
+    async fn run_flyctl(&self, args: &[&str]) -> Result<std::process::Output> {
+        self.flyctl_output(args)
+            .await?
+            .ok_or_else(|| Self::flyctl_timed_out(args))
+    }
+
+    fn flyctl_timed_out(args: &[&str]) -> anyhow::Error {
+        anyhow::anyhow!(
+            "flyctl command timed out after {}s ({})",
+            Self::FLYCTL_TIMEOUT.as_secs(),
+            args.join(" ")
+        )
+    }
+
+    // Read-only commands retry transient failures, including the local timeout.
+    async fn run_flyctl_read(&self, args: &[&str]) -> Result<std::process::Output> {
+        let mut attempt = 0;
+        loop {
+            let reason = match self.flyctl_output(args).await? {
+                Some(output) => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    if output.status.success()
+                        || attempt >= Self::FLYCTL_RETRIES
+                        || !is_transient_flyctl_error(&stderr)
+                    {
+                        return Ok(output);
+                    }
+                    stderr.trim().to_string()
+                }
+                None if attempt < Self::FLYCTL_RETRIES => {
+                    format!("timed out after {}s", Self::FLYCTL_TIMEOUT.as_secs())
+                }
+                None => return Err(Self::flyctl_timed_out(args)),
+            };
+            let delay = flyctl_retry_delay(attempt);
+            attempt += 1;
+            warn!(
+                "flyctl {} failed transiently ({}); retrying in {}ms (attempt {}/{})",
+                args.join(" "),
+                reason,
+                delay.as_millis(),
+                attempt,
+                Self::FLYCTL_RETRIES
+            );
+            sleep(delay).await;
+        }
     }
 
     async fn run_flyctl_json<T: for<'de> Deserialize<'de>>(&self, args: &[&str]) -> Result<T> {
-        let out = self.run_flyctl(args).await?;
+        let out = self.run_flyctl_read(args).await?;
         if !out.status.success() {
             let stderr = String::from_utf8_lossy(&out.stderr);
             anyhow::bail!(
@@ -236,6 +359,7 @@ impl FlyProvider {
                     private_ip: None,
                     server_type: "fly-app".to_string(),
                     region: "global".to_string(),
+                    managed: None,
                 }];
             }
         };
@@ -249,6 +373,7 @@ impl FlyProvider {
                 private_ip: None,
                 server_type: "fly-app/0-machines".to_string(),
                 region: "global".to_string(),
+                managed: None,
             }];
         }
 
@@ -285,6 +410,7 @@ impl FlyProvider {
             private_ip,
             server_type: format!("fly-app/{}-machines", machine_count),
             region,
+            managed: None,
         }]
     }
 }
@@ -298,6 +424,7 @@ impl MetalProvider for FlyProvider {
             supports_provider_ssh: true,
             supports_server_create: true,
             supports_server_destroy: true,
+            supports_firewall: false,
         }
     }
 
@@ -319,6 +446,7 @@ impl MetalProvider for FlyProvider {
                     .region
                     .clone()
                     .unwrap_or_else(|| "global".to_string()),
+                managed: None,
             });
         }
 
@@ -373,6 +501,7 @@ impl MetalProvider for FlyProvider {
             private_ip: machine.private_ip.clone(),
             server_type: Self::server_type_for_machine(&machine),
             region: machine.region.unwrap_or_else(|| "global".to_string()),
+            managed: None,
         })
     }
 
@@ -471,6 +600,7 @@ impl MetalProvider for FlyProvider {
             private_ip: machine.private_ip.clone(),
             server_type: Self::server_type_for_machine(&machine),
             region: machine.region.unwrap_or_else(|| "global".to_string()),
+            managed: None,
         })
     }
 
@@ -523,7 +653,43 @@ impl MetalProvider for FlyProvider {
 
 #[cfg(test)]
 mod tests {
-    use super::FlyProvider;
+    use super::{is_transient_flyctl_error, FlyProvider};
+
+    #[test]
+    fn classifies_transient_and_deterministic_flyctl_errors() {
+        assert!(is_transient_flyctl_error(
+            "Error: Get \"https://api.machines.dev/v1/apps\": dial tcp: i/o timeout"
+        ));
+        assert!(is_transient_flyctl_error(
+            "Error: server returned a non-200 status code: 503"
+        ));
+        assert!(is_transient_flyctl_error("read: connection reset by peer"));
+        assert!(!is_transient_flyctl_error(
+            "Error: Could not find App \"demo\""
+        ));
+        assert!(!is_transient_flyctl_error(
+            "Error: No access token available. Please login with 'flyctl auth login'"
+        ));
+        assert!(!is_transient_flyctl_error("Error: unauthorized"));
+        assert!(!is_transient_flyctl_error(""));
+    }
+
+    #[test]
+    fn only_5xx_status_codes_count_as_transient() {
+        assert!(is_transient_flyctl_error(
+            "Error: request failed with status code 502"
+        ));
+        assert!(is_transient_flyctl_error("Error: unexpected EOF"));
+        assert!(!is_transient_flyctl_error(
+            "Error: status code: 5000 is out of range"
+        ));
+        assert!(!is_transient_flyctl_error(
+            "Error: image tag v500 has no manifest for linux/amd64"
+        ));
+        assert!(!is_transient_flyctl_error(
+            "Error: volume vol_500 is attached to machine 1850ee5 (EOF-safe mode)"
+        ));
+    }
 
     #[test]
     fn parse_server_id_supports_app_and_machine() {
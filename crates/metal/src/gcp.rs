@@ -0,0 +1,541 @@
+use crate::{
+    CreateRequestValidation, CreateServerRequest, MetalProvider, ProviderCapabilities, Server,
+    ServerStatus, MANAGED_LABEL_KEY, MANAGED_LABEL_VALUE,
+};
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use reqwest::Client;
+use ring::rand::SystemRandom;
+use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+const COMPUTE_SCOPE: &str = "https://www.googleapis.com/auth/compute";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    token_uri: Option<String>,
+    project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GcpInstance {
+    #[serde(default)]
+    labels: HashMap<String, String>,
+    name: String,
+    status: String,
+    zone: String,
+    machine_type: String,
+    #[serde(default)]
+    network_interfaces: Vec<GcpNetworkInterface>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GcpNetworkInterface {
+    #[serde(rename = "networkIP")]
+    network_ip: Option<String>,
+    #[serde(default)]
+    access_configs: Vec<GcpAccessConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GcpAccessConfig {
+    #[serde(rename = "natIP")]
+    nat_ip: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GcpAggregatedInstances {
+    #[serde(default)]
+    items: HashMap<String, GcpScopedInstances>,
+    #[serde(rename = "nextPageToken")]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GcpScopedInstances {
+    #[serde(default)]
+    instances: Vec<GcpInstance>,
+}
+
+pub struct GcpProvider {
+    client: Client,
+    key: ServiceAccountKey,
+    project: String,
+    base_url: String,
+    image: String,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl GcpProvider {
+    const DEFAULT_ZONE_SUFFIX: &'static str = "a";
+    const DEFAULT_IMAGE: &'static str =
+        "projects/ubuntu-os-cloud/global/images/family/ubuntu-2404-lts-amd64";
+    const CREATE_POLL_ATTEMPTS: u32 = 30;
+
+    pub fn new(config: HashMap<String, String>) -> Result<Self> {
+        let credentials_path = config
+            .get("credentials_file")
+            .cloned()
+            .or_else(|| std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok())
+            .context(
+                "GCP credentials not found: set GOOGLE_APPLICATION_CREDENTIALS to a service-account JSON key",
+            )?;
+        let raw = std::fs::read_to_string(&credentials_path).with_context(|| {
+            format!("Failed to read GCP credentials file: {}", credentials_path)
+        })?;
+        let key: ServiceAccountKey = serde_json::from_str(&raw)
+            .context("GCP credentials file is not a service-account JSON key")?;
+        let project = config
+            .get("project")
+            .cloned()
+            .or_else(|| std::env::var("GOOGLE_CLOUD_PROJECT").ok())
+            .or_else(|| key.project_id.clone())
+            .context("GCP project not found in config, GOOGLE_CLOUD_PROJECT, or credentials")?;
+        let image = config
+            .get("image")
+            .cloned()
+            .unwrap_or_else(|| Self::DEFAULT_IMAGE.to_string());
+
+        let client = Client::builder()
+            .user_agent("airstack/0.1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            key,
+            project,
+            base_url: "https://compute.googleapis.com/compute/v1".to_string(),
+            image,
+            token: Mutex::new(None),
+        })
+    }
+
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref() {
+            if Instant::now() + Duration::from_secs(60) < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let token_uri = self
+            .key
+            .token_uri
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TOKEN_URI.to_string());
+        let assertion = sign_jwt_assertion(&self.key, &token_uri, unix_now())?;
+        let response = self
+            .client
+            .post(&token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .context("Failed to send GCP token request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to obtain GCP access token: {}", error_text);
+        }
+        let body: TokenResponse = response
+            .json()
+            .await
+            .context("Failed to parse GCP token response")?;
+        let expires_at = Instant::now() + Duration::from_secs(body.expires_in.unwrap_or(3600));
+        *cached = Some((body.access_token.clone(), expires_at));
+        Ok(body.access_token)
+    }
+
+    async fn get_json(&self, url: &str) -> Result<reqwest::Response> {
+        let token = self.access_token().await?;
+        self.client
+            .get(url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send GCP request: {}", url))
+    }
+
+    fn instance_url(&self, zone: &str, name: &str) -> String {
+        format!(
+            "{}/projects/{}/zones/{}/instances/{}",
+            self.base_url, self.project, zone, name
+        )
+    }
+
+    fn convert_instance(instance: GcpInstance) -> Server {
+        let zone = last_path_segment(&instance.zone).to_string();
+        let nic = instance.network_interfaces.first();
+        let managed =
+            instance.labels.get(MANAGED_LABEL_KEY).map(String::as_str) == Some(MANAGED_LABEL_VALUE);
+        Server {
+            id: format!("{}/{}", zone, instance.name),
+            name: instance.name,
+            status: convert_status(&instance.status),
+            public_ip: nic.and_then(|n| n.access_configs.iter().find_map(|a| a.nat_ip.clone())),
+            private_ip: nic.and_then(|n| n.network_ip.clone()),
+            server_type: last_path_segment(&instance.machine_type).to_string(),
+            region: zone,
+            managed: Some(managed),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MetalProvider for GcpProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_public_ip: true,
+            supports_direct_ssh: true,
+            supports_provider_ssh: false,
+            supports_server_create: true,
+            supports_server_destroy: true,
+            supports_firewall: false,
+        }
+    }
+
+    async fn create_server(&self, request: CreateServerRequest) -> Result<Server> {
+        info!("Creating GCP instance: {}", request.name);
+        let (zone, machine_type) =
+            resolve_zone_and_machine_type(&request.region, &request.server_type)?;
+        let public_key = self
+            .upload_ssh_key(&format!("{}-key", request.name), &request.ssh_key)
+            .await?;
+
+        let mut labels = request.labels.clone();
+        labels.insert(
+            MANAGED_LABEL_KEY.to_string(),
+            MANAGED_LABEL_VALUE.to_string(),
+        );
+        let payload = serde_json::json!({
+            "name": request.name,
+            "machineType": format!("zones/{}/machineTypes/{}", zone, machine_type),
+            "labels": labels,
+            "metadata": {
+                "items": [{ "key": "ssh-keys", "value": format!("root:{}", public_key) }]
+            },
+            "disks": [{
+                "boot": true,
+                "autoDelete": true,
+                "initializeParams": { "sourceImage": self.image }
+            }],
+            "networkInterfaces": [{
+                "network": "global/networks/default",
+                "accessConfigs": [{ "type": "ONE_TO_ONE_NAT", "name": "External NAT" }]
+            }]
+        });
+
+        let token = self.access_token().await?;
+        let response = self
+            .client
+            .post(format!(
+                "{}/projects/{}/zones/{}/instances",
+                self.base_url, self.project, zone
+            ))
+            .bearer_auth(token)
+            .json(&payload)
+            .send()
+            .await
+            .context("Failed to send create instance request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to create GCP instance: {}", error_text);
+        }
+
+        // Instance insert returns an operation; poll until the instance resource is readable.
+        let id = format!("{}/{}", zone, request.name);
+        let mut last_err = None;
+        for _ in 0..Self::CREATE_POLL_ATTEMPTS {
+            match self.get_server(&id).await {
+                Ok(server) => {
+                    info!("Successfully created GCP instance: {}", id);
+                    return Ok(server);
+                }
+                Err(e) => last_err = Some(e),
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("instance not readable")))
+            .with_context(|| format!("GCP instance '{}' was not visible after create", id))
+    }
+
+    async fn destroy_server(&self, id: &str) -> Result<()> {
+        info!("Destroying GCP instance: {}", id);
+        let (zone, name) = split_instance_id(id)?;
+        let token = self.access_token().await?;
+        let response = self
+            .client
+            .delete(self.instance_url(zone, name))
+            .bearer_auth(token)
+            .send()
+            .await
+            .context("Failed to send delete instance request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to destroy GCP instance: {}", error_text);
+        }
+        Ok(())
+    }
+
+    async fn get_server(&self, id: &str) -> Result<Server> {
+        debug!("Getting GCP instance: {}", id);
+        let (zone, name) = split_instance_id(id)?;
+        let response = self.get_json(&self.instance_url(zone, name)).await?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get GCP instance: {}", error_text);
+        }
+        let instance: GcpInstance = response
+            .json()
+            .await
+            .context("Failed to parse GCP instance response")?;
+        Ok(Self::convert_instance(instance))
+    }
+
+    async fn list_servers(&self) -> Result<Vec<Server>> {
+        let mut servers = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut url = format!(
+                "{}/projects/{}/aggregated/instances?filter=labels.{}%3D{}",
+                self.base_url, self.project, MANAGED_LABEL_KEY, MANAGED_LABEL_VALUE
+            );
+            if let Some(token) = &page_token {
+                url.push_str(&format!("&pageToken={}", token));
+            }
+            let response = self.get_json(&url).await?;
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to list GCP instances: {}", error_text);
+            }
+            let body: GcpAggregatedInstances = response
+                .json()
+                .await
+                .context("Failed to parse GCP instance list response")?;
+            servers.extend(
+                body.items
+                    .into_values()
+                    .flat_map(|scoped| scoped.instances)
+                    .map(Self::convert_instance),
+            );
+            match body.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+        Ok(servers)
+    }
+
+    async fn upload_ssh_key(&self, _name: &str, public_key_path: &str) -> Result<String> {
+        // GCP has no key registry for instances; the key is embedded in instance metadata.
+        crate::read_public_key(public_key_path)
+    }
+
+    async fn attach_floating_ip(&self, _server_id: &str) -> Result<String> {
+        anyhow::bail!(
+            "floating_ip is not supported for provider 'gcp'; instances use an ephemeral external IP"
+        )
+    }
+
+    async fn validate_create_request(
+        &self,
+        request: &CreateServerRequest,
+    ) -> Result<CreateRequestValidation> {
+        Ok(
+            match resolve_zone_and_machine_type(&request.region, &request.server_type) {
+                Ok(_) => CreateRequestValidation {
+                    valid: true,
+                    reason: None,
+                    valid_regions_for_type: Vec::new(),
+                    valid_server_types_for_region: Vec::new(),
+                    suggested_region: None,
+                    suggested_server_type: None,
+                    permanent: false,
+                },
+                Err(e) => CreateRequestValidation {
+                    valid: false,
+                    reason: Some(e.to_string()),
+                    valid_regions_for_type: Vec::new(),
+                    valid_server_types_for_region: Vec::new(),
+                    suggested_region: None,
+                    suggested_server_type: None,
+                    permanent: true,
+                },
+            },
+        )
+    }
+}
+
+fn resolve_zone_and_machine_type(region: &str, server_type: &str) -> Result<(String, String)> {
+    let region = region.trim();
+    let parts = region.split('-').collect::<Vec<_>>();
+    let valid_region = parts.len() >= 2
+        && parts.iter().all(|p| {
+            !p.is_empty()
+                && p.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+        && parts[1].chars().last().is_some_and(|c| c.is_ascii_digit());
+    if !valid_region {
+        anyhow::bail!(
+            "invalid GCP region/zone '{}' (expected e.g. us-central1 or us-central1-a)",
+            region
+        );
+    }
+    let zone = match parts.len() {
+        2 => format!("{}-{}", region, GcpProvider::DEFAULT_ZONE_SUFFIX),
+        3 if parts[2].len() == 1 && parts[2].chars().all(|c| c.is_ascii_lowercase()) => {
+            region.to_string()
+        }
+        _ => anyhow::bail!(
+            "invalid GCP region/zone '{}' (expected e.g. us-central1 or us-central1-a)",
+            region
+        ),
+    };
+
+    let machine_type = server_type.trim();
+    let valid_machine_type = machine_type.split_once('-').is_some_and(|(family, rest)| {
+        family
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase())
+            && family
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            && !rest.is_empty()
+            && rest
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    });
+    if !valid_machine_type {
+        anyhow::bail!(
+            "invalid GCP machine type '{}' (expected e.g. e2-medium or n2-standard-4)",
+            server_type
+        );
+    }
+    Ok((zone, machine_type.to_string()))
+}
+
+fn convert_status(status: &str) -> ServerStatus {
+    match status {
+        "PROVISIONING" | "STAGING" => ServerStatus::Creating,
+        "RUNNING" => ServerStatus::Running,
+        "STOPPING" | "STOPPED" | "SUSPENDING" | "SUSPENDED" | "TERMINATED" => ServerStatus::Stopped,
+        _ => ServerStatus::Error,
+    }
+}
+
+fn split_instance_id(id: &str) -> Result<(&str, &str)> {
+    id.split_once('/')
+        .filter(|(zone, name)| !zone.is_empty() && !name.is_empty())
+        .with_context(|| format!("invalid GCP instance id '{}' (expected <zone>/<name>)", id))
+}
+
+fn last_path_segment(value: &str) -> &str {
+    value.rsplit('/').next().unwrap_or(value)
+}
+
+fn sign_jwt_assertion(key: &ServiceAccountKey, audience: &str, now: u64) -> Result<String> {
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"RS256","typ":"JWT"}"#);
+    let claims = serde_json::json!({
+        "iss": key.client_email,
+        "scope": COMPUTE_SCOPE,
+        "aud": audience,
+        "iat": now,
+        "exp": now + 3600,
+    });
+    let claims = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims)?);
+    let signing_input = format!("{}.{}", header, claims);
+
+    let der = pem_to_der(&key.private_key)?;
+    let key_pair = RsaKeyPair::from_pkcs8(&der)
+        .map_err(|e| anyhow::anyhow!("invalid service-account private key: {}", e))?;
+    let mut signature = vec![0u8; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            signing_input.as_bytes(),
+            &mut signature,
+        )
+        .map_err(|e| anyhow::anyhow!("failed to sign GCP token assertion: {}", e))?;
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+fn pem_to_der(pem: &str) -> Result<Vec<u8>> {
+    let body = pem
+        .lines()
+        .filter(|l| !l.starts_with("-----"))
+        .collect::<String>();
+    STANDARD
+        .decode(body.trim())
+        .context("service-account private key is not valid PEM")
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_status, resolve_zone_and_machine_type, split_instance_id};
+    use crate::ServerStatus;
+
+    #[test]
+    fn resolves_region_to_zone_and_validates_machine_type() {
+        assert_eq!(
+            resolve_zone_and_machine_type("us-central1", "e2-medium").expect("valid"),
+            ("us-central1-a".to_string(), "e2-medium".to_string())
+        );
+        assert_eq!(
+            resolve_zone_and_machine_type("europe-west4-b", "n2-standard-4").expect("valid"),
+            ("europe-west4-b".to_string(), "n2-standard-4".to_string())
+        );
+        assert!(resolve_zone_and_machine_type("", "e2-medium").is_err());
+        assert!(resolve_zone_and_machine_type("nbg1", "e2-medium").is_err());
+        assert!(resolve_zone_and_machine_type("us-central1-zz", "e2-medium").is_err());
+        assert!(resolve_zone_and_machine_type("us-central1", "cpx21").is_err());
+        assert!(resolve_zone_and_machine_type("us-central1", "E2-Medium").is_err());
+    }
+
+    #[test]
+    fn maps_instance_status_and_ids() {
+        assert!(matches!(convert_status("RUNNING"), ServerStatus::Running));
+        assert!(matches!(convert_status("STAGING"), ServerStatus::Creating));
+        assert!(matches!(
+            convert_status("TERMINATED"),
+            ServerStatus::Stopped
+        ));
+        assert_eq!(
+            split_instance_id("us-central1-a/web-1").expect("valid id"),
+            ("us-central1-a", "web-1")
+        );
+        assert!(split_instance_id("web-1").is_err());
+    }
+}
@@ -1,12 +1,14 @@
 use crate::{
-    CapacityResolveOptions, CreateRequestValidation, CreateServerRequest, FirewallRuleSpec,
-    FirewallSpec, MetalProvider, ProviderCapabilities, Server, ServerStatus,
+    diff_firewall_rules, BlockVolume, BlockVolumeSpec, CapacityResolveOptions,
+    CreateRequestValidation, CreateServerRequest, FirewallRuleSpec, FirewallSpec, MetalProvider,
+    ProviderCapabilities, Server, ServerStatus, MANAGED_LABEL_KEY, MANAGED_LABEL_VALUE,
 };
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, HashMap};
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
 #[derive(Debug)]
 pub struct HetznerProvider {
@@ -24,11 +26,15 @@ struct HetznerServer {
     private_net: Vec<HetznerPrivateNet>,
     server_type: HetznerServerType,
     datacenter: HetznerDatacenter,
+    #[serde(default)]
+    labels: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct HetznerPublicNet {
     ipv4: Option<HetznerIp>,
+    #[serde(default)]
+    ipv6: Option<HetznerIp>,
     floating_ips: Vec<u64>,
 }
 
@@ -61,6 +67,17 @@ struct HetznerLocation {
 struct HetznerResponse<T> {
     servers: Option<Vec<T>>,
     server: Option<T>,
+    meta: Option<HetznerMeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerMeta {
+    pagination: Option<HetznerPagination>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerPagination {
+    next_page: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -79,6 +96,20 @@ struct HetznerSshKeysResponse {
 struct HetznerFirewall {
     id: u64,
     name: String,
+    #[serde(default)]
+    rules: Vec<HetznerFirewallRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HetznerFirewallRule {
+    direction: String,
+    protocol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    source_ips: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    destination_ips: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -86,6 +117,43 @@ struct HetznerFirewallsResponse {
     firewalls: Option<Vec<HetznerFirewall>>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerVolume {
+    id: u64,
+    name: String,
+    server: Option<u64>,
+    linux_device: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerVolumesResponse {
+    volumes: Option<Vec<HetznerVolume>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerVolumeResponse {
+    volume: HetznerVolume,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerAction {
+    id: u64,
+    status: String,
+    #[serde(default)]
+    error: Option<HetznerActionError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerActionError {
+    code: String,
+    message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HetznerActionResponse {
+    action: HetznerAction,
+}
+
 #[derive(Debug, Serialize)]
 struct CreateServerPayload {
     name: String,
@@ -94,6 +162,9 @@ struct CreateServerPayload {
     image: String,
     ssh_keys: Vec<String>,
     public_net: CreateServerPublicNet,
+    labels: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_data: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -105,6 +176,12 @@ struct CreateServerPublicNet {
 impl HetznerProvider {
     const DEFAULT_REGION: &'static str = "ash";
     const PREFERRED_REGIONS: [&'static str; 5] = ["ash", "hel1", "nbg1", "fsn1", "hil"];
+    const LIST_PAGE_SIZE: u64 = 50;
+    const RATE_LIMIT_RETRIES: u32 = 3;
+    const MAX_RETRY_AFTER_SECS: u64 = 60;
+    const ACTION_POLL_INTERVAL_SECS: u64 = 2;
+    const ACTION_MAX_POLLS: u32 = 300;
+    const SHUTDOWN_MAX_POLLS: u32 = 30;
 
     pub fn new(config: HashMap<String, String>) -> Result<Self> {
         let api_token = if let Some(token) = config.get("api_token") {
@@ -133,6 +210,40 @@ impl HetznerProvider {
         })
     }
 
+    async fn send_with_retry<F>(&self, build: F) -> reqwest::Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            let response = build()
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .send()
+                .await?;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS
+                || attempt >= Self::RATE_LIMIT_RETRIES
+            {
+                return Ok(response);
+            }
+            let delay = parse_retry_after(
+                response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok()),
+            )
+            .unwrap_or_else(|| Duration::from_secs(1 << attempt))
+            .min(Duration::from_secs(Self::MAX_RETRY_AFTER_SECS));
+            attempt += 1;
+            warn!(
+                "Hetzner API rate limited (429); retrying in {}s (attempt {}/{})",
+                delay.as_secs(),
+                attempt,
+                Self::RATE_LIMIT_RETRIES
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
     fn convert_status(status: &str) -> ServerStatus {
         match status {
             "initializing" | "starting" => ServerStatus::Creating,
@@ -143,15 +254,46 @@ impl HetznerProvider {
         }
     }
 
+    fn create_server_payload(
+        request: &CreateServerRequest,
+        ssh_key: String,
+    ) -> CreateServerPayload {
+        CreateServerPayload {
+            name: request.name.clone(),
+            server_type: request.server_type.clone(),
+            location: request.region.clone(),
+            // Hetzner API requires image in create payload.
+            image: "ubuntu-24.04".to_string(),
+            ssh_keys: vec![ssh_key],
+            public_net: CreateServerPublicNet {
+                enable_ipv4: request.enable_ipv4,
+                enable_ipv6: request.enable_ipv6,
+            },
+            labels: request.labels.clone(),
+            user_data: request.user_data.clone(),
+        }
+    }
+
     fn convert_server(hetzner_server: HetznerServer) -> Server {
+        let managed = hetzner_server
+            .labels
+            .get(MANAGED_LABEL_KEY)
+            .map(String::as_str)
+            == Some(MANAGED_LABEL_VALUE);
         Server {
             id: hetzner_server.id.to_string(),
             name: hetzner_server.name,
             status: Self::convert_status(&hetzner_server.status),
-            public_ip: hetzner_server.public_net.ipv4.map(|ip| ip.ip),
+            public_ip: hetzner_server.public_net.ipv4.map(|ip| ip.ip).or_else(|| {
+                hetzner_server
+                    .public_net
+                    .ipv6
+                    .map(|net| ipv6_host_address(&net.ip))
+            }),
             private_ip: hetzner_server.private_net.first().map(|net| net.ip.clone()),
             server_type: hetzner_server.server_type.name,
             region: hetzner_server.datacenter.location.name,
+            managed: Some(managed),
         }
     }
 
@@ -207,6 +349,117 @@ impl HetznerProvider {
         Ok(result.server.map(|s| s.datacenter.location.name))
     }
 
+    // Keep the disk size so the server can be scaled back down later.
+    fn change_type_payload(server_type: &str) -> serde_json::Value {
+        serde_json::json!({
+            "server_type": server_type,
+            "upgrade_disk": false
+        })
+    }
+
+    async fn run_server_action(
+        &self,
+        server_id: &str,
+        action: &str,
+        payload: Option<&serde_json::Value>,
+    ) -> Result<()> {
+        let response = self
+            .send_with_retry(|| {
+                let request = self.client.post(format!(
+                    "{}/servers/{}/actions/{}",
+                    self.base_url, server_id, action
+                ));
+                match payload {
+                    Some(body) => request.json(body),
+                    None => request,
+                }
+            })
+            .await
+            .with_context(|| format!("Failed to send server {} request", action))?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to {} server {}: {}", action, server_id, error_text);
+        }
+        let body: HetznerActionResponse = response
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse server {} response", action))?;
+        self.wait_for_action(body.action).await
+    }
+
+    async fn server_power_status(&self, server_id: &str) -> Result<String> {
+        let response = self
+            .send_with_retry(|| {
+                self.client
+                    .get(format!("{}/servers/{}", self.base_url, server_id))
+            })
+            .await
+            .context("Failed to send get server request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get server {}: {}", server_id, error_text);
+        }
+        let result: HetznerResponse<HetznerServer> = response
+            .json()
+            .await
+            .context("Failed to parse get server response")?;
+        Ok(result.server.context("No server in response")?.status)
+    }
+
+    // ACPI shutdown first so the OS flushes disks; a hard poweroff only if it
+    // does not stop in time (or the shutdown request itself fails).
+    async fn stop_server(&self, server_id: &str) -> Result<()> {
+        if let Err(e) = self.run_server_action(server_id, "shutdown", None).await {
+            warn!("Graceful shutdown of server {} failed: {:#}", server_id, e);
+            return self.run_server_action(server_id, "poweroff", None).await;
+        }
+        for _ in 0..Self::SHUTDOWN_MAX_POLLS {
+            if self.server_power_status(server_id).await? == "off" {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_secs(Self::ACTION_POLL_INTERVAL_SECS)).await;
+        }
+        warn!(
+            "Server {} did not shut down gracefully; powering off",
+            server_id
+        );
+        self.run_server_action(server_id, "poweroff", None).await
+    }
+
+    async fn wait_for_action(&self, mut action: HetznerAction) -> Result<()> {
+        for _ in 0..Self::ACTION_MAX_POLLS {
+            match action.status.as_str() {
+                "success" => return Ok(()),
+                "error" => {
+                    let detail = action
+                        .error
+                        .map(|e| format!("{}: {}", e.code, e.message))
+                        .unwrap_or_else(|| "unknown error".to_string());
+                    anyhow::bail!("Hetzner action {} failed: {}", action.id, detail);
+                }
+                _ => {}
+            }
+            tokio::time::sleep(Duration::from_secs(Self::ACTION_POLL_INTERVAL_SECS)).await;
+            let response = self
+                .send_with_retry(|| {
+                    self.client
+                        .get(format!("{}/actions/{}", self.base_url, action.id))
+                })
+                .await
+                .context("Failed to poll Hetzner action")?;
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to poll action {}: {}", action.id, error_text);
+            }
+            action = response
+                .json::<HetznerActionResponse>()
+                .await
+                .context("Failed to parse Hetzner action response")?
+                .action;
+        }
+        anyhow::bail!("Timed out waiting for Hetzner action {}", action.id)
+    }
+
     fn floating_ip_create_payload(
         &self,
         server_id: u64,
@@ -230,10 +483,7 @@ impl HetznerProvider {
         BTreeMap<String, BTreeSet<String>>,
     )> {
         let response = self
-            .client
-            .get(format!("{}/server_types", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
+            .send_with_retry(|| self.client.get(format!("{}/server_types", self.base_url)))
             .await
             .context("Failed to send server_types request")?;
 
@@ -301,7 +551,7 @@ impl HetznerProvider {
         mapped
     }
 
-    async fn find_firewall_by_name(&self, name: &str) -> Result<Option<String>> {
+    async fn find_firewall_by_name(&self, name: &str) -> Result<Option<HetznerFirewall>> {
         let response = self
             .client
             .get(format!("{}/firewalls", self.base_url))
@@ -321,8 +571,124 @@ impl HetznerProvider {
             .firewalls
             .unwrap_or_default()
             .into_iter()
-            .find(|f| f.name == name)
-            .map(|f| f.id.to_string()))
+            .find(|f| f.name == name))
+    }
+
+    async fn reconcile_firewall_rules(
+        &self,
+        existing: &HetznerFirewall,
+        spec: &FirewallSpec,
+    ) -> Result<()> {
+        let (inbound, outbound): (Vec<_>, Vec<_>) = existing
+            .rules
+            .iter()
+            .cloned()
+            .partition(|r| r.direction == "in");
+        let current = inbound
+            .into_iter()
+            .map(|r| FirewallRuleSpec {
+                protocol: r.protocol,
+                port: r.port,
+                source_ips: r.source_ips,
+            })
+            .collect::<Vec<_>>();
+        let diff = diff_firewall_rules(&current, &spec.rules);
+        if diff.is_empty() {
+            debug!("Firewall '{}' rules are up to date", spec.name);
+            return Ok(());
+        }
+        for rule in &diff.added {
+            info!("Firewall '{}': adding rule {}", spec.name, rule.describe());
+        }
+        for rule in &diff.removed {
+            info!(
+                "Firewall '{}': removing rule {}",
+                spec.name,
+                rule.describe()
+            );
+        }
+
+        // Only inbound rules are managed from config; keep any outbound rules as-is.
+        let mut rules = spec
+            .rules
+            .iter()
+            .map(Self::map_firewall_rule)
+            .collect::<Vec<_>>();
+        for rule in outbound {
+            rules.push(serde_json::to_value(rule)?);
+        }
+        let response = self
+            .client
+            .post(format!(
+                "{}/firewalls/{}/actions/set_rules",
+                self.base_url, existing.id
+            ))
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .json(&serde_json::json!({ "rules": rules }))
+            .send()
+            .await
+            .context("Failed to send firewall set_rules request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to update firewall rules: {}", error_text);
+        }
+        info!(
+            "Firewall '{}' updated ({} added, {} removed)",
+            spec.name,
+            diff.added.len(),
+            diff.removed.len()
+        );
+        Ok(())
+    }
+
+    async fn find_volume_by_name(&self, name: &str) -> Result<Option<BlockVolume>> {
+        let response = self
+            .send_with_retry(|| {
+                self.client
+                    .get(format!("{}/volumes", self.base_url))
+                    .query(&[("name", name)])
+            })
+            .await
+            .context("Failed to send list volumes request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to list volumes: {}", error_text);
+        }
+        let body: HetznerVolumesResponse = response
+            .json()
+            .await
+            .context("Failed to parse list volumes response")?;
+        Ok(body
+            .volumes
+            .unwrap_or_default()
+            .into_iter()
+            .find(|v| v.name == name)
+            .map(Self::convert_volume))
+    }
+
+    fn convert_volume(volume: HetznerVolume) -> BlockVolume {
+        BlockVolume {
+            id: volume.id.to_string(),
+            name: volume.name,
+            server_id: volume.server.map(|id| id.to_string()),
+            linux_device: volume.linux_device,
+            // Where automount puts a formatted volume once it is attached.
+            mount_path: Some(format!("/mnt/HC_Volume_{}", volume.id)),
+        }
+    }
+}
+
+fn parse_retry_after(value: Option<&str>) -> Option<Duration> {
+    value?.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+// Hetzner reports the server's IPv6 /64 network; the host answers on ::1 of it.
+fn ipv6_host_address(network: &str) -> String {
+    let prefix = network.split('/').next().unwrap_or(network);
+    if prefix.ends_with("::") {
+        format!("{}1", prefix)
+    } else {
+        prefix.to_string()
     }
 }
 
@@ -335,6 +701,7 @@ impl MetalProvider for HetznerProvider {
             supports_provider_ssh: false,
             supports_server_create: true,
             supports_server_destroy: true,
+            supports_firewall: true,
         }
     }
 
@@ -347,28 +714,17 @@ impl MetalProvider for HetznerProvider {
                 .await?;
             key_id
         } else {
-            request.ssh_key
+            request.ssh_key.clone()
         };
 
-        let payload = CreateServerPayload {
-            name: request.name.clone(),
-            server_type: request.server_type,
-            location: request.region,
-            // Hetzner API requires image in create payload.
-            image: "ubuntu-24.04".to_string(),
-            ssh_keys: vec![ssh_key_name],
-            public_net: CreateServerPublicNet {
-                enable_ipv4: true,
-                enable_ipv6: false,
-            },
-        };
+        let payload = Self::create_server_payload(&request, ssh_key_name);
 
         let response = self
-            .client
-            .post(format!("{}/servers", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .json(&payload)
-            .send()
+            .send_with_retry(|| {
+                self.client
+                    .post(format!("{}/servers", self.base_url))
+                    .json(&payload)
+            })
             .await
             .context("Failed to send create server request")?;
 
@@ -558,28 +914,51 @@ impl MetalProvider for HetznerProvider {
     async fn list_servers(&self) -> Result<Vec<Server>> {
         debug!("Listing Hetzner servers");
 
-        let response = self
-            .client
-            .get(format!("{}/servers", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
-            .await
-            .context("Failed to send list servers request")?;
+        let mut servers = Vec::new();
+        let mut page = 1;
+        loop {
+            let response = self
+                .send_with_retry(|| {
+                    self.client.get(format!(
+                        "{}/servers?page={}&per_page={}",
+                        self.base_url,
+                        page,
+                        Self::LIST_PAGE_SIZE
+                    ))
+                })
+                .await
+                .context("Failed to send list servers request")?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to list servers: {}", error_text);
-        }
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to list servers: {}", error_text);
+            }
 
-        let result: HetznerResponse<HetznerServer> = response
-            .json()
-            .await
-            .context("Failed to parse list servers response")?;
+            let result: HetznerResponse<HetznerServer> = response
+                .json()
+                .await
+                .context("Failed to parse list servers response")?;
+
+            servers.extend(result.servers.unwrap_or_default());
+            match result
+                .meta
+                .and_then(|m| m.pagination)
+                .and_then(|p| p.next_page)
+            {
+                Some(next) if next > page => page = next,
+                _ => break,
+            }
+        }
 
-        let servers = result.servers.unwrap_or_default();
         Ok(servers.into_iter().map(Self::convert_server).collect())
     }
 
+    async fn ssh_key_registered(&self, name: &str, public_key_path: &str) -> Result<Option<bool>> {
+        let public_key = crate::read_public_key(public_key_path)?;
+        let existing = self.find_existing_ssh_key_id(name, &public_key).await?;
+        Ok(Some(existing.is_some()))
+    }
+
     async fn upload_ssh_key(&self, name: &str, public_key_path: &str) -> Result<String> {
         info!("Uploading SSH key: {}", name);
 
@@ -680,7 +1059,8 @@ impl MetalProvider for HetznerProvider {
 
     async fn ensure_firewall(&self, spec: &FirewallSpec) -> Result<Option<String>> {
         if let Some(existing) = self.find_firewall_by_name(&spec.name).await? {
-            return Ok(Some(existing));
+            self.reconcile_firewall_rules(&existing, spec).await?;
+            return Ok(Some(existing.id.to_string()));
         }
 
         let rules = spec
@@ -741,12 +1121,386 @@ impl MetalProvider for HetznerProvider {
         }
         Ok(())
     }
+
+    async fn ensure_volume(&self, spec: &BlockVolumeSpec) -> Result<Option<BlockVolume>> {
+        if let Some(existing) = self.find_volume_by_name(&spec.name).await? {
+            return Ok(Some(existing));
+        }
+
+        let mut payload = serde_json::json!({
+            "name": spec.name,
+            "size": spec.size_gb,
+            "location": spec.region,
+            "automount": false,
+        });
+        if let Some(format) = &spec.format {
+            payload["format"] = serde_json::Value::String(format.clone());
+        }
+        let response = self
+            .send_with_retry(|| {
+                self.client
+                    .post(format!("{}/volumes", self.base_url))
+                    .json(&payload)
+            })
+            .await
+            .context("Failed to create volume")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to create volume: {}", error_text);
+        }
+        let body: HetznerVolumeResponse = response
+            .json()
+            .await
+            .context("Failed to parse volume creation response")?;
+        info!("Created volume {} ({})", body.volume.name, body.volume.id);
+        Ok(Some(Self::convert_volume(body.volume)))
+    }
+
+    async fn attach_volume_to_server(&self, volume_id: &str, server_id: &str) -> Result<()> {
+        let server_id = server_id
+            .parse::<u64>()
+            .with_context(|| format!("invalid server id '{}' for volume attach", server_id))?;
+        let payload = serde_json::json!({
+            "server": server_id,
+            "automount": true
+        });
+        let response = self
+            .send_with_retry(|| {
+                self.client
+                    .post(format!(
+                        "{}/volumes/{}/actions/attach",
+                        self.base_url, volume_id
+                    ))
+                    .json(&payload)
+            })
+            .await
+            .context("Failed to attach volume to server")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to attach volume to server: {}", error_text);
+        }
+        // The device and automount only exist once the attach action has finished.
+        let body: HetznerActionResponse = response
+            .json()
+            .await
+            .context("Failed to parse volume attach response")?;
+        self.wait_for_action(body.action).await
+    }
+
+    async fn find_volume(&self, name: &str) -> Result<Option<BlockVolume>> {
+        self.find_volume_by_name(name).await
+    }
+
+    async fn detach_volume(&self, volume_id: &str) -> Result<()> {
+        let response = self
+            .send_with_retry(|| {
+                self.client.post(format!(
+                    "{}/volumes/{}/actions/detach",
+                    self.base_url, volume_id
+                ))
+            })
+            .await
+            .context("Failed to detach volume")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to detach volume: {}", error_text);
+        }
+        Ok(())
+    }
+
+    async fn resize_server(&self, id: &str, server_type: &str) -> Result<Server> {
+        info!("Resizing Hetzner server {} to {}", id, server_type);
+        let (types, _) = self.fetch_type_region_matrix().await?;
+        if !types.contains_key(server_type) {
+            anyhow::bail!(
+                "Unknown Hetzner server type '{}'. Valid types: {}",
+                server_type,
+                types.keys().cloned().collect::<Vec<_>>().join(", ")
+            );
+        }
+        // Hetzner only changes the type of a stopped server.
+        if let Err(stop_err) = self.stop_server(id).await {
+            return match self.run_server_action(id, "poweron", None).await {
+                Ok(()) => Err(stop_err.context(format!("resize of server {} aborted", id))),
+                Err(on_err) => anyhow::bail!(
+                    "resize of server {} aborted: {:#}; powering it back on also failed: {:#}",
+                    id,
+                    stop_err,
+                    on_err
+                ),
+            };
+        }
+        let changed = self
+            .run_server_action(
+                id,
+                "change_type",
+                Some(&Self::change_type_payload(server_type)),
+            )
+            .await;
+        // The server is off at this point, so it comes back up whether or not the type changed.
+        let powered_on = self.run_server_action(id, "poweron", None).await;
+        match (changed, powered_on) {
+            (Ok(()), Ok(())) => self.get_server(id).await,
+            (Err(change_err), Ok(())) => Err(change_err.context(format!(
+                "server {} kept its old type and was powered back on",
+                id
+            ))),
+            (Ok(()), Err(on_err)) => Err(on_err.context(format!(
+                "server {} was resized to {} but did not power back on",
+                id, server_type
+            ))),
+            (Err(change_err), Err(on_err)) => anyhow::bail!(
+                "resize of server {} failed: {:#}; powering it back on also failed: {:#}",
+                id,
+                change_err,
+                on_err
+            ),
+        }
+    }
+
+    async fn delete_volume(&self, volume_id: &str) -> Result<()> {
+        let response = self
+            .send_with_retry(|| {
+                self.client
+                    .delete(format!("{}/volumes/{}", self.base_url, volume_id))
+            })
+            .await
+            .context("Failed to delete volume")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to delete volume: {}", error_text);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::HetznerProvider;
+    use super::{parse_retry_after, HetznerProvider, HetznerServer};
+    use crate::{CreateServerRequest, MetalProvider};
     use std::collections::HashMap;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn server_json(id: u64, name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": name,
+            "status": "running",
+            "public_net": { "ipv4": { "ip": "203.0.113.10" }, "floating_ips": [] },
+            "private_net": [],
+            "server_type": { "name": "cpx21" },
+            "datacenter": { "location": { "name": "ash" } }
+        })
+    }
+
+    #[test]
+    fn retry_after_parses_delay_seconds() {
+        assert_eq!(parse_retry_after(Some("3")), Some(Duration::from_secs(3)));
+        assert_eq!(
+            parse_retry_after(Some(" 10 ")),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            parse_retry_after(Some("Wed, 21 Oct 2015 07:28:00 GMT")),
+            None
+        );
+        assert_eq!(parse_retry_after(None), None);
+    }
+
+    #[tokio::test]
+    async fn list_servers_follows_pagination() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener.local_addr().expect("listener addr");
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = vec![0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                assert!(request.contains("per_page=50"), "request: {request}");
+                let body = if request.contains("page=2") {
+                    serde_json::json!({
+                        "servers": [server_json(3, "web-3")],
+                        "meta": { "pagination": { "page": 2, "next_page": null } }
+                    })
+                } else {
+                    serde_json::json!({
+                        "servers": [server_json(1, "web-1"), server_json(2, "web-2")],
+                        "meta": { "pagination": { "page": 1, "next_page": 2 } }
+                    })
+                }
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let mut provider = HetznerProvider::new(HashMap::from([(
+            "api_token".to_string(),
+            "test-token".to_string(),
+        )]))
+        .expect("provider should initialize");
+        provider.base_url = format!("http://{}", addr);
+
+        let servers = provider.list_servers().await.expect("list should succeed");
+        let names = servers.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["web-1", "web-2", "web-3"]);
+    }
+
+    // Serves a scripted Hetzner API and records "METHOD /path" for each request.
+    async fn scripted_provider(
+        respond: fn(&str) -> (u16, serde_json::Value),
+    ) -> (
+        HetznerProvider,
+        std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener.local_addr().expect("listener addr");
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = vec![0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let line = request
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .rsplit_once(' ')
+                    .map(|(head, _)| head.to_string())
+                    .unwrap_or_default();
+                let (status, body) = respond(&line);
+                log.lock().unwrap().push(line);
+                let body = body.to_string();
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        let mut provider = HetznerProvider::new(HashMap::from([(
+            "api_token".to_string(),
+            "test-token".to_string(),
+        )]))
+        .expect("provider should initialize");
+        provider.base_url = format!("http://{}", addr);
+        (provider, seen)
+    }
+
+    fn resize_api(line: &str) -> (u16, serde_json::Value) {
+        let done = serde_json::json!({ "action": { "id": 1, "status": "success" } });
+        match line {
+            "GET /server_types" => (
+                200,
+                serde_json::json!({ "server_types": [{ "name": "cpx31", "prices": [] }] }),
+            ),
+            "GET /servers/7" => {
+                let mut server = server_json(7, "web-1");
+                server["status"] = "off".into();
+                (200, serde_json::json!({ "server": server }))
+            }
+            "POST /servers/7/actions/change_type" => (
+                422,
+                serde_json::json!({ "error": { "code": "invalid_input", "message": "disk too big" } }),
+            ),
+            _ => (200, done),
+        }
+    }
+
+    #[tokio::test]
+    async fn resize_powers_the_server_back_on_when_change_type_fails() {
+        let (provider, seen) = scripted_provider(resize_api).await;
+        let err = provider
+            .resize_server("7", "cpx31")
+            .await
+            .expect_err("change_type failure should surface");
+        assert!(format!("{err:#}").contains("disk too big"), "{err:#}");
+        assert!(format!("{err:#}").contains("powered back on"), "{err:#}");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "GET /server_types",
+                "POST /servers/7/actions/shutdown",
+                "GET /servers/7",
+                "POST /servers/7/actions/change_type",
+                "POST /servers/7/actions/poweron",
+            ]
+        );
+
+        let (provider, seen) = scripted_provider(resize_api).await;
+        let err = provider
+            .resize_server("7", "cx9000")
+            .await
+            .expect_err("unknown type should be rejected");
+        assert!(format!("{err:#}").contains("Unknown Hetzner server type"));
+        assert_eq!(*seen.lock().unwrap(), vec!["GET /server_types"]);
+    }
+
+    #[tokio::test]
+    async fn attached_volume_exposes_device_and_mount_path() {
+        let (provider, seen) = scripted_provider(|line| match line {
+            "POST /volumes/5/actions/attach" => (
+                201,
+                serde_json::json!({ "action": { "id": 9, "status": "success" } }),
+            ),
+            "GET /volumes?name=data" => (
+                200,
+                serde_json::json!({ "volumes": [{
+                    "id": 5,
+                    "name": "data",
+                    "server": 7,
+                    "linux_device": "/dev/disk/by-id/scsi-0HC_Volume_5"
+                }] }),
+            ),
+            _ => (404, serde_json::json!({})),
+        })
+        .await;
+        provider
+            .attach_volume_to_server("5", "7")
+            .await
+            .expect("attach should succeed");
+        let volume = provider
+            .find_volume("data")
+            .await
+            .expect("lookup should succeed")
+            .expect("volume should exist");
+        assert_eq!(volume.server_id.as_deref(), Some("7"));
+        assert_eq!(
+            volume.linux_device.as_deref(),
+            Some("/dev/disk/by-id/scsi-0HC_Volume_5")
+        );
+        assert_eq!(volume.mount_path.as_deref(), Some("/mnt/HC_Volume_5"));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec!["POST /volumes/5/actions/attach", "GET /volumes?name=data"]
+        );
+    }
+
+    #[test]
+    fn change_type_payload_keeps_disk_size() {
+        let payload = HetznerProvider::change_type_payload("cpx31");
+        assert_eq!(payload["server_type"], "cpx31");
+        assert_eq!(payload["upgrade_disk"], false);
+    }
 
     #[test]
     fn floating_ip_payload_uses_valid_type_and_server() {
@@ -761,4 +1515,53 @@ mod tests {
         assert_eq!(payload["server"], 12345);
         assert_eq!(payload["home_location"], "hel1");
     }
+
+    #[test]
+    fn create_payload_honors_ipv6_only_public_net() {
+        let request = CreateServerRequest {
+            name: "web-1".to_string(),
+            server_type: "cpx21".to_string(),
+            region: "hel1".to_string(),
+            ssh_key: "web-1-key".to_string(),
+            attach_floating_ip: false,
+            enable_ipv4: false,
+            enable_ipv6: true,
+            labels: crate::merge_server_labels(None),
+            user_data: None,
+        };
+        let payload = serde_json::to_value(HetznerProvider::create_server_payload(
+            &request,
+            "42".to_string(),
+        ))
+        .expect("payload serializes");
+        assert_eq!(payload["public_net"]["enable_ipv4"], false);
+        assert_eq!(payload["public_net"]["enable_ipv6"], true);
+        assert_eq!(payload["ssh_keys"][0], "42");
+        assert_eq!(payload["labels"]["airstack"], "managed");
+        assert!(payload.get("user_data").is_none());
+
+        let with_user_data = CreateServerRequest {
+            user_data: Some("#cloud-config\npackages: [docker.io]\n".to_string()),
+            ..request.clone()
+        };
+        let payload = serde_json::to_value(HetznerProvider::create_server_payload(
+            &with_user_data,
+            "42".to_string(),
+        ))
+        .expect("payload serializes");
+        assert_eq!(
+            payload["user_data"],
+            "#cloud-config\npackages: [docker.io]\n"
+        );
+
+        let mut server = server_json(7, "web-1");
+        server["public_net"] = serde_json::json!({
+            "ipv4": null,
+            "ipv6": { "ip": "2001:db8:1234::/64" },
+            "floating_ips": []
+        });
+        let server: HetznerServer = serde_json::from_value(server).expect("server parses");
+        let converted = HetznerProvider::convert_server(server);
+        assert_eq!(converted.public_ip.as_deref(), Some("2001:db8:1234::1"));
+    }
 }
@@ -1,9 +1,11 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 pub mod fly;
+pub mod gcp;
 pub mod hetzner;
+pub mod linode;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Server {
@@ -14,6 +16,8 @@ pub struct Server {
     pub private_ip: Option<String>,
     pub server_type: String,
     pub region: String,
+    #[serde(default)]
+    pub managed: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +36,46 @@ pub struct CreateServerRequest {
     pub region: String,
     pub ssh_key: String,
     pub attach_floating_ip: bool,
+    #[serde(default = "default_true")]
+    pub enable_ipv4: bool,
+    #[serde(default)]
+    pub enable_ipv6: bool,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub user_data: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+pub const MANAGED_LABEL_KEY: &str = "airstack";
+pub const MANAGED_LABEL_VALUE: &str = "managed";
+
+// Providers with plain-string tags (Linode) carry the same marker as "airstack=managed".
+pub fn managed_tag() -> String {
+    format!("{MANAGED_LABEL_KEY}={MANAGED_LABEL_VALUE}")
+}
+
+// Servers named in the config (or recorded in state) are airstack's even when the
+// provider lost or never carried the marker.
+pub fn is_managed_server(server: &Server, known_names: &HashSet<String>) -> bool {
+    server.managed == Some(true) || known_names.contains(&server.name)
+}
+
+pub fn merge_server_labels(user: Option<&HashMap<String, String>>) -> BTreeMap<String, String> {
+    let mut labels: BTreeMap<String, String> = user
+        .into_iter()
+        .flatten()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    // The managed marker always wins so airstack can recognise its own servers.
+    labels.insert(
+        MANAGED_LABEL_KEY.to_string(),
+        MANAGED_LABEL_VALUE.to_string(),
+    );
+    labels
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,13 +84,90 @@ pub struct FirewallSpec {
     pub rules: Vec<FirewallRuleSpec>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FirewallRuleSpec {
     pub protocol: String,
     pub port: Option<String>,
     pub source_ips: Vec<String>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirewallRuleDiff {
+    pub added: Vec<FirewallRuleSpec>,
+    pub removed: Vec<FirewallRuleSpec>,
+}
+
+impl FirewallRuleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl FirewallRuleSpec {
+    fn normalized(&self) -> FirewallRuleSpec {
+        let mut source_ips = self.source_ips.clone();
+        source_ips.sort();
+        source_ips.dedup();
+        FirewallRuleSpec {
+            protocol: self.protocol.to_ascii_lowercase(),
+            port: self.port.clone(),
+            source_ips,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.port {
+            Some(port) => format!(
+                "{} {} from {}",
+                self.protocol,
+                port,
+                self.source_ips.join(",")
+            ),
+            None => format!("{} from {}", self.protocol, self.source_ips.join(",")),
+        }
+    }
+}
+
+pub fn diff_firewall_rules(
+    existing: &[FirewallRuleSpec],
+    desired: &[FirewallRuleSpec],
+) -> FirewallRuleDiff {
+    let existing_norm: Vec<_> = existing.iter().map(FirewallRuleSpec::normalized).collect();
+    let desired_norm: Vec<_> = desired.iter().map(FirewallRuleSpec::normalized).collect();
+    FirewallRuleDiff {
+        added: desired
+            .iter()
+            .zip(&desired_norm)
+            .filter(|(_, norm)| !existing_norm.contains(norm))
+            .map(|(rule, _)| rule.clone())
+            .collect(),
+        removed: existing
+            .iter()
+            .zip(&existing_norm)
+            .filter(|(_, norm)| !desired_norm.contains(norm))
+            .map(|(rule, _)| rule.clone())
+            .collect(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockVolumeSpec {
+    pub name: String,
+    pub size_gb: u32,
+    pub region: String,
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockVolume {
+    pub id: String,
+    pub name: String,
+    pub server_id: Option<String>,
+    pub linux_device: Option<String>,
+    #[serde(default)]
+    pub mount_path: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderCapabilities {
     pub supports_public_ip: bool,
@@ -54,6 +175,8 @@ pub struct ProviderCapabilities {
     pub supports_provider_ssh: bool,
     pub supports_server_create: bool,
     pub supports_server_destroy: bool,
+    #[serde(default)]
+    pub supports_firewall: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,12 +205,44 @@ pub trait MetalProvider: Send + Sync {
     async fn list_servers(&self) -> Result<Vec<Server>>;
     async fn upload_ssh_key(&self, name: &str, public_key_path: &str) -> Result<String>;
     async fn attach_floating_ip(&self, server_id: &str) -> Result<String>;
+    async fn ssh_key_registered(
+        &self,
+        _name: &str,
+        _public_key_path: &str,
+    ) -> Result<Option<bool>> {
+        Ok(None)
+    }
     async fn ensure_firewall(&self, _spec: &FirewallSpec) -> Result<Option<String>> {
         Ok(None)
     }
     async fn attach_firewall_to_server(&self, _firewall_id: &str, _server_id: &str) -> Result<()> {
         Ok(())
     }
+    async fn ensure_volume(&self, _spec: &BlockVolumeSpec) -> Result<Option<BlockVolume>> {
+        Ok(None)
+    }
+    async fn attach_volume_to_server(&self, _volume_id: &str, _server_id: &str) -> Result<()> {
+        Ok(())
+    }
+    async fn find_volume(&self, _name: &str) -> Result<Option<BlockVolume>> {
+        Ok(None)
+    }
+    async fn detach_volume(&self, _volume_id: &str) -> Result<()> {
+        Ok(())
+    }
+    async fn delete_volume(&self, volume_id: &str) -> Result<()> {
+        anyhow::bail!(
+            "Deleting volume '{}' is not supported by this provider",
+            volume_id
+        )
+    }
+    async fn resize_server(&self, id: &str, server_type: &str) -> Result<Server> {
+        anyhow::bail!(
+            "Resizing server '{}' to '{}' is not supported by this provider",
+            id,
+            server_type
+        )
+    }
     async fn validate_create_request(
         &self,
         _request: &CreateServerRequest,
@@ -111,6 +266,19 @@ pub trait MetalProvider: Send + Sync {
     }
 }
 
+pub fn read_public_key(public_key_path: &str) -> Result<String> {
+    let expanded_path = if let Some(rest) = public_key_path.strip_prefix("~/") {
+        dirs::home_dir()
+            .context("Could not find home directory")?
+            .join(rest)
+    } else {
+        public_key_path.into()
+    };
+    let public_key = std::fs::read_to_string(&expanded_path)
+        .with_context(|| format!("Failed to read SSH public key: {:?}", expanded_path))?;
+    Ok(public_key.trim().to_string())
+}
+
 pub fn get_provider(
     provider_name: &str,
     config: HashMap<String, String>,
@@ -118,15 +286,86 @@ pub fn get_provider(
     match provider_name {
         "hetzner" => Ok(Box::new(hetzner::HetznerProvider::new(config)?)),
         "fly" => Ok(Box::new(fly::FlyProvider::new(config)?)),
+        "gcp" => Ok(Box::new(gcp::GcpProvider::new(config)?)),
+        "linode" => Ok(Box::new(linode::LinodeProvider::new(config)?)),
         _ => anyhow::bail!("Unsupported metal provider: {}", provider_name),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::get_provider;
+    use super::{
+        diff_firewall_rules, get_provider, managed_tag, merge_server_labels, read_public_key,
+        FirewallRuleSpec,
+    };
     use std::collections::HashMap;
 
+    fn rule(protocol: &str, port: Option<&str>, sources: &[&str]) -> FirewallRuleSpec {
+        FirewallRuleSpec {
+            protocol: protocol.to_string(),
+            port: port.map(str::to_string),
+            source_ips: sources.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn firewall_diff_reports_added_and_removed_rules() {
+        let existing = vec![
+            rule("tcp", Some("22"), &["0.0.0.0/0", "::/0"]),
+            rule("tcp", Some("8080"), &["0.0.0.0/0"]),
+        ];
+        let desired = vec![
+            rule("TCP", Some("22"), &["::/0", "0.0.0.0/0"]),
+            rule("tcp", Some("443"), &["0.0.0.0/0"]),
+            rule("icmp", None, &["0.0.0.0/0"]),
+        ];
+        let diff = diff_firewall_rules(&existing, &desired);
+        assert_eq!(
+            diff.added,
+            vec![
+                rule("tcp", Some("443"), &["0.0.0.0/0"]),
+                rule("icmp", None, &["0.0.0.0/0"])
+            ]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![rule("tcp", Some("8080"), &["0.0.0.0/0"])]
+        );
+        assert_eq!(diff.added[1].describe(), "icmp from 0.0.0.0/0");
+    }
+
+    #[test]
+    fn firewall_diff_is_empty_when_rules_match() {
+        let rules = vec![rule("tcp", Some("22"), &["10.0.0.0/8"])];
+        assert!(diff_firewall_rules(&rules, &rules).is_empty());
+        assert!(diff_firewall_rules(&[], &[]).is_empty());
+        assert_eq!(diff_firewall_rules(&rules, &[]).removed.len(), 1);
+    }
+
+    #[test]
+    fn server_labels_merge_managed_marker_with_user_labels() {
+        let user = HashMap::from([
+            ("team".to_string(), "payments".to_string()),
+            ("airstack".to_string(), "spoofed".to_string()),
+        ]);
+        let labels = merge_server_labels(Some(&user));
+        assert_eq!(labels.get("team").map(String::as_str), Some("payments"));
+        assert_eq!(labels.get("airstack").map(String::as_str), Some("managed"));
+        assert_eq!(labels.len(), 2);
+
+        let defaults = merge_server_labels(None);
+        assert_eq!(
+            defaults.into_iter().collect::<Vec<_>>(),
+            vec![("airstack".to_string(), "managed".to_string())]
+        );
+        assert_eq!(managed_tag(), "airstack=managed");
+    }
+
+    #[test]
+    fn bare_tilde_key_path_errors_instead_of_panicking() {
+        assert!(read_public_key("~").is_err());
+    }
+
     #[test]
     fn rejects_unsupported_provider() {
         let err = match get_provider("nope", HashMap::new()) {
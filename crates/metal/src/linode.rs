@@ -0,0 +1,372 @@
+use crate::{
+    managed_tag, CreateServerRequest, MetalProvider, ProviderCapabilities, Server, ServerStatus,
+    MANAGED_LABEL_KEY, MANAGED_LABEL_VALUE,
+};
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::Client;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+pub struct LinodeProvider {
+    client: Client,
+    api_token: String,
+    base_url: String,
+    image: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinodeInstance {
+    id: u64,
+    label: String,
+    status: String,
+    #[serde(default)]
+    ipv4: Vec<String>,
+    #[serde(rename = "type")]
+    plan: Option<String>,
+    region: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinodePage<T> {
+    data: Vec<T>,
+    page: u64,
+    pages: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinodeSshKey {
+    id: u64,
+    ssh_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinodeIpAddress {
+    address: String,
+}
+
+impl LinodeProvider {
+    const DEFAULT_IMAGE: &'static str = "linode/ubuntu24.04";
+
+    pub fn new(config: HashMap<String, String>) -> Result<Self> {
+        let api_token = config
+            .get("api_token")
+            .cloned()
+            .or_else(|| std::env::var("LINODE_TOKEN").ok())
+            .context("Linode API token not found in config or env var LINODE_TOKEN")?;
+        let image = config
+            .get("image")
+            .cloned()
+            .unwrap_or_else(|| Self::DEFAULT_IMAGE.to_string());
+
+        let client = Client::builder()
+            .user_agent("airstack/0.1.0")
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            api_token,
+            base_url: "https://api.linode.com/v4".to_string(),
+            image,
+        })
+    }
+
+    fn convert_instance(instance: LinodeInstance) -> Server {
+        let private_ip = instance.ipv4.iter().find(|ip| is_private_ipv4(ip)).cloned();
+        let public_ip = instance
+            .ipv4
+            .iter()
+            .find(|ip| !is_private_ipv4(ip))
+            .cloned();
+        Server {
+            id: instance.id.to_string(),
+            name: instance.label,
+            status: convert_status(&instance.status),
+            public_ip,
+            private_ip,
+            server_type: instance.plan.unwrap_or_default(),
+            region: instance.region,
+            managed: Some(instance.tags.contains(&managed_tag())),
+        }
+    }
+
+    async fn find_profile_ssh_key(&self, public_key: &str) -> Result<Option<u64>> {
+        let response = self
+            .client
+            .get(format!("{}/profile/sshkeys", self.base_url))
+            .bearer_auth(&self.api_token)
+            .send()
+            .await
+            .context("Failed to send list SSH keys request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to list SSH keys: {}", error_text);
+        }
+        let keys: LinodePage<LinodeSshKey> = response
+            .json()
+            .await
+            .context("Failed to parse list SSH keys response")?;
+        Ok(keys
+            .data
+            .into_iter()
+            .find(|k| k.ssh_key.trim() == public_key)
+            .map(|k| k.id))
+    }
+
+    async fn ensure_profile_ssh_key(&self, name: &str, public_key: &str) -> Result<()> {
+        if let Some(existing) = self.find_profile_ssh_key(public_key).await? {
+            debug!("Reusing Linode SSH key {}", existing);
+            return Ok(());
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/profile/sshkeys", self.base_url))
+            .bearer_auth(&self.api_token)
+            .json(&serde_json::json!({ "label": name, "ssh_key": public_key }))
+            .send()
+            .await
+            .context("Failed to send upload SSH key request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to upload SSH key: {}", error_text);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl MetalProvider for LinodeProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_public_ip: true,
+            supports_direct_ssh: true,
+            supports_provider_ssh: false,
+            supports_server_create: true,
+            supports_server_destroy: true,
+            supports_firewall: false,
+        }
+    }
+
+    async fn create_server(&self, request: CreateServerRequest) -> Result<Server> {
+        info!("Creating Linode instance: {}", request.name);
+        let public_key = self
+            .upload_ssh_key(&format!("{}-key", request.name), &request.ssh_key)
+            .await?;
+
+        let payload = serde_json::json!({
+            "label": request.name,
+            "region": request.region,
+            "type": request.server_type,
+            "image": self.image,
+            "root_pass": generate_root_password()?,
+            "authorized_keys": [public_key],
+            "tags": linode_tags(&request.labels),
+        });
+        let response = self
+            .client
+            .post(format!("{}/linode/instances", self.base_url))
+            .bearer_auth(&self.api_token)
+            .json(&payload)
+            .send()
+            .await
+            .context("Failed to send create instance request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to create Linode instance: {}", error_text);
+        }
+        let instance: LinodeInstance = response
+            .json()
+            .await
+            .context("Failed to parse create instance response")?;
+        let mut server = Self::convert_instance(instance);
+
+        if request.attach_floating_ip {
+            let ip = self.attach_floating_ip(&server.id).await?;
+            server.public_ip = Some(ip);
+        }
+
+        info!(
+            "Successfully created Linode instance: {} ({})",
+            request.name, server.id
+        );
+        Ok(server)
+    }
+
+    async fn destroy_server(&self, id: &str) -> Result<()> {
+        info!("Destroying Linode instance: {}", id);
+        let response = self
+            .client
+            .delete(format!("{}/linode/instances/{}", self.base_url, id))
+            .bearer_auth(&self.api_token)
+            .send()
+            .await
+            .context("Failed to send destroy instance request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to destroy Linode instance: {}", error_text);
+        }
+        Ok(())
+    }
+
+    async fn get_server(&self, id: &str) -> Result<Server> {
+        debug!("Getting Linode instance: {}", id);
+        let response = self
+            .client
+            .get(format!("{}/linode/instances/{}", self.base_url, id))
+            .bearer_auth(&self.api_token)
+            .send()
+            .await
+            .context("Failed to send get instance request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to get Linode instance: {}", error_text);
+        }
+        let instance: LinodeInstance = response
+            .json()
+            .await
+            .context("Failed to parse get instance response")?;
+        Ok(Self::convert_instance(instance))
+    }
+
+    async fn list_servers(&self) -> Result<Vec<Server>> {
+        let mut servers = Vec::new();
+        let mut page = 1;
+        loop {
+            let response = self
+                .client
+                .get(format!("{}/linode/instances", self.base_url))
+                .query(&[("page", page)])
+                .header("X-Filter", managed_filter())
+                .bearer_auth(&self.api_token)
+                .send()
+                .await
+                .context("Failed to send list instances request")?;
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to list Linode instances: {}", error_text);
+            }
+            let body: LinodePage<LinodeInstance> = response
+                .json()
+                .await
+                .context("Failed to parse list instances response")?;
+            servers.extend(body.data.into_iter().map(Self::convert_instance));
+            if body.page >= body.pages {
+                break;
+            }
+            page = body.page + 1;
+        }
+        Ok(servers)
+    }
+
+    async fn upload_ssh_key(&self, name: &str, public_key_path: &str) -> Result<String> {
+        info!("Uploading SSH key: {}", name);
+        let public_key = crate::read_public_key(public_key_path)?;
+        self.ensure_profile_ssh_key(name, &public_key).await?;
+        // Linode instance creation takes key material via authorized_keys, not key ids.
+        Ok(public_key)
+    }
+
+    async fn ssh_key_registered(&self, _name: &str, public_key_path: &str) -> Result<Option<bool>> {
+        let public_key = crate::read_public_key(public_key_path)?;
+        Ok(Some(
+            self.find_profile_ssh_key(&public_key).await?.is_some(),
+        ))
+    }
+
+    async fn attach_floating_ip(&self, server_id: &str) -> Result<String> {
+        let linode_id = server_id
+            .parse::<u64>()
+            .with_context(|| format!("invalid Linode id '{}' for IP allocation", server_id))?;
+        let response = self
+            .client
+            .post(format!("{}/networking/ips", self.base_url))
+            .bearer_auth(&self.api_token)
+            .json(&serde_json::json!({
+                "type": "ipv4",
+                "public": true,
+                "linode_id": linode_id
+            }))
+            .send()
+            .await
+            .context("Failed to send allocate IP request")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to allocate Linode IPv4: {}", error_text);
+        }
+        let ip: LinodeIpAddress = response
+            .json()
+            .await
+            .context("Failed to parse allocate IP response")?;
+        info!("Successfully allocated Linode IPv4: {}", ip.address);
+        Ok(ip.address)
+    }
+}
+
+fn convert_status(status: &str) -> ServerStatus {
+    match status {
+        "running" => ServerStatus::Running,
+        "booting" | "provisioning" | "rebooting" | "migrating" | "cloning" | "restoring"
+        | "rebuilding" | "resizing" => ServerStatus::Creating,
+        "offline" | "shutting_down" | "stopped" => ServerStatus::Stopped,
+        "deleting" => ServerStatus::Deleting,
+        _ => ServerStatus::Error,
+    }
+}
+
+fn linode_tags(labels: &std::collections::BTreeMap<String, String>) -> Vec<String> {
+    // Linode tags are plain strings, so every label (the managed marker too) becomes key=value.
+    let mut labels = labels.clone();
+    labels.insert(
+        MANAGED_LABEL_KEY.to_string(),
+        MANAGED_LABEL_VALUE.to_string(),
+    );
+    labels.iter().map(|(k, v)| format!("{k}={v}")).collect()
+}
+
+// Only instances carrying the managed tag are airstack's to list.
+fn managed_filter() -> String {
+    serde_json::json!({ "tags": managed_tag() }).to_string()
+}
+
+fn is_private_ipv4(ip: &str) -> bool {
+    ip.starts_with("192.168.")
+}
+
+fn generate_root_password() -> Result<String> {
+    let mut bytes = [0u8; 24];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| anyhow::anyhow!("failed to generate Linode root password"))?;
+    Ok(STANDARD.encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_status, managed_filter};
+    use crate::ServerStatus;
+
+    #[test]
+    fn lists_only_instances_with_the_managed_tag() {
+        assert_eq!(managed_filter(), r#"{"tags":"airstack=managed"}"#);
+    }
+
+    #[test]
+    fn maps_linode_statuses_onto_server_status() {
+        assert!(matches!(convert_status("running"), ServerStatus::Running));
+        assert!(matches!(convert_status("booting"), ServerStatus::Creating));
+        assert!(matches!(
+            convert_status("provisioning"),
+            ServerStatus::Creating
+        ));
+        assert!(matches!(convert_status("offline"), ServerStatus::Stopped));
+        assert!(matches!(convert_status("deleting"), ServerStatus::Deleting));
+        assert!(matches!(convert_status("weird"), ServerStatus::Error));
+    }
+}